@@ -0,0 +1,136 @@
+//! The `#[derive(TaskMessage)]` macro of `xcode_log_parser`.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, Error, Ident, LitStr};
+
+/// The options read from the `#[task(...)]` attribute.
+struct TaskOptions {
+    summary: Ident,
+    queue: Ident,
+    message_after_created: Option<Ident>,
+    open: String,
+    close: String,
+}
+
+impl TaskOptions {
+    /// Reads the options from the attributes of the derived type.
+    fn from_input(input: &DeriveInput) -> syn::Result<Self> {
+        let mut summary = None;
+        let mut queue = None;
+        let mut message_after_created = None;
+        let mut open = "s#".to_string();
+        let mut close = "#s".to_string();
+
+        for attribute in input
+            .attrs
+            .iter()
+            .filter(|attribute| attribute.path().is_ident("task"))
+        {
+            attribute.parse_nested_meta(|meta| {
+                let value: LitStr = meta.value()?.parse()?;
+                let field = || Ident::new(&value.value(), value.span());
+                if meta.path.is_ident("summary") {
+                    summary = Some(field());
+                } else if meta.path.is_ident("queue") {
+                    queue = Some(field());
+                } else if meta.path.is_ident("message_after_created") {
+                    message_after_created = Some(field());
+                } else if meta.path.is_ident("open") {
+                    open = value.value();
+                } else if meta.path.is_ident("close") {
+                    close = value.value();
+                } else {
+                    return Err(meta.error(
+                        "expected `summary`, `queue`, `message_after_created`, `open` or `close`",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+
+        let missing = |key: &str| {
+            Error::new(
+                Span::call_site(),
+                format!("missing `#[task({} = \"...\")]` attribute", key),
+            )
+        };
+        Ok(TaskOptions {
+            summary: summary.ok_or_else(|| missing("summary"))?,
+            queue: queue.ok_or_else(|| missing("queue"))?,
+            message_after_created,
+            open,
+            close,
+        })
+    }
+}
+
+/// Derives `TaskMessage` and `RegexParse` for a type deserialized from the task payload.
+///
+/// The type must also implement `serde::Deserialize`. The `#[task(...)]` attribute names
+/// the fields holding the summary and the queue, and optionally the field holding the
+/// message displayed after the task is created and the markers around the JSON payload:
+///
+/// ```ignore
+/// #[derive(Debug, serde::Deserialize, TaskMessage)]
+/// #[task(summary = "title", queue = "board", open = "TASK", close = "TASK")]
+/// struct Ticket {
+///     title: String,
+///     board: String,
+/// }
+/// ```
+#[proc_macro_derive(TaskMessage, attributes(task))]
+pub fn derive_task_message(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let options = match TaskOptions::from_input(&input) {
+        Ok(options) => options,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    let TaskOptions {
+        summary,
+        queue,
+        message_after_created,
+        open,
+        close,
+    } = options;
+    let message_after_created = match message_after_created {
+        Some(field) => quote!(::std::string::ToString::to_string(&self.#field)),
+        None => quote!(::std::string::String::new()),
+    };
+
+    quote! {
+        impl #impl_generics ::xcode_log_parser::TaskMessage for #name #type_generics #where_clause {
+            fn task_summary(&self) -> ::std::string::String {
+                ::std::string::ToString::to_string(&self.#summary)
+            }
+
+            fn task_queue(&self) -> ::std::string::String {
+                ::std::string::ToString::to_string(&self.#queue)
+            }
+
+            fn warning_message_after_created(&self) -> ::std::string::String {
+                #message_after_created
+            }
+        }
+
+        impl #impl_generics ::xcode_log_parser::RegexParse for #name #type_generics #where_clause {
+            fn regex_value() -> &'static ::xcode_log_parser::__private::Regex {
+                static REGEX: ::xcode_log_parser::__private::Lazy<::xcode_log_parser::__private::Regex> =
+                    ::xcode_log_parser::__private::Lazy::new(|| {
+                        ::xcode_log_parser::__private::payload_regex(#open, #close)
+                    });
+                &REGEX
+            }
+
+            fn new_from_regex(haystack: &str) -> ::std::option::Option<Self> {
+                let cap = Self::regex_value().captures(haystack)?;
+                ::xcode_log_parser::__private::serde_json::from_str(cap.get(1)?.as_str()).ok()
+            }
+        }
+    }
+    .into()
+}
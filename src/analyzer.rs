@@ -0,0 +1,90 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Matches the clang-tidy name of an analyzer checker at the end of a message,
+/// such as `[clang-analyzer-core.NullDereference]`.
+static TIDY_CHECKER_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"\s\[clang-analyzer-(?P<checker>[A-Za-z0-9_.]+)\]$"#).unwrap());
+
+/// Matches the name of an analyzer checker at the end of a message, such as `[deadcode.DeadStores]`.
+static CHECKER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\s\[(?P<checker>[a-z][A-Za-z0-9_]*(?:\.[A-Za-z0-9_]+)+)\]$"#).unwrap()
+});
+
+/// Matches a report written by the analyzer: the `-o` output of an analyzer command, or
+/// the directory `scan-build` asks to open with `scan-view`.
+static REPORT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?:\s-o (?P<output>\S+\.(?:plist|html))(?:\s|$))|(?:Run 'scan-view (?P<directory>[^']+)')"#)
+        .unwrap()
+});
+
+/// Returns the analyzer checker named at the end of a diagnostic message, if any.
+///
+/// # Arguments
+///
+/// * `text` - The message of the diagnostic, following its severity.
+/// * `in_analyze_step` - Whether the diagnostic was printed by an `Analyze` step, where
+///   checkers are named without the `clang-analyzer-` prefix.
+pub(crate) fn checker(text: &str, in_analyze_step: bool) -> Option<String> {
+    let cap = TIDY_CHECKER_REGEX.captures(text).or_else(|| {
+        in_analyze_step
+            .then(|| CHECKER_REGEX.captures(text))
+            .flatten()
+    })?;
+    Some(cap["checker"].to_string())
+}
+
+/// Returns the path of the analyzer report a log line refers to, if any.
+///
+/// # Arguments
+///
+/// * `line` - A string slice that holds the log line, such as the invocation of an analyzer.
+pub(crate) fn report_path(line: &str) -> Option<String> {
+    let cap = REPORT_REGEX.captures(line)?;
+    let path = cap.name("output").or_else(|| cap.name("directory"))?;
+    Some(path.as_str().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BuildLog, BuildStep, Category, MyWarning};
+
+    /// Tests tagging analyzer diagnostics and collecting their reports.
+    #[test]
+    fn test_build_log_analyzer_diagnostics() {
+        let log = "Analyze /src/A.m normal arm64 (in target 'App' from project 'App')\n\
+                   \x20   cd /src\n\
+                   \x20   /usr/bin/clang -x objective-c --analyze /src/A.m -o /build/StaticAnalyzer/App/A.plist\n\
+                   /src/A.m:12:5: warning: Value stored to 'x' is never read [deadcode.DeadStores]\n\
+                   CompileC /build/B.o /src/B.m normal arm64 objective-c (in target 'App' from project 'App')\n\
+                   /src/B.m:3:1: warning: unused variable [-Wunused-variable]\n\
+                   /src/C.cpp:7:9: warning: Dereference of null pointer [clang-analyzer-core.NullDereference]\n\
+                   scan-build: Run 'scan-view /tmp/scan-build-2024-05-01-1' to examine bug reports.\n";
+        let build_log = BuildLog::<MyWarning>::parse(log);
+
+        assert_eq!(build_log.build_steps()[0].step(), &BuildStep::Analyze);
+        let tags: Vec<_> = build_log
+            .diagnostics()
+            .iter()
+            .map(|log_file| {
+                let code_fragment = log_file.code_fragment().unwrap();
+                (code_fragment.category(), code_fragment.rule())
+            })
+            .collect();
+        assert_eq!(
+            tags,
+            vec![
+                (Some(Category::Analyzer), Some("deadcode.DeadStores")),
+                (None, None),
+                (Some(Category::Analyzer), Some("core.NullDereference")),
+            ]
+        );
+        assert_eq!(
+            build_log.analyzer_reports(),
+            [
+                "/build/StaticAnalyzer/App/A.plist",
+                "/tmp/scan-build-2024-05-01-1"
+            ]
+        );
+    }
+}
@@ -0,0 +1,323 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Matches the step generating a dSYM, such as
+/// `GenerateDSYMFile /build/App.app.dSYM /build/App.app/App (in target 'App' from project 'App')`.
+static GENERATE_DSYM_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"^GenerateDSYMFile (?P<arguments>.+?)(?: \(in target '(?P<target>[^']+)' from project '[^']+'\))?$"#,
+    )
+    .unwrap()
+});
+
+/// Matches a line printed by `dwarfdump --uuid`, such as
+/// `UUID: 0A1B2C3D-4E5F-6071-8293-A4B5C6D7E8F9 (arm64) /build/App.app.dSYM/Contents/Resources/DWARF/App`.
+static UUID_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"^UUID: (?P<uuid>[0-9A-Fa-f]{8}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{12}) \((?P<architecture>[^)]+)\) (?P<path>.+)$"#,
+    )
+    .unwrap()
+});
+
+/// Matches the `-archivePath` and `-exportPath` options of an echoed `xcodebuild` command.
+static PATH_OPTION_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\s-(?P<option>archivePath|exportPath) (?:"(?P<quoted>[^"]+)"|'(?P<single>[^']+)'|(?P<plain>\S+))"#)
+        .unwrap()
+});
+
+/// Matches the line printed by `xcodebuild -exportArchive`, such as `Exported App to: /build/export`.
+static EXPORTED_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^Exported .+ to: (?P<path>.+)$"#).unwrap());
+
+/// Represents the UUID of one architecture slice of a binary.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BinaryUuid {
+    uuid: String,
+    architecture: String,
+}
+
+impl BinaryUuid {
+    /// Returns the UUID, in upper case, such as `0A1B2C3D-4E5F-6071-8293-A4B5C6D7E8F9`.
+    pub fn uuid(&self) -> &str {
+        &self.uuid
+    }
+
+    /// Returns the architecture of the slice, such as `arm64`.
+    pub fn architecture(&self) -> &str {
+        &self.architecture
+    }
+}
+
+/// Represents a dSYM bundle produced by the build.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Dsym {
+    path: String,
+    binary: Option<String>,
+    target: Option<String>,
+    uuids: Vec<BinaryUuid>,
+}
+
+impl Dsym {
+    /// Returns the path of the `.dSYM` bundle.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the path of the binary the dSYM was generated from, if printed.
+    pub fn binary(&self) -> Option<&str> {
+        self.binary.as_deref()
+    }
+
+    /// Returns the name of the target that produced the binary, if printed.
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
+    /// Returns the UUIDs of the binary, one per architecture, if the log contains
+    /// the output of `dwarfdump --uuid`.
+    pub fn uuids(&self) -> &[BinaryUuid] {
+        &self.uuids
+    }
+}
+
+/// Represents the artifacts an archive build produced.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Artifacts {
+    dsyms: Vec<Dsym>,
+    archive_path: Option<String>,
+    export_path: Option<String>,
+}
+
+impl Artifacts {
+    /// Returns the dSYM bundles, in the order they were generated.
+    pub fn dsyms(&self) -> &[Dsym] {
+        &self.dsyms
+    }
+
+    /// Returns the path of the `.xcarchive`, if the log echoes the `-archivePath` option.
+    pub fn archive_path(&self) -> Option<&str> {
+        self.archive_path.as_deref()
+    }
+
+    /// Returns the directory the archive was exported to, if the log contains an export.
+    pub fn export_path(&self) -> Option<&str> {
+        self.export_path.as_deref()
+    }
+
+    /// Returns whether the log names no artifact.
+    pub fn is_empty(&self) -> bool {
+        self.dsyms.is_empty() && self.archive_path.is_none() && self.export_path.is_none()
+    }
+}
+
+/// A stateful parser collecting the artifacts named by an archive build.
+///
+/// ```text
+/// $ xcodebuild -scheme App -archivePath /build/App.xcarchive archive
+/// GenerateDSYMFile /build/App.app.dSYM /build/App.app/App (in target 'App' from project 'App')
+/// UUID: 0A1B2C3D-4E5F-6071-8293-A4B5C6D7E8F9 (arm64) /build/App.app.dSYM/Contents/Resources/DWARF/App
+/// Exported App to: /build/export
+/// ```
+///
+/// Lines are fed one at a time with [`push_line`](Self::push_line). UUIDs are attached to
+/// the dSYM they were read from, or to the dSYM generated from the binary they were read from.
+#[derive(Debug, Default)]
+pub struct ArtifactsParser {
+    artifacts: Artifacts,
+}
+
+impl ArtifactsParser {
+    /// Creates a new `ArtifactsParser`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds the next line of the log to the parser.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - A string slice that holds the log line.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the line names an artifact.
+    pub fn push_line(&mut self, line: &str) -> bool {
+        let line = line.trim_end();
+        if let Some(cap) = GENERATE_DSYM_REGEX.captures(line) {
+            let mut arguments = split_arguments(&cap["arguments"]).into_iter();
+            let Some(path) = arguments.next() else {
+                return false;
+            };
+            let binary = arguments.next();
+            let target = cap.name("target").map(|target| target.as_str().to_string());
+            match self.dsym_mut(&path) {
+                Some(dsym) => {
+                    dsym.binary = binary.or(dsym.binary.take());
+                    dsym.target = target.or(dsym.target.take());
+                }
+                None => self.artifacts.dsyms.push(Dsym {
+                    path,
+                    binary,
+                    target,
+                    uuids: Vec::new(),
+                }),
+            }
+            return true;
+        }
+        if let Some(cap) = UUID_REGEX.captures(line) {
+            return self.push_uuid(&cap["uuid"], &cap["architecture"], &cap["path"]);
+        }
+        if let Some(cap) = EXPORTED_REGEX.captures(line) {
+            self.artifacts.export_path = Some(cap["path"].to_string());
+            return true;
+        }
+
+        let mut found = false;
+        for cap in PATH_OPTION_REGEX.captures_iter(line) {
+            let path = ["quoted", "single", "plain"]
+                .into_iter()
+                .find_map(|name| cap.name(name))
+                .map(|path| path.as_str().to_string());
+            match &cap["option"] {
+                "archivePath" => self.artifacts.archive_path = path,
+                _ => self.artifacts.export_path = path,
+            }
+            found = true;
+        }
+        found
+    }
+
+    /// Attaches a UUID to the dSYM it was read from, or generated from the binary it was read from.
+    fn push_uuid(&mut self, uuid: &str, architecture: &str, path: &str) -> bool {
+        let uuid = BinaryUuid {
+            uuid: uuid.to_ascii_uppercase(),
+            architecture: architecture.to_string(),
+        };
+        let dsym = match path.find(".dSYM/") {
+            Some(end) => {
+                let dsym_path = &path[..end + ".dSYM".len()];
+                if self.dsym_mut(dsym_path).is_none() {
+                    self.artifacts.dsyms.push(Dsym {
+                        path: dsym_path.to_string(),
+                        binary: None,
+                        target: None,
+                        uuids: Vec::new(),
+                    });
+                }
+                self.dsym_mut(dsym_path)
+            }
+            None => self
+                .artifacts
+                .dsyms
+                .iter_mut()
+                .find(|dsym| dsym.binary.as_deref() == Some(path)),
+        };
+        let Some(dsym) = dsym else {
+            return false;
+        };
+        if !dsym.uuids.contains(&uuid) {
+            dsym.uuids.push(uuid);
+        }
+        true
+    }
+
+    /// Returns the dSYM at the given path, if already seen.
+    fn dsym_mut(&mut self, path: &str) -> Option<&mut Dsym> {
+        self.artifacts
+            .dsyms
+            .iter_mut()
+            .find(|dsym| dsym.path == path)
+    }
+
+    /// Consumes the parser and returns the artifacts named by the log.
+    pub fn finish(self) -> Artifacts {
+        self.artifacts
+    }
+}
+
+/// Splits the arguments of a build step header, where spaces in paths are escaped with `\`.
+fn split_arguments(arguments: &str) -> Vec<String> {
+    let mut split = Vec::new();
+    let mut current = String::new();
+    let mut characters = arguments.chars();
+    while let Some(character) = characters.next() {
+        match character {
+            '\\' => current.extend(characters.next()),
+            ' ' => {
+                if !current.is_empty() {
+                    split.push(std::mem::take(&mut current));
+                }
+            }
+            character => current.push(character),
+        }
+    }
+    if !current.is_empty() {
+        split.push(current);
+    }
+    split
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BuildLog, BuildStep, MyWarning};
+
+    /// Tests collecting the dSYMs, their UUIDs and the archive of an archive build.
+    #[test]
+    fn test_build_log_artifacts() {
+        let log = "$ xcodebuild -scheme App -archivePath \"/build/My App.xcarchive\" archive\n\
+                   GenerateDSYMFile /build/My\\ App.app.dSYM /build/My\\ App.app/My\\ App (in target 'App' from project 'App')\n\
+                   GenerateDSYMFile /build/Kit.framework.dSYM /build/Kit.framework/Kit\n\
+                   UUID: 0a1b2c3d-4e5f-6071-8293-a4b5c6d7e8f9 (arm64) /build/My App.app.dSYM/Contents/Resources/DWARF/My App\n\
+                   UUID: 11111111-2222-3333-4444-555555555555 (arm64) /build/Kit.framework/Kit\n\
+                   UUID: 66666666-7777-8888-9999-000000000000 (x86_64) /build/Widget.appex.dSYM/Contents/Resources/DWARF/Widget\n\
+                   ** ARCHIVE SUCCEEDED **\n\
+                   Exported App to: /build/export\n";
+        let build_log = BuildLog::<MyWarning>::parse(log);
+        let artifacts = build_log.artifacts();
+
+        let dsyms: Vec<_> = artifacts
+            .dsyms()
+            .iter()
+            .map(|dsym| {
+                let uuids: Vec<_> = dsym
+                    .uuids()
+                    .iter()
+                    .map(|uuid| (uuid.uuid(), uuid.architecture()))
+                    .collect();
+                (dsym.path(), dsym.binary(), dsym.target(), uuids)
+            })
+            .collect();
+        assert_eq!(
+            dsyms,
+            vec![
+                (
+                    "/build/My App.app.dSYM",
+                    Some("/build/My App.app/My App"),
+                    Some("App"),
+                    vec![("0A1B2C3D-4E5F-6071-8293-A4B5C6D7E8F9", "arm64")]
+                ),
+                (
+                    "/build/Kit.framework.dSYM",
+                    Some("/build/Kit.framework/Kit"),
+                    None,
+                    vec![("11111111-2222-3333-4444-555555555555", "arm64")]
+                ),
+                (
+                    "/build/Widget.appex.dSYM",
+                    None,
+                    None,
+                    vec![("66666666-7777-8888-9999-000000000000", "x86_64")]
+                ),
+            ]
+        );
+        assert_eq!(artifacts.archive_path(), Some("/build/My App.xcarchive"));
+        assert_eq!(artifacts.export_path(), Some("/build/export"));
+        assert_eq!(
+            build_log.build_steps()[0].step(),
+            &BuildStep::Other("GenerateDSYMFile".to_string())
+        );
+        assert!(BuildLog::<MyWarning>::parse("** BUILD SUCCEEDED **\n")
+            .artifacts()
+            .is_empty());
+    }
+}
@@ -0,0 +1,346 @@
+use std::convert::Infallible;
+
+use crate::build_log::{strip_ansi, STDERR_LOG_REGEX};
+use crate::continuation::{caret_start, Continuation};
+use crate::{Dialect, LogFile, Note, Severity, TaskMessage};
+
+/// Something read from a line of a log, passed to a [`DiagnosticAssembler`].
+// Entries are moved out as soon as they are returned, so boxing diagnostics would only add allocations.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+pub(crate) enum Entry<T: TaskMessage, E> {
+    /// A diagnostic, with the 1-based number of the line it was parsed from.
+    Diagnostic(usize, LogFile<T>),
+    /// Anything else worth keeping, such as a build step, kept in the order of the log.
+    Other(E),
+}
+
+impl<T: TaskMessage> Entry<T, Infallible> {
+    /// Returns the line number and the diagnostic, the only entry of a reader of diagnostics.
+    pub(crate) fn into_diagnostic(self) -> (usize, LogFile<T>) {
+        match self {
+            Entry::Diagnostic(line_number, log_file) => (line_number, log_file),
+            Entry::Other(never) => match never {},
+        }
+    }
+}
+
+/// What the assembler expects from the next line.
+#[derive(Debug, Default)]
+enum State {
+    /// A diagnostic or a note, or a line unrelated to the last diagnostic.
+    #[default]
+    Idle,
+    /// The source excerpt following a diagnostic or a note.
+    Excerpt,
+    /// The caret line following a source excerpt.
+    Caret { excerpt: String },
+    /// The fix-it line following a caret line.
+    Fixit {
+        excerpt: String,
+        caret: String,
+        caret_start: usize,
+    },
+}
+
+/// Attaches the source excerpt, caret and fix-it lines and the `note:` lines that follow a
+/// diagnostic to it while a log is read line by line, for [`BuildLog::parse`](crate::BuildLog::parse)
+/// and the readers parsing a log as it arrives.
+///
+/// A diagnostic is only returned once the lines belonging to it have ended, so it lags one
+/// line or more behind the log. [`finish`](Self::finish) returns what is left at its end.
+/// Only a line nothing was read from can be a source excerpt.
+#[derive(Debug)]
+pub(crate) struct DiagnosticAssembler<T: TaskMessage> {
+    dialect: Dialect,
+    parent: Option<(usize, LogFile<T>)>,
+    note: Option<LogFile<T>>,
+    state: State,
+}
+
+impl<T: TaskMessage> DiagnosticAssembler<T> {
+    /// Creates a new `DiagnosticAssembler` reading continuation lines decorated by the given dialect.
+    pub(crate) fn new(dialect: Dialect) -> Self {
+        DiagnosticAssembler {
+            dialect,
+            parent: None,
+            note: None,
+            state: State::Idle,
+        }
+    }
+
+    /// Reads the next line of the log.
+    ///
+    /// The lines `xcodebuild` logs to stderr are skipped, so that they do not separate a
+    /// diagnostic from its excerpt or notes.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The line as it was read, without its line break.
+    /// * `entry` - What was parsed from the line, if anything.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Entry<T, E>>` - The diagnostics whose lines have ended and the other entries
+    ///   read before them, in the order of the log.
+    pub(crate) fn push<E>(&mut self, line: &str, entry: Option<Entry<T, E>>) -> Vec<Entry<T, E>> {
+        let stripped = strip_ansi(line);
+        let line = self.dialect.undecorate(&stripped);
+        if STDERR_LOG_REGEX.is_match(&line) {
+            return Vec::new();
+        }
+        self.push_clean(&line, entry)
+    }
+
+    /// Returns the diagnostic and the entries still held at the end of the log.
+    pub(crate) fn finish<E>(&mut self) -> Vec<Entry<T, E>> {
+        if let State::Fixit { excerpt, caret, .. } = std::mem::take(&mut self.state) {
+            self.attach(&excerpt, &caret, None);
+        }
+        self.flush()
+    }
+
+    /// Reads a line already without its ANSI escape sequences and decoration, and not logged
+    /// to stderr, like [`push`](Self::push) does.
+    pub(crate) fn push_clean<E>(
+        &mut self,
+        line: &str,
+        entry: Option<Entry<T, E>>,
+    ) -> Vec<Entry<T, E>> {
+        match std::mem::take(&mut self.state) {
+            State::Idle => self.accept(entry),
+            // A line read as something else, such as the verdict of the build, is not
+            // held, so that it is returned as soon as it is read.
+            State::Excerpt if entry.is_some() => self.accept(entry),
+            State::Excerpt => {
+                self.state = State::Caret {
+                    excerpt: line.to_string(),
+                };
+                Vec::new()
+            }
+            State::Caret { excerpt } => match caret_start(line) {
+                Some(caret_start) => {
+                    self.state = State::Fixit {
+                        excerpt,
+                        caret: line.to_string(),
+                        caret_start,
+                    };
+                    Vec::new()
+                }
+                None => {
+                    let mut entries = self.flush();
+                    entries.extend(self.push_clean(line, entry));
+                    entries
+                }
+            },
+            State::Fixit {
+                excerpt,
+                caret,
+                caret_start,
+            } => {
+                let indentation = line.len() - line.trim_start().len();
+                if indentation == caret_start && !line.trim().is_empty() {
+                    self.attach(&excerpt, &caret, Some(line));
+                    Vec::new()
+                } else {
+                    self.attach(&excerpt, &caret, None);
+                    self.accept(entry)
+                }
+            }
+        }
+    }
+
+    /// Reads what was parsed from a line that is not a continuation line.
+    fn accept<E>(&mut self, entry: Option<Entry<T, E>>) -> Vec<Entry<T, E>> {
+        match entry {
+            Some(Entry::Diagnostic(line_number, log_file)) => {
+                self.state = State::Excerpt;
+                let is_note = log_file
+                    .code_fragment()
+                    .is_some_and(|code_fragment| code_fragment.severity() == Some(Severity::Note));
+                if is_note && self.parent.is_some() {
+                    self.push_note();
+                    self.note = Some(log_file);
+                    return Vec::new();
+                }
+                let entries = self.flush();
+                self.parent = Some((line_number, log_file));
+                entries
+            }
+            Some(entry) => {
+                let mut entries = self.flush();
+                entries.push(entry);
+                entries
+            }
+            None => self.flush(),
+        }
+    }
+
+    /// Attaches continuation lines to the last note, or to the diagnostic if it has none yet.
+    fn attach(&mut self, excerpt: &str, caret: &str, fixit: Option<&str>) {
+        let continuation = Continuation {
+            source_excerpt: excerpt,
+            caret,
+            fixit,
+        };
+        let log_file = match self.note.as_mut() {
+            Some(note) => Some(note),
+            None => self.parent.as_mut().map(|(_, log_file)| log_file),
+        };
+        if let Some(code_fragment) = log_file.and_then(|log_file| log_file.code_fragment.as_mut()) {
+            code_fragment.attach_continuation(&continuation);
+        }
+    }
+
+    /// Moves the last note into the diagnostic it follows.
+    fn push_note(&mut self) {
+        let Some(mut note) = self.note.take() else {
+            return;
+        };
+        let parent = self
+            .parent
+            .as_mut()
+            .and_then(|(_, log_file)| log_file.code_fragment.as_mut());
+        if let (Some(parent), Some(code_fragment)) = (parent, note.code_fragment.take()) {
+            parent.push_note(Note::new(note.absolute_path, code_fragment));
+        }
+    }
+
+    /// Returns the diagnostic, with its notes, once the lines belonging to it have ended.
+    fn flush<E>(&mut self) -> Vec<Entry<T, E>> {
+        self.push_note();
+        self.parent
+            .take()
+            .map(|(line_number, log_file)| Entry::Diagnostic(line_number, log_file))
+            .into_iter()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BuildLog, MyWarning};
+
+    /// Feeds every line of a log to an assembler, parsing diagnostics on their own and
+    /// keeping the other lines that are not indented.
+    fn assemble(log: &str) -> Vec<Entry<MyWarning, String>> {
+        let mut assembler = DiagnosticAssembler::new(Dialect::default());
+        let mut entries = Vec::new();
+        for (index, line) in log.lines().enumerate() {
+            let entry =
+                match LogFile::parse(line).filter(|log_file| log_file.code_fragment().is_some()) {
+                    Some(log_file) => Some(Entry::Diagnostic(index + 1, log_file)),
+                    None => (!line.starts_with(' ')).then(|| Entry::Other(line.to_string())),
+                };
+            entries.extend(assembler.push(line, entry));
+        }
+        entries.extend(assembler.finish());
+        entries
+    }
+
+    /// Tests that excerpts and notes are attached as they are by `BuildLog::parse`.
+    #[test]
+    fn test_diagnostic_assembler_matches_build_log() {
+        let log = "/src/B.m:3:10: error: use of undeclared identifier 'fo'; did you mean 'foo'?\n\
+                   \x20   return fo;\n\
+                   \x20          ^~\n\
+                   \x20          foo\n\
+                   /src/B.m:1:5: note: 'foo' declared here\n\
+                   \x20   int foo;\n\
+                   \x20       ^\n\
+                   2024-05-01 10:00:00.000 xcodebuild[123:4567] Writing result bundle\n\
+                   /src/A.swift:1:2: note: expanded from here\n\
+                   1 error generated.\n\
+                   /src/C.swift:4:1: warning: unused\n";
+        let entries = assemble(log);
+        let diagnostics: Vec<&LogFile<MyWarning>> = entries
+            .iter()
+            .filter_map(|entry| match entry {
+                Entry::Diagnostic(_, log_file) => Some(log_file),
+                Entry::Other(_) => None,
+            })
+            .collect();
+        let build_log = BuildLog::<MyWarning>::parse(log);
+        assert_eq!(
+            diagnostics,
+            build_log.diagnostics().iter().collect::<Vec<_>>()
+        );
+        assert_eq!(diagnostics[0].code_fragment().unwrap().notes().len(), 2);
+        assert!(matches!(
+            &entries[..],
+            [
+                Entry::Diagnostic(1, _),
+                Entry::Other(line),
+                Entry::Diagnostic(11, _)
+            ] if line == "1 error generated."
+        ));
+    }
+
+    /// Tests that a line that turns out not to be a source excerpt ends the diagnostic.
+    #[test]
+    fn test_diagnostic_assembler_replays_excerpt() {
+        let entries = assemble(
+            "/src/A.swift:1:2: warning: unused\n\
+             \x20   cd /src\n\
+             /src/A.swift:3:4: note: declared here\n\
+             \x20   cd /src\n",
+        );
+        assert!(matches!(
+            &entries[..],
+            [Entry::Diagnostic(1, _), Entry::Diagnostic(3, _)]
+        ));
+    }
+
+    /// Tests reading a source excerpt, caret and fix-it.
+    #[test]
+    fn test_diagnostic_assembler_with_fixit() {
+        let entries = assemble(
+            "/src/B.m:3:10: error: use of undeclared identifier 'fo'; did you mean 'foo'?\n\
+             \x20   return fo;\n\
+             \x20          ^~\n\
+             \x20          foo\n\
+             next\n",
+        );
+        let [Entry::Diagnostic(1, log_file), Entry::Other(line)] = &entries[..] else {
+            panic!("unexpected entries: {entries:?}");
+        };
+        let code_fragment = log_file.code_fragment().unwrap();
+        assert_eq!(code_fragment.source_excerpt(), Some("    return fo;"));
+        assert_eq!(code_fragment.caret(), Some("           ^~"));
+        assert_eq!(code_fragment.fixit(), Some("foo"));
+        assert_eq!(line, "next");
+    }
+
+    /// Tests that a misaligned line after the caret is not a fix-it.
+    #[test]
+    fn test_diagnostic_assembler_without_fixit() {
+        let entries = assemble(
+            "/src/A.m:1:9: warning: unused variable 'x'\n\
+             \x20   int x = 0;\n\
+             \x20       ^\n\
+             \x20   cd /src\n",
+        );
+        let [Entry::Diagnostic(1, log_file)] = &entries[..] else {
+            panic!("unexpected entries: {entries:?}");
+        };
+        let code_fragment = log_file.code_fragment().unwrap();
+        assert_eq!(code_fragment.caret(), Some("        ^"));
+        assert_eq!(code_fragment.fixit(), None);
+    }
+
+    /// Tests that lines without a caret line are not a source excerpt.
+    #[test]
+    fn test_diagnostic_assembler_without_caret() {
+        let entries = assemble(
+            "/src/A.swift:1:2: warning: unused\n\
+             \x20   let x = 1\n\
+             ** BUILD FAILED **\n",
+        );
+        let [Entry::Diagnostic(1, log_file), Entry::Other(line)] = &entries[..] else {
+            panic!("unexpected entries: {entries:?}");
+        };
+        assert_eq!(log_file.code_fragment().unwrap().source_excerpt(), None);
+        assert_eq!(line, "** BUILD FAILED **");
+    }
+}
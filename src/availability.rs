@@ -0,0 +1,194 @@
+use std::cmp::Ordering;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::Note;
+
+/// Matches the use of an API newer than the deployment target, such as the Swift compiler's
+/// `'fetch()' is only available in iOS 15.0 or newer` or clang's
+/// `'fetch' is only available on iOS 15.0 or newer`.
+static ONLY_AVAILABLE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"^(?:'(?P<symbol>.+?)'|(?P<subject>.+?)) is only available (?:in|on) (?P<platform>[A-Za-z]+(?: application extension)?) (?P<version>\d+(?:\.\d+)*) or newer"#,
+    )
+    .unwrap()
+});
+
+/// Matches the use of an API that is not available on the platform at all, such as
+/// `'open' is unavailable in macOS` or `'shared' is unavailable in application extensions for iOS`.
+static UNAVAILABLE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"^'(?P<symbol>.+?)' is unavailable(?: in (?:application extensions for (?P<extension>[A-Za-z]+)|(?P<platform>[A-Za-z]+(?: application extension)?)))?"#,
+    )
+    .unwrap()
+});
+
+/// Matches the note clang prints after an availability warning, such as
+/// `'fetch' has been marked as being introduced in iOS 15.0 here, but the deployment target is iOS 13.0.0`.
+static DEPLOYMENT_TARGET_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"but the deployment target is [A-Za-z]+(?: application extension)? (?P<version>\d+(?:\.\d+)*)"#)
+        .unwrap()
+});
+
+/// Represents the use of an API that is not available on the deployment target, read from
+/// a diagnostic such as `'fetch()' is only available in iOS 15.0 or newer`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Availability {
+    symbol: String,
+    platform: Option<String>,
+    required_version: Option<String>,
+    deployment_target: Option<String>,
+}
+
+impl Availability {
+    /// Returns the availability issue named by a diagnostic, if it reports one.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The message of the diagnostic, following its severity.
+    /// * `notes` - The notes printed after the diagnostic, which may name the deployment target.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self>` - The availability issue, if the text reports an API that is only
+    ///   available on newer OS versions or unavailable on the platform.
+    pub(crate) fn from_diagnostic(text: &str, notes: &[Note]) -> Option<Self> {
+        let deployment_target = notes.iter().find_map(|note| {
+            DEPLOYMENT_TARGET_REGEX
+                .captures(note.text())
+                .map(|cap| cap["version"].to_string())
+        });
+        if let Some(cap) = ONLY_AVAILABLE_REGEX.captures(text) {
+            let symbol = cap.name("symbol").or(cap.name("subject"))?;
+            return Some(Availability {
+                symbol: symbol.as_str().to_string(),
+                platform: Some(cap["platform"].to_string()),
+                required_version: Some(cap["version"].to_string()),
+                deployment_target,
+            });
+        }
+        let cap = UNAVAILABLE_REGEX.captures(text)?;
+        Some(Availability {
+            symbol: cap["symbol"].to_string(),
+            platform: match cap.name("extension") {
+                Some(platform) => Some(format!("{} application extension", platform.as_str())),
+                None => cap
+                    .name("platform")
+                    .map(|platform| platform.as_str().to_string()),
+            },
+            required_version: None,
+            deployment_target,
+        })
+    }
+
+    /// Returns the API used, such as `fetch()`, or the subject of the message when the API is
+    /// not quoted, such as `conformance of 'Model' to 'Sendable'`.
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Returns the platform the API is restricted on, such as `iOS`, if the message names one.
+    pub fn platform(&self) -> Option<&str> {
+        self.platform.as_deref()
+    }
+
+    /// Returns the OS version the API was introduced in, such as `15.0`, or `None` if the API
+    /// is unavailable on the platform whatever its version.
+    pub fn required_version(&self) -> Option<&str> {
+        self.required_version.as_deref()
+    }
+
+    /// Returns the deployment target the code was compiled for, such as `13.0.0`, if a note
+    /// names it, as clang's do.
+    pub fn deployment_target(&self) -> Option<&str> {
+        self.deployment_target.as_deref()
+    }
+
+    /// Returns whether the issue remains once the deployment target is raised to a version.
+    ///
+    /// # Arguments
+    ///
+    /// * `deployment_target` - The new deployment target, such as `15.0`.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the API is introduced after `deployment_target`, or is
+    ///   unavailable on the platform.
+    pub fn blocks(&self, deployment_target: &str) -> bool {
+        match &self.required_version {
+            Some(required_version) => {
+                compare_versions(required_version, deployment_target) == Ordering::Greater
+            }
+            None => true,
+        }
+    }
+}
+
+/// Compares two dotted version numbers component by component, missing components being 0,
+/// so that `15` equals `15.0.0`.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let components = |version: &str| -> Vec<u32> {
+        version
+            .split('.')
+            .map(|component| component.parse().unwrap_or(0))
+            .collect()
+    };
+    let (a, b) = (components(a), components(b));
+    (0..a.len().max(b.len()))
+        .map(|index| {
+            let component = |version: &[u32]| version.get(index).copied().unwrap_or(0);
+            component(&a).cmp(&component(&b))
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BuildLog, MyWarning};
+
+    /// Tests reading availability issues and the blockers of a new deployment target.
+    #[test]
+    fn test_availability_from_diagnostic() {
+        let log = "/src/A.swift:3:9: error: 'refreshable(action:)' is only available in iOS 15.0 or newer\n\
+                   /src/B.m:7:5: warning: 'fetch' is only available on iOS 16.1 or newer [-Wunguarded-availability-new]\n\
+                   /sdk/Fetch.h:2:1: note: 'fetch' has been marked as being introduced in iOS 16.1 here, but the deployment target is iOS 13.0.0\n\
+                   /src/C.swift:1:1: error: 'shared' is unavailable in application extensions for iOS: Use view controller based solutions where appropriate instead.\n\
+                   /src/D.swift:2:1: warning: unused variable 'x'\n";
+        let build_log = BuildLog::<MyWarning>::parse(log);
+        let issues: Vec<_> = build_log
+            .diagnostics()
+            .iter()
+            .filter_map(|log_file| log_file.code_fragment()?.availability())
+            .collect();
+
+        let fields: Vec<_> = issues
+            .iter()
+            .map(|issue| {
+                (
+                    issue.symbol(),
+                    issue.platform(),
+                    issue.required_version(),
+                    issue.deployment_target(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("refreshable(action:)", Some("iOS"), Some("15.0"), None),
+                ("fetch", Some("iOS"), Some("16.1"), Some("13.0.0")),
+                ("shared", Some("iOS application extension"), None, None),
+            ]
+        );
+        let blockers: Vec<_> = issues
+            .iter()
+            .filter(|issue| issue.blocks("16"))
+            .map(Availability::symbol)
+            .collect();
+        assert_eq!(blockers, vec!["fetch", "shared"]);
+    }
+}
@@ -0,0 +1,372 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{parse_log, LogFile, Severity, TaskMessage};
+
+/// Identifies a diagnostic across builds by its path, severity and message text.
+///
+/// The line is kept to pair repeated diagnostics with the closest one, but it is not
+/// part of the identity, so a warning moved by an edit above it is still matched.
+/// The column is ignored.
+///
+/// The path is the one printed in the log, unless made [relative](Self::relative_to) to the
+/// root of the sources, so that builds checked out in different directories match.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    #[serde(alias = "absolute_path")]
+    path: String,
+    line: usize,
+    severity: Option<Severity>,
+    text: String,
+}
+
+impl Fingerprint {
+    /// Returns the fingerprint of a diagnostic, or `None` if it has no code fragment.
+    ///
+    /// # Arguments
+    ///
+    /// * `log_file` - The parsed diagnostic.
+    pub fn of<T: TaskMessage>(log_file: &LogFile<T>) -> Option<Self> {
+        let code_fragment = log_file.code_fragment()?;
+        Some(Fingerprint {
+            path: log_file.absolute_path().to_string(),
+            line: code_fragment.line(),
+            severity: code_fragment.severity(),
+            text: code_fragment.text().to_string(),
+        })
+    }
+
+    /// Returns the fingerprint with its path relative to the given root, if it is under it.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The absolute path of the root of the sources, such as the CI checkout.
+    pub fn relative_to(mut self, root: &str) -> Self {
+        if let Some(path) = strip_root(&self.path, root) {
+            self.path = path.to_string();
+        }
+        self
+    }
+
+    /// Returns the path of the file the diagnostic refers to, relative to the root of the
+    /// sources if the fingerprint was made [relative](Self::relative_to).
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the line the diagnostic was reported at.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the severity of the diagnostic, if known.
+    pub fn severity(&self) -> Option<Severity> {
+        self.severity
+    }
+
+    /// Returns the text of the diagnostic.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns whether a diagnostic has the path, severity and text of the fingerprint,
+    /// regardless of its line.
+    ///
+    /// A relative path matches the absolute paths ending with it, so a fingerprint made
+    /// relative to the root of another checkout still matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `log_file` - The parsed diagnostic.
+    pub fn matches<T: TaskMessage>(&self, log_file: &LogFile<T>) -> bool {
+        let Some(code_fragment) = log_file.code_fragment() else {
+            return false;
+        };
+        let path = log_file.absolute_path();
+        let same_path = path == self.path
+            || (!self.path.starts_with('/')
+                && path
+                    .strip_suffix(self.path.as_str())
+                    .is_some_and(|root| root.ends_with('/')));
+        same_path && code_fragment.severity() == self.severity && code_fragment.text() == self.text
+    }
+
+    /// Returns the parts identifying the diagnostic regardless of its line.
+    pub(crate) fn key(&self) -> (&str, Option<Severity>, &str) {
+        (&self.path, self.severity, &self.text)
+    }
+}
+
+/// Returns a path relative to a root, or `None` if it is not under it.
+fn strip_root<'a>(path: &'a str, root: &str) -> Option<&'a str> {
+    path.strip_prefix(root.trim_end_matches('/'))?
+        .strip_prefix('/')
+}
+
+/// The diagnostics of a previous build, compared against the current one by [`diff`].
+///
+/// A `Baseline` implements `Serialize` and `Deserialize`, so it can be stored between
+/// CI runs instead of the whole log. Builds checked out in different directories are
+/// matched by setting the [root](Self::with_root) of the sources of each one.
+///
+/// ```
+/// use xcode_log_parser::{diff, parse_log, Baseline, MyWarning};
+///
+/// let baseline = Baseline::from_log::<MyWarning>("/builds/1/App/A.swift:3:1: warning: unused\n")
+///     .with_root("/builds/1");
+/// let json = serde_json::to_string(&baseline).unwrap();
+///
+/// let baseline: Baseline = serde_json::from_str(&json).unwrap();
+/// let current = parse_log::<MyWarning>("/builds/2/App/A.swift:3:1: warning: unused\n");
+/// assert!(diff(&current, &baseline.with_root("/builds/2")).is_empty());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    fingerprints: Vec<Fingerprint>,
+    /// The root of the sources of the current build, which is not stored.
+    #[serde(skip)]
+    root: Option<String>,
+}
+
+impl Baseline {
+    /// Creates a `Baseline` from parsed diagnostics.
+    ///
+    /// # Arguments
+    ///
+    /// * `diagnostics` - The diagnostics of the previous build.
+    pub fn from_diagnostics<T: TaskMessage>(diagnostics: &[LogFile<T>]) -> Self {
+        Baseline {
+            fingerprints: diagnostics.iter().filter_map(Fingerprint::of).collect(),
+            root: None,
+        }
+    }
+
+    /// Creates a `Baseline` from a complete build log, parsed with [`parse_log`].
+    ///
+    /// # Arguments
+    ///
+    /// * `log` - A string slice that holds the whole build log of the previous build.
+    pub fn from_log<T: TaskMessage>(log: &str) -> Self {
+        Self::from_diagnostics(&parse_log::<T>(log))
+    }
+
+    /// Sets the root of the sources, making the paths under it relative.
+    ///
+    /// The paths of the baseline diagnostics under `root` are made relative to it, and so
+    /// are the paths of the diagnostics compared against the baseline by [`diff`]. Setting
+    /// the root of the previous build, then the one of the current build, matches builds
+    /// checked out in different directories.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The absolute path of the root of the sources, such as the CI checkout.
+    pub fn with_root(mut self, root: impl Into<String>) -> Self {
+        let root = root.into();
+        self.fingerprints = self
+            .fingerprints
+            .into_iter()
+            .map(|fingerprint| fingerprint.relative_to(&root))
+            .collect();
+        self.root = Some(root);
+        self
+    }
+
+    /// Returns the fingerprints of the baseline diagnostics.
+    pub fn fingerprints(&self) -> &[Fingerprint] {
+        &self.fingerprints
+    }
+
+    /// Returns the fingerprint of a diagnostic compared against the baseline.
+    fn fingerprint<T: TaskMessage>(&self, log_file: &LogFile<T>) -> Option<Fingerprint> {
+        let fingerprint = Fingerprint::of(log_file)?;
+        Some(match &self.root {
+            Some(root) => fingerprint.relative_to(root),
+            None => fingerprint,
+        })
+    }
+}
+
+/// The result of comparing diagnostics against a [`Baseline`].
+#[derive(Debug)]
+pub struct BaselineDiff<'a, T: TaskMessage> {
+    added: Vec<&'a LogFile<T>>,
+    fixed: Vec<Fingerprint>,
+}
+
+impl<'a, T: TaskMessage> BaselineDiff<'a, T> {
+    /// Returns the diagnostics missing from the baseline, in the order of the log.
+    pub fn added(&self) -> &[&'a LogFile<T>] {
+        &self.added
+    }
+
+    /// Returns the baseline diagnostics no longer reported.
+    pub fn fixed(&self) -> &[Fingerprint] {
+        &self.fixed
+    }
+
+    /// Returns whether no diagnostic was added or fixed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.fixed.is_empty()
+    }
+}
+
+/// Compares the current diagnostics against a baseline.
+///
+/// Diagnostics with the same path, severity and text are matched regardless of their
+/// line. When such a diagnostic is repeated, each one is paired with the baseline
+/// diagnostic at the same line, or else the closest one. Paths are compared relative to
+/// the [root](Baseline::with_root) of the baseline when it is set.
+///
+/// # Arguments
+///
+/// * `current` - The diagnostics of the current build.
+/// * `baseline` - The diagnostics of the previous build.
+///
+/// # Returns
+///
+/// * `BaselineDiff<T>` - The added and fixed diagnostics.
+pub fn diff<'a, T: TaskMessage>(
+    current: &'a [LogFile<T>],
+    baseline: &Baseline,
+) -> BaselineDiff<'a, T> {
+    let mut remaining: HashMap<_, Vec<&Fingerprint>> = HashMap::new();
+    for fingerprint in &baseline.fingerprints {
+        remaining
+            .entry(fingerprint.key())
+            .or_default()
+            .push(fingerprint);
+    }
+
+    let current: Vec<(&LogFile<T>, Option<Fingerprint>)> = current
+        .iter()
+        .map(|log_file| (log_file, baseline.fingerprint(log_file)))
+        .collect();
+    let mut matched = vec![false; current.len()];
+
+    // Pairs unmoved diagnostics first, so a moved duplicate cannot take their place.
+    for exact in [true, false] {
+        for (index, (_, fingerprint)) in current.iter().enumerate() {
+            let Some(fingerprint) = fingerprint else {
+                matched[index] = true;
+                continue;
+            };
+            if matched[index] {
+                continue;
+            }
+            let Some(candidates) = remaining.get_mut(&fingerprint.key()) else {
+                continue;
+            };
+            let closest = candidates
+                .iter()
+                .enumerate()
+                .filter(|(_, candidate)| !exact || candidate.line == fingerprint.line)
+                .min_by_key(|(_, candidate)| candidate.line.abs_diff(fingerprint.line))
+                .map(|(position, _)| position);
+            if let Some(position) = closest {
+                candidates.swap_remove(position);
+                matched[index] = true;
+            }
+        }
+    }
+
+    BaselineDiff {
+        added: current
+            .iter()
+            .zip(&matched)
+            .filter(|(_, matched)| !**matched)
+            .map(|((log_file, _), _)| *log_file)
+            .collect(),
+        fixed: baseline
+            .fingerprints
+            .iter()
+            .filter(|&fingerprint| {
+                remaining.get(&fingerprint.key()).is_some_and(|candidates| {
+                    candidates
+                        .iter()
+                        .any(|candidate| std::ptr::eq(*candidate, fingerprint))
+                })
+            })
+            .cloned()
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MyWarning;
+
+    /// Tests that moved warnings are matched while new and removed ones are reported.
+    #[test]
+    fn test_diff_matches_moved_diagnostics() {
+        let previous = "/src/A.swift:10:5: warning: unused variable 'x'\n\
+                        /src/A.swift:20:5: warning: unused variable 'x'\n\
+                        /src/B.swift:3:1: warning: deprecated API\n";
+        let current = "/src/A.swift:12:5: warning: unused variable 'x'\n\
+                       /src/A.swift:22:5: warning: unused variable 'x'\n\
+                       /src/A.swift:30:1: error: cannot find 'y' in scope\n";
+        let baseline = Baseline::from_log::<MyWarning>(previous);
+        let current = parse_log::<MyWarning>(current);
+        let diff = diff(&current, &baseline);
+
+        let added: Vec<usize> = diff
+            .added()
+            .iter()
+            .map(|log_file| log_file.code_fragment().unwrap().line())
+            .collect();
+        assert_eq!(added, vec![30]);
+        assert_eq!(diff.fixed().len(), 1);
+        assert_eq!(diff.fixed()[0].path(), "/src/B.swift");
+    }
+
+    /// Tests that a duplicate keeps its exact match when another one moves.
+    #[test]
+    fn test_diff_prefers_unmoved_duplicates() {
+        let previous = "/src/A.swift:10:5: warning: unused\n";
+        let current = "/src/A.swift:9:5: warning: unused\n\
+                       /src/A.swift:10:5: warning: unused\n";
+        let baseline = Baseline::from_log::<MyWarning>(previous);
+        let current = parse_log::<MyWarning>(current);
+        let diff = diff(&current, &baseline);
+
+        assert_eq!(diff.added().len(), 1);
+        assert_eq!(diff.added()[0].code_fragment().unwrap().line(), 9);
+        assert!(diff.fixed().is_empty());
+    }
+
+    /// Tests matching builds checked out under different roots.
+    #[test]
+    fn test_diff_with_root() {
+        let previous = "/builds/1/App/A.swift:10:5: warning: unused\n\
+                        /builds/1/App/B.swift:3:1: warning: deprecated API\n\
+                        /usr/include/C.h:1:1: warning: outside\n";
+        let current = "/ci/work/App/A.swift:11:5: warning: unused\n\
+                       /usr/include/C.h:1:1: warning: outside\n";
+        let baseline = Baseline::from_log::<MyWarning>(previous).with_root("/builds/1/");
+        let paths: Vec<&str> = baseline
+            .fingerprints()
+            .iter()
+            .map(Fingerprint::path)
+            .collect();
+        assert_eq!(
+            paths,
+            vec!["App/A.swift", "App/B.swift", "/usr/include/C.h"]
+        );
+
+        let current = parse_log::<MyWarning>(current);
+        assert_eq!(diff(&current, &baseline).added().len(), 1);
+
+        let baseline = baseline.with_root("/ci/work");
+        let diff = diff(&current, &baseline);
+        assert!(diff.added().is_empty());
+        assert_eq!(diff.fixed().len(), 1);
+        assert_eq!(diff.fixed()[0].path(), "App/B.swift");
+        assert!(diff.fixed()[0].matches(
+            &parse_log::<MyWarning>("/elsewhere/App/B.swift:9:1: warning: deprecated API\n")[0]
+        ));
+        assert!(!diff.fixed()[0].matches(
+            &parse_log::<MyWarning>("/elsewhere/MyApp/B.swift:3:1: warning: deprecated API\n")[0]
+        ));
+    }
+}
@@ -0,0 +1,202 @@
+use crate::scanner;
+use crate::{
+    split_message, CodeFragment, LogFile, ParserBackend, Severity, SeverityLexicon, TaskMessage,
+    CODE_FRAGMENT_REGEX,
+};
+
+/// A borrowed counterpart of [`LogFile`] whose fields point into the parsed line.
+///
+/// Parsing a line into a `LogFileRef` does not allocate, which makes it suited for
+/// scanning large logs. The task payload is only deserialized by [`to_owned`](Self::to_owned).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogFileRef<'a> {
+    absolute_path: &'a str,
+    code_fragment: Option<CodeFragmentRef<'a>>,
+}
+
+impl<'a> LogFileRef<'a> {
+    /// Parses a log line without copying any part of it.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - A string slice that holds the log line to be parsed.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self>` - A `LogFileRef` borrowing from `haystack` if parsing is successful, otherwise `None`.
+    pub fn parse(haystack: &'a str) -> Option<Self> {
+        Self::parse_with(haystack, ParserBackend::default())
+    }
+
+    /// Parses a log line without copying any part of it, using the given backend.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - A string slice that holds the log line to be parsed.
+    /// * `backend` - The parser used for the `path:line:column:` prefix.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self>` - A `LogFileRef` borrowing from `haystack` if parsing is successful, otherwise `None`.
+    pub fn parse_with(haystack: &'a str, backend: ParserBackend) -> Option<Self> {
+        Self::parse_with_lexicon(haystack, backend, SeverityLexicon::standard())
+    }
+
+    /// Parses a log line without copying any part of it, recognizing the given severity keywords.
+    pub(crate) fn parse_with_lexicon(
+        haystack: &'a str,
+        backend: ParserBackend,
+        lexicon: &SeverityLexicon,
+    ) -> Option<Self> {
+        let scanned = scanner::split(haystack, backend)?;
+
+        Some(LogFileRef {
+            absolute_path: scanned.absolute_path,
+            code_fragment: scanned.location.map(|(line, column, haystack)| {
+                CodeFragmentRef::from_location(line, column, haystack, lexicon)
+            }),
+        })
+    }
+
+    /// Returns the absolute path of the file the log line refers to.
+    pub fn absolute_path(&self) -> &'a str {
+        self.absolute_path
+    }
+
+    /// Returns the code fragment parsed from the log line, if any.
+    pub fn code_fragment(&self) -> Option<&CodeFragmentRef<'a>> {
+        self.code_fragment.as_ref()
+    }
+
+    /// Copies the borrowed fields into an owned [`LogFile`], parsing the task payload.
+    pub fn to_owned<T: TaskMessage>(&self) -> LogFile<T> {
+        LogFile::new(
+            self.absolute_path.to_string(),
+            self.code_fragment.as_ref().map(CodeFragmentRef::to_owned),
+        )
+    }
+}
+
+/// A borrowed counterpart of [`CodeFragment`] whose text points into the parsed line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodeFragmentRef<'a> {
+    line: usize,
+    column: usize,
+    severity: Option<Severity>,
+    text: &'a str,
+}
+
+impl<'a> CodeFragmentRef<'a> {
+    /// Parses the part of a log line following the file path, without copying it.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - A string slice that holds the code fragment to be parsed.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self>` - A `CodeFragmentRef` borrowing from `haystack` if parsing is successful, otherwise `None`.
+    pub fn parse(haystack: &'a str) -> Option<Self> {
+        let cap = CODE_FRAGMENT_REGEX.captures(haystack)?;
+        let line: usize = cap.get(1)?.as_str().parse().ok()?;
+        let column: usize = cap.get(2)?.as_str().parse().ok()?;
+        let haystack = cap.get(3)?.as_str();
+
+        Some(Self::from_location(
+            line,
+            column,
+            haystack,
+            SeverityLexicon::standard(),
+        ))
+    }
+
+    /// Creates a new `CodeFragmentRef` from a location and the rest of the log line following it.
+    fn from_location(
+        line: usize,
+        column: usize,
+        haystack: &'a str,
+        lexicon: &SeverityLexicon,
+    ) -> Self {
+        let (severity, _, text) = split_message(haystack, lexicon);
+
+        CodeFragmentRef {
+            line,
+            column,
+            severity,
+            text: text.trim(),
+        }
+    }
+
+    /// Returns the line number of the code fragment.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the column number of the code fragment.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Returns the severity of the diagnostic, if the log line names a known one.
+    pub fn severity(&self) -> Option<Severity> {
+        self.severity
+    }
+
+    /// Returns the text of the diagnostic without the severity keyword.
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// Copies the borrowed fields into an owned [`CodeFragment`], parsing the task payload.
+    pub fn to_owned<T: TaskMessage>(&self) -> CodeFragment<T> {
+        CodeFragment::new(self.line, self.column, self.severity, self.text.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MyWarning, RegexParse};
+
+    /// Tests that the borrowed fields point into the parsed line.
+    #[test]
+    fn test_log_file_ref_parse_borrows_line() {
+        let line = String::from("/src/File.swift:10:5: warning: unused variable 'x'");
+        let log_file = LogFileRef::parse(&line).unwrap();
+        assert_eq!(log_file.absolute_path(), "/src/File.swift");
+
+        let code_fragment = log_file.code_fragment().unwrap();
+        assert_eq!((code_fragment.line(), code_fragment.column()), (10, 5));
+        assert_eq!(code_fragment.severity(), Some(Severity::Warning));
+        assert_eq!(code_fragment.text(), "unused variable 'x'");
+        assert!(line
+            .as_bytes()
+            .as_ptr_range()
+            .contains(&code_fragment.text().as_ptr()));
+    }
+
+    /// Tests that converting to owned values matches parsing them directly.
+    #[test]
+    fn test_log_file_ref_to_owned() {
+        let line =
+            r#"/src/File.swift:10:5: error: s#{"queue": "TESTAPI", "summary": "Create a task"}#s"#;
+        let owned: LogFile<MyWarning> = LogFileRef::parse(line).unwrap().to_owned();
+        let parsed = LogFile::<MyWarning>::new_from_regex(line).unwrap();
+        assert_eq!(owned.absolute_path(), parsed.absolute_path());
+
+        let (owned, parsed) = (
+            owned.code_fragment().unwrap(),
+            parsed.code_fragment().unwrap(),
+        );
+        assert_eq!(owned.text(), parsed.text());
+        assert_eq!(owned.severity(), parsed.severity());
+        assert_eq!(
+            owned
+                .task_info()
+                .map(|message| message.task().task_summary()),
+            parsed
+                .task_info()
+                .map(|message| message.task().task_summary())
+        );
+    }
+}
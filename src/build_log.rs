@@ -0,0 +1,790 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io::BufRead;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::analyzer;
+use crate::assembler::{DiagnosticAssembler, Entry};
+use crate::{
+    Artifacts, ArtifactsParser, BuildOutcome, BuildOutcomeParser, BuildStep, BuildStepHeader,
+    Category, Environment, EnvironmentParser, ExportError, InfrastructureError, LinkerError,
+    LinkerErrorParser, LogFile, LogIndex, LogParser, Notarization, NotarizationParser,
+    ParserBackend, PhaseTimings, PhaseTimingsParser, RegexParse, ResourceIssue, SanitizerReport,
+    SanitizerReportParser, SigningIssue, TaskMessage, TestEvent, TimingSummary,
+    TimingSummaryParser, ToolchainCrash, ToolchainCrashParser,
+};
+
+/// Matches the timestamp and process prefix `xcodebuild` puts in front of the lines it logs
+/// to stderr, such as `2024-05-01 10:00:00.000 xcodebuild[123:4567] `.
+pub(crate) static STDERR_LOG_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}\.\d+ xcodebuild\[\d+:\d+\] "#).unwrap()
+});
+
+/// Matches the ANSI escape sequences coloring the output of compilers run with colors forced on.
+static ANSI_ESCAPE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"\x1b\[[0-9;]*[A-Za-z]"#).unwrap());
+
+/// Returns a line without the ANSI escape sequences coloring it.
+pub(crate) fn strip_ansi(line: &str) -> Cow<'_, str> {
+    ANSI_ESCAPE_REGEX.replace_all(line, "")
+}
+
+/// Represents every diagnostic and test event parsed from a complete build log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildLog<T: TaskMessage> {
+    diagnostics: Vec<LogFile<T>>,
+    test_events: Vec<TestEvent>,
+    linker_errors: Vec<LinkerError>,
+    signing_issues: Vec<SigningIssue>,
+    infrastructure_errors: Vec<InfrastructureError>,
+    resource_issues: Vec<ResourceIssue>,
+    export_errors: Vec<ExportError>,
+    notarization: Option<Notarization>,
+    outcome: Option<BuildOutcome>,
+    build_steps: Vec<BuildStepHeader>,
+    /// Index into `build_steps` of the step each diagnostic occurred in.
+    diagnostic_steps: Vec<Option<usize>>,
+    analyzer_reports: Vec<String>,
+    sanitizer_reports: Vec<SanitizerReport>,
+    toolchain_crashes: Vec<ToolchainCrash>,
+    timing_summary: Option<TimingSummary>,
+    artifacts: Artifacts,
+    environment: Environment,
+    phase_timings: PhaseTimings,
+}
+
+impl<T: TaskMessage> BuildLog<T> {
+    /// Parses a complete build log, such as the output of `xcodebuild`.
+    ///
+    /// Every line is parsed on its own. The lines `xcodebuild` logs to stderr, prefixed with
+    /// a timestamp and its process id, are dropped first, so that they do not separate a
+    /// diagnostic from its excerpt or notes when both streams are merged with `2>&1`.
+    /// Lines that neither point at a location in a file (`path:line:column:`), report an
+    /// XCTest result, a signing issue or a build system failure, refer to an analyzer
+    /// report, nor start a build step are skipped, except for the multi-line blocks described by
+    /// [`EnvironmentParser`], [`LinkerErrorParser`], [`SanitizerReportParser`],
+    /// [`ToolchainCrashParser`], [`TimingSummaryParser`], [`NotarizationParser`] and
+    /// [`BuildOutcomeParser`].
+    ///
+    /// Each diagnostic is attributed to the last build step started before it, and
+    /// the source excerpt, caret and fix-it lines printed after it are attached to
+    /// its code fragment. `note:` lines printed right after a diagnostic are attached
+    /// to it as [`Note`](crate::Note)s instead of being collected as diagnostics of their own.
+    /// Diagnostics printed by an `Analyze` step are tagged as [`Category::Analyzer`],
+    /// with the checker named at the end of their message, such as `[deadcode.DeadStores]`.
+    /// The warnings and errors of `actool` and `ibtool`, which point at an asset catalog member
+    /// or an Interface Builder object rather than a line, are read as diagnostics at line 0
+    /// tagged as [`Category::AssetCatalog`] or [`Category::InterfaceBuilder`].
+    /// Sanitizer reports and toolchain crashes pointing at a source file are appended to
+    /// the diagnostics as errors, after those read from single lines. With
+    /// [`LogParser::parse_build_log`], all of these diagnostics are filtered by the
+    /// [`ParseOptions`](crate::ParseOptions) of the parser. ANSI color codes are removed before
+    /// the lines are read.
+    ///
+    /// # Arguments
+    ///
+    /// * `log` - A string slice that holds the whole build log.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A `BuildLog` holding the diagnostics in the order they appear in the log.
+    pub fn parse(log: &str) -> Self {
+        Self::parse_with(log, ParserBackend::default())
+    }
+
+    /// Parses a complete build log, using the given backend for diagnostic lines.
+    ///
+    /// # Arguments
+    ///
+    /// * `log` - A string slice that holds the whole build log.
+    /// * `backend` - The parser used for the `path:line:column:` prefix of each line.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A `BuildLog` holding the diagnostics in the order they appear in the log.
+    pub fn parse_with(log: &str, backend: ParserBackend) -> Self {
+        LogParser::builder()
+            .backend(backend)
+            .build()
+            .parse_build_log(log)
+    }
+
+    /// Parses a complete build log, reading diagnostic lines with the given parser.
+    pub(crate) fn parse_with_parser(log: &str, parser: &LogParser) -> Self {
+        let mut diagnostics = Vec::new();
+        let mut test_events = Vec::new();
+        let mut build_steps = Vec::new();
+        let mut diagnostic_steps = Vec::new();
+        let mut linker_errors = LinkerErrorParser::new();
+        let mut signing_issues = Vec::new();
+        let mut infrastructure_errors = Vec::new();
+        let mut resource_issues = Vec::new();
+        let mut export_errors = Vec::new();
+        let mut notarization = NotarizationParser::new();
+        let mut outcome = BuildOutcomeParser::with_dialect(parser.dialect());
+        let mut analyzer_reports = Vec::new();
+        let mut sanitizer_reports = SanitizerReportParser::new();
+        let mut toolchain_crashes = ToolchainCrashParser::new();
+        let mut timing_summary = TimingSummaryParser::new();
+        let mut artifacts = ArtifactsParser::new();
+        let mut environment = EnvironmentParser::new();
+
+        // Lines are cleaned one at a time, so that the spans of their diagnostics can be moved
+        // back to the lines as they were read.
+        let stripped: Vec<(&str, Cow<str>)> =
+            log.lines().map(|line| (line, strip_ansi(line))).collect();
+        // Steps are timed before the dialect decoration, which carries fastlane's timestamps,
+        // is stripped.
+        let mut phase_timings = PhaseTimingsParser::with_dialect(parser.dialect());
+        for (_, line) in &stripped {
+            phase_timings.push_line(line);
+        }
+        let (originals, undecorated): (Vec<&str>, Vec<Cow<str>>) = stripped
+            .iter()
+            .map(|(original, line)| (*original, parser.dialect().undecorate(line)))
+            .filter(|(_, line)| !STDERR_LOG_REGEX.is_match(line))
+            .unzip();
+        let lines: Vec<&str> = undecorated.iter().map(|line| &**line).collect();
+        // Diagnostics are returned by the assembler once the lines following them have been
+        // read, with the number of their line, which tells the build step they occurred in.
+        let mut assembler = DiagnosticAssembler::new(parser.dialect());
+        let mut entries = Vec::new();
+        let mut step_lines = Vec::new();
+        for (index, (&line, &original)) in lines.iter().zip(&originals).enumerate() {
+            let entry = 'entry: {
+                // `GenerateDSYMFile` lines also start a build step, so they are not consumed.
+                artifacts.push_line(line);
+                if environment.push_line(line)
+                    || linker_errors.push_line(line)
+                    || sanitizer_reports.push_line(line)
+                    || toolchain_crashes.push_line(line)
+                    || timing_summary.push_line(line)
+                    || notarization.push_line(line)
+                    || outcome.push_line(line)
+                {
+                    break 'entry Some(Entry::Other(None));
+                }
+                if let Some(build_step) = parser.dialect().build_step(line) {
+                    build_steps.push(build_step);
+                    step_lines.push(index + 1);
+                    break 'entry Some(Entry::Other(None));
+                }
+                if let Some(test_event) = TestEvent::new_from_regex(line) {
+                    test_events.push(test_event);
+                    break 'entry Some(Entry::Other(None));
+                }
+                if let Some(report) = analyzer::report_path(line) {
+                    if !analyzer_reports.contains(&report) {
+                        analyzer_reports.push(report);
+                    }
+                    break 'entry Some(Entry::Other(None));
+                }
+                if let Some(mut log_file) = parser.parse_line(line) {
+                    log_file.relocate_spans(original, line);
+                    if let Some(code_fragment) = log_file.code_fragment.as_mut() {
+                        let in_analyze_step =
+                            build_steps.last().is_some_and(|step: &BuildStepHeader| {
+                                step.step() == &BuildStep::Analyze
+                            });
+                        if in_analyze_step && code_fragment.category().is_none() {
+                            let checker = analyzer::checker(code_fragment.text(), true);
+                            code_fragment.categorize(Category::Analyzer, checker);
+                        }
+                        break 'entry Some(Entry::Diagnostic(index + 1, log_file));
+                    }
+                }
+                // Export failures are often signing issues too, so both are read.
+                let export_error = ExportError::new_from_regex(line);
+                let is_export_error = export_error.is_some();
+                export_errors.extend(export_error);
+                if let Some(signing_issue) = SigningIssue::new_from_regex(line) {
+                    signing_issues.push(signing_issue);
+                } else if let Some(error) = InfrastructureError::new_from_regex(line) {
+                    infrastructure_errors.push(error);
+                } else if let Some(issue) = ResourceIssue::new_from_regex(line) {
+                    // Read as something other than a compiler diagnostic, so that a `note:`
+                    // of `actool` is not attached to the diagnostic before it.
+                    let log_file = issue.to_log_file();
+                    if parser.options().keeps(&log_file) {
+                        resource_issues.push(issue);
+                        break 'entry Some(Entry::Other(Some((index + 1, log_file))));
+                    }
+                } else if !is_export_error {
+                    break 'entry None;
+                }
+                Some(Entry::Other(None))
+            };
+            entries.extend(assembler.push_clean(line, entry));
+        }
+        entries.extend(assembler.finish());
+        for entry in entries {
+            let (Entry::Diagnostic(line_number, log_file)
+            | Entry::Other(Some((line_number, log_file)))) = entry
+            else {
+                continue;
+            };
+            diagnostics.push(log_file);
+            diagnostic_steps.push(
+                step_lines
+                    .partition_point(|&start| start < line_number)
+                    .checked_sub(1),
+            );
+        }
+
+        let sanitizer_reports = sanitizer_reports.finish();
+        push_kept(
+            &mut diagnostics,
+            &mut diagnostic_steps,
+            sanitizer_reports
+                .iter()
+                .filter_map(SanitizerReport::to_log_file),
+            parser,
+        );
+        let toolchain_crashes = toolchain_crashes.finish();
+        push_kept(
+            &mut diagnostics,
+            &mut diagnostic_steps,
+            toolchain_crashes
+                .iter()
+                .filter_map(ToolchainCrash::to_log_file),
+            parser,
+        );
+
+        BuildLog {
+            diagnostics,
+            test_events,
+            linker_errors: linker_errors.finish(),
+            signing_issues,
+            infrastructure_errors,
+            resource_issues,
+            export_errors,
+            notarization: notarization.finish(),
+            outcome: outcome.finish(),
+            build_steps,
+            diagnostic_steps,
+            analyzer_reports,
+            sanitizer_reports,
+            toolchain_crashes,
+            timing_summary: timing_summary.finish(),
+            artifacts: artifacts.finish(),
+            environment: environment.finish(),
+            phase_timings: phase_timings.finish(),
+        }
+    }
+
+    /// Returns the parsed diagnostics.
+    pub fn diagnostics(&self) -> &[LogFile<T>] {
+        &self.diagnostics
+    }
+
+    /// Returns the XCTest events, in the order they appear in the log.
+    pub fn test_events(&self) -> &[TestEvent] {
+        &self.test_events
+    }
+
+    /// Returns the undefined symbols reported by the linker.
+    pub fn linker_errors(&self) -> &[LinkerError] {
+        &self.linker_errors
+    }
+
+    /// Returns the code signing and provisioning failures.
+    pub fn signing_issues(&self) -> &[SigningIssue] {
+        &self.signing_issues
+    }
+
+    /// Returns the failures of the build system itself, such as a crash of the build service
+    /// or a locked build database, which are usually fixed by building again.
+    pub fn infrastructure_errors(&self) -> &[InfrastructureError] {
+        &self.infrastructure_errors
+    }
+
+    /// Returns the warnings and errors of `actool` and `ibtool`, with the asset catalog member
+    /// or Interface Builder object they point at, if kept by the [`ParseOptions`](crate::ParseOptions)
+    /// of the parser. They are also among the diagnostics.
+    pub fn resource_issues(&self) -> &[ResourceIssue] {
+        &self.resource_issues
+    }
+
+    /// Returns the failures of `xcodebuild -exportArchive`, such as a provisioning profile
+    /// missing a capability.
+    pub fn export_errors(&self) -> &[ExportError] {
+        &self.export_errors
+    }
+
+    /// Returns the submission ID, status and issues of a notarization with `notarytool`, and
+    /// whether `stapler` attached its ticket, if the log contains one.
+    pub fn notarization(&self) -> Option<&Notarization> {
+        self.notarization.as_ref()
+    }
+
+    /// Returns the final verdict of the build, if the log contains one.
+    pub fn outcome(&self) -> Option<&BuildOutcome> {
+        self.outcome.as_ref()
+    }
+
+    /// Returns the build steps, in the order they were started.
+    pub fn build_steps(&self) -> &[BuildStepHeader] {
+        &self.build_steps
+    }
+
+    /// Returns the paths of the Clang Static Analyzer reports the log refers to, such as
+    /// the `.plist` written for each analyzed source or the directory of `scan-build`'s HTML reports.
+    pub fn analyzer_reports(&self) -> &[String] {
+        &self.analyzer_reports
+    }
+
+    /// Returns the reports printed by AddressSanitizer, ThreadSanitizer,
+    /// UndefinedBehaviorSanitizer and LeakSanitizer, in the order they appear in the log.
+    pub fn sanitizer_reports(&self) -> &[SanitizerReport] {
+        &self.sanitizer_reports
+    }
+
+    /// Returns the crashes of `swift-frontend`, `clang` and the other tools of the toolchain,
+    /// in the order they appear in the log.
+    pub fn toolchain_crashes(&self) -> &[ToolchainCrash] {
+        &self.toolchain_crashes
+    }
+
+    /// Returns the time spent on each kind of task, if the build was run with
+    /// `-showBuildTimingSummary`.
+    pub fn timing_summary(&self) -> Option<&TimingSummary> {
+        self.timing_summary.as_ref()
+    }
+
+    /// Returns the dSYMs, binary UUIDs and archive paths named by an archive build.
+    pub fn artifacts(&self) -> &Artifacts {
+        &self.artifacts
+    }
+
+    /// Returns the Xcode version, SDK, scheme, configuration and destination the build ran
+    /// with, as far as the log names them.
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    /// Returns the time spent on each target and each kind of build step, reconstructed
+    /// from the timestamps of the lines starting the steps.
+    pub fn phase_timings(&self) -> &PhaseTimings {
+        &self.phase_timings
+    }
+
+    /// Returns the diagnostics paired with the build step they occurred in, if any.
+    pub fn diagnostics_with_build_steps(
+        &self,
+    ) -> impl Iterator<Item = (&LogFile<T>, Option<&BuildStepHeader>)> {
+        self.diagnostics
+            .iter()
+            .zip(&self.diagnostic_steps)
+            .map(|(log_file, step)| (log_file, step.map(|index| &self.build_steps[index])))
+    }
+
+    /// Returns the diagnostics grouped by the target of the build step they occurred in.
+    ///
+    /// With [`Dialect::SwiftPm`](crate::Dialect::SwiftPm), the target is the module or
+    /// product being built. Diagnostics printed outside of a step naming a target are left out.
+    ///
+    /// # Returns
+    ///
+    /// * `BTreeMap<&str, Vec<&LogFile<T>>>` - The diagnostics of each target, in the order they appear in the log.
+    pub fn by_target(&self) -> BTreeMap<&str, Vec<&LogFile<T>>> {
+        let mut targets: BTreeMap<&str, Vec<&LogFile<T>>> = BTreeMap::new();
+        for (log_file, step) in self.diagnostics_with_build_steps() {
+            if let Some(target) = step.and_then(BuildStepHeader::target) {
+                targets.entry(target).or_default().push(log_file);
+            }
+        }
+        targets
+    }
+
+    /// Returns the diagnostics grouped by the file they point at.
+    ///
+    /// The files are ordered by path, and the diagnostics of each file by line, then
+    /// column. Diagnostics at the same location keep the order they appear in the log,
+    /// so the result is the same for the same log.
+    ///
+    /// # Returns
+    ///
+    /// * `BTreeMap<&str, Vec<&LogFile<T>>>` - The diagnostics of each absolute path, sorted by location.
+    pub fn by_file(&self) -> BTreeMap<&str, Vec<&LogFile<T>>> {
+        let mut files: BTreeMap<&str, Vec<&LogFile<T>>> = BTreeMap::new();
+        for log_file in &self.diagnostics {
+            files
+                .entry(log_file.absolute_path())
+                .or_default()
+                .push(log_file);
+        }
+        for diagnostics in files.values_mut() {
+            diagnostics.sort_by_key(|log_file| {
+                log_file
+                    .code_fragment()
+                    .map(|code_fragment| (code_fragment.line(), code_fragment.column()))
+            });
+        }
+        files
+    }
+
+    /// Returns an index over the diagnostics, for lookups by file, line range and fingerprint.
+    pub fn index(&self) -> LogIndex<'_, T> {
+        LogIndex::new(&self.diagnostics)
+    }
+
+    /// Consumes the build log and returns the parsed diagnostics.
+    pub fn into_diagnostics(self) -> Vec<LogFile<T>> {
+        self.diagnostics
+    }
+}
+
+/// Parses a complete build log and returns all the diagnostics found in it.
+///
+/// # Arguments
+///
+/// * `log` - A string slice that holds the whole build log.
+///
+/// # Returns
+///
+/// * `Vec<LogFile<T>>` - The diagnostics in the order they appear in the log.
+pub fn parse_log<T: TaskMessage>(log: &str) -> Vec<LogFile<T>> {
+    BuildLog::parse(log).into_diagnostics()
+}
+
+/// Reads a build log to its end and returns all the diagnostics found in it.
+///
+/// This is the shorthand for piping `xcodebuild ... 2>&1` into a program, as in
+/// `parse_reader::<MyWarning, _>(std::io::stdin().lock())`. Invalid UTF-8 is replaced
+/// rather than rejected.
+///
+/// # Arguments
+///
+/// * `reader` - A buffered reader that yields the build log, such as the locked stdin.
+///
+/// # Returns
+///
+/// * `io::Result<Vec<LogFile<T>>>` - The diagnostics in the order they appear in the log, or the read error.
+pub fn parse_reader<T: TaskMessage, R: BufRead>(reader: R) -> std::io::Result<Vec<LogFile<T>>> {
+    Ok(LogParser::default()
+        .parse_reader(reader)?
+        .into_diagnostics())
+}
+
+/// Appends the diagnostics read from several lines, such as sanitizer reports and
+/// toolchain crashes, that are
+/// kept by the [`ParseOptions`](crate::ParseOptions) of the parser. They are not attributed
+/// to a build step.
+///
+/// # Arguments
+///
+/// * `diagnostics` - The diagnostics read so far.
+/// * `diagnostic_steps` - The build step of each diagnostic read so far.
+/// * `log_files` - The diagnostics to append.
+/// * `parser` - The parser the log is read with.
+fn push_kept<T: TaskMessage>(
+    diagnostics: &mut Vec<LogFile<T>>,
+    diagnostic_steps: &mut Vec<Option<usize>>,
+    log_files: impl IntoIterator<Item = LogFile<T>>,
+    parser: &LogParser,
+) {
+    for log_file in log_files {
+        if parser.options().keeps(&log_file) {
+            diagnostics.push(log_file);
+            diagnostic_steps.push(None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MyWarning, ParseOptions, PathFilter, Severity};
+
+    const LOG: &str = r#"Build settings from command line:
+    SDKROOT = iphonesimulator
+
+CompileSwift normal arm64 /path/to/App/File.swift (in target 'App' from project 'App')
+/path/to/App/File.swift:10:5: warning: s#{"queue": "TESTAPI", "summary": "Create a task"}#s
+/path/to/App/Other.swift:3:1: error: s#{"queue": "TESTAPI", "summary": "Fix the build"}#s
+** BUILD FAILED **
+"#;
+
+    /// Tests that only lines with a file location are collected, in order.
+    #[test]
+    fn test_build_log_parse_collects_diagnostics() {
+        let build_log = BuildLog::<MyWarning>::parse(LOG);
+        let paths: Vec<&str> = build_log
+            .diagnostics()
+            .iter()
+            .map(|log_file| log_file.absolute_path())
+            .collect();
+        assert_eq!(
+            paths,
+            vec!["/path/to/App/File.swift", "/path/to/App/Other.swift"]
+        );
+    }
+
+    /// Tests that test results are collected apart from compiler diagnostics.
+    #[test]
+    fn test_build_log_parse_collects_test_events() {
+        let log = "Test Case '-[AppTests.LoginTests testLogin]' started.\n\
+                   /src/LoginTests.swift:42: error: -[AppTests.LoginTests testLogin] : failed\n\
+                   Test Case '-[AppTests.LoginTests testLogin]' failed (0.010 seconds).\n";
+        let build_log = BuildLog::<MyWarning>::parse(log);
+        assert!(build_log.diagnostics().is_empty());
+        assert_eq!(build_log.test_events().len(), 3);
+        assert!(matches!(
+            build_log.test_events()[1],
+            TestEvent::Failure { line: 42, .. }
+        ));
+    }
+
+    /// Tests that diagnostics are attributed to the step they occurred in.
+    #[test]
+    fn test_build_log_parse_attributes_build_steps() {
+        let log = "/src/Early.swift:1:1: warning: before any step\n\
+                   CompileSwift normal arm64 /src/A.swift (in target 'App' from project 'App')\n\
+                   /src/A.swift:2:3: warning: in app\n\
+                   CompileC /build/B.o /src/B.m normal arm64 objective-c (in target 'Kit' from project 'Kit')\n\
+                   /src/B.m:4:5: error: in kit\n";
+        let build_log = BuildLog::<MyWarning>::parse(log);
+        assert_eq!(build_log.build_steps().len(), 2);
+
+        let targets: Vec<Option<&str>> = build_log
+            .diagnostics_with_build_steps()
+            .map(|(_, step)| step.and_then(BuildStepHeader::target))
+            .collect();
+        assert_eq!(targets, vec![None, Some("App"), Some("Kit")]);
+
+        let by_target = build_log.by_target();
+        assert_eq!(
+            by_target.keys().copied().collect::<Vec<_>>(),
+            ["App", "Kit"]
+        );
+        assert_eq!(by_target["Kit"][0].absolute_path(), "/src/B.m");
+    }
+
+    /// Tests that diagnostics are grouped by path and sorted by location, ties in log order.
+    #[test]
+    fn test_build_log_by_file() {
+        let log = "/src/B.swift:9:1: warning: last\n\
+                   /src/A.swift:3:4: warning: second\n\
+                   /src/A.swift:1:7: error: first\n\
+                   /src/A.swift:3:4: note: attached to second\n\
+                   /src/A.swift:3:4: warning: third\n";
+        let build_log = BuildLog::<MyWarning>::parse(log);
+        let files: Vec<(&str, Vec<&str>)> = build_log
+            .by_file()
+            .into_iter()
+            .map(|(path, diagnostics)| {
+                let texts = diagnostics
+                    .iter()
+                    .map(|log_file| log_file.code_fragment().unwrap().text())
+                    .collect();
+                (path, texts)
+            })
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                ("/src/A.swift", vec!["first", "second", "third"]),
+                ("/src/B.swift", vec!["last"]),
+            ]
+        );
+    }
+
+    /// Tests that undefined symbol blocks are collected as linker errors.
+    #[test]
+    fn test_build_log_parse_collects_linker_errors() {
+        let log = "Undefined symbols for architecture x86_64:\n\
+                   \x20 \"_foo\", referenced from:\n\
+                   \x20     _main in main.o\n\
+                   ld: symbol(s) not found for architecture x86_64\n";
+        let build_log = BuildLog::<MyWarning>::parse(log);
+        assert_eq!(build_log.linker_errors().len(), 1);
+        assert_eq!(build_log.linker_errors()[0].symbol(), "_foo");
+        assert_eq!(build_log.linker_errors()[0].architecture(), "x86_64");
+    }
+
+    /// Tests that signing failures are collected apart from compiler diagnostics.
+    #[test]
+    fn test_build_log_parse_collects_signing_issues() {
+        let log = "/src/App.xcodeproj: error: No profiles for 'com.example.app' were found\n\
+                   /src/A.swift:1:2: error: cannot find 'x' in scope\n";
+        let build_log = BuildLog::<MyWarning>::parse(log);
+        assert_eq!(build_log.diagnostics().len(), 1);
+        assert_eq!(build_log.signing_issues().len(), 1);
+        assert_eq!(
+            build_log.signing_issues()[0].profile(),
+            Some("com.example.app")
+        );
+    }
+
+    /// Tests that the final verdict is captured.
+    #[test]
+    fn test_build_log_parse_outcome() {
+        let build_log = BuildLog::<MyWarning>::parse(LOG);
+        let outcome = build_log.outcome().unwrap();
+        assert_eq!(outcome.action(), "BUILD");
+        assert!(!outcome.succeeded());
+    }
+
+    /// Tests that source excerpts and fix-its are attached to the diagnostic before them.
+    #[test]
+    fn test_build_log_parse_attaches_continuation() {
+        let log = "/src/B.m:3:10: error: use of undeclared identifier 'fo'; did you mean 'foo'?\n\
+                   \x20   return fo;\n\
+                   \x20          ^~\n\
+                   \x20          foo\n\
+                   /src/A.m:1:5: warning: unused variable 'x'\n\
+                   \x20   int x = 0;\n\
+                   \x20       ^\n\
+                   1 warning and 1 error generated.\n";
+        let build_log = BuildLog::<MyWarning>::parse(log);
+        assert_eq!(build_log.diagnostics().len(), 2);
+
+        let code_fragment = build_log.diagnostics()[0].code_fragment().unwrap();
+        assert_eq!(code_fragment.source_excerpt(), Some("    return fo;"));
+        assert_eq!(code_fragment.caret(), Some("           ^~"));
+        assert_eq!(code_fragment.fixit(), Some("foo"));
+
+        let code_fragment = build_log.diagnostics()[1].code_fragment().unwrap();
+        assert_eq!(code_fragment.source_excerpt(), Some("    int x = 0;"));
+        assert_eq!(code_fragment.fixit(), None);
+    }
+
+    /// Tests that notes are attached to the diagnostic they follow.
+    #[test]
+    fn test_build_log_parse_attaches_notes() {
+        let log = "/src/A.m:5:3: warning: implicit conversion loses precision\n\
+                   /src/Macros.h:2:20: note: expanded from macro 'TRUNCATE'\n\
+                   \x20#define TRUNCATE(x) (int)(x)\n\
+                   \x20                  ^\n\
+                   /src/Macros.h:1:9: note: expanded from macro 'WRAP'\n\
+                   1 warning generated.\n\
+                   /src/B.m:1:1: note: unrelated note\n";
+        let build_log = BuildLog::<MyWarning>::parse(log);
+        assert_eq!(build_log.diagnostics().len(), 2);
+
+        let notes = build_log.diagnostics()[0].code_fragment().unwrap().notes();
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].absolute_path(), "/src/Macros.h");
+        assert_eq!(notes[0].text(), "expanded from macro 'TRUNCATE'");
+        assert_eq!(
+            notes[0].source_excerpt(),
+            Some(" #define TRUNCATE(x) (int)(x)")
+        );
+        assert_eq!(notes[1].line(), 1);
+
+        let standalone = build_log.diagnostics()[1].code_fragment().unwrap();
+        assert_eq!(standalone.severity(), Some(Severity::Note));
+        assert!(standalone.notes().is_empty());
+    }
+
+    /// Tests parsing a log without any diagnostics.
+    #[test]
+    fn test_parse_log_without_diagnostics() {
+        let diagnostics = parse_log::<MyWarning>("** BUILD SUCCEEDED **\n");
+        assert!(diagnostics.is_empty());
+    }
+
+    /// Tests reading a log merged with stderr, with a stderr line inside a diagnostic and invalid UTF-8.
+    #[test]
+    fn test_parse_reader_merged_stderr() {
+        let log = b"/src/A.swift:3:9: error: cannot find 'foo' in scope\n\
+                    2024-05-01 10:00:00.123 xcodebuild[4521:88812] Requested but did not find extension point\n\
+                    \x20   let x = foo() // \xff\n\
+                    \x20           ^~~\n";
+        let diagnostics = parse_reader::<MyWarning, _>(&log[..]).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        let code_fragment = diagnostics[0].code_fragment().unwrap();
+        assert_eq!(
+            code_fragment.source_excerpt(),
+            Some("    let x = foo() // \u{fffd}")
+        );
+        assert_eq!(code_fragment.caret(), Some("            ^~~"));
+    }
+
+    /// Tests that `actool` issues pass through the path and severity filters of the parser.
+    #[test]
+    fn test_build_log_filters_resource_issues() {
+        let log = "/App/Pods/X/Assets.xcassets:./AppIcon.appiconset/[][ipad]: warning: A 76x76@1x app icon is required\n\
+                   /App/Sources/Assets.xcassets:./Logo.imageset: warning: The image set has an unassigned child\n\
+                   /App/Sources/Main.storyboard:BYZ-38-t0r: error: Illegal configuration\n";
+        let paths = |options: ParseOptions| -> Vec<String> {
+            let build_log = LogParser::builder()
+                .options(options)
+                .build()
+                .parse_build_log::<MyWarning>(log);
+            assert_eq!(
+                build_log.resource_issues().len(),
+                build_log.diagnostics().len()
+            );
+            build_log
+                .diagnostics()
+                .iter()
+                .map(|log_file| log_file.absolute_path().to_string())
+                .collect()
+        };
+
+        assert_eq!(
+            paths(ParseOptions {
+                paths: PathFilter::new().exclude("Pods/**"),
+                ..ParseOptions::default()
+            }),
+            vec![
+                "/App/Sources/Assets.xcassets",
+                "/App/Sources/Main.storyboard"
+            ]
+        );
+        assert_eq!(
+            paths(ParseOptions {
+                min_severity: Some(Severity::Error),
+                ..ParseOptions::default()
+            }),
+            vec!["/App/Sources/Main.storyboard"]
+        );
+    }
+
+    /// Tests that sanitizer reports in excluded paths are not read as diagnostics.
+    #[test]
+    fn test_build_log_filters_sanitizer_reports() {
+        let log = "/App/Pods/X/Math.c:12:5: runtime error: signed integer overflow\n\
+                   /App/Sources/Math.c:3:1: runtime error: division by zero\n";
+        let build_log = LogParser::builder()
+            .options(ParseOptions {
+                paths: PathFilter::new().exclude("Pods/**"),
+                ..ParseOptions::default()
+            })
+            .build()
+            .parse_build_log::<MyWarning>(log);
+        assert_eq!(build_log.sanitizer_reports().len(), 2);
+        let paths: Vec<&str> = build_log
+            .diagnostics()
+            .iter()
+            .map(LogFile::absolute_path)
+            .collect();
+        assert_eq!(paths, vec!["/App/Sources/Math.c"]);
+    }
+
+    /// Tests that toolchain crashes in excluded paths are not read as diagnostics.
+    #[test]
+    fn test_build_log_filters_toolchain_crashes() {
+        let log = "error: compile command failed due to signal 11 (use -v to see invocation)\n\
+                   Stack dump:\n\
+                   0.\tProgram arguments: /usr/bin/swift-frontend -frontend -c -primary-file /App/Pods/X/A.swift -o /build/A.o\n\
+                   1.\tApple Swift version 5.9 (swiftlang-5.9.0.128.108 clang-1500.0.40.1)\n";
+        let parse = |options: ParseOptions| {
+            LogParser::builder()
+                .options(options)
+                .build()
+                .parse_build_log::<MyWarning>(log)
+        };
+        assert_eq!(parse(ParseOptions::default()).diagnostics().len(), 1);
+
+        let build_log = parse(ParseOptions {
+            paths: PathFilter::new().exclude("Pods/**"),
+            ..ParseOptions::default()
+        });
+        assert_eq!(build_log.toolchain_crashes().len(), 1);
+        assert!(build_log.diagnostics().is_empty());
+    }
+}
@@ -0,0 +1,286 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::RegexParse;
+
+/// Represents the kind of an `xcodebuild` build step.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BuildStep {
+    /// `CompileSwift`, `SwiftCompile`, `CompileSwiftSources` and `SwiftEmitModule`.
+    CompileSwift,
+    /// `CompileC`, used for C, Objective-C and C++ sources.
+    CompileC,
+    /// `Ld`, linking a binary.
+    Ld,
+    /// `CodeSign`.
+    CodeSign,
+    /// `CopyFile` and the other copy steps (`Copy`, `CpResource`, `CpHeader`, ...).
+    CopyFile,
+    /// `PhaseScriptExecution`, running a script build phase.
+    PhaseScriptExecution,
+    /// `Analyze` and `AnalyzeShallow`, running the Clang Static Analyzer on a source.
+    Analyze,
+    /// `CompileMetalFile` and `MetalLink`, compiling Metal shaders and linking them into a
+    /// `.metallib`.
+    Metal,
+    /// Any other step attributed to a target, holding the step name.
+    Other(String),
+}
+
+impl BuildStep {
+    /// Returns the step matching the name printed by `xcodebuild`, if it is a known one.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - A string slice that holds the step name, such as `CompileSwift`.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self>` - The matching `BuildStep`, otherwise `None`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "CompileSwift" | "SwiftCompile" | "CompileSwiftSources" | "SwiftEmitModule" => {
+                Some(BuildStep::CompileSwift)
+            }
+            "CompileC" => Some(BuildStep::CompileC),
+            "Ld" => Some(BuildStep::Ld),
+            "CodeSign" => Some(BuildStep::CodeSign),
+            "CopyFile" | "Copy" | "CpResource" | "CpHeader" | "CopyPlistFile"
+            | "CopyStringsFile" | "CopySwiftLibs" | "Ditto" => Some(BuildStep::CopyFile),
+            "PhaseScriptExecution" => Some(BuildStep::PhaseScriptExecution),
+            "Analyze" | "AnalyzeShallow" => Some(BuildStep::Analyze),
+            "CompileMetalFile" | "MetalLink" => Some(BuildStep::Metal),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the step, the first of the names matched by
+    /// [`from_name`](Self::from_name), such as `CompileSwift`, or the name of an `Other` step.
+    pub fn name(&self) -> &str {
+        match self {
+            BuildStep::CompileSwift => "CompileSwift",
+            BuildStep::CompileC => "CompileC",
+            BuildStep::Ld => "Ld",
+            BuildStep::CodeSign => "CodeSign",
+            BuildStep::CopyFile => "CopyFile",
+            BuildStep::PhaseScriptExecution => "PhaseScriptExecution",
+            BuildStep::Analyze => "Analyze",
+            BuildStep::Metal => "CompileMetalFile",
+            BuildStep::Other(name) => name,
+        }
+    }
+}
+
+/// Represents the header line `xcodebuild` prints when it starts a build step.
+///
+/// For example `CompileSwift normal arm64 /path/File.swift (in target 'App' from project 'App')`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BuildStepHeader {
+    step: BuildStep,
+    arguments: String,
+    target: Option<String>,
+    project: Option<String>,
+}
+
+impl BuildStepHeader {
+    /// Creates a `BuildStepHeader` from values read from a line of another tool.
+    pub(crate) fn new(
+        step: BuildStep,
+        arguments: String,
+        target: Option<String>,
+        project: Option<String>,
+    ) -> Self {
+        BuildStepHeader {
+            step,
+            arguments,
+            target,
+            project,
+        }
+    }
+
+    /// Returns the kind of the step.
+    pub fn step(&self) -> &BuildStep {
+        &self.step
+    }
+
+    /// Returns the arguments following the step name, such as the compiled file.
+    pub fn arguments(&self) -> &str {
+        &self.arguments
+    }
+
+    /// Returns the name of the target the step belongs to, if printed.
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
+    /// Returns the name of the project the step belongs to, if printed.
+    pub fn project(&self) -> Option<&str> {
+        self.project.as_deref()
+    }
+}
+
+impl RegexParse for BuildStepHeader {
+    /// Returns the regular expression used to parse a build step header.
+    fn regex_value() -> &'static Regex {
+        static REGEX: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(
+                r#"^(?P<name>[A-Z][A-Za-z]+) (?P<arguments>.+?)(?: \(in target '(?P<target>[^']+)' from project '(?P<project>[^']+)'\))?$"#,
+            )
+            .unwrap()
+        });
+        &REGEX
+    }
+
+    /// Creates a new `BuildStepHeader` from the given string using regular expression parsing.
+    ///
+    /// Unknown step names are only accepted when the line names a target, and known ones
+    /// when it names a target or an absolute path, so ordinary log lines starting with a
+    /// step name, such as `Analyze the results`, are not mistaken for steps.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - A string slice that holds the log line to be parsed.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self>` - A `BuildStepHeader` instance if parsing is successful, otherwise `None`.
+    fn new_from_regex(haystack: &str) -> Option<Self> {
+        let cap = &Self::regex_value().captures(haystack.trim_end())?;
+        let name = cap.name("name")?.as_str();
+        let target = cap.name("target").map(|m| m.as_str().to_string());
+        let project = cap.name("project").map(|m| m.as_str().to_string());
+
+        let arguments = cap.name("arguments")?.as_str();
+
+        let step = match BuildStep::from_name(name) {
+            Some(step) if target.is_some() || has_path_argument(arguments) => step,
+            None if target.is_some() => BuildStep::Other(name.to_string()),
+            _ => return None,
+        };
+
+        Some(BuildStepHeader {
+            step,
+            arguments: arguments.to_string(),
+            target,
+            project,
+        })
+    }
+}
+
+/// Returns whether one of the arguments of a build step is an absolute path.
+///
+/// # Arguments
+///
+/// * `arguments` - A string slice that holds the arguments following the step name.
+fn has_path_argument(arguments: &str) -> bool {
+    arguments
+        .split_whitespace()
+        .any(|argument| argument.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests parsing the headers of known steps.
+    #[test]
+    fn test_build_step_header_parse_known_steps() {
+        let header = BuildStepHeader::new_from_regex(
+            "SwiftCompile normal arm64 /src/App/File.swift (in target 'App' from project 'App')",
+        )
+        .unwrap();
+        assert_eq!(header.step(), &BuildStep::CompileSwift);
+        assert_eq!(header.arguments(), "normal arm64 /src/App/File.swift");
+        assert_eq!(header.target(), Some("App"));
+        assert_eq!(header.project(), Some("App"));
+
+        let header = BuildStepHeader::new_from_regex("Ld /build/App.app/App normal arm64").unwrap();
+        assert_eq!(header.step(), &BuildStep::Ld);
+        assert_eq!(header.target(), None);
+
+        let header = BuildStepHeader::new_from_regex(
+            "PhaseScriptExecution Run\\ Script /build/Script-1.sh (in target 'Kit' from project 'Pods')",
+        )
+        .unwrap();
+        assert_eq!(header.step(), &BuildStep::PhaseScriptExecution);
+        assert_eq!(header.project(), Some("Pods"));
+    }
+
+    /// Tests that unknown steps need a target to be recognized.
+    #[test]
+    fn test_build_step_header_parse_unknown_steps() {
+        let header = BuildStepHeader::new_from_regex(
+            "ProcessInfoPlistFile /build/Info.plist /src/Info.plist (in target 'App' from project 'App')",
+        )
+        .unwrap();
+        assert_eq!(
+            header.step(),
+            &BuildStep::Other("ProcessInfoPlistFile".to_string())
+        );
+
+        assert!(BuildStepHeader::new_from_regex("Build settings from command line:").is_none());
+        assert!(BuildStepHeader::new_from_regex("/src/File.swift:1:2: error: oops").is_none());
+    }
+
+    /// Tests that known step names need a target or a path to be recognized.
+    #[test]
+    fn test_build_step_header_parse_plain_lines() {
+        assert!(BuildStepHeader::new_from_regex("Ld failed, see the log above").is_none());
+        assert!(BuildStepHeader::new_from_regex("CpResource skipped for Info.plist").is_none());
+        assert!(BuildStepHeader::new_from_regex("Analyze the results before merging").is_none());
+
+        let log =
+            "CompileSwift normal arm64 /src/App/File.swift (in target 'App' from project 'App')\n\
+                   Analyze the results before merging\n\
+                   /src/App/File.swift:1:2: warning: unused\n";
+        let build_log = crate::BuildLog::<crate::MyWarning>::parse(log);
+        assert_eq!(build_log.build_steps().len(), 1);
+        let steps: Vec<_> = build_log
+            .diagnostics_with_build_steps()
+            .map(|(_, step)| step.map(BuildStepHeader::step))
+            .collect();
+        assert_eq!(steps, vec![Some(&BuildStep::CompileSwift)]);
+    }
+
+    /// Tests attributing Metal shader diagnostics and `air-lld` errors to the Metal steps.
+    #[test]
+    fn test_build_log_metal() {
+        let log = "CompileMetalFile /src/Shaders.metal (in target 'App' from project 'App')\n\
+                   /src/Shaders.metal:12:9: warning: unused variable 'scale' [-Wunused-variable]\n\
+                   In file included from /src/Shaders.metal:3:\n\
+                   /src/Common.h:4:1: error: unknown type name 'float5'\n\
+                   MetalLink /build/App.app/default.metallib (in target 'App' from project 'App')\n\
+                   air-lld: error: undefined symbol: shade(float4)\n\
+                   >>> referenced by /build/App.build/Metal/Shaders.air\n\
+                   metallib: error: linker command failed with exit code 1 (use -v to see invocation)\n";
+        let build_log = crate::BuildLog::<crate::MyWarning>::parse(log);
+
+        let diagnostics: Vec<_> = build_log
+            .diagnostics_with_build_steps()
+            .map(|(log_file, step)| {
+                let code_fragment = log_file.code_fragment().unwrap();
+                (
+                    log_file.absolute_path(),
+                    code_fragment.flag(),
+                    step.map(BuildStepHeader::step),
+                )
+            })
+            .collect();
+        assert_eq!(
+            diagnostics,
+            vec![
+                (
+                    "/src/Shaders.metal",
+                    Some("-Wunused-variable"),
+                    Some(&BuildStep::Metal)
+                ),
+                ("/src/Common.h", None, Some(&BuildStep::Metal)),
+            ]
+        );
+        assert_eq!(build_log.build_steps()[1].step(), &BuildStep::Metal);
+        let linker_errors = build_log.linker_errors();
+        assert_eq!(linker_errors.len(), 1);
+        assert_eq!(linker_errors[0].architecture(), "air64");
+        assert_eq!(linker_errors[0].targets(), ["App"]);
+    }
+}
@@ -0,0 +1,15 @@
+use serde::Serialize;
+
+/// The kind of tool that reported a diagnostic, when it is not the compiler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    /// The Clang Static Analyzer, run by `xcodebuild analyze` or as clang-tidy checks.
+    Analyzer,
+    /// A linter, such as SwiftLint.
+    Lint,
+    /// `actool`, compiling asset catalogs.
+    AssetCatalog,
+    /// `ibtool`, compiling storyboards and XIBs.
+    InterfaceBuilder,
+}
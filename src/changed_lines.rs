@@ -0,0 +1,229 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::{LogFile, TaskMessage};
+
+/// Matches the header of a hunk and captures the first line and the length of each side.
+static HUNK_HEADER_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^@@ -\d+(?:,(\d+))? \+(\d+)(?:,(\d+))? @@"#).unwrap());
+
+/// Represents the lines added or modified by a unified diff, such as the output of
+/// `git diff origin/main`, by file path relative to the root of the repository, or
+/// resolved against it once the [root](Self::with_root) is set.
+///
+/// ```
+/// use xcode_log_parser::ChangedLines;
+///
+/// let diff = "+++ b/Sources/A.swift\n@@ -1,2 +1,2 @@\n let a = 1\n-let b = 2\n+let b = 3\n";
+/// let changed_lines = ChangedLines::parse(diff).with_root("/src/App");
+/// assert!(changed_lines.contains("/src/App/Sources/A.swift", 2));
+/// assert!(!changed_lines.contains("/src/App/Sources/A.swift", 1));
+/// assert!(!changed_lines.contains("/src/Kit/Sources/A.swift", 2));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangedLines {
+    files: HashMap<PathBuf, HashSet<usize>>,
+    /// Whether the paths were resolved against the root of the repository.
+    rooted: bool,
+}
+
+impl ChangedLines {
+    /// Reads the lines of the new side of each file added by a unified diff. A modified line
+    /// is removed and added again, so it is among them. Deleted files are left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `diff` - The unified diff, with the `a/` and `b/` prefixes of git or without them.
+    pub fn parse(diff: &str) -> Self {
+        let mut files: HashMap<PathBuf, HashSet<usize>> = HashMap::new();
+        let mut path: Option<String> = None;
+        let (mut line, mut old_remaining, mut new_remaining): (usize, usize, usize) = (0, 0, 0);
+        for diff_line in diff.lines() {
+            // The lines of a hunk are read by count, since a removed `-- x` line would
+            // otherwise be taken for the header of the next file.
+            if old_remaining > 0 || new_remaining > 0 {
+                match diff_line.as_bytes().first() {
+                    Some(b'+') => {
+                        if let Some(path) = &path {
+                            files.entry(path.into()).or_default().insert(line);
+                        }
+                        line += 1;
+                        new_remaining = new_remaining.saturating_sub(1);
+                    }
+                    Some(b'-') => old_remaining = old_remaining.saturating_sub(1),
+                    Some(b'\\') => {}
+                    _ => {
+                        line += 1;
+                        old_remaining = old_remaining.saturating_sub(1);
+                        new_remaining = new_remaining.saturating_sub(1);
+                    }
+                }
+            } else if let Some(new_path) = diff_line.strip_prefix("+++ ") {
+                let new_path = new_path.split('\t').next().unwrap_or(new_path);
+                path = (new_path != "/dev/null")
+                    .then(|| new_path.strip_prefix("b/").unwrap_or(new_path).to_string());
+            } else if let Some(captures) = HUNK_HEADER_REGEX.captures(diff_line) {
+                let count = |index| captures.get(index).map_or(Ok(1), |m| m.as_str().parse());
+                old_remaining = count(1).unwrap_or(0);
+                line = captures[2].parse().unwrap_or(0);
+                new_remaining = count(3).unwrap_or(0);
+            }
+        }
+        ChangedLines {
+            files,
+            rooted: false,
+        }
+    }
+
+    /// Resolves the paths of the diff against the root of the repository, so that each
+    /// path is looked up exactly rather than by the relative path it ends with.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The absolute path of the root of the repository, such as `/src/App`.
+    pub fn with_root(self, root: impl AsRef<Path>) -> Self {
+        let root = root.as_ref();
+        ChangedLines {
+            files: self
+                .files
+                .into_iter()
+                .map(|(path, lines)| (root.join(path), lines))
+                .collect(),
+            rooted: true,
+        }
+    }
+
+    /// Returns the paths of the files with added lines, relative to the root of the
+    /// repository, or absolute once the [root](Self::with_root) is set.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.keys().map(PathBuf::as_path)
+    }
+
+    /// Returns whether a line was added or modified by the diff.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file. Once the [root](Self::with_root) is set, it must be
+    ///   the path of a file of the diff. Otherwise an absolute path matches the relative path
+    ///   of the diff it ends with, so `/src/App/Sources/A.swift` matches `Sources/A.swift`.
+    /// * `line` - The line number, starting at 1.
+    pub fn contains(&self, path: &str, line: usize) -> bool {
+        if self.rooted {
+            return self
+                .files
+                .get(Path::new(path))
+                .is_some_and(|lines| lines.contains(&line));
+        }
+        self.files.iter().any(|(changed_path, lines)| {
+            lines.contains(&line) && Path::new(path).ends_with(changed_path)
+        })
+    }
+
+    /// Returns whether a diagnostic is on a line added or modified by the diff, such as to
+    /// filter diagnostics one at a time while they are streamed. Diagnostics without a line
+    /// are not.
+    ///
+    /// # Arguments
+    ///
+    /// * `log_file` - The diagnostic.
+    pub fn covers<T: TaskMessage>(&self, log_file: &LogFile<T>) -> bool {
+        log_file.code_fragment().is_some_and(|code_fragment| {
+            self.contains(log_file.absolute_path(), code_fragment.line())
+        })
+    }
+}
+
+/// Keeps the diagnostics on the lines added or modified by a unified diff, such as
+/// `git diff origin/main`, to gate a pull request on the diagnostics it introduces.
+///
+/// Diagnostics without a line, such as the warnings about a whole project, are dropped.
+///
+/// # Arguments
+///
+/// * `diagnostics` - The parsed diagnostics, such as [`BuildLog::into_diagnostics`](crate::BuildLog::into_diagnostics).
+/// * `diff` - The unified diff.
+///
+/// # Returns
+///
+/// * `Vec<LogFile<T>>` - The diagnostics on changed lines, in the order of the diagnostics.
+pub fn filter_by_diff<T: TaskMessage>(
+    diagnostics: impl IntoIterator<Item = LogFile<T>>,
+    diff: &str,
+) -> Vec<LogFile<T>> {
+    let changed_lines = ChangedLines::parse(diff);
+    diagnostics
+        .into_iter()
+        .filter(|log_file| changed_lines.covers(log_file))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_log, MyWarning};
+
+    /// Tests keeping the diagnostics on added and modified lines only.
+    #[test]
+    fn test_filter_by_diff() {
+        let diff = "diff --git a/Sources/A.swift b/Sources/A.swift\n\
+                    index 1234567..89abcde 100644\n\
+                    --- a/Sources/A.swift\n\
+                    +++ b/Sources/A.swift\n\
+                    @@ -1,3 +1,3 @@\n \
+                    let a = 1\n\
+                    --- let b = 2\n\
+                    +let b = 3\n \
+                    let c = 4\n\
+                    @@ -10 +10,2 @@\n \
+                    func f() {\n\
+                    +    let x = 1\n\
+                    diff --git a/Sources/Old.swift b/Sources/Old.swift\n\
+                    deleted file mode 100644\n\
+                    --- a/Sources/Old.swift\n\
+                    +++ /dev/null\n\
+                    @@ -1 +0,0 @@\n\
+                    -let old = 1\n";
+        let log = "/src/App/Sources/A.swift:2:5: warning: variable 'b' was never mutated\n\
+                   /src/App/Sources/A.swift:3:5: warning: unused variable 'c'\n\
+                   /src/App/Sources/A.swift:11:9: warning: unused variable 'x'\n\
+                   /src/App/MySources/A.swift:2:5: warning: unused variable 'b'\n\
+                   /src/App/Sources/Old.swift:1:5: warning: unused variable 'old'\n";
+        let diagnostics = filter_by_diff(parse_log::<MyWarning>(log), diff);
+
+        let lines: Vec<_> = diagnostics
+            .iter()
+            .map(|log_file| log_file.code_fragment().unwrap().line())
+            .collect();
+        assert_eq!(lines, vec![2, 11]);
+        assert_eq!(
+            ChangedLines::parse(diff).paths().collect::<Vec<_>>(),
+            vec![Path::new("Sources/A.swift")]
+        );
+    }
+
+    /// Tests that files sharing a relative suffix are told apart once the root is set.
+    #[test]
+    fn test_changed_lines_with_root() {
+        let diff = "+++ b/ModuleB/View.swift\n\
+                    @@ -1 +1 @@\n\
+                    -let a = 1\n\
+                    +let a = 2\n";
+        let changed_lines = ChangedLines::parse(diff);
+        assert!(changed_lines.contains("/src/App/ModuleB/View.swift", 1));
+        assert!(changed_lines.contains("/src/Other/ModuleB/View.swift", 1));
+        assert!(!changed_lines.contains("/src/App/ModuleA/View.swift", 1));
+
+        let changed_lines = changed_lines.with_root("/src/App/");
+        assert_eq!(
+            changed_lines.paths().collect::<Vec<_>>(),
+            vec![Path::new("/src/App/ModuleB/View.swift")]
+        );
+        assert!(changed_lines.contains("/src/App/ModuleB/View.swift", 1));
+        assert!(!changed_lines.contains("/src/App/ModuleB/View.swift", 2));
+        assert!(!changed_lines.contains("/src/Other/ModuleB/View.swift", 1));
+        assert!(!changed_lines.contains("ModuleB/View.swift", 1));
+    }
+}
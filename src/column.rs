@@ -0,0 +1,117 @@
+use serde::Serialize;
+
+/// The column of a diagnostic counted in the units used by different tools.
+///
+/// Clang and the Swift compiler report 1-based columns counted in UTF-8 bytes, so the
+/// column following `🐶` is 4 more than the one before it. Editors count characters,
+/// or, like the Language Server Protocol, UTF-16 code units, in which `🐶` counts 2.
+///
+/// ```
+/// use xcode_log_parser::Columns;
+///
+/// // `bar` starts at byte 18 of the line, reported as column 18.
+/// let columns = Columns::from_byte_column("let 🐶 = \"ü\"; bar()", 18);
+/// assert_eq!((columns.byte(), columns.character(), columns.utf16()), (18, 14, 15));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct Columns {
+    byte: usize,
+    character: usize,
+    utf16: usize,
+}
+
+impl Columns {
+    /// Converts a column reported by the compiler, using the source line it points into.
+    ///
+    /// A column inside a multi-byte character points at that character. Past the end
+    /// of the line, each byte counts as one character.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_line` - The source line of the diagnostic, without its line break.
+    /// * `column` - The 1-based column reported by the compiler, in UTF-8 bytes.
+    pub fn from_byte_column(source_line: &str, column: usize) -> Self {
+        let offset = column.saturating_sub(1);
+        let mut characters = 0;
+        let mut utf16 = 0;
+        let mut end = 0;
+        for (index, character) in source_line.char_indices() {
+            if index + character.len_utf8() > offset {
+                break;
+            }
+            characters += 1;
+            utf16 += character.len_utf16();
+            end = index + character.len_utf8();
+        }
+        if offset > source_line.len() {
+            characters += offset - end;
+            utf16 += offset - end;
+        }
+        Columns {
+            byte: column,
+            character: characters + 1,
+            utf16: utf16 + 1,
+        }
+    }
+
+    /// Returns the 1-based column in UTF-8 bytes, as reported by the compiler.
+    pub fn byte(&self) -> usize {
+        self.byte
+    }
+
+    /// Returns the 1-based column in characters, as counted by most editors.
+    pub fn character(&self) -> usize {
+        self.character
+    }
+
+    /// Returns the 1-based column in UTF-16 code units, as counted by the Language Server
+    /// Protocol and by `NSString`.
+    pub fn utf16(&self) -> usize {
+        self.utf16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{attach_snippets, parse_log, BuildLog, MyWarning};
+
+    /// Tests converting columns after emoji and multi-byte identifiers, from the excerpt
+    /// printed in the log and from the source file.
+    #[test]
+    fn test_code_fragment_columns() {
+        let columns = |line: &str, column| {
+            let columns = Columns::from_byte_column(line, column);
+            (columns.character(), columns.utf16())
+        };
+        assert_eq!(columns("let x = 1", 5), (5, 5));
+        assert_eq!(columns("let café = 1", 11), (10, 10));
+        assert_eq!(columns("let 🐶 = 1", 10), (7, 8));
+        // Inside the emoji, and past the end of the line.
+        assert_eq!(columns("let 🐶 = 1", 6), (5, 5));
+        assert_eq!(columns("🐶", 7), (4, 5));
+
+        let log = "/src/A.swift:1:18: error: cannot find 'bar' in scope\n\
+                   let 🐶 = \"ü\"; bar()\n\
+                   \x20             ^~~\n";
+        let build_log = BuildLog::<MyWarning>::parse(log);
+        let code_fragment = build_log.diagnostics()[0].code_fragment().unwrap();
+        assert_eq!(code_fragment.column(), 18);
+        assert_eq!(code_fragment.columns().unwrap().character(), 14);
+
+        let path = std::env::temp_dir().join("xcode_log_parser_columns.swift");
+        std::fs::write(&path, "import Foundation\nlet naïve = 🐶()\n").unwrap();
+        let mut diagnostics =
+            parse_log::<MyWarning>(&format!("{}:2:18: warning: x\n", path.display()));
+        assert!(diagnostics[0].code_fragment().unwrap().columns().is_none());
+        attach_snippets(&mut diagnostics, 0);
+        std::fs::remove_file(&path).unwrap();
+        let code_fragment = diagnostics[0].code_fragment().unwrap();
+        let columns = code_fragment.columns().unwrap();
+        assert_eq!((columns.character(), columns.utf16()), (14, 15));
+        assert_eq!(
+            code_fragment.snippet().unwrap().caret(),
+            format!("{}^", " ".repeat(13))
+        );
+    }
+}
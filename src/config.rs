@@ -0,0 +1,403 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{Budget, ParseOptions, PathFilter, PayloadDelimiters, PayloadFormat, Policy, Severity};
+
+/// The name of the configuration file looked up by [`Config::discover`].
+pub const CONFIG_FILE_NAME: &str = ".xcodelogparser.toml";
+
+/// Matches a `${NAME}` reference to an environment variable.
+static ENV_VARIABLE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"\$\{([A-Za-z_][A-Za-z0-9_]*)\}"#).unwrap());
+
+/// Reports why a configuration file could not be loaded.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    /// The file could not be read.
+    #[error("cannot read {path}: {reason}")]
+    Io { path: String, reason: String },
+    /// The file is not valid TOML or holds unexpected settings.
+    #[error("invalid configuration: {reason}")]
+    Invalid { reason: String },
+    /// A `${NAME}` reference names an environment variable that is not set.
+    #[error("environment variable {name} is not set")]
+    MissingVariable { name: String },
+}
+
+/// Settings shared by the library and the command line tool, read from a
+/// `.xcodelogparser.toml` file.
+///
+/// Every section and setting is optional. `${NAME}` in any string is replaced by the
+/// environment variable `NAME`, so credentials can stay out of the file. The strings of
+/// an `[integrations.<name>]` section are only replaced once it is read with
+/// [`Config::integration`], so a variable missing for an unused integration is not an error.
+///
+/// ```toml
+/// [filters]
+/// exclude = ["Pods/**"]
+/// min_severity = "warning"
+///
+/// [thresholds]
+/// max_warnings = 100
+///
+/// [thresholds.budgets]
+/// "Modules/Legacy/**" = 40
+///
+/// [payload]
+/// open = "TASK"
+/// close = "TASK"
+/// format = "json"
+///
+/// [output]
+/// format = "github"
+///
+/// [integrations.github]
+/// repository = "octo/app"
+/// token = "${GITHUB_TOKEN}"
+/// ```
+///
+/// ```
+/// use xcode_log_parser::{Config, LogParser, MyWarning};
+///
+/// let config: Config = "[filters]\nexclude = [\"Pods/**\"]".parse().unwrap();
+/// let parser = LogParser::builder().config(&config).build();
+/// assert!(parser
+///     .parse_line::<MyWarning>("/App/Pods/A.swift:1:2: warning: unused")
+///     .is_none());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The diagnostics kept.
+    pub filters: FilterConfig,
+    /// The thresholds of the quality gate.
+    pub thresholds: ThresholdConfig,
+    /// The markers and format of the task payload.
+    pub payload: PayloadConfig,
+    /// The output of the command line tool.
+    pub output: OutputConfig,
+    /// The settings of each integration, by name, read with [`Config::integration`].
+    pub integrations: BTreeMap<String, toml::Table>,
+}
+
+/// The `[filters]` section of a [`Config`], turned into [`ParseOptions`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FilterConfig {
+    /// Keeps only diagnostics in paths matching one of these glob patterns.
+    pub include: Vec<String>,
+    /// Drops diagnostics in paths matching one of these glob patterns.
+    pub exclude: Vec<String>,
+    /// Keeps only diagnostics enabled by one of these warning flags.
+    pub flags: Vec<String>,
+    /// Keeps diagnostics at least as severe as this one.
+    pub min_severity: Option<Severity>,
+    /// Keeps only diagnostics of these severities.
+    pub kinds: Option<Vec<Severity>>,
+    /// The JSON [`Suppressions`](crate::Suppressions) file applied by the command line tool.
+    pub suppressions: Option<PathBuf>,
+}
+
+/// The `[thresholds]` section of a [`Config`], turned into a [`Policy`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThresholdConfig {
+    /// The maximum number of warnings allowed.
+    pub max_warnings: Option<usize>,
+    /// The maximum number of errors allowed.
+    pub max_errors: Option<usize>,
+    /// Fails when a diagnostic is missing from the baseline.
+    pub fail_on_new: bool,
+    /// The maximum number of warnings allowed in the files matching each glob pattern,
+    /// set in a `[thresholds.budgets]` table.
+    pub budgets: BTreeMap<String, usize>,
+}
+
+/// The `[payload]` section of a [`Config`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PayloadConfig {
+    /// The marker placed before the payload, `s#` by default.
+    pub open: Option<String>,
+    /// The marker placed after the payload, `#s` by default.
+    pub close: Option<String>,
+    /// The format of the payload, `json`, `yaml` or `toml`.
+    pub format: Option<PayloadFormat>,
+}
+
+/// The `[output]` section of a [`Config`], read by the command line tool.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputConfig {
+    /// The name of the output format, such as `github`.
+    pub format: Option<String>,
+    /// The Handlebars template rendering each diagnostic.
+    pub template: Option<PathBuf>,
+}
+
+impl Config {
+    /// Reads a configuration file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the TOML file.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, ConfigError>` - The configuration, or the reason it could not be read.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        std::fs::read_to_string(path)
+            .map_err(|error| ConfigError::Io {
+                path: path.display().to_string(),
+                reason: error.to_string(),
+            })?
+            .parse()
+    }
+
+    /// Returns the closest `.xcodelogparser.toml` in the directory or its ancestors.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The directory the search starts from.
+    pub fn find(start: &Path) -> Option<PathBuf> {
+        start
+            .ancestors()
+            .map(|directory| directory.join(CONFIG_FILE_NAME))
+            .find(|path| path.is_file())
+    }
+
+    /// Reads the closest `.xcodelogparser.toml` found walking up from the current directory.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Self>, ConfigError>` - The configuration, `None` if there is no file,
+    ///   or the reason it could not be read.
+    pub fn discover() -> Result<Option<Self>, ConfigError> {
+        let current_dir = std::env::current_dir().map_err(|error| ConfigError::Io {
+            path: ".".to_string(),
+            reason: error.to_string(),
+        })?;
+        Self::find(&current_dir).map(Self::load).transpose()
+    }
+
+    /// Returns the options selecting the diagnostics kept, from the `[filters]` section.
+    pub fn parse_options(&self) -> ParseOptions {
+        let filters = &self.filters;
+        let mut paths = PathFilter::new();
+        for pattern in &filters.include {
+            paths = paths.include(pattern.clone());
+        }
+        for pattern in &filters.exclude {
+            paths = paths.exclude(pattern.clone());
+        }
+        ParseOptions {
+            min_severity: filters.min_severity,
+            kinds: filters.kinds.clone(),
+            paths,
+            flags: (!filters.flags.is_empty()).then(|| filters.flags.clone()),
+        }
+    }
+
+    /// Returns the payload markers, if the `[payload]` section sets either of them.
+    pub fn payload_delimiters(&self) -> Option<PayloadDelimiters> {
+        let PayloadConfig { open, close, .. } = &self.payload;
+        if open.is_none() && close.is_none() {
+            return None;
+        }
+        let defaults = PayloadDelimiters::default();
+        Some(PayloadDelimiters::new(
+            open.as_deref().unwrap_or(defaults.open()),
+            close.as_deref().unwrap_or(defaults.close()),
+        ))
+    }
+
+    /// Returns the quality gate of the `[thresholds]` section.
+    pub fn policy(&self) -> Policy {
+        Policy {
+            max_warnings: self.thresholds.max_warnings,
+            max_errors: self.thresholds.max_errors,
+            fail_on_new: self.thresholds.fail_on_new,
+            budgets: self
+                .thresholds
+                .budgets
+                .iter()
+                .map(|(pattern, max_warnings)| Budget::new(pattern.clone(), *max_warnings))
+                .collect(),
+        }
+    }
+
+    /// Reads the `[integrations.<name>]` section into the configuration of an integration,
+    /// such as `GitHubConfig`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the section, such as `github`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<C>, ConfigError>` - The configuration, `None` if the section is missing,
+    ///   or the reason it does not match the configuration type, such as an environment
+    ///   variable that is not set.
+    pub fn integration<C: DeserializeOwned>(&self, name: &str) -> Result<Option<C>, ConfigError> {
+        self.integrations
+            .get(name)
+            .map(|table| {
+                let mut value = toml::Value::Table(table.clone());
+                interpolate(&mut value)?;
+                value
+                    .try_into()
+                    .map_err(|error: toml::de::Error| ConfigError::Invalid {
+                        reason: format!("[integrations.{}]: {}", name, error.message()),
+                    })
+            })
+            .transpose()
+    }
+}
+
+impl FromStr for Config {
+    type Err = ConfigError;
+
+    /// Parses the TOML text of a configuration, replacing `${NAME}` references first,
+    /// except in the integration sections.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = |error: toml::de::Error| ConfigError::Invalid {
+            reason: error.message().to_string(),
+        };
+        let mut value: toml::Value = toml::from_str(text).map_err(invalid)?;
+        if let toml::Value::Table(sections) = &mut value {
+            sections
+                .iter_mut()
+                .filter(|(name, _)| *name != "integrations")
+                .try_for_each(|(_, section)| interpolate(section))?;
+        }
+        value.try_into().map_err(invalid)
+    }
+}
+
+/// Replaces `${NAME}` references in every string of the value by environment variables.
+fn interpolate(value: &mut toml::Value) -> Result<(), ConfigError> {
+    match value {
+        toml::Value::String(text) => {
+            let mut missing = None;
+            let replaced = ENV_VARIABLE_REGEX.replace_all(text, |captures: &Captures| {
+                std::env::var(&captures[1]).unwrap_or_else(|_| {
+                    missing.get_or_insert_with(|| captures[1].to_string());
+                    String::new()
+                })
+            });
+            if let Some(name) = missing {
+                return Err(ConfigError::MissingVariable { name });
+            }
+            *text = replaced.into_owned();
+        }
+        toml::Value::Array(values) => values.iter_mut().try_for_each(interpolate)?,
+        toml::Value::Table(table) => table
+            .iter_mut()
+            .try_for_each(|(_, value)| interpolate(value))?,
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests reading every section, with environment variables in strings.
+    #[test]
+    fn test_config_from_str() {
+        std::env::set_var("XCODE_LOG_PARSER_TEST_TOKEN", "secret");
+        let config: Config = r#"
+            [filters]
+            exclude = ["Pods/**"]
+            min_severity = "warning"
+
+            [thresholds]
+            max_warnings = 10
+            budgets = { "Modules/Legacy/**" = 40 }
+
+            [payload]
+            open = "TASK"
+            format = "json"
+
+            [output]
+            format = "github"
+
+            [integrations.slack]
+            token = "Bearer ${XCODE_LOG_PARSER_TEST_TOKEN}"
+        "#
+        .parse()
+        .unwrap();
+
+        let options = config.parse_options();
+        assert_eq!(options.min_severity, Some(Severity::Warning));
+        assert!(!options.paths.matches("/App/Pods/A.swift"));
+        assert_eq!(config.policy().max_warnings, Some(10));
+        assert_eq!(
+            config.policy().budgets,
+            vec![Budget::new("Modules/Legacy/**", 40)]
+        );
+        assert_eq!(
+            config.payload_delimiters(),
+            Some(PayloadDelimiters::new("TASK", "#s"))
+        );
+        assert_eq!(config.payload.format, Some(PayloadFormat::Json));
+        assert_eq!(config.output.format.as_deref(), Some("github"));
+
+        #[derive(Deserialize)]
+        struct Slack {
+            token: String,
+        }
+        let slack: Slack = config.integration("slack").unwrap().unwrap();
+        assert_eq!(slack.token, "Bearer secret");
+        assert!(config.integration::<Slack>("jira").unwrap().is_none());
+
+        assert!(matches!(
+            "[output]\ntemplate = \"${XCODE_LOG_PARSER_TEST_UNSET}\"".parse::<Config>(),
+            Err(ConfigError::MissingVariable { name }) if name == "XCODE_LOG_PARSER_TEST_UNSET"
+        ));
+        assert!(matches!(
+            "[filter]".parse::<Config>(),
+            Err(ConfigError::Invalid { .. })
+        ));
+    }
+
+    /// Tests that a variable missing from an integration section only fails reading that section.
+    #[test]
+    fn test_config_integration_missing_variable() {
+        let config: Config = r#"
+            [filters]
+            exclude = ["Pods/**"]
+
+            [integrations.github]
+            token = "${XCODE_LOG_PARSER_TEST_UNSET_TOKEN}"
+        "#
+        .parse()
+        .unwrap();
+
+        assert_eq!(config.filters.exclude, ["Pods/**"]);
+        assert!(matches!(
+            config.integration::<toml::Table>("github"),
+            Err(ConfigError::MissingVariable { name }) if name == "XCODE_LOG_PARSER_TEST_UNSET_TOKEN"
+        ));
+    }
+
+    /// Tests finding the configuration file in a parent directory.
+    #[test]
+    fn test_config_find() {
+        let root = std::env::temp_dir().join("xcode_log_parser_config_find");
+        let nested = root.join("App/Sources");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(root.join(CONFIG_FILE_NAME), "").unwrap();
+        assert_eq!(Config::find(&nested), Some(root.join(CONFIG_FILE_NAME)));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
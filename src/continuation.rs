@@ -0,0 +1,44 @@
+/// Represents the lines clang and the Swift compiler print after a diagnostic:
+/// the offending source line, a caret line marking the range, and an optional fix-it.
+///
+/// ```text
+/// /src/B.m:3:10: error: use of undeclared identifier 'fo'; did you mean 'foo'?
+///     return fo;
+///            ^~
+///            foo
+/// ```
+#[derive(Debug, PartialEq)]
+pub(crate) struct Continuation<'a> {
+    pub(crate) source_excerpt: &'a str,
+    pub(crate) caret: &'a str,
+    pub(crate) fixit: Option<&'a str>,
+}
+
+/// Returns the byte offset of the first range marker if the line is a caret line.
+///
+/// A caret line only holds whitespace, `~` range markers and at least one `^`.
+pub(crate) fn caret_start(line: &str) -> Option<usize> {
+    let line = line.trim_end();
+    let is_caret_line = line.contains('^')
+        && line
+            .chars()
+            .all(|character| matches!(character, ' ' | '\t' | '~' | '^'));
+    if !is_caret_line {
+        return None;
+    }
+    line.find(['~', '^'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests reading where the range markers of a caret line start.
+    #[test]
+    fn test_caret_start() {
+        assert_eq!(caret_start("           ^~"), Some(11));
+        assert_eq!(caret_start("\t  ~~^~~  "), Some(3));
+        assert_eq!(caret_start("    let x = 1"), None);
+        assert_eq!(caret_start("   ~~~"), None);
+    }
+}
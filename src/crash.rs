@@ -0,0 +1,317 @@
+use std::path::Path;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::{CodeFragment, LogFile, Severity, TaskMessage};
+
+/// Matches the error the driver prints when a frontend crashes, such as
+/// `<unknown>:0: error: compile command failed due to signal 11 (use -v to see invocation)`
+/// or `clang: error: clang frontend command failed due to signal (use -v to see invocation)`.
+static FAILED_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"^(?:(?P<driver>[\w-]+): )?(?:\S+:\d+: )?error: (?:[\w-]+ )*command failed due to signal(?: (?P<signal>\d+))?"#,
+    )
+    .unwrap()
+});
+
+/// Matches the error the driver prints when a frontend is killed, such as
+/// `clang: error: unable to execute command: Segmentation fault: 11`.
+static UNABLE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^(?:(?P<driver>[\w-]+): )?(?:\S+:\d+: )?error: unable to execute command: (?P<signal>.+)$"#)
+        .unwrap()
+});
+
+/// Matches the line the shell prints for a process killed by a signal, such as `Segmentation fault: 11`.
+static SIGNAL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^(?:Segmentation fault|Bus error|Illegal instruction|Abort trap|Trace/BPT trap|Killed): \d+$"#)
+        .unwrap()
+});
+
+/// Matches an entry of the `Stack dump:` block, such as `3. While type-checking 'Foo' (at /src/A.swift:10:1)`.
+static STACK_ENTRY_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^\d+\.\s+(?P<entry>.+)$"#).unwrap());
+
+/// Matches a frame of the backtrace printed after the stack dump, such as
+/// `0  swift-frontend 0x0000000107b0f6e8 llvm::sys::PrintStackTrace(llvm::raw_ostream&, int) + 56`.
+static BACKTRACE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^\d+\s+\S+\s+0x[0-9a-fA-F]+"#).unwrap());
+
+/// Matches a source location in a stack dump entry.
+static LOCATION_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?P<path>/[^\s:"]+):(?P<line>\d+):(?P<column>\d+)"#).unwrap());
+
+/// The extensions of the sources a frontend compiles.
+const SOURCE_EXTENSIONS: [&str; 7] = ["swift", "c", "m", "mm", "cc", "cpp", "cxx"];
+
+/// Represents a crash of `swift-frontend`, `clang` or another tool of the toolchain.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ToolchainCrash {
+    tool: Option<String>,
+    signal: Option<String>,
+    file: Option<String>,
+    command: Option<String>,
+    stack: Vec<String>,
+    /// The first source location named by the stack dump.
+    location: Option<(String, usize, usize)>,
+}
+
+impl ToolchainCrash {
+    /// Returns the name of the tool that crashed, such as `swift-frontend` or `clang`, if printed.
+    pub fn tool(&self) -> Option<&str> {
+        self.tool.as_deref()
+    }
+
+    /// Returns the signal that killed the tool, such as `Segmentation fault: 11` or `signal 6`,
+    /// if printed.
+    pub fn signal(&self) -> Option<&str> {
+        self.signal.as_deref()
+    }
+
+    /// Returns the source file being compiled, read from the `-primary-file` of the command
+    /// or its last source argument, if the log contains a stack dump.
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
+    /// Returns the command that crashed, which reproduces the crash when run again, if the
+    /// log contains a stack dump.
+    pub fn command(&self) -> Option<&str> {
+        self.command.as_deref()
+    }
+
+    /// Returns the entries of the stack dump after the command, describing what the tool was
+    /// doing, such as `While type-checking 'Foo' (at /src/A.swift:10:1)`.
+    pub fn stack(&self) -> &[String] {
+        &self.stack
+    }
+
+    /// Converts the crash into an error diagnostic.
+    ///
+    /// It points at the first source location of the stack dump, or else at line 0 of the
+    /// crashing file, as the compiler does for errors without a location.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<LogFile<T>>` - The diagnostic, or `None` if the crashing file is unknown.
+    pub fn to_log_file<T: TaskMessage>(&self) -> Option<LogFile<T>> {
+        let (path, line, column) = match &self.location {
+            Some(location) => location.clone(),
+            None => (self.file.clone()?, 0, 0),
+        };
+        let mut text = format!("{} crashed", self.tool.as_deref().unwrap_or("The compiler"));
+        if let Some(signal) = &self.signal {
+            text.push_str(&format!(" ({})", signal));
+        }
+        Some(LogFile::new(
+            path,
+            Some(CodeFragment::new(line, column, Some(Severity::Error), text)),
+        ))
+    }
+
+    /// Reads the tool and the crashing file from the `Program arguments:` of a stack dump.
+    fn set_command(&mut self, command: &str) {
+        let arguments: Vec<&str> = command.split_whitespace().collect();
+        if let Some(program) = arguments.first() {
+            self.tool = Path::new(program)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned());
+        }
+        let primary_file = arguments
+            .iter()
+            .position(|&argument| argument == "-primary-file")
+            .and_then(|index| arguments.get(index + 1));
+        let source = arguments.iter().rev().find(|argument| {
+            Path::new(argument)
+                .extension()
+                .is_some_and(|extension| SOURCE_EXTENSIONS.contains(&&*extension.to_string_lossy()))
+        });
+        self.file = primary_file.or(source).map(|file| file.to_string());
+        self.command = Some(command.to_string());
+    }
+}
+
+/// A stateful parser for the crash reports of `swift-frontend` and `clang`.
+///
+/// ```text
+/// error: compile command failed due to signal 11 (use -v to see invocation)
+/// Please submit a bug report (https://swift.org/contributing/#reporting-bugs) and include the crash backtrace.
+/// Stack dump:
+/// 0. Program arguments: /usr/bin/swift-frontend -frontend -c -primary-file /src/A.swift -o /build/A.o
+/// 1. Apple Swift version 5.9 (swiftlang-5.9.0.128.108 clang-1500.0.40.1)
+/// 2. While type-checking 'Foo' (at /src/A.swift:10:1)
+/// Stack dump without symbol names (ensure you have llvm-symbolizer in your PATH ...):
+/// 0  swift-frontend           0x0000000107b0f6e8 llvm::sys::PrintStackTrace(llvm::raw_ostream&, int) + 56
+/// ```
+///
+/// Lines are fed one at a time with [`push_line`](Self::push_line), and the crashes
+/// collected so far are returned by [`finish`](Self::finish). A crash ends with the
+/// first line that cannot belong to it.
+#[derive(Debug, Default)]
+pub struct ToolchainCrashParser {
+    in_crash: bool,
+    crashes: Vec<ToolchainCrash>,
+}
+
+impl ToolchainCrashParser {
+    /// Creates a new `ToolchainCrashParser`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds the next line of the log to the parser.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - A string slice that holds the log line.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the line belongs to a crash report.
+    pub fn push_line(&mut self, line: &str) -> bool {
+        let line = line.trim_end();
+        if let Some(cap) = FAILED_REGEX.captures(line) {
+            let crash = self.crash();
+            crash.tool = crash
+                .tool
+                .take()
+                .or(cap.name("driver").map(|driver| driver.as_str().to_string()));
+            if let Some(signal) = cap.name("signal") {
+                crash
+                    .signal
+                    .get_or_insert_with(|| format!("signal {}", signal.as_str()));
+            }
+            return true;
+        }
+        if let Some(cap) = UNABLE_REGEX.captures(line) {
+            let crash = self.crash();
+            crash.tool = crash
+                .tool
+                .take()
+                .or(cap.name("driver").map(|driver| driver.as_str().to_string()));
+            crash.signal = Some(cap["signal"].to_string());
+            return true;
+        }
+        if SIGNAL_REGEX.is_match(line) {
+            self.crash().signal = Some(line.to_string());
+            return true;
+        }
+        if line == "Stack dump:"
+            || line
+                .to_ascii_lowercase()
+                .starts_with("please submit a bug report")
+        {
+            self.crash();
+            return true;
+        }
+        if !self.in_crash {
+            return false;
+        }
+        let Some(crash) = self.crashes.last_mut() else {
+            return false;
+        };
+
+        if let Some(cap) = STACK_ENTRY_REGEX.captures(line) {
+            let entry = &cap["entry"];
+            match entry.strip_prefix("Program arguments: ") {
+                Some(command) => crash.set_command(command),
+                None => {
+                    if crash.location.is_none() {
+                        crash.location = LOCATION_REGEX.captures(entry).and_then(|cap| {
+                            Some((
+                                cap["path"].to_string(),
+                                cap["line"].parse().ok()?,
+                                cap["column"].parse().ok()?,
+                            ))
+                        });
+                    }
+                    crash.stack.push(entry.to_string());
+                }
+            }
+            return true;
+        }
+        if BACKTRACE_REGEX.is_match(line)
+            || line.starts_with("Stack dump without symbol names")
+            || line.contains(": note: diagnostic msg:")
+        {
+            return true;
+        }
+        self.in_crash = false;
+        false
+    }
+
+    /// Consumes the parser and returns the crashes found.
+    pub fn finish(self) -> Vec<ToolchainCrash> {
+        self.crashes
+    }
+
+    /// Returns the crash being read, starting a new one if none is.
+    fn crash(&mut self) -> &mut ToolchainCrash {
+        if !self.in_crash || self.crashes.is_empty() {
+            self.crashes.push(ToolchainCrash::default());
+            self.in_crash = true;
+        }
+        self.crashes.last_mut().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BuildLog, MyWarning, Severity};
+
+    /// Tests reading Swift and Clang crash reports, and the diagnostics they become.
+    #[test]
+    fn test_toolchain_crash_parser() {
+        let log = "SwiftCompile normal arm64 /src/A.swift (in target 'App' from project 'App')\n\
+                   error: compile command failed due to signal 11 (use -v to see invocation)\n\
+                   Please submit a bug report (https://swift.org/contributing/#reporting-bugs) and include the crash backtrace.\n\
+                   Stack dump:\n\
+                   0.\tProgram arguments: /usr/bin/swift-frontend -frontend -c /src/B.swift -primary-file /src/A.swift -o /build/A.o\n\
+                   1.\tApple Swift version 5.9 (swiftlang-5.9.0.128.108 clang-1500.0.40.1)\n\
+                   2.\tWhile type-checking 'Foo' (at /src/A.swift:10:1)\n\
+                   Stack dump without symbol names (ensure you have llvm-symbolizer in your PATH):\n\
+                   0  swift-frontend           0x0000000107b0f6e8 llvm::sys::PrintStackTrace(llvm::raw_ostream&, int) + 56\n\
+                   /src/C.swift:1:2: warning: unused\n\
+                   clang: error: unable to execute command: Segmentation fault: 11\n\
+                   clang: error: clang frontend command failed due to signal (use -v to see invocation)\n\
+                   clang: note: diagnostic msg: /tmp/a-1f2e3d.m\n";
+        let build_log = BuildLog::<MyWarning>::parse(log);
+
+        let crashes = build_log.toolchain_crashes();
+        assert_eq!(crashes.len(), 2);
+        assert_eq!(crashes[0].tool(), Some("swift-frontend"));
+        assert_eq!(crashes[0].signal(), Some("signal 11"));
+        assert_eq!(crashes[0].file(), Some("/src/A.swift"));
+        assert!(crashes[0].command().unwrap().ends_with("-o /build/A.o"));
+        assert_eq!(crashes[0].stack().len(), 2);
+        assert_eq!(crashes[1].tool(), Some("clang"));
+        assert_eq!(crashes[1].signal(), Some("Segmentation fault: 11"));
+        assert_eq!(crashes[1].file(), None);
+
+        let diagnostics: Vec<_> = build_log
+            .diagnostics()
+            .iter()
+            .map(|log_file| {
+                let code_fragment = log_file.code_fragment().unwrap();
+                (
+                    log_file.absolute_path(),
+                    code_fragment.line(),
+                    code_fragment.severity(),
+                    code_fragment.text(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            diagnostics,
+            vec![
+                ("/src/C.swift", 1, Some(Severity::Warning), "unused"),
+                (
+                    "/src/A.swift",
+                    10,
+                    Some(Severity::Error),
+                    "swift-frontend crashed (signal 11)"
+                ),
+            ]
+        );
+    }
+}
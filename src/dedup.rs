@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use crate::{LogFile, TaskMessage};
+
+/// A diagnostic together with the number of times it appears in the log.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Deduplicated<T: TaskMessage> {
+    log_file: LogFile<T>,
+    occurrences: usize,
+}
+
+impl<T: TaskMessage> Deduplicated<T> {
+    /// Returns the first occurrence of the diagnostic.
+    pub fn log_file(&self) -> &LogFile<T> {
+        &self.log_file
+    }
+
+    /// Returns the number of times the diagnostic appears in the log.
+    pub fn occurrences(&self) -> usize {
+        self.occurrences
+    }
+
+    /// Consumes the entry and returns the first occurrence of the diagnostic.
+    pub fn into_log_file(self) -> LogFile<T> {
+        self.log_file
+    }
+}
+
+/// Merges repeated diagnostics, such as a warning emitted once per architecture or target.
+///
+/// Diagnostics are the same when they share the path, line, column and message text.
+/// The first occurrence of each is kept, in the order of the log.
+///
+/// # Arguments
+///
+/// * `diagnostics` - The parsed diagnostics, such as [`BuildLog::into_diagnostics`](crate::BuildLog::into_diagnostics).
+///
+/// # Returns
+///
+/// * `Vec<Deduplicated<T>>` - Each distinct diagnostic with its number of occurrences.
+pub fn dedup<T: TaskMessage>(
+    diagnostics: impl IntoIterator<Item = LogFile<T>>,
+) -> Vec<Deduplicated<T>> {
+    let mut deduplicated: Vec<Deduplicated<T>> = Vec::new();
+    let mut indices: HashMap<_, usize> = HashMap::new();
+    for log_file in diagnostics {
+        let key = (
+            log_file.absolute_path().to_string(),
+            log_file.code_fragment().map(|code_fragment| {
+                (
+                    code_fragment.line(),
+                    code_fragment.column(),
+                    code_fragment.text().to_string(),
+                )
+            }),
+        );
+        match indices.get(&key) {
+            Some(&index) => deduplicated[index].occurrences += 1,
+            None => {
+                indices.insert(key, deduplicated.len());
+                deduplicated.push(Deduplicated {
+                    log_file,
+                    occurrences: 1,
+                });
+            }
+        }
+    }
+    deduplicated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_log, MyWarning};
+
+    /// Tests counting a warning emitted for several architectures.
+    #[test]
+    fn test_dedup_counts_occurrences() {
+        let log = "/src/A.swift:1:2: warning: unused variable 'x'\n\
+                   /src/A.swift:3:4: error: cannot find 'y' in scope\n\
+                   /src/A.swift:1:2: warning: unused variable 'x'\n\
+                   /src/A.swift:1:2: warning: unused variable 'z'\n\
+                   /src/A.swift:1:2: warning: unused variable 'x'\n";
+        let deduplicated = dedup(parse_log::<MyWarning>(log));
+        let counts: Vec<(usize, usize)> = deduplicated
+            .iter()
+            .map(|entry| {
+                (
+                    entry.log_file().code_fragment().unwrap().line(),
+                    entry.occurrences(),
+                )
+            })
+            .collect();
+        assert_eq!(counts, vec![(1, 3), (3, 1), (1, 1)]);
+    }
+}
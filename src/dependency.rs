@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+
+use crate::{LogFile, TaskMessage};
+
+/// Returns the name of the dependency a path belongs to, if it falls under the `Pods`
+/// directory of CocoaPods, such as `Alamofire` for `/src/Pods/Alamofire/Source/Session.swift`,
+/// or under the checkouts of the Swift Package Manager, such as `swift-nio` for
+/// `/DerivedData/App-abc/SourcePackages/checkouts/swift-nio/Sources/NIO/Channel.swift`.
+///
+/// The headers and support files CocoaPods generates per pod, under `Pods/Headers/Public`,
+/// `Pods/Headers/Private` and `Pods/Target Support Files`, are attributed to their pod too,
+/// and the files directly under `Pods`, such as `Pods.xcodeproj`, to `Pods`. Packages are
+/// checked out under `SourcePackages/checkouts` by Xcode and under `.build/checkouts` by
+/// `swift build`.
+///
+/// # Arguments
+///
+/// * `path` - The path of a diagnostic.
+///
+/// # Returns
+///
+/// * `Option<&str>` - The name of the pod or package, or `None` if the path is not part of one.
+pub(crate) fn dependency_of(path: &str) -> Option<&str> {
+    let components: Vec<&str> = path.split('/').collect();
+    let checkouts = components.windows(3).rposition(|window| {
+        matches!(window, ["SourcePackages" | ".build", "checkouts", _]) && !window[2].is_empty()
+    });
+    if let Some(checkouts) = checkouts {
+        return Some(components[checkouts + 2]);
+    }
+
+    let pods = components
+        .iter()
+        .rposition(|component| *component == "Pods")?;
+    let mut rest = &components[pods + 1..];
+    rest = match rest {
+        ["Headers", "Public" | "Private", rest @ ..] => rest,
+        ["Target Support Files", rest @ ..] => rest,
+        _ => rest,
+    };
+    match rest {
+        [name, _, ..] if !name.is_empty() => Some(name),
+        _ => Some(components[pods]),
+    }
+}
+
+/// Separates the diagnostics of the project from those of its dependencies.
+///
+/// # Arguments
+///
+/// * `diagnostics` - The parsed diagnostics, such as [`BuildLog::into_diagnostics`](crate::BuildLog::into_diagnostics).
+///
+/// # Returns
+///
+/// * `(Vec<LogFile<T>>, Vec<LogFile<T>>)` - The first-party diagnostics, then those
+///   attributed to a [`dependency`](LogFile::dependency), each in the order of the log.
+pub fn split_dependencies<T: TaskMessage>(
+    diagnostics: impl IntoIterator<Item = LogFile<T>>,
+) -> (Vec<LogFile<T>>, Vec<LogFile<T>>) {
+    diagnostics
+        .into_iter()
+        .partition(|log_file| log_file.is_first_party())
+}
+
+/// Groups the diagnostics of dependencies by dependency, such as to suppress or report
+/// the warnings of each one upstream.
+///
+/// # Arguments
+///
+/// * `diagnostics` - The diagnostics to group. First-party diagnostics are left out.
+///
+/// # Returns
+///
+/// * `BTreeMap<&str, Vec<&LogFile<T>>>` - The diagnostics of each dependency, sorted by name,
+///   in the order of the log.
+pub fn group_by_dependency<'a, T: TaskMessage + 'a>(
+    diagnostics: impl IntoIterator<Item = &'a LogFile<T>>,
+) -> BTreeMap<&'a str, Vec<&'a LogFile<T>>> {
+    let mut groups: BTreeMap<&str, Vec<&LogFile<T>>> = BTreeMap::new();
+    for log_file in diagnostics {
+        if let Some(dependency) = log_file.dependency() {
+            groups.entry(dependency).or_default().push(log_file);
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_log, MyWarning};
+
+    /// Tests attributing diagnostics to the pod they are reported in.
+    #[test]
+    fn test_split_dependencies() {
+        let log = "/src/App/A.swift:1:2: warning: unused variable 'x'\n\
+                   /src/Pods/Alamofire/Source/Session.swift:3:4: warning: 'init' is deprecated\n\
+                   /src/Pods/Headers/Public/Firebase/Firebase.h:5:1: warning: umbrella header\n\
+                   /src/Pods/Target Support Files/Pods-App/Pods-App-umbrella.h:1:1: warning: double-quoted include\n";
+        let diagnostics = parse_log::<MyWarning>(log);
+        let dependencies: Vec<_> = diagnostics.iter().map(LogFile::dependency).collect();
+        assert_eq!(
+            dependencies,
+            vec![None, Some("Alamofire"), Some("Firebase"), Some("Pods-App")]
+        );
+        assert_eq!(dependency_of("/src/Pods/Pods.xcodeproj"), Some("Pods"));
+
+        let (first_party, dependencies) = split_dependencies(diagnostics);
+        assert_eq!(first_party.len(), 1);
+        assert_eq!(dependencies.len(), 3);
+    }
+
+    /// Tests attributing diagnostics to the Swift package checked out for the build.
+    #[test]
+    fn test_group_by_dependency() {
+        let log = "/DerivedData/App-abc/SourcePackages/checkouts/swift-nio/Sources/NIO/Channel.swift:1:2: warning: 'init' is deprecated\n\
+                   /src/Kit/.build/checkouts/swift-log/Sources/Logging/Logging.swift:3:4: warning: unused variable 'x'\n\
+                   /DerivedData/App-abc/SourcePackages/checkouts/swift-nio/Sources/NIO/Embedded.swift:5:6: warning: unused result\n\
+                   /src/App/SourcePackages/Local/A.swift:7:8: warning: unused variable 'y'\n";
+        let diagnostics = parse_log::<MyWarning>(log);
+
+        let groups: Vec<_> = group_by_dependency(&diagnostics)
+            .into_iter()
+            .map(|(dependency, diagnostics)| (dependency, diagnostics.len()))
+            .collect();
+        assert_eq!(groups, vec![("swift-log", 1), ("swift-nio", 2)]);
+        assert!(diagnostics[3].is_first_party());
+    }
+}
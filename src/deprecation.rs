@@ -0,0 +1,156 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+/// Matches the deprecated symbol a deprecation warning starts with, such as `'foo()' is deprecated`.
+static SYMBOL_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^'(?P<symbol>.+?)' (?:is|was|has been) deprecated"#).unwrap());
+
+/// Matches the OS version an API was deprecated in, such as `deprecated in iOS 16.0` or
+/// clang's `first deprecated in macOS 10.15`.
+static VERSION_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"deprecated in (?P<platform>[A-Za-z]+(?: application extension)?) (?P<version>\d+(?:\.\d+)*)"#,
+    )
+    .unwrap()
+});
+
+/// Matches the replacement suggested by a deprecation message, such as `use 'bar' instead`,
+/// `renamed to 'bar(_:)'` or `Use -sizeWithAttributes:`.
+static REPLACEMENT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"(?i)\b(?:use|renamed to|replaced by)\s+(?:'(?P<quoted>[^']+)'|`(?P<backticked>[^`]+)`|(?P<plain>[^\s'`]+))"#,
+    )
+    .unwrap()
+});
+
+/// Matches the warning flag clang names at the end of a diagnostic, such as `[-Wdeprecated-declarations]`.
+static FLAG_SUFFIX_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\s*\[-W[^\]]+\]$"#).unwrap());
+
+/// Represents the use of a deprecated API, read from the text of a deprecation warning such as
+/// `'init()' was deprecated in iOS 13.0: use 'init(frame:)' instead`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Deprecation {
+    symbol: String,
+    replacement: Option<String>,
+    platform: Option<String>,
+    version: Option<String>,
+}
+
+impl Deprecation {
+    /// Returns the deprecation named by the text of a diagnostic, if it reports one.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The message of the diagnostic, following its severity.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self>` - The deprecation, if the text starts with a quoted deprecated symbol.
+    pub(crate) fn from_text(text: &str) -> Option<Self> {
+        let text = FLAG_SUFFIX_REGEX.replace(text.trim_end(), "");
+        let symbol = SYMBOL_REGEX.captures(&text)?["symbol"].to_string();
+        let (platform, version) = match VERSION_REGEX.captures(&text) {
+            Some(cap) => (
+                Some(cap["platform"].to_string()),
+                Some(cap["version"].to_string()),
+            ),
+            None => (None, None),
+        };
+        let replacement = REPLACEMENT_REGEX.captures(&text).and_then(|cap| {
+            let replacement = ["quoted", "backticked", "plain"]
+                .into_iter()
+                .find_map(|name| cap.name(name))?
+                .as_str()
+                .trim_end_matches(['.', ',', ';']);
+            (!replacement.is_empty()).then(|| replacement.to_string())
+        });
+
+        Some(Deprecation {
+            symbol,
+            replacement,
+            platform,
+            version,
+        })
+    }
+
+    /// Fills in the replacement and OS version from another message about the same symbol.
+    pub(crate) fn merge(&mut self, other: Deprecation) {
+        self.replacement = self.replacement.take().or(other.replacement);
+        if self.version.is_none() {
+            self.platform = other.platform;
+            self.version = other.version;
+        }
+    }
+
+    /// Returns the deprecated symbol, such as `init()` or `UIApplication.keyWindow`.
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Returns the API suggested instead, if the message names one.
+    pub fn replacement(&self) -> Option<&str> {
+        self.replacement.as_deref()
+    }
+
+    /// Returns the platform the symbol was deprecated on, such as `iOS`, if the message names one.
+    pub fn platform(&self) -> Option<&str> {
+        self.platform.as_deref()
+    }
+
+    /// Returns the OS version the symbol was deprecated in, such as `16.0`, if the message names one.
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests reading the symbol, replacement and OS version of Swift and clang deprecation warnings.
+    #[test]
+    fn test_deprecation_from_text() {
+        let cases = [
+            (
+                "'foo()' is deprecated: use 'bar()' instead",
+                ("foo()", Some("bar()"), None, None),
+            ),
+            (
+                "'init()' was deprecated in iOS 13.0: use init(frame:)",
+                ("init()", Some("init(frame:)"), Some("iOS"), Some("13.0")),
+            ),
+            (
+                "'keyWindow' was deprecated in iOS 13.0: Should not be used for applications that support multiple scenes",
+                ("keyWindow", None, Some("iOS"), Some("13.0")),
+            ),
+            (
+                "'load' was deprecated in macOS 12: renamed to 'load(completion:)'",
+                ("load", Some("load(completion:)"), Some("macOS"), Some("12")),
+            ),
+            (
+                "'sizeWithFont:' is deprecated: first deprecated in iOS 7.0 - Use -sizeWithAttributes: [-Wdeprecated-declarations]",
+                ("sizeWithFont:", Some("-sizeWithAttributes:"), Some("iOS"), Some("7.0")),
+            ),
+            (
+                "'openURL:' is deprecated: first deprecated in iOS application extension 10.0 [-Wdeprecated-declarations]",
+                ("openURL:", None, Some("iOS application extension"), Some("10.0")),
+            ),
+        ];
+        for (text, (symbol, replacement, platform, version)) in cases {
+            let deprecation = Deprecation::from_text(text).unwrap();
+            assert_eq!(
+                (
+                    deprecation.symbol(),
+                    deprecation.replacement(),
+                    deprecation.platform(),
+                    deprecation.version()
+                ),
+                (symbol, replacement, platform, version),
+                "{}",
+                text
+            );
+        }
+        assert!(Deprecation::from_text("unused variable 'x'").is_none());
+    }
+}
@@ -0,0 +1,296 @@
+use std::borrow::Cow;
+use std::path::Path;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::{BuildStep, BuildStepHeader, RegexParse};
+
+/// Matches a progress line of SwiftPM, such as `[3/8] Compiling App File.swift`.
+static SWIFTPM_STEP_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^(?:\[\d+/\d+\] )?(?P<action>Compiling|Compile Swift Module|Emitting module|Linking) (?P<arguments>.+)$"#)
+        .unwrap()
+});
+
+/// Matches the message of a SwiftLint violation, such as
+/// `Line Length Violation: Line should be 120 characters or less (line_length)`.
+static SWIFTLINT_RULE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^.+ Violation: .*\((?P<rule>[a-z0-9_]+)\)$"#).unwrap());
+
+/// Matches the decoration fastlane puts in front of the lines it prints, such as
+/// `[10:23:45]: ▸ ` or `INFO [2024-05-01 10:23:45.12]: `.
+static FASTLANE_PREFIX_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^(?:(?:DEBUG|INFO|WARN|ERROR|SUCCESS) )?(?:\[[\d:. -]+\]: )?(?:▸ )?"#).unwrap()
+});
+
+/// Matches a diagnostic reformatted by xcpretty or xcbeautify, whose severity is an emoji,
+/// such as `⚠️  /src/A.swift:3:7: unused variable 'x'`.
+static FORMATTED_DIAGNOSTIC_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^(?P<marker>⚠\x{FE0F}?|❌)\s+(?:(?P<location>.+?:\d+:\d+): )?(?P<text>.*)$"#)
+        .unwrap()
+});
+
+/// Selects the tool that printed a log.
+///
+/// Every supported tool prints diagnostics as `path:line:column: severity: text`, so
+/// the dialect mostly changes how the lines around them, such as build steps and the
+/// final verdict, are read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Dialect {
+    /// `xcodebuild`, printing step headers such as `CompileSwift normal arm64 /src/A.swift`
+    /// and verdicts such as `** BUILD SUCCEEDED **`.
+    #[default]
+    Xcodebuild,
+    /// `swift build` and `swift test`, printing progress lines such as
+    /// `[3/8] Compiling App File.swift`, `Build complete!` when the build succeeds and
+    /// `error: terminated(1): command` for each failed process.
+    SwiftPm,
+    /// SwiftLint's default reporter, printing violations as
+    /// `path:line:column: warning: Rule Name Violation: message (rule_id)` and ending with
+    /// `Done linting! Found 3 violations, 1 serious in 10 files.`
+    ///
+    /// The rule identifier of each violation is available from [`CodeFragment::rule`](crate::CodeFragment::rule).
+    SwiftLint,
+    /// fastlane's `gym` and `scan`, printing the `xcodebuild` output, as is or reformatted
+    /// by xcpretty or xcbeautify, behind a timestamp such as `[10:23:45]: ▸ `.
+    ///
+    /// The decoration is removed before each line is read, and diagnostics marked with
+    /// `⚠️` or `❌` instead of a severity keyword are read as warnings or errors. fastlane's
+    /// own error summaries, such as `[!] Error building the application - see the log above`,
+    /// fail the [`BuildOutcome`](crate::BuildOutcome) and are listed in its
+    /// [`tool_errors`](crate::BuildOutcome::tool_errors).
+    Fastlane,
+}
+
+impl Dialect {
+    /// Returns the build step started by a log line, if it starts one.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - A string slice that holds the log line.
+    pub(crate) fn build_step(self, line: &str) -> Option<BuildStepHeader> {
+        match self {
+            Dialect::Xcodebuild | Dialect::Fastlane => BuildStepHeader::new_from_regex(line),
+            Dialect::SwiftPm => swiftpm_build_step(line.trim_end()),
+            Dialect::SwiftLint => None,
+        }
+    }
+
+    /// Returns the identifier of the lint rule reported by a diagnostic, if the dialect has rules.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The message of the diagnostic, following its severity.
+    pub(crate) fn rule(self, text: &str) -> Option<String> {
+        match self {
+            Dialect::SwiftLint => SWIFTLINT_RULE_REGEX
+                .captures(text)
+                .map(|cap| cap["rule"].to_string()),
+            Dialect::Xcodebuild | Dialect::SwiftPm | Dialect::Fastlane => None,
+        }
+    }
+
+    /// Returns a log line without the decoration the tool adds around the `xcodebuild` output.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - A string slice that holds the log line.
+    pub(crate) fn undecorate(self, line: &str) -> Cow<'_, str> {
+        if self != Dialect::Fastlane {
+            return Cow::Borrowed(line);
+        }
+        let prefix = FASTLANE_PREFIX_REGEX
+            .find(line)
+            .map_or(0, |prefix| prefix.end());
+        let line = &line[prefix..];
+        let Some(cap) = FORMATTED_DIAGNOSTIC_REGEX.captures(line) else {
+            return Cow::Borrowed(line);
+        };
+        let text = cap.name("text").map_or("", |text| text.as_str());
+        let Some(location) = cap.name("location") else {
+            return Cow::Borrowed(text);
+        };
+        let severity = match &cap["marker"] {
+            "❌" => "error",
+            _ => "warning",
+        };
+        if text.starts_with(severity) {
+            return Cow::Owned(format!("{}: {}", location.as_str(), text));
+        }
+        Cow::Owned(format!("{}: {}: {}", location.as_str(), severity, text))
+    }
+}
+
+/// Returns the build step started by a SwiftPM progress line, attributed to its module or product.
+fn swiftpm_build_step(line: &str) -> Option<BuildStepHeader> {
+    let cap = SWIFTPM_STEP_REGEX.captures(line)?;
+    let arguments = cap.name("arguments")?.as_str();
+    let (step, target, arguments) = match &cap["action"] {
+        "Compiling" => {
+            let (module, files) = arguments.split_once(' ').unwrap_or((arguments, ""));
+            let extension = Path::new(files).extension().and_then(|e| e.to_str());
+            let step = match extension {
+                Some("c" | "m" | "mm" | "cc" | "cpp") => BuildStep::CompileC,
+                _ => BuildStep::CompileSwift,
+            };
+            (step, module, files)
+        }
+        // SwiftPM 4 printed `Compile Swift Module 'App' (3 sources)`.
+        "Compile Swift Module" => (
+            BuildStep::CompileSwift,
+            arguments.split('\'').nth(1)?,
+            arguments,
+        ),
+        "Emitting module" => (BuildStep::CompileSwift, arguments, ""),
+        _ => (BuildStep::Ld, arguments, arguments),
+    };
+    Some(BuildStepHeader::new(
+        step,
+        arguments.to_string(),
+        Some(target.to_string()),
+        None,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BuildLog, BuildStatus, LogParser, MyWarning, Severity};
+
+    /// Tests parsing a failed `swift build` log.
+    #[test]
+    fn test_dialect_swiftpm_build_log() {
+        let log = "Building for debugging...\n\
+                   [1/4] Emitting module Core\n\
+                   [2/4] Compiling Core Store.swift\n\
+                   /pkg/Sources/Core/Store.swift:3:9: warning: initialization of immutable value 'x' was never used\n\
+                   [3/4] Compiling CShim shim.c\n\
+                   /pkg/Sources/CShim/shim.c:1:10: error: 'missing.h' file not found\n\
+                   error: terminated(1): /usr/bin/clang -c /pkg/Sources/CShim/shim.c output:\n";
+        let parser = LogParser::builder().dialect(Dialect::SwiftPm).build();
+        let build_log: BuildLog<MyWarning> = parser.parse_build_log(log);
+
+        let steps: Vec<_> = build_log
+            .diagnostics_with_build_steps()
+            .map(|(_, step)| step.map(|step| (step.step().clone(), step.target())))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                Some((BuildStep::CompileSwift, Some("Core"))),
+                Some((BuildStep::CompileC, Some("CShim"))),
+            ]
+        );
+        let outcome = build_log.outcome().unwrap();
+        assert_eq!(outcome.status(), BuildStatus::Failed);
+        assert_eq!(
+            outcome.failed_commands(),
+            ["/usr/bin/clang -c /pkg/Sources/CShim/shim.c"]
+        );
+
+        let parser = LogParser::builder().dialect(Dialect::SwiftPm).build();
+        let build_log: BuildLog<MyWarning> =
+            parser.parse_build_log("[5/5] Linking tool\nBuild complete! (1.52s)\n");
+        assert_eq!(build_log.build_steps()[0].step(), &BuildStep::Ld);
+        assert!(build_log.outcome().unwrap().succeeded());
+    }
+
+    /// Tests parsing a failed `fastlane gym` log with timestamps and formatted diagnostics.
+    #[test]
+    fn test_dialect_fastlane_build_log() {
+        let log = "[10:23:41]: $ set -o pipefail && xcodebuild -scheme App archive | xcpretty\n\
+                   [10:23:44]: ▸ Compiling A.swift\n\
+                   [10:23:45]: ▸ ⚠️  /src/A.swift:3:7: variable 'x' was never used\n\
+                   [10:23:45]: ▸ ❌  /src/B.swift:9:1: cannot find 'y' in scope\n\
+                   [10:23:46]: ▸ ** ARCHIVE FAILED **\n\
+                   [10:23:46]: Exit status: 65\n\
+                   [!] Error building the application - see the log above\n";
+        let parser = LogParser::builder().dialect(Dialect::Fastlane).build();
+        let build_log: BuildLog<MyWarning> = parser.parse_build_log(log);
+
+        let diagnostics: Vec<_> = build_log
+            .diagnostics()
+            .iter()
+            .map(|log_file| {
+                let code_fragment = log_file.code_fragment().unwrap();
+                (
+                    log_file.absolute_path(),
+                    code_fragment.line(),
+                    code_fragment.severity(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            diagnostics,
+            vec![
+                ("/src/A.swift", 3, Some(Severity::Warning)),
+                ("/src/B.swift", 9, Some(Severity::Error)),
+            ]
+        );
+        let outcome = build_log.outcome().unwrap();
+        assert_eq!((outcome.action(), outcome.succeeded()), ("ARCHIVE", false));
+        assert_eq!(
+            outcome.tool_errors(),
+            ["Error building the application - see the log above"]
+        );
+        assert_eq!(
+            Dialect::Fastlane
+                .undecorate("INFO [2024-05-01 10:23:45.12]: ▸ ❌  error: no such module 'Core'"),
+            "error: no such module 'Core'"
+        );
+    }
+
+    /// Tests that the spans of a fastlane diagnostic index the line with its decoration.
+    #[test]
+    fn test_dialect_fastlane_spans() {
+        let line = "[10:00:01]: ▸ /src/A.swift:1:2: warning: unused variable 'x'";
+        let parser = LogParser::builder().dialect(Dialect::Fastlane).build();
+        let log_file = parser.parse_line::<MyWarning>(line).unwrap();
+        let spans = log_file.spans().unwrap();
+        assert_eq!(&line[spans.path()], "/src/A.swift");
+        assert_eq!(&line[spans.text().unwrap()], "unused variable 'x'");
+
+        let log = format!("[10:00:00]: ▸ Compiling A.swift\n{}\n", line);
+        let build_log: BuildLog<MyWarning> = parser.parse_build_log(&log);
+        let spans = build_log.diagnostics()[0].spans().unwrap();
+        assert_eq!(&line[spans.severity().unwrap()], "warning");
+
+        // A diagnostic reformatted by xcpretty is rewritten, so it has no spans.
+        let formatted = "[10:00:01]: ▸ ⚠️  /src/A.swift:1:2: unused variable 'x'";
+        let log_file = parser.parse_line::<MyWarning>(formatted).unwrap();
+        assert!(log_file.spans().is_none());
+    }
+
+    /// Tests extracting the rule of SwiftLint violations and reading the lint summary.
+    #[test]
+    fn test_dialect_swiftlint_rules() {
+        let log = "Linting Swift files in current working directory\n\
+                   Linting 'A.swift' (1/2)\n\
+                   /src/A.swift:12:1: warning: Line Length Violation: Line should be 120 characters or less; currently it has 131 characters (line_length)\n\
+                   /src/B.swift:3:7: error: Force Cast Violation: Force casts should be avoided (force_cast)\n\
+                   /src/B.swift:9:2: warning: unused variable (not a rule)\n\
+                   Done linting! Found 2 violations, 1 serious in 2 files.\n";
+        let parser = LogParser::builder().dialect(Dialect::SwiftLint).build();
+        let build_log: BuildLog<MyWarning> = parser.parse_build_log(log);
+
+        let rules: Vec<_> = build_log
+            .diagnostics()
+            .iter()
+            .map(|log_file| log_file.code_fragment().unwrap().rule())
+            .collect();
+        assert_eq!(rules, vec![Some("line_length"), Some("force_cast"), None]);
+        let code_fragment = build_log.diagnostics()[1].code_fragment().unwrap();
+        assert_eq!(code_fragment.severity(), Some(Severity::Error));
+        let outcome = build_log.outcome().unwrap();
+        assert_eq!((outcome.action(), outcome.succeeded()), ("LINT", false));
+        assert!(LogParser::default()
+            .parse_line::<MyWarning>(
+                "/src/B.swift:3:7: error: Force Cast Violation: x (force_cast)"
+            )
+            .unwrap()
+            .code_fragment()
+            .unwrap()
+            .rule()
+            .is_none());
+    }
+}
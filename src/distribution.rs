@@ -0,0 +1,411 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{RegexParse, Severity};
+
+/// Matches the indented fields `notarytool` prints under a heading, such as `  id: 2efe2717-...`.
+static FIELD_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^\s+(?P<name>id|status|name|path|createdDate): (?P<value>.+)$"#).unwrap()
+});
+
+/// Matches the status `notarytool submit --wait` polls, such as `Current status: In Progress.....`.
+static CURRENT_STATUS_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^Current status: (?P<status>.+?)\.*$"#).unwrap());
+
+/// Matches the verdict of `stapler staple`, such as `The staple and validate action worked!`.
+static STAPLER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^The (?:staple and validate|staple|validate) action (?P<verdict>worked|failed)"#)
+        .unwrap()
+});
+
+/// Represents a failure of `xcodebuild -exportArchive`, printed as
+/// `error: exportArchive: ...` or as the `Error Domain=... Code=...` line following it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExportError {
+    message: String,
+    domain: Option<String>,
+    code: Option<i64>,
+}
+
+impl ExportError {
+    /// Returns the message describing the failure.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the error domain, such as `IDEProvisioningErrorDomain`, if printed.
+    pub fn domain(&self) -> Option<&str> {
+        self.domain.as_deref()
+    }
+
+    /// Returns the error code within its domain, if printed.
+    pub fn code(&self) -> Option<i64> {
+        self.code
+    }
+}
+
+impl RegexParse for ExportError {
+    /// Returns the regular expression used to parse an export failure.
+    fn regex_value() -> &'static Regex {
+        static REGEX: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(
+                r#"^(?:error: exportArchive:? (?P<message>.+)|Error Domain=(?P<domain>\S+) Code=(?P<code>-?\d+) "(?P<description>(?:[^"\\]|\\.)*)".*)$"#,
+            )
+            .unwrap()
+        });
+        &REGEX
+    }
+
+    /// Creates a new `ExportError` from the given string using regular expression parsing.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - A string slice that holds the log line to be parsed.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self>` - An `ExportError` instance if parsing is successful, otherwise `None`.
+    fn new_from_regex(haystack: &str) -> Option<Self> {
+        let cap = Self::regex_value().captures(haystack.trim_end())?;
+        if let Some(message) = cap.name("message") {
+            return Some(ExportError {
+                message: message.as_str().to_string(),
+                domain: None,
+                code: None,
+            });
+        }
+
+        Some(ExportError {
+            message: cap["description"].replace("\\\"", "\""),
+            domain: Some(cap["domain"].to_string()),
+            code: cap["code"].parse().ok(),
+        })
+    }
+}
+
+/// Represents the status of a notarization submission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotarizationStatus {
+    /// The submission is still being processed.
+    InProgress,
+    /// The software was notarized.
+    Accepted,
+    /// The submission failed validation, as described by its issues.
+    Invalid,
+    /// The notary service rejected the submission.
+    Rejected,
+}
+
+impl NotarizationStatus {
+    /// Returns the status matching the name printed by `notarytool`, ignoring case.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - A string slice that holds the status name, such as `In Progress`.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self>` - The matching `NotarizationStatus`, otherwise `None`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "in progress" => Some(NotarizationStatus::InProgress),
+            "accepted" => Some(NotarizationStatus::Accepted),
+            "invalid" => Some(NotarizationStatus::Invalid),
+            "rejected" => Some(NotarizationStatus::Rejected),
+            _ => None,
+        }
+    }
+}
+
+/// Represents a problem the notary service found in a submission, read from the JSON
+/// printed by `notarytool log`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotarizationIssue {
+    severity: Severity,
+    path: Option<String>,
+    message: String,
+    architecture: Option<String>,
+    doc_url: Option<String>,
+}
+
+impl NotarizationIssue {
+    /// Returns the severity of the issue.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Returns the path of the offending file within the submission, if any.
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    /// Returns the message describing the issue, such as
+    /// `The executable requests the com.apple.security.get-task-allow entitlement.`.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the architecture of the offending binary slice, such as `arm64`, if any.
+    pub fn architecture(&self) -> Option<&str> {
+        self.architecture.as_deref()
+    }
+
+    /// Returns the URL of the documentation explaining how to fix the issue, if any.
+    pub fn doc_url(&self) -> Option<&str> {
+        self.doc_url.as_deref()
+    }
+}
+
+/// The JSON log of a submission printed by `notarytool log`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NotaryLog {
+    job_id: String,
+    status: Option<String>,
+    status_summary: Option<String>,
+    issues: Option<Vec<NotarizationIssue>>,
+}
+
+/// Represents the outcome of notarizing software with `notarytool` and stapling its ticket.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Notarization {
+    submission_id: Option<String>,
+    status: Option<NotarizationStatus>,
+    status_summary: Option<String>,
+    issues: Vec<NotarizationIssue>,
+    stapled: Option<bool>,
+}
+
+impl Notarization {
+    /// Returns the ID of the submission, which `notarytool info` and `notarytool log` take.
+    pub fn submission_id(&self) -> Option<&str> {
+        self.submission_id.as_deref()
+    }
+
+    /// Returns the last status printed for the submission, if any.
+    pub fn status(&self) -> Option<NotarizationStatus> {
+        self.status
+    }
+
+    /// Returns the summary of the status, such as `Archive contains critical validation errors`,
+    /// if the log contains the JSON log of the submission.
+    pub fn status_summary(&self) -> Option<&str> {
+        self.status_summary.as_deref()
+    }
+
+    /// Returns the problems found in the submission, such as invalid entitlements or
+    /// unsigned binaries, if the log contains the JSON log of the submission.
+    pub fn issues(&self) -> &[NotarizationIssue] {
+        &self.issues
+    }
+
+    /// Returns whether `stapler` attached the ticket to the software, if it was run.
+    pub fn stapled(&self) -> Option<bool> {
+        self.stapled
+    }
+}
+
+/// A stateful parser for the output of `notarytool` and `stapler`.
+///
+/// ```text
+/// Successfully uploaded file
+///   id: 2efe2717-52ef-43a5-96dc-0797e4ca1041
+///   path: /build/App.zip
+/// Current status: Invalid.....
+/// Processing complete
+///   id: 2efe2717-52ef-43a5-96dc-0797e4ca1041
+///   status: Invalid
+/// ```
+///
+/// The JSON printed by `notarytool log` is read too, for the summary and the issues of the
+/// submission. Its lines are not consumed, as a JSON object may be printed by anything.
+///
+/// Lines are fed one at a time with [`push_line`](Self::push_line).
+#[derive(Debug, Default)]
+pub struct NotarizationParser {
+    notarization: Notarization,
+    seen: bool,
+    /// Whether the last heading is followed by the indented fields of a submission.
+    in_fields: bool,
+    /// The lines of the JSON object being read, from its opening brace.
+    json: Option<String>,
+}
+
+impl NotarizationParser {
+    /// Creates a new `NotarizationParser`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds the next line of the log to the parser.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - A string slice that holds the log line.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the line belongs to the output of `notarytool` or `stapler`.
+    pub fn push_line(&mut self, line: &str) -> bool {
+        let line = line.trim_end();
+        self.push_json_line(line);
+
+        if self.in_fields {
+            if let Some(cap) = FIELD_REGEX.captures(line) {
+                match &cap["name"] {
+                    "id" => self.notarization.submission_id = Some(cap["value"].to_string()),
+                    "status" => self.set_status(&cap["value"]),
+                    _ => {}
+                }
+                return true;
+            }
+            self.in_fields = false;
+        }
+        match line {
+            "Submission ID received"
+            | "Successfully uploaded file"
+            | "Processing complete"
+            | "Successfully received submission info" => {
+                self.seen = true;
+                self.in_fields = true;
+                return true;
+            }
+            _ => {}
+        }
+        if let Some(cap) = CURRENT_STATUS_REGEX.captures(line) {
+            self.set_status(&cap["status"]);
+            return true;
+        }
+        if let Some(cap) = STAPLER_REGEX.captures(line) {
+            self.seen = true;
+            self.notarization.stapled = Some(&cap["verdict"] == "worked");
+            return true;
+        }
+        false
+    }
+
+    /// Sets the status of the submission, if the name is a known one.
+    fn set_status(&mut self, name: &str) {
+        if let Some(status) = NotarizationStatus::from_name(name) {
+            self.seen = true;
+            self.notarization.status = Some(status);
+        }
+    }
+
+    /// Collects the lines of a top-level JSON object, and reads it once closed if it is the
+    /// log of a submission.
+    fn push_json_line(&mut self, line: &str) {
+        if line == "{" {
+            self.json = Some(String::new());
+        }
+        let Some(json) = self.json.as_mut() else {
+            return;
+        };
+        json.push_str(line);
+        json.push('\n');
+        if line != "}" {
+            return;
+        }
+        let json = self.json.take().unwrap();
+        let Ok(log) = serde_json::from_str::<NotaryLog>(&json) else {
+            return;
+        };
+        self.seen = true;
+        self.notarization.submission_id = Some(log.job_id);
+        if let Some(status) = log.status {
+            self.set_status(&status);
+        }
+        self.notarization.status_summary = log.status_summary;
+        self.notarization.issues = log.issues.unwrap_or_default();
+    }
+
+    /// Consumes the parser and returns the notarization, if the log contains one.
+    pub fn finish(self) -> Option<Notarization> {
+        self.seen.then_some(self.notarization)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BuildLog, MyWarning};
+
+    /// Tests reading export failures, and the submission, status and issues of a notarization.
+    #[test]
+    fn test_build_log_export_and_notarization() {
+        let log = r#"error: exportArchive: "App.app" requires a provisioning profile with the Push Notifications feature.
+Error Domain=IDEProvisioningErrorDomain Code=9 "\"App.app\" requires a provisioning profile with the Push Notifications feature." UserInfo={IDEDistributionIssueSeverity=3}
+** EXPORT FAILED **
+Conducting pre-submission checks for App.zip and initiating connection to the Apple notary service...
+Submission ID received
+  id: 2efe2717-52ef-43a5-96dc-0797e4ca1041
+Successfully uploaded file
+  id: 2efe2717-52ef-43a5-96dc-0797e4ca1041
+  path: /build/App.zip
+Waiting for processing to complete.
+Current status: In Progress.....
+Current status: Invalid.....
+Processing complete
+  id: 2efe2717-52ef-43a5-96dc-0797e4ca1041
+  status: Invalid
+{
+  "logFormatVersion": 1,
+  "jobId": "2efe2717-52ef-43a5-96dc-0797e4ca1041",
+  "status": "Invalid",
+  "statusSummary": "Archive contains critical validation errors",
+  "statusCode": 4000,
+  "issues": [
+    {
+      "severity": "error",
+      "code": null,
+      "path": "App.zip/App.app/Contents/MacOS/App",
+      "message": "The executable requests the com.apple.security.get-task-allow entitlement.",
+      "docUrl": "https://developer.apple.com/documentation/security/resolving-common-notarization-issues",
+      "architecture": "arm64"
+    }
+  ]
+}
+The staple and validate action failed! Error 65.
+"#;
+        let build_log = BuildLog::<MyWarning>::parse(log);
+
+        let export_errors: Vec<_> = build_log
+            .export_errors()
+            .iter()
+            .map(|error| (error.domain(), error.code()))
+            .collect();
+        assert_eq!(
+            export_errors,
+            vec![(None, None), (Some("IDEProvisioningErrorDomain"), Some(9))]
+        );
+        assert_eq!(
+            build_log.export_errors()[1].message(),
+            "\"App.app\" requires a provisioning profile with the Push Notifications feature."
+        );
+        assert_eq!(
+            build_log.outcome().map(|outcome| outcome.action()),
+            Some("EXPORT")
+        );
+
+        let notarization = build_log.notarization().unwrap();
+        assert_eq!(
+            notarization.submission_id(),
+            Some("2efe2717-52ef-43a5-96dc-0797e4ca1041")
+        );
+        assert_eq!(notarization.status(), Some(NotarizationStatus::Invalid));
+        assert_eq!(
+            notarization.status_summary(),
+            Some("Archive contains critical validation errors")
+        );
+        let issue = &notarization.issues()[0];
+        assert_eq!(issue.severity(), Severity::Error);
+        assert_eq!(issue.architecture(), Some("arm64"));
+        assert!(issue.message().contains("get-task-allow"));
+        assert_eq!(notarization.stapled(), Some(false));
+        assert!(BuildLog::<MyWarning>::parse("{\n\"a\": 1\n}\n")
+            .notarization()
+            .is_none());
+    }
+}
@@ -0,0 +1,371 @@
+use std::collections::BTreeMap;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Matches the version printed by `xcodebuild -version`, such as `Xcode 15.2`.
+static XCODE_VERSION_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^Xcode (?P<version>\d+(?:\.\d+)*)$"#).unwrap());
+
+/// Matches the version of Xcode in the name of its bundle, such as `/Applications/Xcode_15.2.app`.
+static XCODE_PATH_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"/Xcode[-_ ]?(?P<version>\d+(?:\.\d+)*)\.app/"#).unwrap());
+
+/// Matches an SDK in a path, such as `/SDKs/iPhoneOS17.2.sdk`.
+static SDK_PATH_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"/SDKs/(?P<sdk>[A-Za-z]+\d+(?:\.\d+)*)\.sdk\b"#).unwrap());
+
+/// Matches a build setting, such as `    SDKROOT = iphoneos17.2`.
+static SETTING_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^\s+(?P<name>\w+) = (?P<value>.*)$"#).unwrap());
+
+/// Matches a destination printed by `xcodebuild`, such as
+/// `{ platform:iOS Simulator, id:5A2B, OS:17.2, name:iPhone 15 }`.
+static DESTINATION_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^\s*\{ (?P<fields>.+) \}$"#).unwrap());
+
+/// Represents a device or simulator the build ran for.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Destination {
+    platform: Option<String>,
+    name: Option<String>,
+    os: Option<String>,
+    id: Option<String>,
+}
+
+impl Destination {
+    /// Returns the platform, such as `iOS Simulator`, if printed.
+    pub fn platform(&self) -> Option<&str> {
+        self.platform.as_deref()
+    }
+
+    /// Returns the name of the device, such as `iPhone 15`, if printed.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the version of the operating system, such as `17.2`, if printed.
+    pub fn os(&self) -> Option<&str> {
+        self.os.as_deref()
+    }
+
+    /// Returns the identifier of the device, if printed.
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Parses the fields of a destination, such as `platform:iOS Simulator, OS:17.2, name:iPhone 15`.
+    fn parse(fields: &str) -> Self {
+        let mut destination = Destination::default();
+        for field in fields.split(", ") {
+            let Some((key, value)) = field.split_once(':') else {
+                continue;
+            };
+            let value = Some(value.to_string());
+            match key {
+                "platform" => destination.platform = value,
+                "name" => destination.name = value,
+                "OS" => destination.os = value,
+                "id" => destination.id = value,
+                _ => {}
+            }
+        }
+        destination
+    }
+}
+
+/// Represents the environment a build ran in, read from the preamble `xcodebuild` prints.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Environment {
+    invocation: Option<String>,
+    xcode_version: Option<String>,
+    xcode_build: Option<String>,
+    scheme: Option<String>,
+    configuration: Option<String>,
+    sdk: Option<String>,
+    destination: Option<String>,
+    resolved_destination: Option<Destination>,
+    build_settings: BTreeMap<String, String>,
+}
+
+impl Environment {
+    /// Returns the `xcodebuild` command line, if printed.
+    pub fn invocation(&self) -> Option<&str> {
+        self.invocation.as_deref()
+    }
+
+    /// Returns the version of Xcode, such as `15.2`, read from the output of
+    /// `xcodebuild -version` or the path of Xcode.
+    pub fn xcode_version(&self) -> Option<&str> {
+        self.xcode_version.as_deref()
+    }
+
+    /// Returns the build version of Xcode, such as `15C500b`, if printed.
+    pub fn xcode_build(&self) -> Option<&str> {
+        self.xcode_build.as_deref()
+    }
+
+    /// Returns the scheme passed with `-scheme`.
+    pub fn scheme(&self) -> Option<&str> {
+        self.scheme.as_deref()
+    }
+
+    /// Returns the build configuration passed with `-configuration`, such as `Release`.
+    pub fn configuration(&self) -> Option<&str> {
+        self.configuration.as_deref()
+    }
+
+    /// Returns the SDK, such as `iphoneos` or `iPhoneOS17.2`, read from `-sdk`, the
+    /// `SDKROOT` build setting or the SDK the compiler was run with.
+    pub fn sdk(&self) -> Option<&str> {
+        self.sdk.as_deref()
+    }
+
+    /// Returns the destination specifier passed with `-destination`, such as
+    /// `platform=iOS Simulator,name=iPhone 15`.
+    pub fn destination(&self) -> Option<&str> {
+        self.destination.as_deref()
+    }
+
+    /// Returns the device `xcodebuild` picked among those matching the destination, if it
+    /// printed them.
+    pub fn resolved_destination(&self) -> Option<&Destination> {
+        self.resolved_destination.as_ref()
+    }
+
+    /// Returns the build settings passed on the command line, such as `SDKROOT`.
+    pub fn build_settings(&self) -> &BTreeMap<String, String> {
+        &self.build_settings
+    }
+
+    /// Reads the options of the `xcodebuild` command line.
+    fn set_invocation(&mut self, invocation: &str) {
+        let arguments = shell_words(invocation);
+        for (index, argument) in arguments.iter().enumerate() {
+            let value = arguments.get(index + 1).cloned();
+            match argument.as_str() {
+                "-scheme" => self.scheme = value,
+                "-configuration" => self.configuration = value,
+                "-sdk" => self.sdk = value,
+                "-destination" => self.destination = value,
+                _ => {}
+            }
+        }
+        if let Some(cap) = XCODE_PATH_REGEX.captures(invocation) {
+            self.xcode_version
+                .get_or_insert_with(|| cap["version"].to_string());
+        }
+        self.invocation = Some(invocation.to_string());
+    }
+}
+
+/// The blocks of the preamble.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Block {
+    Invocation,
+    Settings,
+    UserDefaults,
+    Destinations,
+}
+
+/// A stateful parser for the preamble `xcodebuild` prints before building.
+///
+/// ```text
+/// Command line invocation:
+///     /Applications/Xcode_15.2.app/Contents/Developer/usr/bin/xcodebuild -scheme App -configuration Release -destination "platform=iOS Simulator,name=iPhone 15" test
+///
+/// Build settings from command line:
+///     SDKROOT = iphonesimulator17.2
+///
+/// --- xcodebuild: WARNING: Using the first of multiple matching destinations:
+/// { platform:iOS Simulator, id:5A2B, OS:17.2, name:iPhone 15 }
+/// { platform:iOS Simulator, id:9C4D, OS:17.0, name:iPhone 15 }
+/// ```
+///
+/// Lines are fed one at a time with [`push_line`](Self::push_line). The output of
+/// `xcodebuild -version`, when a CI script prints it first, is read too.
+#[derive(Debug, Default)]
+pub struct EnvironmentParser {
+    block: Option<Block>,
+    environment: Environment,
+}
+
+impl EnvironmentParser {
+    /// Creates a new `EnvironmentParser`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds the next line of the log to the parser.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - A string slice that holds the log line.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the line belongs to the preamble.
+    pub fn push_line(&mut self, line: &str) -> bool {
+        let line = line.trim_end();
+        let environment = &mut self.environment;
+        match line {
+            "Command line invocation:" => self.block = Some(Block::Invocation),
+            "Build settings from command line:" => self.block = Some(Block::Settings),
+            "User defaults from command line:" => self.block = Some(Block::UserDefaults),
+            _ if line.ends_with("Using the first of multiple matching destinations:") => {
+                self.block = Some(Block::Destinations)
+            }
+            _ => {
+                if let Some(cap) = XCODE_VERSION_REGEX.captures(line) {
+                    environment.xcode_version = Some(cap["version"].to_string());
+                    return true;
+                }
+                if let Some(build) = line.strip_prefix("Build version ") {
+                    environment.xcode_build = Some(build.to_string());
+                    return true;
+                }
+                if environment.sdk.is_none() {
+                    if let Some(cap) = SDK_PATH_REGEX.captures(line) {
+                        environment.sdk = Some(cap["sdk"].to_string());
+                    }
+                }
+                return self.push_block_line(line);
+            }
+        }
+        true
+    }
+
+    /// Feeds a line to the block being read, if any.
+    fn push_block_line(&mut self, line: &str) -> bool {
+        let Some(block) = self.block else {
+            return false;
+        };
+        let environment = &mut self.environment;
+        match block {
+            Block::Invocation
+                if line.starts_with(char::is_whitespace) && environment.invocation.is_none() =>
+            {
+                environment.set_invocation(line.trim());
+                return true;
+            }
+            Block::Settings | Block::UserDefaults => {
+                if let Some(cap) = SETTING_REGEX.captures(line) {
+                    if block == Block::Settings {
+                        let value = cap["value"].to_string();
+                        if &cap["name"] == "SDKROOT" && environment.sdk.is_none() {
+                            environment.sdk = Some(value.clone());
+                        }
+                        environment
+                            .build_settings
+                            .insert(cap["name"].to_string(), value);
+                    }
+                    return true;
+                }
+            }
+            Block::Destinations => {
+                if let Some(cap) = DESTINATION_REGEX.captures(line) {
+                    environment
+                        .resolved_destination
+                        .get_or_insert_with(|| Destination::parse(&cap["fields"]));
+                    return true;
+                }
+            }
+            Block::Invocation => {}
+        }
+        self.block = None;
+        false
+    }
+
+    /// Consumes the parser and returns the environment read from the preamble.
+    pub fn finish(self) -> Environment {
+        self.environment
+    }
+}
+
+/// Splits a command line into its arguments, removing quotes and backslash escapes.
+fn shell_words(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = None::<String>;
+    let mut quote = None;
+    let mut characters = command.chars();
+    while let Some(character) = characters.next() {
+        match (character, quote) {
+            ('\\', Some('\'')) => word.get_or_insert_with(String::new).push(character),
+            ('\\', _) => word
+                .get_or_insert_with(String::new)
+                .extend(characters.next()),
+            ('"' | '\'', None) => {
+                quote = Some(character);
+                word.get_or_insert_with(String::new);
+            }
+            (character, Some(open)) if character == open => quote = None,
+            (character, None) if character.is_whitespace() => words.extend(word.take()),
+            (character, _) => word.get_or_insert_with(String::new).push(character),
+        }
+    }
+    words.extend(word);
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BuildLog, MyWarning};
+
+    /// Tests reading the invocation, build settings and destination from the preamble.
+    #[test]
+    fn test_build_log_environment() {
+        let log = "Xcode 15.2\n\
+                   Build version 15C500b\n\
+                   Command line invocation:\n\
+                   \x20   /Applications/Xcode_15.2.app/Contents/Developer/usr/bin/xcodebuild -workspace App.xcworkspace -scheme \"My App\" -configuration Release -destination 'platform=iOS Simulator,name=iPhone 15' test\n\
+                   \n\
+                   User defaults from command line:\n\
+                   \x20   IDEPackageSupportUseBuiltinSCM = YES\n\
+                   \n\
+                   Build settings from command line:\n\
+                   \x20   SDKROOT = iphonesimulator17.2\n\
+                   \x20   SWIFT_TREAT_WARNINGS_AS_ERRORS = YES\n\
+                   \n\
+                   --- xcodebuild: WARNING: Using the first of multiple matching destinations:\n\
+                   { platform:iOS Simulator, id:5A2B, OS:17.2, name:iPhone 15 }\n\
+                   { platform:iOS Simulator, id:9C4D, OS:17.0, name:iPhone 15 }\n\
+                   /src/A.swift:1:2: warning: unused\n";
+        let build_log = BuildLog::<MyWarning>::parse(log);
+        let environment = build_log.environment();
+
+        assert_eq!(environment.xcode_version(), Some("15.2"));
+        assert_eq!(environment.xcode_build(), Some("15C500b"));
+        assert_eq!(environment.scheme(), Some("My App"));
+        assert_eq!(environment.configuration(), Some("Release"));
+        assert_eq!(
+            environment.destination(),
+            Some("platform=iOS Simulator,name=iPhone 15")
+        );
+        assert_eq!(environment.sdk(), Some("iphonesimulator17.2"));
+        assert_eq!(environment.build_settings().len(), 2);
+        let destination = environment.resolved_destination().unwrap();
+        assert_eq!(
+            (
+                destination.platform(),
+                destination.name(),
+                destination.os(),
+                destination.id()
+            ),
+            (
+                Some("iOS Simulator"),
+                Some("iPhone 15"),
+                Some("17.2"),
+                Some("5A2B")
+            )
+        );
+        assert_eq!(build_log.diagnostics().len(), 1);
+
+        let build_log = BuildLog::<MyWarning>::parse(
+            "CompileC /build/a.o /src/a.m normal arm64 (in target 'App' from project 'App')\n\
+             \x20   cd /src\n\
+             \x20   /Applications/Xcode.app/Contents/Developer/usr/bin/clang -isysroot /Applications/Xcode.app/Contents/Developer/Platforms/iPhoneOS.platform/Developer/SDKs/iPhoneOS17.2.sdk -c /src/a.m\n",
+        );
+        assert_eq!(build_log.environment().sdk(), Some("iPhoneOS17.2"));
+        assert_eq!(build_log.environment().xcode_version(), None);
+    }
+}
@@ -0,0 +1,28 @@
+use thiserror::Error;
+
+/// Describes the stage at which parsing a log line failed, along with the
+/// offending part of the line.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParseError {
+    /// The text does not match the expected format at all.
+    #[error("`{0}` does not match the expected format")]
+    NoMatch(String),
+    /// The line does not start with a file path followed by `:`.
+    #[error("missing file path in `{0}`")]
+    MissingPath(String),
+    /// The line and column after the path are missing or not numbers.
+    #[error("malformed line and column in `{0}`")]
+    InvalidLocation(String),
+    /// The text after the location does not start with a `severity:` keyword.
+    #[error("missing severity in `{0}`")]
+    MissingSeverity(String),
+    /// The severity keyword is not one of `warning`, `error`, `note` or `remark`.
+    #[error("unknown severity `{0}`")]
+    UnknownSeverity(String),
+    /// The message does not contain an `s#...#s` task payload.
+    #[error("missing task payload in `{0}`")]
+    MissingPayload(String),
+    /// The task payload could not be deserialized.
+    #[error("invalid task payload `{payload}`: {reason}")]
+    InvalidPayload { payload: String, reason: String },
+}
@@ -0,0 +1,326 @@
+//! xcpretty-style rendering of a live build log, one short line per event.
+
+use std::io::{self, BufRead, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+
+use super::{paint, BOLD, GREEN, RED, YELLOW};
+use crate::{
+    BuildLog, BuildOutcome, BuildStep, BuildStepHeader, LogFile, LogParser, Severity, TailEvent,
+    TaskMessage, TestEvent,
+};
+
+/// Prints one concise line per build step, test and diagnostic of a log as it is read,
+/// like `xcpretty`, while keeping the log to parse it as a whole at the end.
+///
+/// Lines that are not worth printing, such as compiler invocations, yield nothing.
+///
+/// ```
+/// use xcode_log_parser::format::compact::CompactFormatter;
+/// use xcode_log_parser::MyWarning;
+///
+/// let mut formatter = CompactFormatter::<MyWarning>::new().color(false);
+/// let lines: Vec<String> = [
+///     "CompileSwift normal arm64 /src/A.swift (in target 'App' from project 'App')",
+///     "    cd /src",
+///     "/src/A.swift:1:2: warning: unused",
+/// ]
+/// .into_iter()
+/// .filter_map(|line| formatter.push_line(line))
+/// .collect();
+/// assert_eq!(lines, ["▸ Compiling A.swift", "⚠️  /src/A.swift:1:2: unused"]);
+/// assert_eq!(formatter.finish().diagnostics().len(), 1);
+/// ```
+#[derive(Debug)]
+pub struct CompactFormatter<T: TaskMessage> {
+    parser: LogParser,
+    color: bool,
+    log: String,
+    line_number: usize,
+    task: PhantomData<fn() -> T>,
+}
+
+impl<T: TaskMessage> Default for CompactFormatter<T> {
+    fn default() -> Self {
+        Self::with_parser(LogParser::default())
+    }
+}
+
+impl<T: TaskMessage> CompactFormatter<T> {
+    /// Creates a `CompactFormatter` printing colors, with the default parser.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a `CompactFormatter` printing colors, with the given parser.
+    ///
+    /// # Arguments
+    ///
+    /// * `parser` - The parser selecting the diagnostics printed and collected.
+    pub fn with_parser(parser: LogParser) -> Self {
+        CompactFormatter {
+            parser,
+            color: true,
+            log: String::new(),
+            line_number: 0,
+            task: PhantomData,
+        }
+    }
+
+    /// Sets whether ANSI colors are printed, such as green passed tests and red errors.
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Keeps a line of the log and returns the concise line to print for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - A line of the log, with or without its line break.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - The line to print, without a line break, or `None` if the line is not printed.
+    pub fn push_line(&mut self, line: &str) -> Option<String> {
+        let event = self.push_event(line)?;
+        self.render_event(&event)
+    }
+
+    /// Keeps a line of the log and returns the event found in it, to be printed with
+    /// [`render_event`](Self::render_event), such as after dropping some diagnostics.
+    ///
+    /// The line is read on its own, so a `note:` line is returned as a diagnostic and no
+    /// source excerpt is attached; [`finish`](Self::finish) attaches both.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - A line of the log, with or without its line break.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<TailEvent<T>>` - The event, or `None` if the line holds none.
+    pub fn push_event(&mut self, line: &str) -> Option<TailEvent<T>> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        self.log.push_str(line);
+        self.log.push('\n');
+
+        self.line_number += 1;
+        TailEvent::from_line(&self.parser, line, self.line_number)
+    }
+
+    /// Returns the concise line to print for an event, such as one read by a
+    /// [`TailParser`](crate::TailParser).
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event found in a line of the log.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - The line to print, without a line break, or `None` if the event is not printed.
+    pub fn render_event(&self, event: &TailEvent<T>) -> Option<String> {
+        match event {
+            TailEvent::Diagnostic { log_file, .. } => self.diagnostic(log_file),
+            TailEvent::BuildStep(header) => self.build_step(header),
+            TailEvent::Test(event) => self.test_event(event),
+            TailEvent::Outcome(outcome) => Some(self.outcome(outcome)),
+            TailEvent::Truncated => None,
+        }
+    }
+
+    /// Feeds every line of a reader to [`push_line`](Self::push_line), writing the printed lines.
+    ///
+    /// Invalid UTF-8 is replaced rather than rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A buffered reader that yields the build log, such as the stdout of `xcodebuild`.
+    /// * `writer` - Where the concise lines are written, each followed by a line break.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<BuildLog<T>>` - The parsed log, or the first error reading or writing.
+    pub fn format_reader<R: BufRead, W: Write>(
+        mut self,
+        mut reader: R,
+        mut writer: W,
+    ) -> io::Result<BuildLog<T>> {
+        let mut buffer = Vec::new();
+        while reader.read_until(b'\n', &mut buffer)? > 0 {
+            if let Some(output) = self.push_line(&String::from_utf8_lossy(&buffer)) {
+                writeln!(writer, "{}", output)?;
+                writer.flush()?;
+            }
+            buffer.clear();
+        }
+        Ok(self.finish())
+    }
+
+    /// Parses every line pushed so far, as described in [`BuildLog::parse`].
+    pub fn finish(self) -> BuildLog<T> {
+        self.parser.parse_build_log(&self.log)
+    }
+
+    /// Returns the line printed for an error or a warning, if the diagnostic is one.
+    fn diagnostic(&self, log_file: &LogFile<T>) -> Option<String> {
+        let code_fragment = log_file.code_fragment()?;
+        let (symbol, style) = match code_fragment.severity() {
+            Some(Severity::Error) => ("❌ ", RED),
+            Some(Severity::Warning) => ("⚠️ ", YELLOW),
+            _ => return None,
+        };
+        let text = match code_fragment.task_info() {
+            Some(message) => message.task().task_summary(),
+            None => code_fragment.text().to_string(),
+        };
+        Some(format!(
+            "{} {}:{}:{}: {}",
+            self.paint(style, symbol),
+            log_file.absolute_path(),
+            code_fragment.line(),
+            code_fragment.column(),
+            self.paint(style, &text)
+        ))
+    }
+
+    /// Returns the verdict line, green if the action succeeded and red otherwise.
+    fn outcome(&self, outcome: &BuildOutcome) -> String {
+        let (status, style) = if outcome.succeeded() {
+            ("SUCCEEDED", GREEN)
+        } else {
+            ("FAILED", RED)
+        };
+        self.paint(style, &format!("** {} {} **", outcome.action(), status))
+    }
+
+    /// Returns the line printed for a test event, if any.
+    fn test_event(&self, event: &TestEvent) -> Option<String> {
+        match event {
+            TestEvent::Started { .. } => None,
+            TestEvent::Passed {
+                test_case,
+                duration,
+            } => Some(format!(
+                "    {} {} ({:.3} seconds)",
+                self.paint(GREEN, "✓"),
+                test_case.name(),
+                duration.as_secs_f64()
+            )),
+            TestEvent::Failed {
+                test_case,
+                duration,
+            } => Some(format!(
+                "    {} {} ({:.3} seconds)",
+                self.paint(RED, "✗"),
+                test_case.name(),
+                duration.as_secs_f64()
+            )),
+            TestEvent::Failure {
+                absolute_path,
+                line,
+                message,
+                ..
+            } => Some(format!(
+                "      {}:{}: {}",
+                absolute_path,
+                line,
+                self.paint(RED, message)
+            )),
+        }
+    }
+
+    /// Returns the line printed for the start of a build step, if any.
+    fn build_step(&self, header: &BuildStepHeader) -> Option<String> {
+        let action = match header.step() {
+            BuildStep::CompileSwift | BuildStep::CompileC | BuildStep::Metal => "Compiling",
+            BuildStep::Ld => "Linking",
+            BuildStep::CodeSign => "Signing",
+            BuildStep::CopyFile => "Copying",
+            BuildStep::PhaseScriptExecution => "Running script",
+            BuildStep::Analyze => "Analyzing",
+            BuildStep::Other(_) => return None,
+        };
+        let subject = header
+            .arguments()
+            .split(' ')
+            .rev()
+            .find(|argument| argument.contains('/'))
+            .and_then(|path| Path::new(path).file_name())
+            .and_then(|name| name.to_str())
+            .or(header.target())
+            .unwrap_or(header.arguments());
+        Some(format!(
+            "{} {}",
+            self.paint(BOLD, &format!("▸ {}", action)),
+            subject
+        ))
+    }
+
+    /// Returns the text wrapped in the given ANSI style, if colors are enabled.
+    fn paint(&self, style: &str, text: &str) -> String {
+        paint(self.color, style, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MyWarning;
+
+    /// Tests the lines printed for steps, tests and the verdict, and the collected log.
+    #[test]
+    fn test_compact_formatter_format_reader() {
+        let log = "CompileSwiftSources normal arm64 com.apple.xcode.tools.swift.compiler (in target 'Core' from project 'App')\n\
+                   Ld /build/App.app/App normal (in target 'App' from project 'App')\n\
+                   /src/A.swift:3:9: error: cannot find 'foo' in scope\n\
+                   /src/B.swift:1:1: note: did you mean 'bar'?\n\
+                   Test Case '-[AppTests testLaunch]' started.\n\
+                   Test Case '-[AppTests testLaunch]' passed (0.125 seconds).\n\
+                   /src/AppTests.swift:42: error: -[AppTests testLogin] : XCTAssertTrue failed\n\
+                   Test Case '-[AppTests testLogin]' failed (1.500 seconds).\n\
+                   ** TEST FAILED **\n";
+        let mut output = Vec::new();
+        let build_log = CompactFormatter::<MyWarning>::new()
+            .color(false)
+            .format_reader(log.as_bytes(), &mut output)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "▸ Compiling Core\n\
+             ▸ Linking App\n\
+             ❌  /src/A.swift:3:9: cannot find 'foo' in scope\n\
+             \x20   ✓ testLaunch (0.125 seconds)\n\
+             \x20     /src/AppTests.swift:42: XCTAssertTrue failed\n\
+             \x20   ✗ testLogin (1.500 seconds)\n\
+             ** TEST FAILED **\n"
+        );
+        assert_eq!(build_log.diagnostics().len(), 1);
+        assert_eq!(
+            build_log.diagnostics()[0]
+                .code_fragment()
+                .unwrap()
+                .notes()
+                .len(),
+            1
+        );
+        assert_eq!(build_log.test_events().len(), 4);
+    }
+
+    /// Tests the colors of a warning and of a successful build.
+    #[test]
+    fn test_compact_formatter_colors() {
+        let mut formatter = CompactFormatter::<MyWarning>::new();
+        assert_eq!(
+            formatter
+                .push_line("/src/A.swift:1:2: warning: unused\n")
+                .unwrap(),
+            "\x1b[1;33m⚠️ \x1b[0m /src/A.swift:1:2: \x1b[1;33munused\x1b[0m"
+        );
+        assert_eq!(
+            formatter.push_line("** BUILD SUCCEEDED **").unwrap(),
+            "\x1b[1;32m** BUILD SUCCEEDED **\x1b[0m"
+        );
+    }
+}
@@ -0,0 +1,22 @@
+//! Renderers printing parsed diagnostics for people rather than tools.
+
+pub mod compact;
+pub mod pretty;
+#[cfg(feature = "template")]
+pub mod template;
+
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const CYAN: &str = "\x1b[1;36m";
+const GREEN: &str = "\x1b[1;32m";
+const RESET: &str = "\x1b[0m";
+
+/// Returns the text wrapped in the given ANSI style, or unchanged without colors.
+fn paint(color: bool, style: &str, text: &str) -> String {
+    if color {
+        format!("{}{}{}", style, text, RESET)
+    } else {
+        text.to_string()
+    }
+}
@@ -0,0 +1,221 @@
+//! Clang-style rendering of parsed diagnostics for terminals.
+
+use std::fmt::Write;
+
+use super::{paint, BOLD, CYAN, GREEN, RED, YELLOW};
+use crate::{LogFile, Severity, TaskMessage};
+
+/// Renders diagnostics the way clang prints them, optionally with ANSI colors.
+///
+/// Each diagnostic is printed as `path:line:column: severity: text`, followed by its
+/// source lines and a caret under the column, then by its notes. The source lines come
+/// from the [`Snippet`](crate::Snippet) attached by [`attach_snippets`](crate::attach_snippets),
+/// or else from the excerpt printed in the log.
+///
+/// ```
+/// use xcode_log_parser::format::pretty::PrettyFormatter;
+/// use xcode_log_parser::{parse_log, MyWarning};
+///
+/// let diagnostics = parse_log::<MyWarning>("/src/A.swift:1:2: warning: unused\n");
+/// let output = PrettyFormatter::new().color(false).render(&diagnostics);
+/// assert_eq!(output, "/src/A.swift:1:2: warning: unused\n");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrettyFormatter {
+    color: bool,
+    snippets: bool,
+}
+
+impl Default for PrettyFormatter {
+    fn default() -> Self {
+        PrettyFormatter {
+            color: true,
+            snippets: true,
+        }
+    }
+}
+
+impl PrettyFormatter {
+    /// Creates a `PrettyFormatter` printing colors and source lines.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether ANSI colors are printed, such as yellow warnings and red errors.
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets whether the source lines and carets are printed below each diagnostic.
+    pub fn snippets(mut self, snippets: bool) -> Self {
+        self.snippets = snippets;
+        self
+    }
+
+    /// Renders the diagnostics.
+    ///
+    /// # Arguments
+    ///
+    /// * `diagnostics` - The parsed diagnostics to render.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The rendered diagnostics, ending with a line break.
+    pub fn render<T: TaskMessage>(&self, diagnostics: &[LogFile<T>]) -> String {
+        let mut output = String::new();
+        for log_file in diagnostics {
+            let Some(code_fragment) = log_file.code_fragment() else {
+                continue;
+            };
+            let text = match code_fragment.task_info() {
+                Some(message) => message.task().task_summary(),
+                None => code_fragment.text().to_string(),
+            };
+            self.header(
+                &mut output,
+                log_file.absolute_path(),
+                code_fragment.line(),
+                code_fragment.column(),
+                code_fragment.severity(),
+                &text,
+            );
+            if self.snippets {
+                match code_fragment.snippet() {
+                    Some(snippet) => {
+                        let last_line = snippet.first_line() + snippet.lines().len() - 1;
+                        let width = last_line.to_string().len();
+                        for (number, line) in (snippet.first_line()..).zip(snippet.lines()) {
+                            let _ = writeln!(output, "{:>width$} | {}", number, line);
+                            if number == snippet.line() {
+                                let caret = self.paint(GREEN, &snippet.caret());
+                                let _ = writeln!(output, "{:>width$} | {}", "", caret);
+                            }
+                        }
+                    }
+                    None => self.excerpt(
+                        &mut output,
+                        code_fragment.source_excerpt(),
+                        code_fragment.caret(),
+                        code_fragment.fixit(),
+                    ),
+                }
+            }
+            for note in code_fragment.notes() {
+                self.header(
+                    &mut output,
+                    note.absolute_path(),
+                    note.line(),
+                    note.column(),
+                    Some(Severity::Note),
+                    note.text(),
+                );
+                if self.snippets {
+                    self.excerpt(&mut output, note.source_excerpt(), note.caret(), None);
+                }
+            }
+        }
+        output
+    }
+
+    /// Writes the `path:line:column: severity: text` line of a diagnostic.
+    fn header(
+        &self,
+        output: &mut String,
+        absolute_path: &str,
+        line: usize,
+        column: usize,
+        severity: Option<Severity>,
+        text: &str,
+    ) {
+        let location = format!("{}:{}:{}:", absolute_path, line, column);
+        let _ = write!(output, "{} ", self.paint(BOLD, &location));
+        if let Some(severity) = severity {
+            let color = match severity {
+                Severity::Error => RED,
+                Severity::Warning => YELLOW,
+                Severity::Note | Severity::Remark => CYAN,
+            };
+            let _ = write!(
+                output,
+                "{} ",
+                self.paint(color, &format!("{}:", severity.name()))
+            );
+        }
+        let _ = writeln!(output, "{}", self.paint(BOLD, text));
+    }
+
+    /// Writes the source excerpt, caret and fix-it printed in the log, if any.
+    fn excerpt(
+        &self,
+        output: &mut String,
+        source_excerpt: Option<&str>,
+        caret: Option<&str>,
+        fixit: Option<&str>,
+    ) {
+        let (Some(source_excerpt), Some(caret)) = (source_excerpt, caret) else {
+            return;
+        };
+        let _ = writeln!(output, "{}", source_excerpt);
+        let _ = writeln!(output, "{}", self.paint(GREEN, caret));
+        if let Some(fixit) = fixit {
+            let indent = caret.len() - caret.trim_start().len();
+            let _ = writeln!(output, "{}{}", &caret[..indent], self.paint(GREEN, fixit));
+        }
+    }
+
+    /// Returns the text wrapped in the given ANSI style, if colors are enabled.
+    fn paint(&self, style: &str, text: &str) -> String {
+        paint(self.color, style, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BuildLog, MyWarning, Snippet};
+
+    /// Tests the excerpt, caret and notes printed in the log, without colors.
+    #[test]
+    fn test_pretty_formatter_render() {
+        let log = "/src/A.swift:3:9: error: cannot find 'foo' in scope\n\
+                   \x20   let x = foo()\n\
+                   \x20           ^~~\n\
+                   /src/B.swift:1:1: note: did you mean 'bar'?\n";
+        let build_log = BuildLog::<MyWarning>::parse(log);
+        let output = PrettyFormatter::new()
+            .color(false)
+            .render(build_log.diagnostics());
+        assert_eq!(
+            output,
+            "/src/A.swift:3:9: error: cannot find 'foo' in scope\n\
+             \x20   let x = foo()\n\
+             \x20           ^~~\n\
+             /src/B.swift:1:1: note: did you mean 'bar'?\n"
+        );
+    }
+
+    /// Tests the colors of the severity and the caret under an attached snippet.
+    #[test]
+    fn test_pretty_formatter_colors_snippet() {
+        let mut diagnostics = crate::parse_log::<MyWarning>("/src/A.swift:2:5: warning: unused\n");
+        let snippet = Snippet::from_source("let a = 1\nlet b = 2\n", 2, 5, 0);
+        diagnostics[0]
+            .code_fragment
+            .as_mut()
+            .unwrap()
+            .attach_snippet(snippet);
+
+        let output = PrettyFormatter::new().render(&diagnostics);
+        assert_eq!(
+            output,
+            "\x1b[1m/src/A.swift:2:5:\x1b[0m \x1b[1;33mwarning:\x1b[0m \x1b[1munused\x1b[0m\n\
+             2 | let b = 2\n  \
+               | \x1b[1;32m    ^\x1b[0m\n"
+        );
+        assert!(!PrettyFormatter::new()
+            .snippets(false)
+            .render(&diagnostics)
+            .contains("let b"));
+    }
+}
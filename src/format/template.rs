@@ -0,0 +1,168 @@
+//! Rendering of parsed diagnostics through a user-supplied Handlebars template, available
+//! with the `template` feature.
+
+use std::path::Path;
+
+use handlebars::Handlebars;
+use serde::Serialize;
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::{LogFile, TaskMessage};
+
+/// The name the template is registered under.
+const TEMPLATE_NAME: &str = "diagnostic";
+
+/// Reports why a template could not be loaded or rendered.
+#[derive(Debug, Error)]
+pub enum TemplateError {
+    /// The template file could not be read.
+    #[error("cannot read template {path}: {reason}")]
+    Io { path: String, reason: String },
+    /// The template is not valid Handlebars.
+    #[error("invalid template: {reason}")]
+    Parse { reason: String },
+    /// The template refers to a helper or partial that does not exist.
+    #[error("cannot render template: {reason}")]
+    Render { reason: String },
+}
+
+/// Renders each diagnostic through a [Handlebars](https://handlebarsjs.com) template,
+/// so bespoke formats can be produced without code changes.
+///
+/// The template is rendered once per diagnostic, with these variables:
+///
+/// * `index` - The position of the diagnostic, starting at 0.
+/// * `path` - The absolute path of the file.
+/// * `line`, `column`, `severity`, `text` - The location, severity keyword and text of
+///   the diagnostic, or `null` for lines without a location.
+/// * `rule`, `flag`, `category` - The lint rule, warning flag and category, if known.
+/// * `payload` - The fields of the task payload, such as `payload.queue`, if any.
+///
+/// Values are not escaped, and variables that are not set render as empty text.
+///
+/// ```
+/// use xcode_log_parser::format::template::TemplateFormatter;
+/// use xcode_log_parser::{parse_log, MyWarning};
+///
+/// let formatter = TemplateFormatter::new("{{path}}|{{line}}|{{severity}}|{{text}}\n").unwrap();
+/// let diagnostics = parse_log::<MyWarning>("/src/A.swift:1:2: warning: unused\n");
+/// assert_eq!(formatter.render(&diagnostics).unwrap(), "/src/A.swift|1|warning|unused\n");
+/// ```
+#[derive(Debug, Clone)]
+pub struct TemplateFormatter {
+    registry: Handlebars<'static>,
+}
+
+impl TemplateFormatter {
+    /// Creates a `TemplateFormatter` from the text of a template.
+    ///
+    /// # Arguments
+    ///
+    /// * `template` - The Handlebars template rendered for each diagnostic.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, TemplateError>` - The formatter, or the reason the template is invalid.
+    pub fn new(template: &str) -> Result<Self, TemplateError> {
+        let mut registry = Handlebars::new();
+        registry.register_escape_fn(handlebars::no_escape);
+        registry
+            .register_template_string(TEMPLATE_NAME, template)
+            .map_err(|error| TemplateError::Parse {
+                reason: error.to_string(),
+            })?;
+        Ok(TemplateFormatter { registry })
+    }
+
+    /// Creates a `TemplateFormatter` from a template file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the Handlebars template.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, TemplateError>` - The formatter, or the reason the template could not be
+    ///   read or is invalid.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, TemplateError> {
+        let path = path.as_ref();
+        let template = std::fs::read_to_string(path).map_err(|error| TemplateError::Io {
+            path: path.display().to_string(),
+            reason: error.to_string(),
+        })?;
+        Self::new(&template)
+    }
+
+    /// Renders the template for each diagnostic and joins the results.
+    ///
+    /// # Arguments
+    ///
+    /// * `diagnostics` - The parsed diagnostics to render.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, TemplateError>` - The rendered diagnostics, or the reason the template
+    ///   could not be rendered.
+    pub fn render<T: TaskMessage + Serialize>(
+        &self,
+        diagnostics: &[LogFile<T>],
+    ) -> Result<String, TemplateError> {
+        let mut output = String::new();
+        for (index, log_file) in diagnostics.iter().enumerate() {
+            let rendered = self
+                .registry
+                .render(TEMPLATE_NAME, &variables(index, log_file))
+                .map_err(|error| TemplateError::Render {
+                    reason: error.to_string(),
+                })?;
+            output.push_str(&rendered);
+        }
+        Ok(output)
+    }
+}
+
+/// Returns the variables a diagnostic is rendered with.
+fn variables<T: TaskMessage + Serialize>(index: usize, log_file: &LogFile<T>) -> Value {
+    let mut variables = json!({ "index": index, "path": log_file.absolute_path() });
+    if let Some(code_fragment) = log_file.code_fragment() {
+        let payload = code_fragment
+            .task_info()
+            .and_then(|message| serde_json::to_value(message.task()).ok());
+        variables["line"] = json!(code_fragment.line());
+        variables["column"] = json!(code_fragment.column());
+        variables["severity"] = json!(code_fragment.severity().map(|severity| severity.name()));
+        variables["text"] = json!(code_fragment.text());
+        variables["rule"] = json!(code_fragment.rule());
+        variables["flag"] = json!(code_fragment.flag());
+        variables["category"] = json!(code_fragment.category());
+        variables["payload"] = json!(payload);
+    }
+    variables
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_log, MyWarning};
+
+    /// Tests the variables available to the template, including payload fields.
+    #[test]
+    fn test_template_formatter_render() {
+        let template = "{{index}} {{path}}:{{line}}:{{column}} [{{severity}}] \
+                        {{#if payload}}{{payload.queue}}: {{payload.summary}}{{else}}{{text}}{{/if}}\
+                        {{#if flag}} ({{flag}}){{/if}}\n";
+        let formatter = TemplateFormatter::new(template).unwrap();
+        let log = "/src/A.swift:3:7: warning: s#{\"queue\": \"API\", \"summary\": \"Retry\"}#s\n\
+                   /src/B.m:9:1: error: 'f' is deprecated [-Wdeprecated-declarations]\n";
+        assert_eq!(
+            formatter.render(&parse_log::<MyWarning>(log)).unwrap(),
+            "0 /src/A.swift:3:7 [warning] API: Retry\n\
+             1 /src/B.m:9:1 [error] 'f' is deprecated [-Wdeprecated-declarations] (-Wdeprecated-declarations)\n"
+        );
+        assert!(matches!(
+            TemplateFormatter::new("{{#if path}}"),
+            Err(TemplateError::Parse { .. })
+        ));
+    }
+}
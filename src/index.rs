@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::ops::{Bound, RangeBounds};
+
+use crate::{Fingerprint, LogFile, TaskMessage};
+
+/// An index over parsed diagnostics, answering lookups by file, line range and
+/// fingerprint without scanning every diagnostic.
+///
+/// ```
+/// use xcode_log_parser::{BuildLog, Fingerprint, MyWarning};
+///
+/// let log = "/src/A.swift:12:5: warning: unused variable 'x'\n\
+///            /src/A.swift:40:1: error: cannot find 'y' in scope\n\
+///            /src/B.swift:3:2: warning: unused variable 'z'\n";
+/// let build_log = BuildLog::<MyWarning>::parse(log);
+/// let index = build_log.index();
+/// assert_eq!(index.diagnostics_in_file("/src/A.swift").len(), 2);
+/// assert_eq!(index.diagnostics_in_range("/src/A.swift", 10..=20).len(), 1);
+///
+/// let fingerprint = Fingerprint::of(&build_log.diagnostics()[2]).unwrap();
+/// assert_eq!(index.by_fingerprint(&fingerprint).len(), 1);
+/// ```
+#[derive(Debug)]
+pub struct LogIndex<'a, T: TaskMessage> {
+    /// The diagnostics of each file, sorted by location, those without one first.
+    files: HashMap<&'a str, Vec<&'a LogFile<T>>>,
+    /// The diagnostics of each message text, to match fingerprints.
+    texts: HashMap<&'a str, Vec<&'a LogFile<T>>>,
+}
+
+impl<'a, T: TaskMessage> LogIndex<'a, T> {
+    /// Indexes parsed diagnostics.
+    ///
+    /// # Arguments
+    ///
+    /// * `diagnostics` - The parsed diagnostics, such as [`BuildLog::diagnostics`](crate::BuildLog::diagnostics).
+    pub fn new(diagnostics: &'a [LogFile<T>]) -> Self {
+        let mut files: HashMap<&str, Vec<&LogFile<T>>> = HashMap::new();
+        let mut texts: HashMap<&str, Vec<&LogFile<T>>> = HashMap::new();
+        for log_file in diagnostics {
+            files
+                .entry(log_file.absolute_path())
+                .or_default()
+                .push(log_file);
+            if let Some(code_fragment) = log_file.code_fragment() {
+                texts
+                    .entry(code_fragment.text())
+                    .or_default()
+                    .push(log_file);
+            }
+        }
+        for diagnostics in files.values_mut() {
+            diagnostics.sort_by_key(|log_file| {
+                log_file
+                    .code_fragment()
+                    .map(|code_fragment| (code_fragment.line(), code_fragment.column()))
+            });
+        }
+        LogIndex { files, texts }
+    }
+
+    /// Returns the diagnostics pointing at a file, sorted by line, then column.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The absolute path of the file, as printed in the log.
+    pub fn diagnostics_in_file(&self, path: &str) -> &[&'a LogFile<T>] {
+        self.files.get(path).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the diagnostics pointing at a range of lines of a file, such as the lines
+    /// changed by a pull request, sorted by line, then column.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The absolute path of the file, as printed in the log.
+    /// * `lines` - The lines, starting at 1, such as `10..=20`.
+    pub fn diagnostics_in_range(
+        &self,
+        path: &str,
+        lines: impl RangeBounds<usize>,
+    ) -> &[&'a LogFile<T>] {
+        let diagnostics = self.diagnostics_in_file(path);
+        let line =
+            |log_file: &&LogFile<T>| log_file.code_fragment().map(|fragment| fragment.line());
+        let start =
+            diagnostics.partition_point(|log_file| match (line(log_file), lines.start_bound()) {
+                (None, _) => true,
+                (Some(line), Bound::Included(start)) => line < *start,
+                (Some(line), Bound::Excluded(start)) => line <= *start,
+                (Some(_), Bound::Unbounded) => false,
+            });
+        let end =
+            diagnostics.partition_point(|log_file| match (line(log_file), lines.end_bound()) {
+                (None, _) => true,
+                (Some(line), Bound::Included(end)) => line <= *end,
+                (Some(line), Bound::Excluded(end)) => line < *end,
+                (Some(_), Bound::Unbounded) => true,
+            });
+        &diagnostics[start..end.max(start)]
+    }
+
+    /// Returns the diagnostics matching a fingerprint, such as one of a [`Baseline`](crate::Baseline).
+    ///
+    /// Diagnostics are matched by path, severity and text, like [`diff`](crate::diff) does,
+    /// so the line of the fingerprint is ignored. They are sorted in the order of the log.
+    ///
+    /// # Arguments
+    ///
+    /// * `fingerprint` - The fingerprint of the diagnostic.
+    pub fn by_fingerprint(&self, fingerprint: &Fingerprint) -> Vec<&'a LogFile<T>> {
+        let Some(diagnostics) = self.texts.get(fingerprint.text()) else {
+            return Vec::new();
+        };
+        diagnostics
+            .iter()
+            .filter(|log_file| fingerprint.matches(log_file))
+            .copied()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_log, Fingerprint, LogIndex, MyWarning};
+
+    /// Tests looking diagnostics up by file, line range and fingerprint.
+    #[test]
+    fn test_log_index_lookups() {
+        let log = "/src/A.swift:40:1: error: cannot find 'y' in scope\n\
+                   /src/A.swift:12:5: warning: unused variable 'x'\n\
+                   /src/A.swift:20:3: warning: unused variable 'x'\n\
+                   /src/B.swift:3:2: warning: unused variable 'x'\n\
+                   /src/A.swift:12:1: warning: unused import\n";
+        let diagnostics = parse_log::<MyWarning>(log);
+        let index = LogIndex::new(&diagnostics);
+        let lines = |found: &[&crate::LogFile<MyWarning>]| -> Vec<(usize, usize)> {
+            found
+                .iter()
+                .map(|log_file| {
+                    let code_fragment = log_file.code_fragment().unwrap();
+                    (code_fragment.line(), code_fragment.column())
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            lines(index.diagnostics_in_file("/src/A.swift")),
+            vec![(12, 1), (12, 5), (20, 3), (40, 1)]
+        );
+        assert!(index.diagnostics_in_file("/src/C.swift").is_empty());
+        assert_eq!(
+            lines(index.diagnostics_in_range("/src/A.swift", 12..=20)),
+            vec![(12, 1), (12, 5), (20, 3)]
+        );
+        assert_eq!(
+            lines(index.diagnostics_in_range("/src/A.swift", 13..40)),
+            vec![(20, 3)]
+        );
+        assert_eq!(
+            lines(index.diagnostics_in_range("/src/A.swift", 41..)),
+            vec![]
+        );
+        assert_eq!(
+            lines(index.diagnostics_in_range("/src/A.swift", ..=12)),
+            vec![(12, 1), (12, 5)]
+        );
+
+        let fingerprint = Fingerprint::of(&diagnostics[2]).unwrap();
+        assert_eq!(
+            lines(&index.by_fingerprint(&fingerprint)),
+            vec![(12, 5), (20, 3)]
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::RegexParse;
+
+/// Represents the kind of a failure of the build system itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InfrastructureErrorKind {
+    /// The build service crashed, as in `The Xcode build system has crashed`.
+    BuildSystemCrashed,
+    /// The build database is used by another build, as in `database is locked`.
+    DatabaseLocked,
+    /// The build service could not start the build, as in
+    /// `Build service could not create build operation`.
+    BuildServiceUnavailable,
+}
+
+/// Represents a failure of the Xcode build system rather than of the code being built.
+///
+/// Such failures are usually transient, caused by concurrent builds sharing derived data
+/// or a crash of the build service, so CI can retry the build instead of reporting them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InfrastructureError {
+    kind: InfrastructureErrorKind,
+    message: String,
+}
+
+impl InfrastructureError {
+    /// Returns the kind of the failure.
+    pub fn kind(&self) -> InfrastructureErrorKind {
+        self.kind
+    }
+
+    /// Returns the message describing the failure.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl RegexParse for InfrastructureError {
+    /// Returns the regular expression used to parse a build system failure.
+    fn regex_value() -> &'static Regex {
+        static REGEX: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(
+                r#"^(?:[\w-]+: )?(?:error: )?(?:unexpected service error: )?(?P<message>(?P<crashed>The Xcode build system has crashed|Build service .*crashed)|(?P<locked>unable to attach DB|.*database is locked)|(?P<service>Build service could not (?:create build operation|start build operation)|unable to initiate PIF transfer session)).*$"#,
+            )
+            .unwrap()
+        });
+        &REGEX
+    }
+
+    /// Creates a new `InfrastructureError` from the given string using regular expression parsing.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - A string slice that holds the log line to be parsed.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self>` - An `InfrastructureError` instance if parsing is successful, otherwise `None`.
+    fn new_from_regex(haystack: &str) -> Option<Self> {
+        let haystack = haystack.trim_end();
+        let cap = &Self::regex_value().captures(haystack)?;
+        let kind = if cap.name("crashed").is_some() {
+            InfrastructureErrorKind::BuildSystemCrashed
+        } else if cap.name("locked").is_some() {
+            InfrastructureErrorKind::DatabaseLocked
+        } else {
+            InfrastructureErrorKind::BuildServiceUnavailable
+        };
+
+        Some(InfrastructureError {
+            kind,
+            message: haystack[cap.name("message")?.start()..].to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BuildLog, MyWarning};
+
+    /// Tests recognizing crashes of the build service, locked databases and failed build operations.
+    #[test]
+    fn test_infrastructure_error_parse() {
+        let log = "/src/A.swift:1:2: warning: unused\n\
+                   error: unable to attach DB: error: accessing build database \"/DerivedData/App/Build/Intermediates.noindex/XCBuildData/build.db\": database is locked Possibly there are two concurrent builds running in the same filesystem location.\n\
+                   xcodebuild: error: unexpected service error: The Xcode build system has crashed. Build again to continue.\n\
+                   Build service could not create build operation: unknown error while handling message: MsgHandlingError(message: \"unable to initiate PIF transfer session (operation in progress?)\")\n\
+                   ** BUILD FAILED **\n";
+        let build_log = BuildLog::<MyWarning>::parse(log);
+
+        let errors: Vec<_> = build_log
+            .infrastructure_errors()
+            .iter()
+            .map(InfrastructureError::kind)
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                InfrastructureErrorKind::DatabaseLocked,
+                InfrastructureErrorKind::BuildSystemCrashed,
+                InfrastructureErrorKind::BuildServiceUnavailable,
+            ]
+        );
+        assert_eq!(
+            build_log.infrastructure_errors()[1].message(),
+            "The Xcode build system has crashed. Build again to continue."
+        );
+        assert!(build_log.infrastructure_errors()[0]
+            .message()
+            .starts_with("unable to attach DB: "));
+        assert_eq!(build_log.diagnostics().len(), 1);
+        assert!(InfrastructureError::new_from_regex("/src/A.swift:1:2: error: oops").is_none());
+    }
+}
@@ -0,0 +1,368 @@
+//! Filing of GitHub issues from task payloads, available with the `github-issues` feature.
+
+use std::fmt;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{
+    encode_query, task_fingerprint, HttpTransport, IntegrationError, Location, Method, Request,
+    TaskRef, TaskSink, Transport, REDACTED,
+};
+use crate::DynTaskMessage;
+
+/// The repository, commit and token used by a [`GitHubClient`].
+///
+/// It implements `Deserialize`, so it can be read from a configuration file, or
+/// from the environment of a GitHub Actions run with [`GitHubConfig::from_env`].
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+pub struct GitHubConfig {
+    /// The repository the issues are filed in, as `owner/name`.
+    pub repository: String,
+    /// A token allowed to create issues in the repository.
+    pub token: String,
+    /// The commit the permalinks point at.
+    pub sha: String,
+    /// The checkout directory, removed from diagnostic paths to build permalinks.
+    pub source_root: String,
+    /// The URL of the REST API, `https://api.github.com` by default.
+    #[serde(default = "default_api_url")]
+    pub api_url: String,
+    /// The URL of the web interface, `https://github.com` by default.
+    #[serde(default = "default_server_url")]
+    pub server_url: String,
+}
+
+fn default_api_url() -> String {
+    "https://api.github.com".to_string()
+}
+
+fn default_server_url() -> String {
+    "https://github.com".to_string()
+}
+
+impl fmt::Debug for GitHubConfig {
+    /// Formats the configuration without its token.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GitHubConfig")
+            .field("repository", &self.repository)
+            .field("token", &REDACTED)
+            .field("sha", &self.sha)
+            .field("source_root", &self.source_root)
+            .field("api_url", &self.api_url)
+            .field("server_url", &self.server_url)
+            .finish()
+    }
+}
+
+impl GitHubConfig {
+    /// Reads the configuration from the variables GitHub Actions sets: `GITHUB_REPOSITORY`,
+    /// `GITHUB_TOKEN`, `GITHUB_SHA`, `GITHUB_WORKSPACE`, and optionally `GITHUB_API_URL`
+    /// and `GITHUB_SERVER_URL`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, IntegrationError>` - The configuration, or the first required variable that is not set.
+    pub fn from_env() -> Result<Self, IntegrationError> {
+        let var = |name: &str| {
+            std::env::var(name).map_err(|_| IntegrationError::MissingConfig {
+                name: name.to_string(),
+            })
+        };
+        Ok(GitHubConfig {
+            repository: var("GITHUB_REPOSITORY")?,
+            token: var("GITHUB_TOKEN")?,
+            sha: var("GITHUB_SHA")?,
+            source_root: var("GITHUB_WORKSPACE")?,
+            api_url: var("GITHUB_API_URL").unwrap_or_else(|_| default_api_url()),
+            server_url: var("GITHUB_SERVER_URL").unwrap_or_else(|_| default_server_url()),
+        })
+    }
+}
+
+/// A client creating GitHub issues through the REST API.
+///
+/// Each `warning:` task becomes an issue titled with [`DynTaskMessage::task_summary`].
+/// Its body names [`DynTaskMessage::task_queue`] and links to the file and line of the
+/// warning at the configured commit, followed by the [`task_fingerprint`], so tasks
+/// with an open issue are not filed again. Issues are closed as completed.
+///
+/// ```no_run
+/// use xcode_log_parser::integrations::github::{GitHubClient, GitHubConfig};
+/// use xcode_log_parser::integrations::TaskSink;
+/// use xcode_log_parser::{parse_log, MyWarning};
+///
+/// let client = GitHubClient::new(GitHubConfig::from_env().unwrap()).labels(["tech-debt"]);
+/// let diagnostics = parse_log::<MyWarning>(&std::fs::read_to_string("build.log").unwrap());
+/// for issue in client.create_issues(&diagnostics).unwrap() {
+///     println!("{}: {}", issue.key(), issue.message());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct GitHubClient<Tr: Transport = HttpTransport> {
+    config: GitHubConfig,
+    labels: Vec<String>,
+    transport: Tr,
+}
+
+impl GitHubClient {
+    /// Creates a new `GitHubClient`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The repository, commit and token to use.
+    pub fn new(config: GitHubConfig) -> Self {
+        GitHubClient {
+            config,
+            labels: Vec::new(),
+            transport: HttpTransport,
+        }
+    }
+}
+
+impl<Tr: Transport> GitHubClient<Tr> {
+    /// Returns the client sending its requests through the given transport.
+    pub fn with_transport<U: Transport>(self, transport: U) -> GitHubClient<U> {
+        GitHubClient {
+            config: self.config,
+            labels: self.labels,
+            transport,
+        }
+    }
+
+    /// Sets the labels added to the created issues.
+    pub fn labels<S: Into<String>>(mut self, labels: impl IntoIterator<Item = S>) -> Self {
+        self.labels = labels.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Returns the link to the given line at the configured commit.
+    ///
+    /// # Arguments
+    ///
+    /// * `absolute_path` - The path of the file, inside the source root.
+    /// * `line` - The line number.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - The permalink, or `None` if the file is outside the source root.
+    pub fn permalink(&self, absolute_path: &str, line: usize) -> Option<String> {
+        let relative_path = Path::new(absolute_path)
+            .strip_prefix(&self.config.source_root)
+            .ok()?;
+        Some(format!(
+            "{}/{}/blob/{}/{}#L{}",
+            self.config.server_url.trim_end_matches('/'),
+            self.config.repository,
+            self.config.sha,
+            relative_path.to_str()?,
+            line
+        ))
+    }
+}
+
+impl<Tr: Transport> TaskSink for GitHubClient<Tr> {
+    fn name(&self) -> &str {
+        "github"
+    }
+
+    fn submit(
+        &self,
+        task: &dyn DynTaskMessage,
+        location: &Location,
+    ) -> Result<TaskRef, IntegrationError> {
+        let url = format!("{}/repos/{}/issues", self.api_url(), self.config.repository);
+        let response = self.send(
+            Method::Post,
+            url.clone(),
+            json!({
+                "title": task.task_summary(),
+                "body": format!(
+                    "Queue: {}\n\nReported at {}\n\nFingerprint: `{}`",
+                    task.task_queue(),
+                    self.reported_at(location),
+                    task_fingerprint(task, location)
+                ),
+                "labels": self.labels,
+            }),
+        )?;
+        task_ref(&url, &response)
+    }
+
+    /// Searches for an open issue whose body holds the fingerprint.
+    fn find_open(&self, fingerprint: &str) -> Result<Option<TaskRef>, IntegrationError> {
+        let query = format!(
+            "repo:{} is:issue is:open in:body \"{}\"",
+            self.config.repository, fingerprint
+        );
+        let url = format!(
+            "{}/search/issues?q={}",
+            self.api_url(),
+            encode_query(&query)
+        );
+        let response = self.send(Method::Get, url.clone(), Value::Null)?;
+        let items =
+            response["items"]
+                .as_array()
+                .ok_or_else(|| IntegrationError::InvalidResponse {
+                    url: url.clone(),
+                    body: response.to_string(),
+                })?;
+        items.first().map(|item| task_ref(&url, item)).transpose()
+    }
+
+    /// Comments the current location of the task on the issue.
+    fn update(
+        &self,
+        existing: &TaskRef,
+        _task: &dyn DynTaskMessage,
+        location: &Location,
+    ) -> Result<TaskRef, IntegrationError> {
+        self.send(
+            Method::Post,
+            format!(
+                "{}/repos/{}/issues/{}/comments",
+                self.api_url(),
+                self.config.repository,
+                existing.key().trim_start_matches('#')
+            ),
+            json!({ "body": format!("Still reported at {}", self.reported_at(location)) }),
+        )?;
+        Ok(existing.clone())
+    }
+
+    /// Comments on the issue and closes it as completed.
+    fn close(&self, existing: &TaskRef) -> Result<(), IntegrationError> {
+        let url = format!(
+            "{}/repos/{}/issues/{}",
+            self.api_url(),
+            self.config.repository,
+            existing.key().trim_start_matches('#')
+        );
+        self.send(
+            Method::Post,
+            format!("{}/comments", url),
+            json!({ "body": "No longer reported in the build log." }),
+        )?;
+        self.send(
+            Method::Patch,
+            url,
+            json!({ "state": "closed", "state_reason": "completed" }),
+        )?;
+        Ok(())
+    }
+}
+
+impl<Tr: Transport> GitHubClient<Tr> {
+    /// Returns the URL of the REST API without a trailing slash.
+    fn api_url(&self) -> &str {
+        self.config.api_url.trim_end_matches('/')
+    }
+
+    /// Returns the permalink of the location, or its path and line outside the source root.
+    fn reported_at(&self, location: &Location) -> String {
+        let (absolute_path, line) = (location.absolute_path(), location.line());
+        match self.permalink(absolute_path, line) {
+            Some(permalink) => permalink,
+            None => format!("`{}:{}`", absolute_path, line),
+        }
+    }
+
+    /// Sends an authenticated request.
+    fn send(&self, method: Method, url: String, body: Value) -> Result<Value, IntegrationError> {
+        self.transport.send(&Request {
+            method,
+            url,
+            headers: vec![
+                (
+                    "Authorization".to_string(),
+                    format!("Bearer {}", self.config.token),
+                ),
+                (
+                    "Accept".to_string(),
+                    "application/vnd.github+json".to_string(),
+                ),
+                ("User-Agent".to_string(), "xcode-log-parser".to_string()),
+            ],
+            body,
+        })
+    }
+}
+
+/// Returns the issue described by the `number` and `html_url` of a response.
+fn task_ref(url: &str, issue: &Value) -> Result<TaskRef, IntegrationError> {
+    let number = issue["number"]
+        .as_u64()
+        .ok_or_else(|| IntegrationError::InvalidResponse {
+            url: url.to_string(),
+            body: issue.to_string(),
+        })?;
+    Ok(TaskRef::new(
+        format!("#{}", number),
+        issue["html_url"].as_str().map(str::to_string),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrations::tests::RecordingTransport;
+    use crate::{parse_log, MyWarning};
+
+    fn config() -> GitHubConfig {
+        GitHubConfig {
+            repository: "octo/app".to_string(),
+            token: "ghp_abc".to_string(),
+            sha: "0123abc".to_string(),
+            source_root: "/work/app".to_string(),
+            api_url: default_api_url(),
+            server_url: default_server_url(),
+        }
+    }
+
+    /// Tests the issue requested for a task, with its permalink and labels.
+    #[test]
+    fn test_github_client_create_issues() {
+        let transport = RecordingTransport::new(json!({ "items": [] }), json!({ "number": 12 }));
+        let client = GitHubClient::new(config())
+            .labels(["tech-debt"])
+            .with_transport(transport);
+        let diagnostics = parse_log::<MyWarning>(
+            "/work/app/Sources/A.swift:7:1: warning: s#{\"queue\": \"API\", \"summary\": \"Retry\"}#s\n",
+        );
+
+        let issues = client.create_issues(&diagnostics).unwrap();
+        assert_eq!(issues[0].key(), "#12");
+
+        let requests = client.transport.requests.borrow();
+        assert!(requests[0]
+            .url
+            .starts_with("https://api.github.com/search/issues?q=repo%3Aocto%2Fapp%20is%3Aissue"));
+        assert_eq!(
+            requests[1].url,
+            "https://api.github.com/repos/octo/app/issues"
+        );
+        assert_eq!(requests[1].body["title"], "Retry");
+        assert_eq!(requests[1].body["labels"], json!(["tech-debt"]));
+        let body = requests[1].body["body"].as_str().unwrap();
+        assert!(body.starts_with(
+            "Queue: API\n\nReported at https://github.com/octo/app/blob/0123abc/Sources/A.swift#L7\n\nFingerprint: `xlp-"
+        ));
+    }
+
+    /// Tests that files outside the source root have no permalink.
+    #[test]
+    fn test_github_client_permalink_outside_source_root() {
+        let client = GitHubClient::new(config());
+        assert_eq!(client.permalink("/tmp/Generated.swift", 1), None);
+    }
+
+    /// Tests that the token is left out of the debug output of the client.
+    #[test]
+    fn test_github_client_debug_redacts_token() {
+        let debug = format!("{:?}", GitHubClient::new(config()));
+        assert!(debug.contains("octo/app"));
+        assert!(!debug.contains("ghp_abc"));
+    }
+}
@@ -0,0 +1,407 @@
+//! Posting of diagnostics as pull request review comments, available with the
+//! `github-review` feature.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{HttpTransport, IntegrationError, Method, Request, Transport, REDACTED};
+use crate::{LogFile, TaskMessage};
+
+/// Matches the header of a hunk and captures the first line of its new side.
+static HUNK_HEADER_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^@@ -\d+(?:,\d+)? \+(\d+)(?:,\d+)? @@"#).unwrap());
+
+/// The number of changed files GitHub returns per page, at most.
+const FILES_PER_PAGE: usize = 100;
+
+/// The pull request and token used by a [`ReviewPoster`].
+///
+/// It implements `Deserialize`, so it can be read from a configuration file, or
+/// from the environment of a GitHub Actions run with [`ReviewConfig::from_env`].
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+pub struct ReviewConfig {
+    /// The repository of the pull request, as `owner/name`.
+    pub repository: String,
+    /// A token allowed to review pull requests in the repository.
+    pub token: String,
+    /// The number of the pull request.
+    pub pull_number: u64,
+    /// The checkout directory, removed from diagnostic paths to match the changed files.
+    pub source_root: String,
+    /// The URL of the REST API, `https://api.github.com` by default.
+    #[serde(default = "default_api_url")]
+    pub api_url: String,
+}
+
+fn default_api_url() -> String {
+    "https://api.github.com".to_string()
+}
+
+impl fmt::Debug for ReviewConfig {
+    /// Formats the configuration without its token.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReviewConfig")
+            .field("repository", &self.repository)
+            .field("token", &REDACTED)
+            .field("pull_number", &self.pull_number)
+            .field("source_root", &self.source_root)
+            .field("api_url", &self.api_url)
+            .finish()
+    }
+}
+
+impl ReviewConfig {
+    /// Reads the configuration from the variables GitHub Actions sets for `pull_request`
+    /// events: `GITHUB_REPOSITORY`, `GITHUB_TOKEN`, `GITHUB_WORKSPACE`, `GITHUB_REF`, which
+    /// reads `refs/pull/<number>/merge`, and optionally `GITHUB_API_URL`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, IntegrationError>` - The configuration, or the first required variable that is
+    ///   not set, including `GITHUB_REF` when the run is not for a pull request.
+    pub fn from_env() -> Result<Self, IntegrationError> {
+        let missing = |name: &str| IntegrationError::MissingConfig {
+            name: name.to_string(),
+        };
+        let var = |name: &str| std::env::var(name).map_err(|_| missing(name));
+        let pull_number = var("GITHUB_REF")?
+            .strip_prefix("refs/pull/")
+            .and_then(|rest| rest.split('/').next())
+            .and_then(|number| number.parse().ok())
+            .ok_or_else(|| missing("GITHUB_REF"))?;
+        Ok(ReviewConfig {
+            repository: var("GITHUB_REPOSITORY")?,
+            token: var("GITHUB_TOKEN")?,
+            pull_number,
+            source_root: var("GITHUB_WORKSPACE")?,
+            api_url: var("GITHUB_API_URL").unwrap_or_else(|_| default_api_url()),
+        })
+    }
+}
+
+/// The review created by [`ReviewPoster::post_review`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PostedReview {
+    id: u64,
+    url: Option<String>,
+    comments: usize,
+}
+
+impl PostedReview {
+    /// Returns the identifier of the review.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the web URL of the review, if the response included it.
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    /// Returns the number of comments the review holds.
+    pub fn comments(&self) -> usize {
+        self.comments
+    }
+}
+
+/// A client posting the diagnostics that fall on lines added by a pull request as
+/// comments of a single review, through the REST API.
+///
+/// The changed files of the pull request are fetched first, and each diagnostic on an
+/// added line of their patch becomes a comment anchored to its position in the diff.
+/// All comments are sent in one request, so a build with many warnings does not run
+/// into the rate limits of the API. Repeated diagnostics, such as those reported once
+/// per architecture, are commented once.
+///
+/// ```no_run
+/// use xcode_log_parser::integrations::github_review::{ReviewConfig, ReviewPoster};
+/// use xcode_log_parser::{parse_log, MyWarning};
+///
+/// let poster = ReviewPoster::new(ReviewConfig::from_env().unwrap()).max_comments(20);
+/// let diagnostics = parse_log::<MyWarning>(&std::fs::read_to_string("build.log").unwrap());
+/// if let Some(review) = poster.post_review(&diagnostics).unwrap() {
+///     println!("Posted {} comments", review.comments());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReviewPoster<Tr: Transport = HttpTransport> {
+    config: ReviewConfig,
+    max_comments: usize,
+    transport: Tr,
+}
+
+impl ReviewPoster {
+    /// Creates a new `ReviewPoster` posting at most 50 comments.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The pull request and token to use.
+    pub fn new(config: ReviewConfig) -> Self {
+        ReviewPoster {
+            config,
+            max_comments: 50,
+            transport: HttpTransport,
+        }
+    }
+}
+
+impl<Tr: Transport> ReviewPoster<Tr> {
+    /// Returns the poster sending its requests through the given transport.
+    pub fn with_transport<U: Transport>(self, transport: U) -> ReviewPoster<U> {
+        ReviewPoster {
+            config: self.config,
+            max_comments: self.max_comments,
+            transport,
+        }
+    }
+
+    /// Sets the maximum number of comments of a review. The review body counts the
+    /// diagnostics left out.
+    pub fn max_comments(mut self, max_comments: usize) -> Self {
+        self.max_comments = max_comments;
+        self
+    }
+
+    /// Posts the diagnostics on lines added by the pull request as a review.
+    ///
+    /// # Arguments
+    ///
+    /// * `diagnostics` - The parsed diagnostics.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<PostedReview>, IntegrationError>` - The created review, `None` if no
+    ///   diagnostic falls on an added line, or the reason a request failed.
+    pub fn post_review<T: TaskMessage>(
+        &self,
+        diagnostics: &[LogFile<T>],
+    ) -> Result<Option<PostedReview>, IntegrationError> {
+        let positions = self.diff_positions()?;
+        let mut seen = HashSet::new();
+        let mut comments = Vec::new();
+        for log_file in diagnostics {
+            let Some(code_fragment) = log_file.code_fragment() else {
+                continue;
+            };
+            let Some(path) = Path::new(log_file.absolute_path())
+                .strip_prefix(&self.config.source_root)
+                .ok()
+                .and_then(Path::to_str)
+            else {
+                continue;
+            };
+            let Some(&position) = positions
+                .get(path)
+                .and_then(|lines| lines.get(&code_fragment.line()))
+            else {
+                continue;
+            };
+            let text = match code_fragment.task_info() {
+                Some(message) => message.task().task_summary(),
+                None => code_fragment.text().to_string(),
+            };
+            let body = match code_fragment.severity() {
+                Some(severity) => format!("**{}:** {}", severity.name(), text),
+                None => text,
+            };
+            if seen.insert((path, position, body.clone())) {
+                comments.push(json!({ "path": path, "position": position, "body": body }));
+            }
+        }
+        if comments.is_empty() {
+            return Ok(None);
+        }
+
+        let left_out = comments.len().saturating_sub(self.max_comments);
+        comments.truncate(self.max_comments);
+        let mut body = format!(
+            "{} diagnostics reported on lines changed by this pull request.",
+            comments.len() + left_out
+        );
+        if left_out > 0 {
+            body.push_str(&format!(" {} are not commented.", left_out));
+        }
+        let count = comments.len();
+        let url = format!("{}/reviews", self.pull_url());
+        let response = self.send(
+            Method::Post,
+            url.clone(),
+            json!({ "event": "COMMENT", "body": body, "comments": comments }),
+        )?;
+        let id = response["id"]
+            .as_u64()
+            .ok_or_else(|| IntegrationError::InvalidResponse {
+                url,
+                body: response.to_string(),
+            })?;
+        Ok(Some(PostedReview {
+            id,
+            url: response["html_url"].as_str().map(str::to_string),
+            comments: count,
+        }))
+    }
+
+    /// Returns the diff position of each added line, by file path, fetching every page
+    /// of changed files.
+    fn diff_positions(&self) -> Result<HashMap<String, HashMap<usize, usize>>, IntegrationError> {
+        let mut positions = HashMap::new();
+        for page in 1.. {
+            let url = format!(
+                "{}/files?per_page={}&page={}",
+                self.pull_url(),
+                FILES_PER_PAGE,
+                page
+            );
+            let response = self.send(Method::Get, url.clone(), Value::Null)?;
+            let files = response
+                .as_array()
+                .ok_or_else(|| IntegrationError::InvalidResponse {
+                    url,
+                    body: response.to_string(),
+                })?;
+            for file in files {
+                if let (Some(path), Some(patch)) =
+                    (file["filename"].as_str(), file["patch"].as_str())
+                {
+                    positions.insert(path.to_string(), added_lines(patch));
+                }
+            }
+            if files.len() < FILES_PER_PAGE {
+                break;
+            }
+        }
+        Ok(positions)
+    }
+
+    /// Returns the URL of the pull request in the REST API.
+    fn pull_url(&self) -> String {
+        format!(
+            "{}/repos/{}/pulls/{}",
+            self.config.api_url.trim_end_matches('/'),
+            self.config.repository,
+            self.config.pull_number
+        )
+    }
+
+    /// Sends an authenticated request.
+    fn send(&self, method: Method, url: String, body: Value) -> Result<Value, IntegrationError> {
+        self.transport.send(&Request {
+            method,
+            url,
+            headers: vec![
+                (
+                    "Authorization".to_string(),
+                    format!("Bearer {}", self.config.token),
+                ),
+                (
+                    "Accept".to_string(),
+                    "application/vnd.github+json".to_string(),
+                ),
+                ("User-Agent".to_string(), "xcode-log-parser".to_string()),
+            ],
+            body,
+        })
+    }
+}
+
+/// Returns the diff position of each line added by a patch, by line number.
+///
+/// The line below the first hunk header is at position 1, and positions keep counting
+/// through the following hunk headers.
+fn added_lines(patch: &str) -> HashMap<usize, usize> {
+    let mut positions = HashMap::new();
+    let mut line = 0;
+    for (position, diff_line) in patch.lines().enumerate() {
+        if let Some(captures) = HUNK_HEADER_REGEX.captures(diff_line) {
+            line = captures[1].parse().unwrap_or(0);
+            continue;
+        }
+        match diff_line.as_bytes().first() {
+            Some(b'+') => {
+                positions.insert(line, position);
+                line += 1;
+            }
+            Some(b'-') | Some(b'\\') => {}
+            _ => line += 1,
+        }
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrations::tests::RecordingTransport;
+    use crate::{parse_log, MyWarning};
+
+    /// Tests that only diagnostics on added lines are commented, at their diff position.
+    #[test]
+    fn test_review_poster_post_review() {
+        let files = json!([{
+            "filename": "Sources/A.swift",
+            "patch": "@@ -1,2 +1,3 @@\n let a = 1\n-let b = 2\n+let b = 3\n+let c = 4\n@@ -10,1 +11,2 @@\n func f() {\n+    let x = 1\n",
+        }]);
+        let transport = RecordingTransport::new(
+            files,
+            json!({ "id": 7, "html_url": "https://github.com/r/7" }),
+        );
+        let poster = ReviewPoster::new(ReviewConfig {
+            repository: "octo/app".to_string(),
+            token: "ghp_abc".to_string(),
+            pull_number: 3,
+            source_root: "/work/app".to_string(),
+            api_url: default_api_url(),
+        })
+        .with_transport(transport);
+        let diagnostics = parse_log::<MyWarning>(
+            "/work/app/Sources/A.swift:1:5: warning: unchanged line\n\
+             /work/app/Sources/A.swift:3:5: warning: unused variable 'c'\n\
+             /work/app/Sources/A.swift:3:5: warning: unused variable 'c'\n\
+             /work/app/Sources/A.swift:12:9: error: cannot find 'y' in scope\n\
+             /work/app/Sources/B.swift:3:5: warning: file not changed\n",
+        );
+
+        let review = poster.post_review(&diagnostics).unwrap().unwrap();
+        assert_eq!((review.id(), review.comments()), (7, 2));
+
+        let requests = poster.transport.requests.borrow();
+        assert_eq!(
+            requests[0].url,
+            "https://api.github.com/repos/octo/app/pulls/3/files?per_page=100&page=1"
+        );
+        assert_eq!(
+            requests[1].url,
+            "https://api.github.com/repos/octo/app/pulls/3/reviews"
+        );
+        assert_eq!(requests[1].body["event"], "COMMENT");
+        assert_eq!(
+            requests[1].body["comments"],
+            json!([
+                { "path": "Sources/A.swift", "position": 4, "body": "**warning:** unused variable 'c'" },
+                { "path": "Sources/A.swift", "position": 7, "body": "**error:** cannot find 'y' in scope" },
+            ])
+        );
+    }
+
+    /// Tests that the token is left out of the debug output of the poster.
+    #[test]
+    fn test_review_poster_debug_redacts_token() {
+        let poster = ReviewPoster::new(ReviewConfig {
+            repository: "octo/app".to_string(),
+            token: "ghp_abc".to_string(),
+            pull_number: 3,
+            source_root: "/work/app".to_string(),
+            api_url: default_api_url(),
+        });
+
+        let debug = format!("{:?}", poster);
+        assert!(debug.contains("octo/app"));
+        assert!(!debug.contains("ghp_abc"));
+    }
+}
@@ -0,0 +1,324 @@
+//! Filing of Jira issues from task payloads, available with the `jira` feature.
+
+use std::fmt;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::{json, Value};
+
+use super::{
+    encode_query, task_fingerprint, HttpTransport, IntegrationError, Location, Method, Request,
+    TaskRef, TaskSink, Transport, REDACTED,
+};
+use crate::DynTaskMessage;
+
+/// A client creating Jira issues through the REST API.
+///
+/// Each `warning:` task is filed in the project named by [`DynTaskMessage::task_queue`],
+/// with [`DynTaskMessage::task_summary`] as the summary and the file and line of the
+/// warning in the description. The [`task_fingerprint`] is added as a label, so
+/// tasks with an unresolved issue are not filed again. Closed issues are moved to
+/// the `Done` status category.
+///
+/// ```no_run
+/// use xcode_log_parser::integrations::jira::JiraClient;
+/// use xcode_log_parser::integrations::TaskSink;
+/// use xcode_log_parser::{parse_log, MyWarning};
+///
+/// let client = JiraClient::new("https://example.atlassian.net", "me@example.com", "token");
+/// let diagnostics = parse_log::<MyWarning>(&std::fs::read_to_string("build.log").unwrap());
+/// for issue in client.create_issues(&diagnostics).unwrap() {
+///     println!("{}: {}", issue.key(), issue.message());
+/// }
+/// ```
+#[derive(Clone)]
+pub struct JiraClient<Tr: Transport = HttpTransport> {
+    base_url: String,
+    authorization: String,
+    issue_type: String,
+    transport: Tr,
+}
+
+impl<Tr: Transport + fmt::Debug> fmt::Debug for JiraClient<Tr> {
+    /// Formats the client without its credentials.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JiraClient")
+            .field("base_url", &self.base_url)
+            .field("authorization", &REDACTED)
+            .field("issue_type", &self.issue_type)
+            .field("transport", &self.transport)
+            .finish()
+    }
+}
+
+impl JiraClient {
+    /// Creates a new `JiraClient` authenticating with an API token.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - The URL of the Jira site, such as `https://example.atlassian.net`.
+    /// * `email` - The email of the account owning the token.
+    /// * `api_token` - The API token.
+    pub fn new(base_url: impl Into<String>, email: &str, api_token: &str) -> Self {
+        JiraClient {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            authorization: format!(
+                "Basic {}",
+                STANDARD.encode(format!("{}:{}", email, api_token))
+            ),
+            issue_type: "Task".to_string(),
+            transport: HttpTransport,
+        }
+    }
+}
+
+impl<Tr: Transport> JiraClient<Tr> {
+    /// Returns the client sending its requests through the given transport.
+    pub fn with_transport<U: Transport>(self, transport: U) -> JiraClient<U> {
+        JiraClient {
+            base_url: self.base_url,
+            authorization: self.authorization,
+            issue_type: self.issue_type,
+            transport,
+        }
+    }
+
+    /// Sets the type of the created issues, `Task` by default.
+    pub fn issue_type(mut self, issue_type: impl Into<String>) -> Self {
+        self.issue_type = issue_type.into();
+        self
+    }
+}
+
+impl<Tr: Transport> TaskSink for JiraClient<Tr> {
+    fn name(&self) -> &str {
+        "jira"
+    }
+
+    fn submit(
+        &self,
+        task: &dyn DynTaskMessage,
+        location: &Location,
+    ) -> Result<TaskRef, IntegrationError> {
+        let url = format!("{}/rest/api/2/issue", self.base_url);
+        let response = self.send(
+            Method::Post,
+            url.clone(),
+            json!({
+                "fields": {
+                    "project": { "key": task.task_queue() },
+                    "summary": task.task_summary(),
+                    "description": format!(
+                        "Reported at {}:{}",
+                        location.absolute_path(),
+                        location.line()
+                    ),
+                    "issuetype": { "name": self.issue_type },
+                    "labels": [task_fingerprint(task, location)],
+                }
+            }),
+        )?;
+        self.task_ref(&url, &response)
+    }
+
+    /// Searches for an unresolved issue labeled with the fingerprint.
+    fn find_open(&self, fingerprint: &str) -> Result<Option<TaskRef>, IntegrationError> {
+        let jql = format!("labels = \"{}\" AND statusCategory != Done", fingerprint);
+        let url = format!(
+            "{}/rest/api/2/search?jql={}&fields=key&maxResults=1",
+            self.base_url,
+            encode_query(&jql)
+        );
+        let response = self.send(Method::Get, url.clone(), Value::Null)?;
+        let issues =
+            response["issues"]
+                .as_array()
+                .ok_or_else(|| IntegrationError::InvalidResponse {
+                    url: url.clone(),
+                    body: response.to_string(),
+                })?;
+        issues
+            .first()
+            .map(|issue| self.task_ref(&url, issue))
+            .transpose()
+    }
+
+    /// Comments the current location of the task on the issue.
+    fn update(
+        &self,
+        existing: &TaskRef,
+        _task: &dyn DynTaskMessage,
+        location: &Location,
+    ) -> Result<TaskRef, IntegrationError> {
+        self.send(
+            Method::Post,
+            format!(
+                "{}/rest/api/2/issue/{}/comment",
+                self.base_url,
+                existing.key()
+            ),
+            json!({
+                "body": format!(
+                    "Still reported at {}:{}",
+                    location.absolute_path(),
+                    location.line()
+                )
+            }),
+        )?;
+        Ok(existing.clone())
+    }
+
+    /// Moves the issue to the first transition into the `Done` status category, with a comment.
+    fn close(&self, existing: &TaskRef) -> Result<(), IntegrationError> {
+        let url = format!(
+            "{}/rest/api/2/issue/{}/transitions",
+            self.base_url,
+            existing.key()
+        );
+        let response = self.send(Method::Get, url.clone(), Value::Null)?;
+        let transition = response["transitions"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|transition| transition["to"]["statusCategory"]["key"] == "done")
+            .and_then(|transition| transition["id"].as_str())
+            .ok_or_else(|| IntegrationError::InvalidResponse {
+                url: url.clone(),
+                body: response.to_string(),
+            })?;
+        self.send(
+            Method::Post,
+            url,
+            json!({
+                "transition": { "id": transition },
+                "update": {
+                    "comment": [{ "add": { "body": "No longer reported in the build log." } }]
+                },
+            }),
+        )?;
+        Ok(())
+    }
+}
+
+impl<Tr: Transport> JiraClient<Tr> {
+    /// Sends an authenticated request.
+    fn send(&self, method: Method, url: String, body: Value) -> Result<Value, IntegrationError> {
+        self.transport.send(&Request {
+            method,
+            url,
+            headers: vec![("Authorization".to_string(), self.authorization.clone())],
+            body,
+        })
+    }
+
+    /// Returns the issue described by the `key` of a response.
+    fn task_ref(&self, url: &str, issue: &Value) -> Result<TaskRef, IntegrationError> {
+        let key = issue["key"]
+            .as_str()
+            .ok_or_else(|| IntegrationError::InvalidResponse {
+                url: url.to_string(),
+                body: issue.to_string(),
+            })?;
+        Ok(TaskRef::new(
+            key,
+            Some(format!("{}/browse/{}", self.base_url, key)),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrations::tests::RecordingTransport;
+    use crate::integrations::{OnDuplicate, Submission};
+    use crate::{parse_log, MyWarning};
+
+    /// Tests the request sent for a task and the returned key.
+    #[test]
+    fn test_jira_client_create_issues() {
+        let transport = RecordingTransport::new(
+            json!({ "issues": [] }),
+            json!({ "id": "10000", "key": "API-42" }),
+        );
+        let client = JiraClient::new("https://jira.example.com/", "me@example.com", "token")
+            .with_transport(transport);
+        let diagnostics = parse_log::<MyWarning>(
+            "/src/A.swift:7:1: warning: s#{\"queue\": \"API\", \"summary\": \"Retry\"}#s\n",
+        );
+
+        let issues = client.create_issues(&diagnostics).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].key(), "API-42");
+        assert_eq!(issues[0].location().line(), 7);
+        assert_eq!(
+            issues[0].task_ref().url(),
+            Some("https://jira.example.com/browse/API-42")
+        );
+
+        let requests = client.transport.requests.borrow();
+        assert!(requests[0]
+            .url
+            .starts_with("https://jira.example.com/rest/api/2/search?jql=labels%20%3D%20%22xlp-"));
+        assert_eq!(requests[1].url, "https://jira.example.com/rest/api/2/issue");
+        assert_eq!(
+            requests[1].headers[0].1,
+            "Basic bWVAZXhhbXBsZS5jb206dG9rZW4="
+        );
+        assert_eq!(requests[1].body["fields"]["project"]["key"], "API");
+        assert_eq!(
+            requests[1].body["fields"]["description"],
+            "Reported at /src/A.swift:7"
+        );
+    }
+
+    /// Tests commenting an open issue found for the task instead of creating one.
+    #[test]
+    fn test_jira_client_updates_open_issue() {
+        let transport =
+            RecordingTransport::new(json!({ "issues": [{ "key": "API-7" }] }), json!({}));
+        let client = JiraClient::new("https://jira.example.com", "me@example.com", "token")
+            .with_transport(transport);
+        let mut dispatcher = crate::integrations::TaskDispatcher::new();
+        let diagnostics = parse_log::<MyWarning>(
+            "/src/A.swift:7:1: warning: s#{\"queue\": \"API\", \"summary\": \"Retry\"}#s\n",
+        );
+
+        let issues = client.create_issues(&diagnostics).unwrap();
+        assert_eq!(issues[0].submission(), Submission::Skipped);
+        assert_eq!(issues[0].key(), "API-7");
+        assert_eq!(client.transport.requests.borrow().len(), 1);
+
+        dispatcher.add(client).on_duplicate(OnDuplicate::Update);
+        let issue = dispatcher.dispatch(&diagnostics).remove(0).unwrap();
+        assert_eq!(issue.submission(), Submission::Updated);
+    }
+
+    /// Tests the transition into the `Done` status category when closing an issue.
+    #[test]
+    fn test_jira_client_close() {
+        let transitions = json!({ "transitions": [
+            { "id": "11", "to": { "statusCategory": { "key": "indeterminate" } } },
+            { "id": "31", "to": { "statusCategory": { "key": "done" } } },
+        ] });
+        let client = JiraClient::new("https://jira.example.com", "me@example.com", "token")
+            .with_transport(RecordingTransport::new(transitions, json!({})));
+
+        client.close(&TaskRef::new("API-7", None)).unwrap();
+        let requests = client.transport.requests.borrow();
+        assert_eq!(
+            requests[1].url,
+            "https://jira.example.com/rest/api/2/issue/API-7/transitions"
+        );
+        assert_eq!(requests[1].body["transition"]["id"], "31");
+    }
+
+    /// Tests that the credentials are left out of the debug output.
+    #[test]
+    fn test_jira_client_debug_redacts_authorization() {
+        let client = JiraClient::new("https://jira.example.com", "me@example.com", "token");
+
+        let debug = format!("{:?}", client);
+        assert!(debug.contains("[redacted]"));
+        assert!(!debug.contains(&client.authorization));
+    }
+}
@@ -0,0 +1,834 @@
+//! Sinks filing issues in issue trackers from the task payloads of parsed diagnostics,
+//! notifiers posting build summaries to chat services, a poster commenting
+//! diagnostics on pull requests, and an exporter of build timelines to tracing backends.
+//!
+//! Each sink, notifier and exporter is behind its own feature. Requests go through a [`Transport`],
+//! which is [`HttpTransport`] by default and can be replaced, for example in tests.
+
+#[cfg(feature = "github-issues")]
+pub mod github;
+#[cfg(feature = "github-review")]
+pub mod github_review;
+#[cfg(feature = "jira")]
+pub mod jira;
+#[cfg(feature = "otel")]
+pub mod otel;
+#[cfg(feature = "slack")]
+pub mod slack;
+#[cfg(feature = "youtrack")]
+pub mod youtrack;
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{self, Debug};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{DynTaskMessage, LogFile, Message, TaskMessage};
+
+/// Printed in place of tokens and other secrets by the `Debug` implementations of the integrations.
+pub(crate) const REDACTED: &str = "[redacted]";
+
+/// Reports why an issue could not be filed.
+#[derive(Debug, Error)]
+pub enum IntegrationError {
+    /// The request could not be sent or its response could not be read.
+    #[error("request to {url} failed: {reason}")]
+    Http { url: String, reason: String },
+    /// The tracker rejected the request.
+    #[error("request to {url} failed with status {status}: {body}")]
+    Status {
+        url: String,
+        status: u16,
+        body: String,
+    },
+    /// A required setting, such as an environment variable, is missing.
+    #[error("missing configuration: {name}")]
+    MissingConfig { name: String },
+    /// The response does not hold the expected fields.
+    #[error("unexpected response from {url}: {body}")]
+    InvalidResponse { url: String, body: String },
+}
+
+/// The HTTP method of a [`Request`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Method {
+    Get,
+    Post,
+    Patch,
+}
+
+/// A JSON request sent to an issue tracker.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    /// The HTTP method.
+    pub method: Method,
+    /// The full URL of the endpoint, including its query.
+    pub url: String,
+    /// The headers sent along with the JSON content type.
+    pub headers: Vec<(String, String)>,
+    /// The JSON body, not sent with [`Method::Get`].
+    pub body: Value,
+}
+
+/// Sends requests to issue trackers.
+pub trait Transport {
+    /// Sends a request and returns its JSON response.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request to send.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Value, IntegrationError>` - The JSON response, a string for responses that are not JSON,
+    ///   or the reason the request failed.
+    fn send(&self, request: &Request) -> Result<Value, IntegrationError>;
+}
+
+/// A [`Transport`] sending requests over HTTP.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HttpTransport;
+
+impl Transport for HttpTransport {
+    fn send(&self, request: &Request) -> Result<Value, IntegrationError> {
+        let http_error = |reason: String| IntegrationError::Http {
+            url: request.url.clone(),
+            reason,
+        };
+        let method = match request.method {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Patch => "PATCH",
+        };
+        let mut http_request = ureq::request(method, &request.url);
+        for (name, value) in &request.headers {
+            http_request = http_request.set(name, value);
+        }
+        let response = match request.method {
+            Method::Get => http_request.call(),
+            Method::Post | Method::Patch => http_request.send_json(&request.body),
+        };
+        match response {
+            Ok(response) => {
+                let text = response
+                    .into_string()
+                    .map_err(|error| http_error(error.to_string()))?;
+                Ok(serde_json::from_str(&text).unwrap_or(Value::String(text)))
+            }
+            Err(ureq::Error::Status(status, response)) => Err(IntegrationError::Status {
+                url: request.url.clone(),
+                status,
+                body: response.into_string().unwrap_or_default(),
+            }),
+            Err(error) => Err(http_error(error.to_string())),
+        }
+    }
+}
+
+/// The place in the source a task warning was reported at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    absolute_path: String,
+    line: usize,
+    column: usize,
+}
+
+impl Location {
+    /// Creates a new `Location`.
+    ///
+    /// # Arguments
+    ///
+    /// * `absolute_path` - The absolute path of the file.
+    /// * `line` - The line number.
+    /// * `column` - The column number.
+    pub fn new(absolute_path: impl Into<String>, line: usize, column: usize) -> Self {
+        Location {
+            absolute_path: absolute_path.into(),
+            line,
+            column,
+        }
+    }
+
+    /// Returns the absolute path of the file.
+    pub fn absolute_path(&self) -> &str {
+        &self.absolute_path
+    }
+
+    /// Returns the line number.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the column number.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+/// Identifies an issue created by a [`TaskSink`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskRef {
+    key: String,
+    url: Option<String>,
+}
+
+impl TaskRef {
+    /// Creates a new `TaskRef`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the issue in the tracker, such as `API-42`.
+    /// * `url` - The web page of the issue, if known.
+    pub fn new(key: impl Into<String>, url: Option<String>) -> Self {
+        TaskRef {
+            key: key.into(),
+            url,
+        }
+    }
+
+    /// Returns the key of the issue in the tracker, such as `API-42`.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the web page of the issue, if known.
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+}
+
+/// A tracker that tasks found in build logs are filed in.
+///
+/// The built-in Jira, YouTrack and GitHub clients implement it, and so can clients
+/// of other trackers, to be used on their own or through a [`TaskDispatcher`].
+pub trait TaskSink {
+    /// Returns the name of the tracker, such as `jira`.
+    fn name(&self) -> &str;
+
+    /// Files an issue for a task.
+    ///
+    /// # Arguments
+    ///
+    /// * `task` - The task parsed from the warning.
+    /// * `location` - The place the warning was reported at.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<TaskRef, IntegrationError>` - The created issue, or the reason it could not be created.
+    fn submit(
+        &self,
+        task: &dyn DynTaskMessage,
+        location: &Location,
+    ) -> Result<TaskRef, IntegrationError>;
+
+    /// Returns the open issue filed for the given [`task_fingerprint`], if any.
+    ///
+    /// Sinks supporting it store the fingerprint on the issues they submit. The
+    /// default implementation does not search, so every task is submitted again.
+    ///
+    /// # Arguments
+    ///
+    /// * `fingerprint` - The fingerprint of the task.
+    fn find_open(&self, fingerprint: &str) -> Result<Option<TaskRef>, IntegrationError> {
+        let _ = fingerprint;
+        Ok(None)
+    }
+
+    /// Records on an existing issue that its task is still reported, such as with a comment.
+    ///
+    /// The default implementation leaves the issue unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `existing` - The issue returned by [`find_open`](Self::find_open).
+    /// * `task` - The task parsed from the warning.
+    /// * `location` - The place the warning was reported at.
+    fn update(
+        &self,
+        existing: &TaskRef,
+        task: &dyn DynTaskMessage,
+        location: &Location,
+    ) -> Result<TaskRef, IntegrationError> {
+        let _ = (task, location);
+        Ok(existing.clone())
+    }
+
+    /// Resolves an issue whose task is no longer reported, such as by commenting and closing it.
+    ///
+    /// The default implementation leaves the issue open.
+    ///
+    /// # Arguments
+    ///
+    /// * `existing` - The issue recorded in [`TrackedIssues`] for the task.
+    fn close(&self, existing: &TaskRef) -> Result<(), IntegrationError> {
+        let _ = existing;
+        Ok(())
+    }
+
+    /// Files one issue per `warning:` task found in the diagnostics, skipping tasks
+    /// that already have an open issue.
+    ///
+    /// # Arguments
+    ///
+    /// * `diagnostics` - The parsed diagnostics.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<CreatedIssue>, IntegrationError>` - The created and existing issues, or the first request that failed.
+    fn create_issues<T: TaskMessage>(
+        &self,
+        diagnostics: &[LogFile<T>],
+    ) -> Result<Vec<CreatedIssue>, IntegrationError>
+    where
+        Self: Sized,
+    {
+        warning_tasks(diagnostics)
+            .map(|(location, task)| file_task(self, task, location, OnDuplicate::Skip))
+            .collect()
+    }
+}
+
+/// What to do with a task that already has an open issue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum OnDuplicate {
+    /// Leave the existing issue unchanged.
+    #[default]
+    Skip,
+    /// Record on the existing issue that the task is still reported.
+    Update,
+}
+
+/// How a task was filed in a [`TaskSink`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Submission {
+    /// A new issue was created.
+    Created,
+    /// An open issue already existed and was left unchanged.
+    Skipped,
+    /// An open issue already existed and was updated.
+    Updated,
+}
+
+/// Returns the fingerprint identifying a task across CI runs, such as `xlp-6f5902ac237024bd`.
+///
+/// It is computed from the summary of the task and the name of the file it is reported
+/// in, so it does not change with the checkout directory or the line of the warning.
+///
+/// # Arguments
+///
+/// * `task` - The task parsed from the warning.
+/// * `location` - The place the warning was reported at.
+pub fn task_fingerprint(task: &dyn DynTaskMessage, location: &Location) -> String {
+    let file_name = Path::new(location.absolute_path())
+        .file_name()
+        .and_then(|file_name| file_name.to_str())
+        .unwrap_or(location.absolute_path());
+
+    // 64-bit FNV-1a, which unlike the standard library hashers is stable across releases.
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let summary = task.task_summary();
+    for byte in summary.bytes().chain([0]).chain(file_name.bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("xlp-{:016x}", hash)
+}
+
+/// Files a task in a sink, unless it already has an open issue.
+fn file_task<T: TaskMessage>(
+    sink: &dyn TaskSink,
+    task: &T,
+    location: Location,
+    on_duplicate: OnDuplicate,
+) -> Result<CreatedIssue, IntegrationError> {
+    let erased = ErasedRef(task);
+    let fingerprint = task_fingerprint(&erased, &location);
+    let (task_ref, submission) = match sink.find_open(&fingerprint)? {
+        Some(existing) => match on_duplicate {
+            OnDuplicate::Skip => (existing, Submission::Skipped),
+            OnDuplicate::Update => (
+                sink.update(&existing, &erased, &location)?,
+                Submission::Updated,
+            ),
+        },
+        None => (sink.submit(&erased, &location)?, Submission::Created),
+    };
+    Ok(CreatedIssue {
+        sink: sink.name().to_string(),
+        fingerprint,
+        task_ref,
+        submission,
+        location,
+        message: task.warning_message_after_created(),
+    })
+}
+
+/// Percent-encodes a value for use in the query of a URL.
+#[cfg(any(feature = "jira", feature = "youtrack", feature = "github-issues"))]
+pub(crate) fn encode_query(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(char::from(byte))
+            }
+            byte => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Fans the tasks found in build logs out to several [`TaskSink`]s.
+///
+/// ```
+/// use xcode_log_parser::integrations::TaskDispatcher;
+/// use xcode_log_parser::{parse_log, MyWarning};
+///
+/// let dispatcher = TaskDispatcher::new();
+/// let diagnostics = parse_log::<MyWarning>("/src/A.swift:1:2: warning: unused\n");
+/// assert!(dispatcher.dispatch(&diagnostics).is_empty());
+/// ```
+#[derive(Default)]
+pub struct TaskDispatcher {
+    sinks: Vec<Box<dyn TaskSink>>,
+    on_duplicate: OnDuplicate,
+}
+
+impl TaskDispatcher {
+    /// Creates a `TaskDispatcher` without sinks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a sink every task is filed in.
+    pub fn add(&mut self, sink: impl TaskSink + 'static) -> &mut Self {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    /// Sets what to do with tasks that already have an open issue, skipping them by default.
+    pub fn on_duplicate(&mut self, on_duplicate: OnDuplicate) -> &mut Self {
+        self.on_duplicate = on_duplicate;
+        self
+    }
+
+    /// Files every `warning:` task found in the diagnostics in every sink.
+    ///
+    /// A failing sink does not stop the others.
+    ///
+    /// # Arguments
+    ///
+    /// * `diagnostics` - The parsed diagnostics.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Result<CreatedIssue, IntegrationError>>` - The outcome of each submission, task by task
+    ///   and in the order the sinks were added.
+    pub fn dispatch<T: TaskMessage>(
+        &self,
+        diagnostics: &[LogFile<T>],
+    ) -> Vec<Result<CreatedIssue, IntegrationError>> {
+        let mut results = Vec::new();
+        for (location, task) in warning_tasks(diagnostics) {
+            for sink in &self.sinks {
+                results.push(file_task(
+                    sink.as_ref(),
+                    task,
+                    location.clone(),
+                    self.on_duplicate,
+                ));
+            }
+        }
+        results
+    }
+
+    /// Files every `warning:` task found in the diagnostics in every sink, like
+    /// [`dispatch`](Self::dispatch), then closes the tracked issues of tasks that are
+    /// no longer reported.
+    ///
+    /// The filed issues are recorded in `tracked`, and the closed ones removed from it,
+    /// so it should be stored for the next run. Issues of sinks that are not added to
+    /// the dispatcher are left untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `diagnostics` - The parsed diagnostics.
+    /// * `tracked` - The issues filed by previous runs.
+    ///
+    /// # Returns
+    ///
+    /// * `SyncReport` - The outcome of each submission and of each attempt to close an issue.
+    pub fn sync<T: TaskMessage>(
+        &self,
+        diagnostics: &[LogFile<T>],
+        tracked: &mut TrackedIssues,
+    ) -> SyncReport {
+        let filed = self.dispatch(diagnostics);
+        for issue in filed.iter().flatten() {
+            tracked.insert(&issue.fingerprint, &issue.sink, issue.task_ref.clone());
+        }
+
+        let reported: HashSet<String> = warning_tasks(diagnostics)
+            .map(|(location, task)| task_fingerprint(&ErasedRef(task), &location))
+            .collect();
+        let mut closed = Vec::new();
+        for sink in &self.sinks {
+            let gone: Vec<(String, TaskRef)> = tracked
+                .issues
+                .iter()
+                .filter(|(fingerprint, _)| !reported.contains(*fingerprint))
+                .filter_map(|(fingerprint, issues)| {
+                    Some((fingerprint.clone(), issues.get(sink.name())?.clone()))
+                })
+                .collect();
+            for (fingerprint, task_ref) in gone {
+                closed.push(sink.close(&task_ref).map(|()| {
+                    tracked.remove(&fingerprint, sink.name());
+                    ClosedIssue {
+                        sink: sink.name().to_string(),
+                        fingerprint,
+                        task_ref,
+                    }
+                }));
+            }
+        }
+        SyncReport { filed, closed }
+    }
+}
+
+/// The issues filed for each [`task_fingerprint`], in each sink, kept between runs by
+/// [`TaskDispatcher::sync`].
+///
+/// It implements `Serialize` and `Deserialize`, so it can be stored between CI runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackedIssues {
+    issues: BTreeMap<String, BTreeMap<String, TaskRef>>,
+}
+
+impl TrackedIssues {
+    /// Creates a `TrackedIssues` without issues.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the issue filed for a task in the given sink, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `fingerprint` - The fingerprint of the task.
+    /// * `sink` - The name of the sink, such as `jira`.
+    pub fn get(&self, fingerprint: &str, sink: &str) -> Option<&TaskRef> {
+        self.issues.get(fingerprint)?.get(sink)
+    }
+
+    /// Records the issue filed for a task in the given sink.
+    pub fn insert(&mut self, fingerprint: &str, sink: &str, task_ref: TaskRef) {
+        self.issues
+            .entry(fingerprint.to_string())
+            .or_default()
+            .insert(sink.to_string(), task_ref);
+    }
+
+    /// Forgets the issue filed for a task in the given sink.
+    pub fn remove(&mut self, fingerprint: &str, sink: &str) -> Option<TaskRef> {
+        let issues = self.issues.get_mut(fingerprint)?;
+        let task_ref = issues.remove(sink);
+        if issues.is_empty() {
+            self.issues.remove(fingerprint);
+        }
+        task_ref
+    }
+
+    /// Returns the number of tasks with a tracked issue.
+    pub fn len(&self) -> usize {
+        self.issues.len()
+    }
+
+    /// Returns whether no issue is tracked.
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// The outcome of [`TaskDispatcher::sync`].
+#[derive(Debug)]
+pub struct SyncReport {
+    filed: Vec<Result<CreatedIssue, IntegrationError>>,
+    closed: Vec<Result<ClosedIssue, IntegrationError>>,
+}
+
+impl SyncReport {
+    /// Returns the outcome of each submission, as returned by [`TaskDispatcher::dispatch`].
+    pub fn filed(&self) -> &[Result<CreatedIssue, IntegrationError>] {
+        &self.filed
+    }
+
+    /// Returns the outcome of closing each issue whose task is no longer reported.
+    pub fn closed(&self) -> &[Result<ClosedIssue, IntegrationError>] {
+        &self.closed
+    }
+}
+
+/// An issue closed because its task is no longer reported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClosedIssue {
+    sink: String,
+    fingerprint: String,
+    task_ref: TaskRef,
+}
+
+impl ClosedIssue {
+    /// Returns the name of the tracker the issue was closed in.
+    pub fn sink(&self) -> &str {
+        &self.sink
+    }
+
+    /// Returns the fingerprint of the task the issue was filed for.
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    /// Returns the closed issue.
+    pub fn task_ref(&self) -> &TaskRef {
+        &self.task_ref
+    }
+}
+
+/// An issue filed from the task payload of a diagnostic, or found open for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreatedIssue {
+    sink: String,
+    fingerprint: String,
+    task_ref: TaskRef,
+    submission: Submission,
+    location: Location,
+    message: String,
+}
+
+impl CreatedIssue {
+    /// Returns the name of the tracker the issue was filed in.
+    pub fn sink(&self) -> &str {
+        &self.sink
+    }
+
+    /// Returns the [`task_fingerprint`] of the task.
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    /// Returns the key of the issue in the tracker, such as `API-42`.
+    pub fn key(&self) -> &str {
+        self.task_ref.key()
+    }
+
+    /// Returns the issue in the tracker.
+    pub fn task_ref(&self) -> &TaskRef {
+        &self.task_ref
+    }
+
+    /// Returns whether the issue was created, or already existed.
+    pub fn submission(&self) -> Submission {
+        self.submission
+    }
+
+    /// Returns the place the task warning was reported at.
+    pub fn location(&self) -> &Location {
+        &self.location
+    }
+
+    /// Returns the message to display now that the issue is created, from
+    /// [`TaskMessage::warning_message_after_created`]. It may be empty.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// Borrows a task as a [`DynTaskMessage`].
+struct ErasedRef<'a, T>(&'a T);
+
+impl<T: Debug> Debug for ErasedRef<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: TaskMessage> DynTaskMessage for ErasedRef<'_, T> {
+    fn task_summary(&self) -> String {
+        self.0.task_summary()
+    }
+
+    fn task_queue(&self) -> String {
+        self.0.task_queue()
+    }
+
+    fn warning_message_after_created(&self) -> String {
+        self.0.warning_message_after_created()
+    }
+}
+
+/// Returns the [`Message::Warning`] tasks of the diagnostics, with the location of each.
+fn warning_tasks<T: TaskMessage>(
+    diagnostics: &[LogFile<T>],
+) -> impl Iterator<Item = (Location, &T)> {
+    diagnostics.iter().filter_map(|log_file| {
+        let code_fragment = log_file.code_fragment()?;
+        match code_fragment.task_info()? {
+            Message::Warning(task) => Some((
+                Location::new(
+                    log_file.absolute_path(),
+                    code_fragment.line(),
+                    code_fragment.column(),
+                ),
+                task,
+            )),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// A [`Transport`] recording requests and answering each with the same response per method.
+    pub(crate) struct RecordingTransport {
+        pub(crate) requests: RefCell<Vec<Request>>,
+        get_response: Value,
+        post_response: Value,
+    }
+
+    impl RecordingTransport {
+        pub(crate) fn new(get_response: Value, post_response: Value) -> Self {
+            RecordingTransport {
+                requests: RefCell::new(Vec::new()),
+                get_response,
+                post_response,
+            }
+        }
+    }
+
+    impl Transport for RecordingTransport {
+        fn send(&self, request: &Request) -> Result<Value, IntegrationError> {
+            self.requests.borrow_mut().push(request.clone());
+            match request.method {
+                Method::Get => Ok(self.get_response.clone()),
+                Method::Post | Method::Patch => Ok(self.post_response.clone()),
+            }
+        }
+    }
+
+    /// Tests that only warning tasks are filed.
+    #[test]
+    fn test_warning_tasks() {
+        let log = "/src/A.swift:1:1: warning: s#{\"queue\": \"API\", \"summary\": \"Retry\"}#s\n\
+                   /src/A.swift:2:1: error: s#{\"queue\": \"API\", \"summary\": \"Fix\"}#s\n\
+                   /src/A.swift:3:1: warning: unused variable 'x'\n";
+        let diagnostics = crate::parse_log::<crate::MyWarning>(log);
+        let lines: Vec<usize> = warning_tasks(&diagnostics)
+            .map(|(location, _)| location.line())
+            .collect();
+        assert_eq!(lines, vec![1]);
+    }
+
+    /// A [`TaskSink`] keying issues by queue, failing for the `BROKEN` queue.
+    struct QueueSink;
+
+    impl TaskSink for QueueSink {
+        fn name(&self) -> &str {
+            "queue"
+        }
+
+        fn submit(
+            &self,
+            task: &dyn DynTaskMessage,
+            location: &Location,
+        ) -> Result<TaskRef, IntegrationError> {
+            match task.task_queue().as_str() {
+                "BROKEN" => Err(IntegrationError::InvalidResponse {
+                    url: "queue".to_string(),
+                    body: String::new(),
+                }),
+                queue => Ok(TaskRef::new(format!("{}-{}", queue, location.line()), None)),
+            }
+        }
+
+        fn close(&self, existing: &TaskRef) -> Result<(), IntegrationError> {
+            if existing.key().starts_with("BROKEN") {
+                return Err(IntegrationError::InvalidResponse {
+                    url: "queue".to_string(),
+                    body: String::new(),
+                });
+            }
+            Ok(())
+        }
+    }
+
+    /// Tests that the fingerprint ignores the directory and line of the warning.
+    #[test]
+    fn test_task_fingerprint() {
+        let task: crate::MyWarning =
+            serde_json::from_str(r#"{"queue": "API", "summary": "Retry"}"#).unwrap();
+        let fingerprint =
+            |path: &str, line| task_fingerprint(&ErasedRef(&task), &Location::new(path, line, 1));
+        assert_eq!(
+            fingerprint("/ci/run-1/Sources/A.swift", 3),
+            fingerprint("/Users/me/App/Sources/A.swift", 9)
+        );
+        assert_ne!(
+            fingerprint("/ci/Sources/A.swift", 3),
+            fingerprint("/ci/Sources/B.swift", 3)
+        );
+    }
+
+    /// Tests that every task is submitted to every sink, even after a failure.
+    #[test]
+    fn test_task_dispatcher_dispatch() {
+        let log = "/src/A.swift:1:1: warning: s#{\"queue\": \"BROKEN\", \"summary\": \"S\"}#s\n\
+                   /src/A.swift:2:1: warning: s#{\"queue\": \"API\", \"summary\": \"S\"}#s\n";
+        let diagnostics = crate::parse_log::<crate::MyWarning>(log);
+        let mut dispatcher = TaskDispatcher::new();
+        dispatcher.add(QueueSink).add(QueueSink);
+
+        let results = dispatcher.dispatch(&diagnostics);
+        assert_eq!(results.len(), 4);
+        assert!(results[..2].iter().all(Result::is_err));
+        let issue = results[3].as_ref().unwrap();
+        assert_eq!((issue.sink(), issue.key()), ("queue", "API-2"));
+    }
+
+    /// Tests that issues of tasks no longer reported are closed and forgotten.
+    #[test]
+    fn test_task_dispatcher_sync() {
+        let diagnostics = crate::parse_log::<crate::MyWarning>(
+            "/src/A.swift:2:1: warning: s#{\"queue\": \"API\", \"summary\": \"S\"}#s\n",
+        );
+        let mut tracked = TrackedIssues::new();
+        tracked.insert("xlp-gone", "queue", TaskRef::new("API-1", None));
+        tracked.insert("xlp-stuck", "queue", TaskRef::new("BROKEN-1", None));
+        tracked.insert("xlp-gone", "jira", TaskRef::new("API-7", None));
+        let mut dispatcher = TaskDispatcher::new();
+        dispatcher.add(QueueSink);
+
+        let report = dispatcher.sync(&diagnostics, &mut tracked);
+        let fingerprint = report.filed()[0]
+            .as_ref()
+            .unwrap()
+            .fingerprint()
+            .to_string();
+        assert_eq!(report.closed().len(), 2);
+        assert_eq!(
+            report.closed()[0].as_ref().unwrap().task_ref().key(),
+            "API-1"
+        );
+        assert!(report.closed()[1].is_err());
+        assert_eq!(tracked.get(&fingerprint, "queue").unwrap().key(), "API-2");
+        assert_eq!(tracked.get("xlp-gone", "queue"), None);
+        assert!(tracked.get("xlp-stuck", "queue").is_some());
+        assert!(tracked.get("xlp-gone", "jira").is_some());
+        assert_eq!(tracked.len(), 3);
+    }
+}
@@ -0,0 +1,453 @@
+//! Export of the targets and build steps of a build as OpenTelemetry spans over OTLP/HTTP,
+//! available with the `otel` feature.
+
+use std::process;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{HttpTransport, IntegrationError, Method, Request, Transport};
+use crate::{BuildLog, Severity, TaskMessage};
+
+/// Counts the traces created by this process, so that two traces started at once differ.
+static TRACE_COUNT: AtomicU64 = AtomicU64::new(0);
+
+/// The collector and service name used by an [`OtlpExporter`].
+///
+/// It implements `Deserialize`, so it can be read from a configuration file, or from
+/// the standard OpenTelemetry environment variables with [`OtlpConfig::from_env`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct OtlpConfig {
+    /// The base URL of the OTLP/HTTP receiver, such as `http://localhost:4318`.
+    /// Spans are posted to its `/v1/traces` path.
+    pub endpoint: String,
+    /// The `service.name` of the spans, `xcodebuild` by default.
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+    /// The headers sent with each request, such as an API key of a hosted backend.
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+}
+
+/// Returns the default `service.name` of the spans.
+fn default_service_name() -> String {
+    "xcodebuild".to_string()
+}
+
+impl OtlpConfig {
+    /// Reads the configuration from `OTEL_EXPORTER_OTLP_ENDPOINT`, `OTEL_SERVICE_NAME` and
+    /// `OTEL_EXPORTER_OTLP_HEADERS`, a comma-separated list of `name=value` pairs.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, IntegrationError>` - The configuration, or the missing variable.
+    pub fn from_env() -> Result<Self, IntegrationError> {
+        let name = "OTEL_EXPORTER_OTLP_ENDPOINT";
+        let endpoint = std::env::var(name).map_err(|_| IntegrationError::MissingConfig {
+            name: name.to_string(),
+        })?;
+        let headers = std::env::var("OTEL_EXPORTER_OTLP_HEADERS")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|header| header.split_once('='))
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .collect();
+        Ok(OtlpConfig {
+            endpoint,
+            service_name: std::env::var("OTEL_SERVICE_NAME")
+                .unwrap_or_else(|_| default_service_name()),
+            headers,
+        })
+    }
+}
+
+/// The value of a span attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeValue {
+    String(String),
+    Int(i64),
+}
+
+impl AttributeValue {
+    /// Returns the value in the OTLP JSON encoding, where 64-bit integers are strings.
+    fn to_otlp(&self) -> Value {
+        match self {
+            AttributeValue::String(value) => json!({ "stringValue": value }),
+            AttributeValue::Int(value) => json!({ "intValue": value.to_string() }),
+        }
+    }
+}
+
+/// A span of a [`BuildTrace`]: the whole build, a target, or the steps of one kind of a target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildSpan {
+    span_id: String,
+    parent_span_id: Option<String>,
+    name: String,
+    start: SystemTime,
+    end: SystemTime,
+    attributes: Vec<(String, AttributeValue)>,
+}
+
+impl BuildSpan {
+    /// Returns the id of the span, as 16 hexadecimal digits.
+    pub fn span_id(&self) -> &str {
+        &self.span_id
+    }
+
+    /// Returns the id of the enclosing span, or `None` for the span of the whole build.
+    pub fn parent_span_id(&self) -> Option<&str> {
+        self.parent_span_id.as_deref()
+    }
+
+    /// Returns the name of the span, such as `build`, `App` or `CompileSwift`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the time the span starts at.
+    pub fn start(&self) -> SystemTime {
+        self.start
+    }
+
+    /// Returns the time the span ends at.
+    pub fn end(&self) -> SystemTime {
+        self.end
+    }
+
+    /// Returns the value of an attribute, such as `xcode.warnings`.
+    pub fn attribute(&self, key: &str) -> Option<&AttributeValue> {
+        self.attributes
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns the span in the OTLP JSON encoding.
+    fn to_otlp(&self, trace_id: &str) -> Value {
+        let unix_nanos = |time: SystemTime| {
+            time.duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+                .to_string()
+        };
+        let attributes: Vec<Value> = self
+            .attributes
+            .iter()
+            .map(|(key, value)| json!({ "key": key, "value": value.to_otlp() }))
+            .collect();
+        json!({
+            "traceId": trace_id,
+            "spanId": self.span_id,
+            "parentSpanId": self.parent_span_id.as_deref().unwrap_or_default(),
+            "name": self.name,
+            // SPAN_KIND_INTERNAL
+            "kind": 1,
+            "startTimeUnixNano": unix_nanos(self.start),
+            "endTimeUnixNano": unix_nanos(self.end),
+            "attributes": attributes,
+        })
+    }
+}
+
+/// The spans of a build: one for the whole build, one child per target and, under each
+/// target, one child per kind of build step, timed by the [`PhaseTimings`](crate::PhaseTimings)
+/// of the log.
+///
+/// The steps of `xcodebuild` run in parallel, and their durations share out the wall-clock
+/// time of the build among them, so the spans of targets and steps are laid end to end from
+/// the start of the build rather than overlapping. Logs without timestamps give empty spans.
+/// Each span of a target or of the build holds its number of warnings and errors as the
+/// `xcode.warnings` and `xcode.errors` attributes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildTrace {
+    trace_id: String,
+    spans: Vec<BuildSpan>,
+}
+
+impl BuildTrace {
+    /// Creates the spans of a parsed build.
+    ///
+    /// # Arguments
+    ///
+    /// * `build_log` - The parsed build log.
+    /// * `start` - The time the build started at, such as the modification time of the log
+    ///   minus the duration of the build.
+    pub fn new<T: TaskMessage>(build_log: &BuildLog<T>, start: SystemTime) -> Self {
+        let trace_id = trace_id(start);
+        let mut spans = Vec::new();
+        let span_id =
+            |spans: &Vec<BuildSpan>| format!("{}{:04x}", &trace_id[..12], spans.len() + 1);
+        let count = |target: Option<&str>, severity: Severity| -> i64 {
+            build_log
+                .diagnostics_with_build_steps()
+                .filter(|(log_file, step)| {
+                    target.is_none_or(|target| step.and_then(|step| step.target()) == Some(target))
+                        && log_file
+                            .code_fragment()
+                            .is_some_and(|code_fragment| code_fragment.severity() == Some(severity))
+                })
+                .count() as i64
+        };
+        let counts = |target: Option<&str>| {
+            vec![
+                (
+                    "xcode.warnings".to_string(),
+                    AttributeValue::Int(count(target, Severity::Warning)),
+                ),
+                (
+                    "xcode.errors".to_string(),
+                    AttributeValue::Int(count(target, Severity::Error)),
+                ),
+            ]
+        };
+
+        let build_id = span_id(&spans);
+        spans.push(BuildSpan {
+            span_id: build_id.clone(),
+            parent_span_id: None,
+            name: "build".to_string(),
+            start,
+            end: start,
+            attributes: counts(None),
+        });
+        let mut target_start = start;
+        for target in build_log.phase_timings().targets() {
+            let target_id = span_id(&spans);
+            let target_end = target_start + target.duration().unwrap_or_default();
+            let mut attributes = vec![(
+                "xcode.target".to_string(),
+                AttributeValue::String(target.target().to_string()),
+            )];
+            attributes.extend(counts(Some(target.target())));
+            spans.push(BuildSpan {
+                span_id: target_id.clone(),
+                parent_span_id: Some(build_id.clone()),
+                name: target.target().to_string(),
+                start: target_start,
+                end: target_end,
+                attributes,
+            });
+
+            let mut phase_start = target_start;
+            for phase in target.phases() {
+                let phase_end = phase_start + phase.duration().unwrap_or_default();
+                spans.push(BuildSpan {
+                    span_id: span_id(&spans),
+                    parent_span_id: Some(target_id.clone()),
+                    name: phase.step().name().to_string(),
+                    start: phase_start,
+                    end: phase_end,
+                    attributes: vec![
+                        (
+                            "xcode.target".to_string(),
+                            AttributeValue::String(target.target().to_string()),
+                        ),
+                        (
+                            "xcode.step_count".to_string(),
+                            AttributeValue::Int(phase.step_count() as i64),
+                        ),
+                    ],
+                });
+                phase_start = phase_end;
+            }
+            target_start = target_end;
+        }
+        spans[0].end = target_start;
+
+        BuildTrace { trace_id, spans }
+    }
+
+    /// Returns the id of the trace, as 32 hexadecimal digits.
+    pub fn trace_id(&self) -> &str {
+        &self.trace_id
+    }
+
+    /// Returns the spans, the span of the whole build first, each target followed by its steps.
+    pub fn spans(&self) -> &[BuildSpan] {
+        &self.spans
+    }
+
+    /// Returns the duration of the build.
+    pub fn duration(&self) -> Duration {
+        let build = &self.spans[0];
+        build.end.duration_since(build.start).unwrap_or_default()
+    }
+
+    /// Converts the trace into the body of an OTLP/HTTP JSON export request.
+    ///
+    /// # Arguments
+    ///
+    /// * `service_name` - The `service.name` resource attribute, such as `xcodebuild`.
+    pub fn to_otlp_json(&self, service_name: &str) -> Value {
+        let spans: Vec<Value> = self
+            .spans
+            .iter()
+            .map(|span| span.to_otlp(&self.trace_id))
+            .collect();
+        json!({
+            "resourceSpans": [{
+                "resource": {
+                    "attributes": [
+                        { "key": "service.name", "value": { "stringValue": service_name } },
+                    ],
+                },
+                "scopeSpans": [{
+                    "scope": { "name": "xcode-log-parser", "version": env!("CARGO_PKG_VERSION") },
+                    "spans": spans,
+                }],
+            }],
+        })
+    }
+}
+
+/// Returns a trace id unlikely to be reused, from the start of the build, the process id
+/// and the number of traces created before.
+fn trace_id(start: SystemTime) -> String {
+    let nanos = start
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    let count = TRACE_COUNT.fetch_add(1, Ordering::Relaxed);
+    // 64-bit FNV-1a, seeded differently for each half.
+    let hash = |seed: u64| {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325 ^ seed;
+        for value in [nanos, u64::from(process::id()), count] {
+            for byte in value.to_le_bytes() {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+            }
+        }
+        hash
+    };
+    format!("{:016x}{:016x}", hash(1), hash(2))
+}
+
+/// Exports [`BuildTrace`]s to an OpenTelemetry collector or tracing backend, such as
+/// Jaeger or Tempo, over OTLP/HTTP with the JSON encoding.
+#[derive(Debug, Clone)]
+pub struct OtlpExporter<U: Transport = HttpTransport> {
+    config: OtlpConfig,
+    transport: U,
+}
+
+impl OtlpExporter {
+    /// Creates an `OtlpExporter` sending its requests over HTTP.
+    pub fn new(config: OtlpConfig) -> Self {
+        OtlpExporter {
+            config,
+            transport: HttpTransport,
+        }
+    }
+}
+
+impl<U: Transport> OtlpExporter<U> {
+    /// Returns the exporter sending its requests through the given transport.
+    pub fn with_transport<V: Transport>(self, transport: V) -> OtlpExporter<V> {
+        OtlpExporter {
+            config: self.config,
+            transport,
+        }
+    }
+
+    /// Exports the spans of a build.
+    ///
+    /// # Arguments
+    ///
+    /// * `trace` - The spans of the build.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), IntegrationError>` - The reason the request failed, if it did.
+    pub fn export(&self, trace: &BuildTrace) -> Result<(), IntegrationError> {
+        self.transport.send(&Request {
+            method: Method::Post,
+            url: format!("{}/v1/traces", self.config.endpoint.trim_end_matches('/')),
+            headers: self.config.headers.clone(),
+            body: trace.to_otlp_json(&self.config.service_name),
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrations::tests::RecordingTransport;
+    use crate::{Dialect, LogParser, MyWarning};
+
+    /// Tests exporting a span per target and kind of step, with the warning counts.
+    #[test]
+    fn test_otlp_exporter_export() {
+        // Diagnostics are attributed to the targets of the steps in fastlane's timestamped logs.
+        let log = "[10:00:00]: ▸ SwiftCompile normal arm64 /src/A.swift (in target 'Core' from project 'App')\n\
+                   [10:00:01]: ▸ /src/A.swift:1:2: warning: unused variable 'x'\n\
+                   [10:00:04]: ▸ Ld /build/Core.framework/Core normal (in target 'Core' from project 'App')\n\
+                   [10:00:05]: ▸ SwiftCompile normal arm64 /src/App.swift (in target 'App' from project 'App')\n\
+                   [10:00:06]: ▸ /src/App.swift:3:4: warning: unused variable 'y'\n\
+                   [10:00:06.500]: ▸ /src/App.swift:5:6: error: cannot find 'z' in scope\n\
+                   [10:00:07]: ▸ ** BUILD FAILED **\n";
+        let parser = LogParser::builder().dialect(Dialect::Fastlane).build();
+        let build_log: BuildLog<MyWarning> = parser.parse_build_log(log);
+        let start = UNIX_EPOCH + Duration::from_secs(1_714_557_600);
+        let trace = BuildTrace::new(&build_log, start);
+
+        let spans: Vec<_> = trace
+            .spans()
+            .iter()
+            .map(|span| {
+                let parent = trace
+                    .spans()
+                    .iter()
+                    .find(|parent| Some(parent.span_id()) == span.parent_span_id())
+                    .map(BuildSpan::name);
+                let duration = span.end().duration_since(span.start()).unwrap();
+                (
+                    span.name(),
+                    parent,
+                    duration,
+                    span.attribute("xcode.warnings").cloned(),
+                )
+            })
+            .collect();
+        let warnings = |count| Some(AttributeValue::Int(count));
+        assert_eq!(
+            spans,
+            vec![
+                ("build", None, Duration::from_secs(7), warnings(2)),
+                ("Core", Some("build"), Duration::from_secs(5), warnings(1)),
+                ("CompileSwift", Some("Core"), Duration::from_secs(4), None),
+                ("Ld", Some("Core"), Duration::from_secs(1), None),
+                ("App", Some("build"), Duration::from_secs(2), warnings(1)),
+                ("CompileSwift", Some("App"), Duration::from_secs(2), None),
+            ]
+        );
+        assert_eq!(trace.duration(), Duration::from_secs(7));
+
+        let transport = RecordingTransport::new(Value::Null, json!({}));
+        let exporter = OtlpExporter::new(OtlpConfig {
+            endpoint: "http://localhost:4318/".to_string(),
+            service_name: "ios-app".to_string(),
+            headers: vec![("x-api-key".to_string(), "secret".to_string())],
+        })
+        .with_transport(transport);
+        exporter.export(&trace).unwrap();
+
+        let requests = exporter.transport.requests.borrow();
+        assert_eq!(requests[0].url, "http://localhost:4318/v1/traces");
+        let resource_spans = &requests[0].body["resourceSpans"][0];
+        assert_eq!(
+            resource_spans["resource"]["attributes"][0]["value"]["stringValue"],
+            "ios-app"
+        );
+        let app = &resource_spans["scopeSpans"][0]["spans"][4];
+        assert_eq!(app["traceId"], trace.trace_id());
+        assert_eq!(app["startTimeUnixNano"], "1714557605000000000");
+        assert_eq!(
+            app["attributes"][2],
+            json!({ "key": "xcode.errors", "value": { "intValue": "1" } })
+        );
+    }
+}
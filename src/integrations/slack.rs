@@ -0,0 +1,345 @@
+//! Posting of build summaries to Slack, available with the `slack` feature.
+
+use std::fmt;
+
+use serde_json::{json, Value};
+
+use super::{HttpTransport, IntegrationError, Method, Request, Transport, REDACTED};
+use crate::{BaselineDiff, Fingerprint, LogFile, Severity, TaskMessage, Verdict};
+
+/// The figures of a build posted by a [`SlackNotifier`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BuildSummary {
+    errors: usize,
+    warnings: usize,
+    new_issues: Vec<Fingerprint>,
+    new_count: usize,
+    verdict: Option<Verdict>,
+}
+
+impl BuildSummary {
+    /// Creates a `BuildSummary` counting the errors and warnings of the diagnostics.
+    ///
+    /// # Arguments
+    ///
+    /// * `diagnostics` - The diagnostics of the build.
+    pub fn new<T: TaskMessage>(diagnostics: &[LogFile<T>]) -> Self {
+        let count = |severity: Severity| {
+            diagnostics
+                .iter()
+                .filter_map(LogFile::code_fragment)
+                .filter(|code_fragment| code_fragment.severity() == Some(severity))
+                .count()
+        };
+        BuildSummary {
+            errors: count(Severity::Error),
+            warnings: count(Severity::Warning),
+            ..Self::default()
+        }
+    }
+
+    /// Adds the first diagnostics missing from a baseline.
+    ///
+    /// # Arguments
+    ///
+    /// * `diff` - The comparison of the diagnostics against the baseline.
+    /// * `limit` - The maximum number of new diagnostics to keep.
+    pub fn new_issues<T: TaskMessage>(mut self, diff: &BaselineDiff<'_, T>, limit: usize) -> Self {
+        self.new_issues = diff
+            .added()
+            .iter()
+            .filter_map(|log_file| Fingerprint::of(log_file))
+            .take(limit)
+            .collect();
+        self.new_count = diff.added().len();
+        self
+    }
+
+    /// Adds the verdict of a [`Policy`](crate::Policy).
+    pub fn verdict(mut self, verdict: Verdict) -> Self {
+        self.verdict = Some(verdict);
+        self
+    }
+
+    /// Returns the number of errors.
+    pub fn errors(&self) -> usize {
+        self.errors
+    }
+
+    /// Returns the number of warnings.
+    pub fn warnings(&self) -> usize {
+        self.warnings
+    }
+
+    /// Returns the first diagnostics missing from the baseline.
+    pub fn top_new_issues(&self) -> &[Fingerprint] {
+        &self.new_issues
+    }
+
+    /// Returns the number of diagnostics missing from the baseline, including those not kept.
+    pub fn new_count(&self) -> usize {
+        self.new_count
+    }
+
+    /// Returns the verdict of the policy, if one was evaluated.
+    pub fn policy_verdict(&self) -> Option<&Verdict> {
+        self.verdict.as_ref()
+    }
+}
+
+/// Lays out the message a [`SlackNotifier`] posts.
+///
+/// It is implemented by closures taking a [`BuildSummary`], so a team can replace
+/// [`DefaultTemplate`] with its own layout, such as Block Kit blocks.
+pub trait SlackTemplate {
+    /// Returns the JSON payload posted to the webhook.
+    ///
+    /// # Arguments
+    ///
+    /// * `summary` - The figures of the build.
+    fn render(&self, summary: &BuildSummary) -> Value;
+}
+
+impl<F: Fn(&BuildSummary) -> Value> SlackTemplate for F {
+    fn render(&self, summary: &BuildSummary) -> Value {
+        self(summary)
+    }
+}
+
+/// The [`SlackTemplate`] used by default: a `mrkdwn` text with the verdict, the counts
+/// and one line per new diagnostic.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultTemplate;
+
+impl SlackTemplate for DefaultTemplate {
+    fn render(&self, summary: &BuildSummary) -> Value {
+        let mut text = match summary.policy_verdict() {
+            Some(verdict) if verdict.passed() => ":white_check_mark: *Build passed*".to_string(),
+            Some(verdict) => {
+                let violations: Vec<String> = verdict
+                    .violations()
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect();
+                format!(":x: *Build failed*: {}", violations.join(", "))
+            }
+            None => "*Build finished*".to_string(),
+        };
+        text.push_str(&format!(
+            "\nErrors: {}, warnings: {}",
+            summary.errors(),
+            summary.warnings()
+        ));
+        if summary.new_count() > 0 {
+            text.push_str(&format!("\n*New issues ({}):*", summary.new_count()));
+            for issue in summary.top_new_issues() {
+                text.push_str(&format!(
+                    "\n• `{}:{}` {}",
+                    issue.path(),
+                    issue.line(),
+                    issue.text()
+                ));
+            }
+        }
+        json!({ "text": text })
+    }
+}
+
+/// A notifier posting build summaries to a Slack incoming webhook.
+///
+/// ```no_run
+/// use xcode_log_parser::integrations::slack::{BuildSummary, SlackNotifier};
+/// use xcode_log_parser::{parse_log, MyWarning};
+///
+/// let notifier = SlackNotifier::new("https://hooks.slack.com/services/T000/B000/XXXX");
+/// let diagnostics = parse_log::<MyWarning>(&std::fs::read_to_string("build.log").unwrap());
+/// notifier.notify(&BuildSummary::new(&diagnostics)).unwrap();
+/// ```
+#[derive(Clone)]
+pub struct SlackNotifier<Tm: SlackTemplate = DefaultTemplate, Tr: Transport = HttpTransport> {
+    webhook_url: String,
+    template: Tm,
+    transport: Tr,
+}
+
+impl<Tm: SlackTemplate + fmt::Debug, Tr: Transport + fmt::Debug> fmt::Debug
+    for SlackNotifier<Tm, Tr>
+{
+    /// Formats the notifier without its webhook URL, which grants posting to the channel.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SlackNotifier")
+            .field("webhook_url", &REDACTED)
+            .field("template", &self.template)
+            .field("transport", &self.transport)
+            .finish()
+    }
+}
+
+impl SlackNotifier {
+    /// Creates a new `SlackNotifier` using the [`DefaultTemplate`].
+    ///
+    /// # Arguments
+    ///
+    /// * `webhook_url` - The URL of the incoming webhook.
+    pub fn new(webhook_url: impl Into<String>) -> Self {
+        SlackNotifier {
+            webhook_url: webhook_url.into(),
+            template: DefaultTemplate,
+            transport: HttpTransport,
+        }
+    }
+
+    /// Creates a new `SlackNotifier` posting to the `SLACK_WEBHOOK_URL` environment variable.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, IntegrationError>` - The notifier, or an error if the variable is not set.
+    pub fn from_env() -> Result<Self, IntegrationError> {
+        let name = "SLACK_WEBHOOK_URL";
+        std::env::var(name)
+            .map(Self::new)
+            .map_err(|_| IntegrationError::MissingConfig {
+                name: name.to_string(),
+            })
+    }
+}
+
+impl<Tm: SlackTemplate, Tr: Transport> SlackNotifier<Tm, Tr> {
+    /// Returns the notifier laying out its messages with the given template.
+    pub fn template<U: SlackTemplate>(self, template: U) -> SlackNotifier<U, Tr> {
+        SlackNotifier {
+            webhook_url: self.webhook_url,
+            template,
+            transport: self.transport,
+        }
+    }
+
+    /// Returns the notifier sending its requests through the given transport.
+    pub fn with_transport<U: Transport>(self, transport: U) -> SlackNotifier<Tm, U> {
+        SlackNotifier {
+            webhook_url: self.webhook_url,
+            template: self.template,
+            transport,
+        }
+    }
+
+    /// Posts a build summary to the webhook.
+    ///
+    /// # Arguments
+    ///
+    /// * `summary` - The figures of the build.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), IntegrationError>` - Nothing, or the reason the message could not be posted.
+    pub fn notify(&self, summary: &BuildSummary) -> Result<(), IntegrationError> {
+        self.transport
+            .send(&Request {
+                method: Method::Post,
+                url: self.webhook_url.clone(),
+                headers: Vec::new(),
+                body: self.template.render(summary),
+            })
+            .map_err(|error| redact_url(error, &self.webhook_url))?;
+        Ok(())
+    }
+}
+
+/// Replaces the webhook URL in an error, including in the reason given by the HTTP client.
+fn redact_url(error: IntegrationError, webhook_url: &str) -> IntegrationError {
+    let redact = |text: String| text.replace(webhook_url, REDACTED);
+    match error {
+        IntegrationError::Http { url, reason } => IntegrationError::Http {
+            url: redact(url),
+            reason: redact(reason),
+        },
+        IntegrationError::Status { url, status, body } => IntegrationError::Status {
+            url: redact(url),
+            status,
+            body: redact(body),
+        },
+        IntegrationError::InvalidResponse { url, body } => IntegrationError::InvalidResponse {
+            url: redact(url),
+            body: redact(body),
+        },
+        error @ IntegrationError::MissingConfig { .. } => error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrations::tests::RecordingTransport;
+    use crate::{diff, parse_log, Baseline, MyWarning, Policy};
+
+    /// Tests the default message posted for a failed build with new issues.
+    #[test]
+    fn test_slack_notifier_default_template() {
+        let baseline = Baseline::from_log::<MyWarning>("/src/A.swift:1:1: warning: old\n");
+        let diagnostics = parse_log::<MyWarning>(
+            "/src/A.swift:1:1: warning: old\n\
+             /src/A.swift:2:1: warning: new\n\
+             /src/A.swift:3:1: error: broken\n",
+        );
+        let policy = Policy {
+            max_errors: Some(0),
+            ..Policy::default()
+        };
+        let summary = BuildSummary::new(&diagnostics)
+            .new_issues(&diff(&diagnostics, &baseline), 1)
+            .verdict(policy.evaluate(&diagnostics, None));
+        let notifier = SlackNotifier::new("https://hooks.slack.com/services/T/B/X")
+            .with_transport(RecordingTransport::new(json!(null), json!("ok")));
+
+        notifier.notify(&summary).unwrap();
+        let requests = notifier.transport.requests.borrow();
+        assert_eq!(requests[0].url, "https://hooks.slack.com/services/T/B/X");
+        assert_eq!(
+            requests[0].body["text"],
+            ":x: *Build failed*: 1 errors exceed the maximum of 0\n\
+             Errors: 1, warnings: 2\n\
+             *New issues (2):*\n\
+             • `/src/A.swift:2` new"
+        );
+    }
+
+    /// Tests that a closure replaces the default layout.
+    #[test]
+    fn test_slack_notifier_custom_template() {
+        let diagnostics = parse_log::<MyWarning>("/src/A.swift:2:1: warning: new\n");
+        let notifier = SlackNotifier::new("https://hooks.slack.com/services/T/B/X")
+            .template(|summary: &BuildSummary| json!({ "text": summary.warnings().to_string() }))
+            .with_transport(RecordingTransport::new(json!(null), json!("ok")));
+
+        notifier.notify(&BuildSummary::new(&diagnostics)).unwrap();
+        assert_eq!(notifier.transport.requests.borrow()[0].body["text"], "1");
+    }
+
+    /// A [`Transport`] failing like the HTTP client, whose reason repeats the URL.
+    #[derive(Debug)]
+    struct FailingTransport;
+
+    impl Transport for FailingTransport {
+        fn send(&self, request: &Request) -> Result<Value, IntegrationError> {
+            Err(IntegrationError::Http {
+                url: request.url.clone(),
+                reason: format!("{}: Connection Failed", request.url),
+            })
+        }
+    }
+
+    /// Tests that the webhook URL is left out of the debug output and of the errors.
+    #[test]
+    fn test_slack_notifier_redacts_webhook_url() {
+        let notifier = SlackNotifier::new("https://hooks.slack.com/services/T/B/X")
+            .with_transport(FailingTransport);
+        assert!(!format!("{:?}", notifier).contains("services/T/B/X"));
+
+        let error = notifier.notify(&BuildSummary::default()).unwrap_err();
+        assert!(!format!("{:?}", error).contains("services/T/B/X"));
+        assert_eq!(
+            error.to_string(),
+            "request to [redacted] failed: [redacted]: Connection Failed"
+        );
+    }
+}
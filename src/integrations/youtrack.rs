@@ -0,0 +1,317 @@
+//! Filing of YouTrack issues from task payloads, available with the `youtrack` feature.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{
+    encode_query, task_fingerprint, HttpTransport, IntegrationError, Location, Method, Request,
+    TaskRef, TaskSink, Transport, REDACTED,
+};
+use crate::DynTaskMessage;
+
+/// The site and permanent token used by a [`YouTrackClient`].
+///
+/// It implements `Deserialize`, so it can be read from a configuration file, or
+/// from the environment with [`YouTrackConfig::from_env`].
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+pub struct YouTrackConfig {
+    /// The URL of the YouTrack site, such as `https://example.youtrack.cloud`.
+    pub base_url: String,
+    /// A permanent token of the account filing the issues.
+    pub token: String,
+}
+
+impl fmt::Debug for YouTrackConfig {
+    /// Formats the configuration without its token.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("YouTrackConfig")
+            .field("base_url", &self.base_url)
+            .field("token", &REDACTED)
+            .finish()
+    }
+}
+
+impl YouTrackConfig {
+    /// Reads the configuration from the `YOUTRACK_URL` and `YOUTRACK_TOKEN` environment variables.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, IntegrationError>` - The configuration, or the first variable that is not set.
+    pub fn from_env() -> Result<Self, IntegrationError> {
+        let var = |name: &str| {
+            std::env::var(name).map_err(|_| IntegrationError::MissingConfig {
+                name: name.to_string(),
+            })
+        };
+        Ok(YouTrackConfig {
+            base_url: var("YOUTRACK_URL")?,
+            token: var("YOUTRACK_TOKEN")?,
+        })
+    }
+}
+
+/// A client creating YouTrack issues through the REST API.
+///
+/// Each `warning:` task is filed in the project named by [`DynTaskMessage::task_queue`],
+/// with [`DynTaskMessage::task_summary`] as the summary. The file and line of the warning
+/// are added as a comment on the issue, and the [`task_fingerprint`] to its description,
+/// so tasks with an unresolved issue are not filed again. Issues are closed with the
+/// [`close_command`](Self::close_command).
+///
+/// Projects are referenced by their short name, such as `API`. Queues mapped with
+/// [`project_id`](Self::project_id) are sent as the project id instead.
+///
+/// ```no_run
+/// use xcode_log_parser::integrations::youtrack::{YouTrackClient, YouTrackConfig};
+/// use xcode_log_parser::integrations::TaskSink;
+/// use xcode_log_parser::{parse_log, MyWarning};
+///
+/// let client = YouTrackClient::new(YouTrackConfig::from_env().unwrap());
+/// let diagnostics = parse_log::<MyWarning>(&std::fs::read_to_string("build.log").unwrap());
+/// for issue in client.create_issues(&diagnostics).unwrap() {
+///     println!("{}: {}", issue.key(), issue.message());
+/// }
+/// ```
+#[derive(Clone)]
+pub struct YouTrackClient<Tr: Transport = HttpTransport> {
+    base_url: String,
+    authorization: String,
+    project_ids: HashMap<String, String>,
+    close_command: String,
+    transport: Tr,
+}
+
+impl<Tr: Transport + fmt::Debug> fmt::Debug for YouTrackClient<Tr> {
+    /// Formats the client without its token.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("YouTrackClient")
+            .field("base_url", &self.base_url)
+            .field("authorization", &REDACTED)
+            .field("project_ids", &self.project_ids)
+            .field("close_command", &self.close_command)
+            .field("transport", &self.transport)
+            .finish()
+    }
+}
+
+impl YouTrackClient {
+    /// Creates a new `YouTrackClient`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The site and token to use.
+    pub fn new(config: YouTrackConfig) -> Self {
+        YouTrackClient {
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            authorization: format!("Bearer {}", config.token),
+            project_ids: HashMap::new(),
+            close_command: "State Fixed".to_string(),
+            transport: HttpTransport,
+        }
+    }
+}
+
+impl<Tr: Transport> YouTrackClient<Tr> {
+    /// Returns the client sending its requests through the given transport.
+    pub fn with_transport<U: Transport>(self, transport: U) -> YouTrackClient<U> {
+        YouTrackClient {
+            base_url: self.base_url,
+            authorization: self.authorization,
+            project_ids: self.project_ids,
+            close_command: self.close_command,
+            transport,
+        }
+    }
+
+    /// Files the tasks of the given queue in the project with the given id, such as `0-1`.
+    pub fn project_id(mut self, queue: impl Into<String>, id: impl Into<String>) -> Self {
+        self.project_ids.insert(queue.into(), id.into());
+        self
+    }
+
+    /// Sets the command applied to issues whose task is no longer reported, `State Fixed` by default.
+    pub fn close_command(mut self, command: impl Into<String>) -> Self {
+        self.close_command = command.into();
+        self
+    }
+
+    /// Sends an authenticated request.
+    fn send(&self, method: Method, url: String, body: Value) -> Result<Value, IntegrationError> {
+        self.transport.send(&Request {
+            method,
+            url,
+            headers: vec![("Authorization".to_string(), self.authorization.clone())],
+            body,
+        })
+    }
+
+    /// Comments the location of the task on the issue with the given id.
+    fn comment(&self, id: &str, text: String) -> Result<(), IntegrationError> {
+        self.send(
+            Method::Post,
+            format!("{}/api/issues/{}/comments", self.base_url, id),
+            json!({ "text": text }),
+        )?;
+        Ok(())
+    }
+
+    /// Returns the issue described by the `id` and `idReadable` of a response.
+    fn task_ref(&self, url: &str, issue: &Value) -> Result<(String, TaskRef), IntegrationError> {
+        let (Some(id), Some(key)) = (issue["id"].as_str(), issue["idReadable"].as_str()) else {
+            return Err(IntegrationError::InvalidResponse {
+                url: url.to_string(),
+                body: issue.to_string(),
+            });
+        };
+        Ok((
+            id.to_string(),
+            TaskRef::new(key, Some(format!("{}/issue/{}", self.base_url, key))),
+        ))
+    }
+}
+
+impl<Tr: Transport> TaskSink for YouTrackClient<Tr> {
+    fn name(&self) -> &str {
+        "youtrack"
+    }
+
+    fn submit(
+        &self,
+        task: &dyn DynTaskMessage,
+        location: &Location,
+    ) -> Result<TaskRef, IntegrationError> {
+        let queue = task.task_queue();
+        let project = match self.project_ids.get(&queue) {
+            Some(id) => json!({ "id": id }),
+            None => json!({ "shortName": queue }),
+        };
+        let url = format!("{}/api/issues?fields=id,idReadable", self.base_url);
+        let issue = self.send(
+            Method::Post,
+            url.clone(),
+            json!({
+                "project": project,
+                "summary": task.task_summary(),
+                "description": format!("Fingerprint: {}", task_fingerprint(task, location)),
+            }),
+        )?;
+        let (id, task_ref) = self.task_ref(&url, &issue)?;
+        self.comment(
+            &id,
+            format!(
+                "Reported at {}:{}",
+                location.absolute_path(),
+                location.line()
+            ),
+        )?;
+        Ok(task_ref)
+    }
+
+    /// Searches for an unresolved issue whose description holds the fingerprint.
+    fn find_open(&self, fingerprint: &str) -> Result<Option<TaskRef>, IntegrationError> {
+        let query = format!("#Unresolved \"{}\"", fingerprint);
+        let url = format!(
+            "{}/api/issues?query={}&fields=id,idReadable&$top=1",
+            self.base_url,
+            encode_query(&query)
+        );
+        let response = self.send(Method::Get, url.clone(), Value::Null)?;
+        let issues = response
+            .as_array()
+            .ok_or_else(|| IntegrationError::InvalidResponse {
+                url: url.clone(),
+                body: response.to_string(),
+            })?;
+        issues
+            .first()
+            .map(|issue| Ok(self.task_ref(&url, issue)?.1))
+            .transpose()
+    }
+
+    /// Comments the current location of the task on the issue.
+    fn update(
+        &self,
+        existing: &TaskRef,
+        _task: &dyn DynTaskMessage,
+        location: &Location,
+    ) -> Result<TaskRef, IntegrationError> {
+        self.comment(
+            existing.key(),
+            format!(
+                "Still reported at {}:{}",
+                location.absolute_path(),
+                location.line()
+            ),
+        )?;
+        Ok(existing.clone())
+    }
+
+    /// Applies the [`close_command`](YouTrackClient::close_command) to the issue, with a comment.
+    fn close(&self, existing: &TaskRef) -> Result<(), IntegrationError> {
+        self.send(
+            Method::Post,
+            format!("{}/api/commands", self.base_url),
+            json!({
+                "query": self.close_command,
+                "issues": [{ "idReadable": existing.key() }],
+                "comment": "No longer reported in the build log.",
+            }),
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrations::tests::RecordingTransport;
+    use crate::{parse_log, MyWarning};
+
+    /// Tests the issue and comment requests sent for a task.
+    #[test]
+    fn test_youtrack_client_create_issues() {
+        let transport =
+            RecordingTransport::new(json!([]), json!({ "id": "2-15", "idReadable": "API-15" }));
+        let config = YouTrackConfig {
+            base_url: "https://example.youtrack.cloud/".to_string(),
+            token: "perm:abc".to_string(),
+        };
+        let client = YouTrackClient::new(config)
+            .project_id("CORE", "0-1")
+            .with_transport(transport);
+        let diagnostics = parse_log::<MyWarning>(
+            "/src/A.swift:7:1: warning: s#{\"queue\": \"API\", \"summary\": \"Retry\"}#s\n\
+             /src/B.swift:9:1: warning: s#{\"queue\": \"CORE\", \"summary\": \"Clean up\"}#s\n",
+        );
+
+        let issues = client.create_issues(&diagnostics).unwrap();
+        assert_eq!(issues[0].key(), "API-15");
+
+        let requests = client.transport.requests.borrow();
+        assert_eq!(requests.len(), 6);
+        assert_eq!(requests[0].method, Method::Get);
+        assert_eq!(requests[1].headers[0].1, "Bearer perm:abc");
+        assert_eq!(requests[1].body["project"]["shortName"], "API");
+        assert_eq!(
+            requests[2].url,
+            "https://example.youtrack.cloud/api/issues/2-15/comments"
+        );
+        assert_eq!(requests[2].body["text"], "Reported at /src/A.swift:7");
+        assert_eq!(requests[4].body["project"]["id"], "0-1");
+    }
+
+    /// Tests that the token is left out of the debug output.
+    #[test]
+    fn test_youtrack_debug_redacts_token() {
+        let config = YouTrackConfig {
+            base_url: "https://example.youtrack.cloud".to_string(),
+            token: "perm:abc".to_string(),
+        };
+
+        assert!(!format!("{:?}", config).contains("perm:abc"));
+        assert!(!format!("{:?}", YouTrackClient::new(config)).contains("perm:abc"));
+    }
+}
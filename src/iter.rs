@@ -0,0 +1,164 @@
+use std::collections::VecDeque;
+use std::io::BufRead;
+
+use crate::assembler::{DiagnosticAssembler, Entry};
+use crate::{LogFile, LogParser, ParserBackend, TaskMessage};
+
+/// An iterator that lazily parses diagnostics from any buffered reader.
+///
+/// Lines are read one at a time, so the whole log never has to be held in memory.
+/// Each item carries the 1-based number of the source line it was parsed from.
+/// As in [`BuildLog::parse`](crate::BuildLog::parse), the source excerpt, caret and
+/// `note:` lines following a diagnostic are attached to it, so a diagnostic is only
+/// yielded once the line after them was read.
+pub struct LogFileIter<R: BufRead, T: TaskMessage> {
+    reader: R,
+    buffer: Vec<u8>,
+    line_number: usize,
+    parser: LogParser,
+    assembler: DiagnosticAssembler<T>,
+    ready: VecDeque<(usize, LogFile<T>)>,
+    exhausted: bool,
+}
+
+impl<R: BufRead, T: TaskMessage> LogFileIter<R, T> {
+    /// Creates a new `LogFileIter` reading from the given source.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A buffered reader that yields the build log.
+    pub fn new(reader: R) -> Self {
+        Self::with_backend(reader, ParserBackend::default())
+    }
+
+    /// Creates a new `LogFileIter` reading from the given source with the given parser backend.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A buffered reader that yields the build log.
+    /// * `backend` - The parser used for the `path:line:column:` prefix of each line.
+    pub fn with_backend(reader: R, backend: ParserBackend) -> Self {
+        Self::with_parser(reader, LogParser::builder().backend(backend).build())
+    }
+
+    /// Creates a new `LogFileIter` reading from the given source with the given parser.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A buffered reader that yields the build log.
+    /// * `parser` - The parser used for each line.
+    pub fn with_parser(reader: R, parser: LogParser) -> Self {
+        LogFileIter {
+            reader,
+            buffer: Vec::new(),
+            line_number: 0,
+            assembler: DiagnosticAssembler::new(parser.dialect()),
+            parser,
+            ready: VecDeque::new(),
+            exhausted: false,
+        }
+    }
+
+    /// Returns the number of lines read from the source so far.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+}
+
+impl<R: BufRead, T: TaskMessage> Iterator for LogFileIter<R, T> {
+    type Item = std::io::Result<(usize, LogFile<T>)>;
+
+    /// Reads lines until the next diagnostic is found.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self::Item>` - The source line number and the diagnostic, a read error,
+    ///   or `None` once the source is exhausted.
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.ready.pop_front() {
+                return Some(Ok(item));
+            }
+            if self.exhausted {
+                return None;
+            }
+            self.buffer.clear();
+            let entries = match self.reader.read_until(b'\n', &mut self.buffer) {
+                Ok(0) => {
+                    self.exhausted = true;
+                    self.assembler.finish()
+                }
+                Ok(_) => {
+                    self.line_number += 1;
+                    let line = String::from_utf8_lossy(&self.buffer);
+                    let line = line.trim_end_matches(['\n', '\r']);
+                    let entry = self
+                        .parser
+                        .parse_line(line)
+                        .filter(|log_file| log_file.code_fragment().is_some())
+                        .map(|log_file| Entry::Diagnostic(self.line_number, log_file));
+                    self.assembler.push(line, entry)
+                }
+                Err(error) => return Some(Err(error)),
+            };
+            self.ready
+                .extend(entries.into_iter().map(Entry::into_diagnostic));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MyWarning;
+
+    /// Tests that diagnostics are yielded with the number of the line they came from.
+    #[test]
+    fn test_log_file_iter_tracks_line_numbers() {
+        let log = "Build settings from command line:\r\n\
+                   /path/File.swift:1:2: warning: s#{\"queue\": \"Q\", \"summary\": \"S\"}#s\r\n\
+                   \n\
+                   /path/Other.swift:3:4: error: unused variable\n";
+        let mut iter = LogFileIter::<_, MyWarning>::new(log.as_bytes());
+
+        let (line_number, log_file) = iter.next().unwrap().unwrap();
+        assert_eq!(line_number, 2);
+        assert_eq!(log_file.absolute_path(), "/path/File.swift");
+        assert!(log_file.code_fragment().unwrap().task_info().is_some());
+
+        let (line_number, log_file) = iter.next().unwrap().unwrap();
+        assert_eq!(line_number, 4);
+        assert_eq!(log_file.absolute_path(), "/path/Other.swift");
+
+        assert!(iter.next().is_none());
+        assert_eq!(iter.line_number(), 4);
+    }
+
+    /// Tests that invalid UTF-8 does not stop the iteration.
+    #[test]
+    fn test_log_file_iter_invalid_utf8() {
+        let log: &[u8] = b"\xff\xfe garbage\n/path/File.swift:1:2: note: text\n";
+        let items: Vec<_> = LogFileIter::<_, MyWarning>::new(log)
+            .collect::<std::io::Result<_>>()
+            .unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].0, 2);
+    }
+
+    /// Tests that the excerpt and notes following a diagnostic are attached to it.
+    #[test]
+    fn test_log_file_iter_attaches_notes() {
+        let log = "/src/A.swift:1:9: error: cannot find 'b' in scope\n\
+                   \x20   let a = b\n\
+                   \x20           ^\n\
+                   /src/B.swift:3:4: note: did you mean 'c'?\n\
+                   ** BUILD FAILED **\n";
+        let items: Vec<_> = LogFileIter::<_, MyWarning>::new(log.as_bytes())
+            .collect::<std::io::Result<_>>()
+            .unwrap();
+        assert_eq!(items.len(), 1);
+        let code_fragment = items[0].1.code_fragment().unwrap();
+        assert_eq!(code_fragment.source_excerpt(), Some("    let a = b"));
+        assert_eq!(code_fragment.notes()[0].absolute_path(), "/src/B.swift");
+    }
+}
@@ -0,0 +1,190 @@
+//! A versioned JSON representation of parsed diagnostics for downstream tools.
+//!
+//! Diagnostics are wrapped in an envelope holding the schema version, so consumers
+//! can detect changes to the layout of the parsed types:
+//!
+//! ```json
+//! {
+//!   "schema_version": 1,
+//!   "diagnostics": [
+//!     {
+//!       "absolute_path": "/src/File.swift",
+//!       "code_fragment": {
+//!         "line": 10,
+//!         "column": 5,
+//!         "severity": "warning",
+//!         "text": "s#{\"queue\": \"TESTAPI\", \"summary\": \"Create a task\"}#s",
+//!         "task_info": {
+//!           "severity": "warning",
+//!           "task": { "summary": "Create a task", "queue": "TESTAPI" }
+//!         },
+//!         "source_excerpt": null,
+//!         "caret": null,
+//!         "fixit": null,
+//!         "notes": [],
+//!         "snippet": null,
+//!         "rule": null,
+//!         "category": null,
+//!         "type_check_ms": null,
+//!         "flag": null,
+//!         "theme": "other"
+//!       }
+//!     }
+//!   ]
+//! }
+//! ```
+
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::{LogFile, TaskMessage};
+
+/// The version of the JSON schema produced by this module.
+///
+/// It is increased whenever a field is renamed or removed, or its meaning changes.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The top-level object of the JSON representation.
+#[derive(Debug, Serialize)]
+pub struct Envelope<'a, T: TaskMessage> {
+    schema_version: u32,
+    diagnostics: &'a [LogFile<T>],
+}
+
+impl<'a, T: TaskMessage> Envelope<'a, T> {
+    /// Wraps the given diagnostics into an envelope of the current schema version.
+    pub fn new(diagnostics: &'a [LogFile<T>]) -> Self {
+        Envelope {
+            schema_version: SCHEMA_VERSION,
+            diagnostics,
+        }
+    }
+
+    /// Returns the schema version of the envelope.
+    pub fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+
+    /// Returns the diagnostics held by the envelope.
+    pub fn diagnostics(&self) -> &'a [LogFile<T>] {
+        self.diagnostics
+    }
+}
+
+/// Serializes parsed diagnostics into a JSON document wrapped in an [`Envelope`].
+///
+/// # Arguments
+///
+/// * `diagnostics` - The parsed diagnostics to serialize.
+///
+/// # Returns
+///
+/// * `serde_json::Result<String>` - The JSON document, or the serialization error.
+pub fn to_json<T: TaskMessage + Serialize>(
+    diagnostics: &[LogFile<T>],
+) -> serde_json::Result<String> {
+    serde_json::to_string(&Envelope::new(diagnostics))
+}
+
+/// Writes diagnostics as JSON Lines: one JSON object per diagnostic and line, laid out as
+/// the elements of [`Envelope::diagnostics`], without the envelope.
+///
+/// Each line is flushed as soon as it is written, so that the output of a running build can
+/// be piped into `jq`, a log shipper or a BigQuery load without waiting for the build to end.
+///
+/// ```
+/// use xcode_log_parser::json::JsonLinesWriter;
+/// use xcode_log_parser::{LogFileIter, MyWarning};
+///
+/// let log = "/src/A.swift:1:2: warning: unused variable 'x'\n/src/B.swift:3:4: error: cannot find 'y' in scope\n";
+/// let mut writer = JsonLinesWriter::new(Vec::new());
+/// for item in LogFileIter::<_, MyWarning>::new(log.as_bytes()) {
+///     let (_, log_file) = item.unwrap();
+///     writer.write(&log_file).unwrap();
+/// }
+/// let output = String::from_utf8(writer.into_inner()).unwrap();
+/// assert_eq!(output.lines().count(), 2);
+/// ```
+#[derive(Debug)]
+pub struct JsonLinesWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+    /// Creates a `JsonLinesWriter` writing to the given writer, such as the locked stdout.
+    pub fn new(writer: W) -> Self {
+        JsonLinesWriter { writer }
+    }
+
+    /// Writes a diagnostic on its own line and flushes it.
+    ///
+    /// # Arguments
+    ///
+    /// * `log_file` - The diagnostic to write.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<()>` - An error if the diagnostic could not be serialized or written.
+    pub fn write<T: TaskMessage + Serialize>(&mut self, log_file: &LogFile<T>) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, log_file)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+
+    /// Consumes the writer, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_log, MyWarning};
+    use serde_json::{json, Value};
+
+    /// Tests the layout of the JSON document.
+    #[test]
+    fn test_to_json_envelope() {
+        let log = r#"/src/File.swift:10:5: warning: s#{"queue": "TESTAPI", "summary": "Create a task"}#s
+/src/Other.swift:3:1: error: cannot find 'x' in scope"#;
+        let output = to_json(&parse_log::<MyWarning>(log)).unwrap();
+        let value: Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(value["schema_version"], json!(SCHEMA_VERSION));
+        let diagnostics = value["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0]["absolute_path"], "/src/File.swift");
+        assert_eq!(
+            diagnostics[0]["code_fragment"]["task_info"],
+            json!({
+                "severity": "warning",
+                "task": { "summary": "Create a task", "queue": "TESTAPI" },
+            })
+        );
+        assert_eq!(diagnostics[1]["code_fragment"]["severity"], "error");
+        assert_eq!(diagnostics[1]["code_fragment"]["task_info"], Value::Null);
+        assert_eq!(diagnostics[1]["code_fragment"]["notes"], json!([]));
+    }
+
+    /// Tests writing one JSON object per line, laid out as the diagnostics of the envelope.
+    #[test]
+    fn test_json_lines_writer() {
+        let diagnostics = parse_log::<MyWarning>(
+            "/src/A.swift:1:2: warning: unused variable 'x'\n/src/B.swift:3:4: error: cannot find 'y' in scope\n",
+        );
+        let mut writer = JsonLinesWriter::new(Vec::new());
+        for log_file in &diagnostics {
+            writer.write(log_file).unwrap();
+        }
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+
+        let envelope: Value = serde_json::from_str(&to_json(&diagnostics).unwrap()).unwrap();
+        let lines: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(&lines, envelope["diagnostics"].as_array().unwrap());
+    }
+}
@@ -0,0 +1,132 @@
+use once_cell::sync::Lazy;
+
+use crate::{MessageNames, Severity};
+
+/// The keywords `warning`, `error`, `note` and `remark`.
+static STANDARD: Lazy<SeverityLexicon> = Lazy::new(SeverityLexicon::default);
+
+/// The severity keywords recognized before the `:` following the location of a diagnostic.
+///
+/// Keywords are matched case-insensitively, so `Warning:` and `ERROR:` are recognized
+/// by default. More keywords can be added for other tools or localized logs, and given
+/// to a [`LogParser`](crate::LogParser) with [`lexicon`](crate::LogParserBuilder::lexicon).
+///
+/// ```
+/// use xcode_log_parser::{Severity, SeverityLexicon};
+///
+/// let lexicon = SeverityLexicon::new().keyword("fatal error", Severity::Error);
+/// assert_eq!(lexicon.severity("Fatal Error"), Some(Severity::Error));
+/// assert_eq!(lexicon.severity("WARNING"), Some(Severity::Warning));
+/// assert_eq!(lexicon.severity("info"), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeverityLexicon {
+    /// Lowercase keywords and the severity they stand for.
+    keywords: Vec<(String, Severity)>,
+}
+
+impl Default for SeverityLexicon {
+    fn default() -> Self {
+        SeverityLexicon {
+            keywords: vec![
+                (MessageNames::WARNING.to_string(), Severity::Warning),
+                (MessageNames::ERROR.to_string(), Severity::Error),
+                (MessageNames::NOTE.to_string(), Severity::Note),
+                (MessageNames::REMARK.to_string(), Severity::Remark),
+            ],
+        }
+    }
+}
+
+impl SeverityLexicon {
+    /// Creates a `SeverityLexicon` holding the keywords `warning`, `error`, `note` and `remark`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the lexicon holding the standard keywords, shared by the parsers that do not set one.
+    pub(crate) fn standard() -> &'static Self {
+        &STANDARD
+    }
+
+    /// Adds a keyword, replacing the severity of a keyword already known.
+    ///
+    /// # Arguments
+    ///
+    /// * `keyword` - The keyword as printed in logs, such as `fatal error` or `Warnung`.
+    /// * `severity` - The severity the keyword stands for.
+    pub fn keyword(mut self, keyword: &str, severity: Severity) -> Self {
+        let keyword = keyword.trim().to_lowercase();
+        self.keywords.retain(|(known, _)| *known != keyword);
+        self.keywords.push((keyword, severity));
+        self
+    }
+
+    /// Returns the severity a keyword stands for, ignoring case and surrounding whitespace.
+    ///
+    /// # Arguments
+    ///
+    /// * `keyword` - A string slice that holds the keyword, such as `Warning`.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Severity>` - The matching `Severity`, otherwise `None`.
+    pub fn severity(&self, keyword: &str) -> Option<Severity> {
+        let keyword = keyword.trim();
+        let matches = |known: &str| {
+            if keyword.is_ascii() {
+                known.eq_ignore_ascii_case(keyword)
+            } else {
+                known == keyword.to_lowercase()
+            }
+        };
+        self.keywords
+            .iter()
+            .find(|(known, _)| matches(known))
+            .map(|(_, severity)| *severity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LogParser, MyWarning};
+
+    /// Tests reading capitalized and added keywords with a parser.
+    #[test]
+    fn test_log_parser_severity_lexicon() {
+        let log = "/src/A.swift:1:2: Warning: unused\n\
+                   /src/B.m:1:10: fatal error: 'missing.h' file not found\n\
+                   /src/C.swift:3:4: Fehler: Typkonflikt\n";
+        let severities = |parser: LogParser| -> Vec<Option<Severity>> {
+            parser
+                .parse_build_log::<MyWarning>(log)
+                .diagnostics()
+                .iter()
+                .map(|log_file| log_file.code_fragment().unwrap().severity())
+                .collect()
+        };
+        assert_eq!(
+            severities(LogParser::default()),
+            vec![Some(Severity::Warning), None, None]
+        );
+
+        let lexicon = SeverityLexicon::new()
+            .keyword("fatal error", Severity::Error)
+            .keyword("Fehler", Severity::Error);
+        let parser = LogParser::builder().lexicon(lexicon).build();
+        assert_eq!(
+            severities(parser.clone()),
+            vec![
+                Some(Severity::Warning),
+                Some(Severity::Error),
+                Some(Severity::Error)
+            ]
+        );
+        let log_file = parser.parse_line::<MyWarning>("/src/C.swift:3:4: FEHLER: Typkonflikt");
+        assert_eq!(
+            log_file.unwrap().code_fragment().unwrap().text(),
+            "Typkonflikt"
+        );
+    }
+}
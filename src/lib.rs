@@ -1,17 +1,411 @@
+use once_cell::sync::Lazy;
 use regex::Regex;
-use serde::Deserialize;
+use serde::ser::SerializeStruct;
+use serde::de::DeserializeOwned;
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+mod build_log;
+mod borrowed;
+mod analyzer;
+mod artifacts;
+mod assembler;
+mod availability;
+mod baseline;
+mod build_step;
+mod category;
+mod changed_lines;
+mod column;
+#[cfg(feature = "toml")]
+mod config;
+mod continuation;
+mod crash;
+mod dedup;
+mod dependency;
+mod deprecation;
+mod dialect;
+mod distribution;
+mod environment;
+mod error;
+pub mod format;
+mod index;
+mod infrastructure;
+mod iter;
+mod lexicon;
+#[cfg(any(
+    feature = "jira",
+    feature = "youtrack",
+    feature = "github-issues",
+    feature = "github-review",
+    feature = "slack",
+    feature = "otel"
+))]
+pub mod integrations;
+pub mod json;
+mod linker;
+mod note;
+mod outcome;
+mod ownership;
+mod parser;
+mod path_filter;
+mod payload;
+mod phase_timing;
+mod policy;
+mod registry;
+mod resource;
+mod sanitizer;
+mod scanner;
+pub mod report;
+pub mod sarif;
+mod signing;
+mod snippet;
+mod span;
+mod stats;
+mod suppression;
+#[cfg(feature = "sqlite")]
+pub mod storage;
+mod tail;
+mod test_event;
+mod theme;
+mod timing;
+#[cfg(feature = "xcactivitylog")]
+pub mod xcactivitylog;
+pub mod xcresult;
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "tokio")]
+mod stream;
+
+pub use artifacts::{Artifacts, ArtifactsParser, BinaryUuid, Dsym};
+pub use availability::Availability;
+pub use baseline::{diff, Baseline, BaselineDiff, Fingerprint};
+pub use borrowed::{CodeFragmentRef, LogFileRef};
+pub use build_log::{parse_log, parse_reader, BuildLog};
+pub use build_step::{BuildStep, BuildStepHeader};
+pub use category::Category;
+pub use changed_lines::{filter_by_diff, ChangedLines};
+pub use column::Columns;
+#[cfg(feature = "toml")]
+pub use config::{
+    Config, ConfigError, FilterConfig, OutputConfig, PayloadConfig, ThresholdConfig, CONFIG_FILE_NAME,
+};
+pub use crash::{ToolchainCrash, ToolchainCrashParser};
+pub use dedup::{dedup, Deduplicated};
+pub use dependency::{group_by_dependency, split_dependencies};
+pub use deprecation::Deprecation;
+pub use dialect::Dialect;
+pub use distribution::{
+    ExportError, Notarization, NotarizationIssue, NotarizationParser, NotarizationStatus,
+};
+pub use environment::{Destination, Environment, EnvironmentParser};
+pub use error::ParseError;
+pub use index::LogIndex;
+pub use infrastructure::{InfrastructureError, InfrastructureErrorKind};
+pub use iter::LogFileIter;
+pub use lexicon::SeverityLexicon;
+pub use linker::{LinkerError, LinkerErrorKind, LinkerErrorParser};
+pub use note::Note;
+pub use outcome::{BuildOutcome, BuildOutcomeParser, BuildStatus};
+pub use ownership::CodeOwners;
+pub use parser::{LogParser, LogParserBuilder, ParseOptions};
+pub use path_filter::PathFilter;
+pub use payload::{PayloadDelimiters, PayloadFormat};
+pub use phase_timing::{PhaseTiming, PhaseTimings, PhaseTimingsParser, TargetTiming};
+pub use policy::{Budget, Policy, Verdict, Violation};
+pub use registry::{Discriminator, DynTaskMessage, MessageRegistry};
+pub use resource::{ResourceIssue, ResourceTool};
+pub use sanitizer::{Sanitizer, SanitizerReport, SanitizerReportParser, StackFrame};
+pub use scanner::ParserBackend;
+pub use signing::{SigningIssue, SigningIssueKind};
+pub use snippet::{attach_snippets, Snippet};
+pub use span::Spans;
+pub use stats::{Stats, TypeCheck};
+pub use suppression::{Date, Suppression, Suppressions};
+pub use tail::{TailEvent, TailParser};
+pub use test_event::{TestCase, TestEvent};
+pub use theme::Theme;
+pub use timing::{TaskTiming, TimingSummary, TimingSummaryParser};
+#[cfg(feature = "rayon")]
+pub use parallel::parse_log_parallel;
+#[cfg(feature = "tokio")]
+pub use stream::LogFileStream;
+#[cfg(feature = "derive")]
+pub use xcode_log_parser_derive::TaskMessage;
+
+#[cfg(all(test, feature = "derive"))]
+extern crate self as xcode_log_parser;
+
+/// Items used by the code generated by `#[derive(TaskMessage)]`. Not public API.
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    pub use once_cell::sync::Lazy;
+    pub use regex::Regex;
+    pub use serde_json;
+
+    /// Builds the regular expression capturing the payload between the given markers.
+    pub fn payload_regex(open: &str, close: &str) -> Regex {
+        Regex::new(&format!("{}(.+?){}", regex::escape(open), regex::escape(close))).unwrap()
+    }
+}
+
+/// Matches the file path at the start of a log line, followed by the rest of the line.
+static LOG_FILE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(.+?):(.*)?"#).unwrap());
+
+/// Matches the file path at the start of a log line up to the first `:line:column:`,
+/// followed by the location and the rest of the line.
+static LOCATED_LOG_FILE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^(.+?):(\d+:\d+:.*)"#).unwrap());
+
+/// Splits a log line into its file path and the rest of the line following the `:` after it.
+///
+/// Paths such as `/Users/me/My Project (iOS)/File.swift` may hold spaces, parentheses
+/// and `:`, so the path ends at the first `:line:column:` of the line. Lines without
+/// one, such as `ld: warning: ...`, end their path at the first `:`.
+fn split_path(haystack: &str) -> Option<(&str, &str)> {
+    let cap = LOCATED_LOG_FILE_REGEX
+        .captures(haystack)
+        .or_else(|| LOG_FILE_REGEX.captures(haystack))?;
+    Some((cap.get(1)?.as_str(), cap.get(2).map_or("", |m| m.as_str())))
+}
+
+/// Matches the `line:column:` location, followed by the rest of the line.
+static CODE_FRAGMENT_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(\d+):(\d+):(.*)?"#).unwrap());
+
+/// Matches the severity keyword of a message, followed by its text.
+static MESSAGE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\s?(.+?):\s?(.+)"#).unwrap());
+
+/// Matches the warnings enabled by `-warn-long-expression-type-checking` and
+/// `-warn-long-function-bodies`, such as `instance method 'load()' took 512ms to type-check (limit: 100ms)`.
+static TYPE_CHECK_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^.+ took (\d+)ms to type-check"#).unwrap());
+
+/// Matches the warning flag clang names at the end of a diagnostic, such as
+/// `[-Wdeprecated-declarations]` or `[-Werror,-Wunused-variable]`, or GCC's
+/// `[-Werror=unused-variable]`.
+static FLAG_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\[(?P<prefix>(?:-W[^\],\s]+,)*)(?:(?P<werror>-Werror=)|-W)(?P<flag>[^\],\s]+)\]$"#)
+        .unwrap()
+});
+
+/// Splits the rest of a log line following its location into its severity and message.
+///
+/// # Arguments
+///
+/// * `haystack` - A string slice that holds the rest of the line following `line:column:`.
+/// * `lexicon` - The severity keywords recognized.
+///
+/// # Returns
+///
+/// * `(Option<Severity>, Option<&str>, &str)` - The severity and its keyword if the line
+///   names a known one, and the message following it, otherwise the whole `haystack`.
+fn split_message<'a>(
+    haystack: &'a str,
+    lexicon: &SeverityLexicon,
+) -> (Option<Severity>, Option<&'a str>, &'a str) {
+    let Some(cap) = MESSAGE_REGEX.captures(haystack) else {
+        return (None, None, haystack);
+    };
+    match (lexicon.severity(&cap[1]), cap.get(1), cap.get(2)) {
+        (Some(severity), Some(keyword), Some(message)) => (
+            Some(severity),
+            Some(keyword.as_str().trim()),
+            message.as_str(),
+        ),
+        _ => (None, None, haystack),
+    }
+}
+
+/// Returns the warning flag named at the end of a diagnostic, without `-Werror`.
+fn warning_flag(text: &str) -> Option<String> {
+    FLAG_REGEX
+        .captures(text.trim_end())
+        .map(|cap| format!("-W{}", &cap["flag"]))
+}
+
+/// Returns whether the flags named at the end of a diagnostic turn it into an error with
+/// `-Werror`, as in `[-Werror,-Wunused-variable]` or `[-Werror=unused-variable]`.
+fn escalated_by_werror(text: &str) -> bool {
+    FLAG_REGEX.captures(text.trim_end()).is_some_and(|cap| {
+        cap.name("werror").is_some()
+            || cap["prefix"]
+                .split(',')
+                .any(|flag| flag == "-Werror" || flag.starts_with("-Werror="))
+    })
+}
+
+/// Returns the milliseconds reported by a slow type-checking warning, if the text is one.
+fn type_check_ms(text: &str) -> Option<u64> {
+    TYPE_CHECK_REGEX.captures(text)?[1].parse().ok()
+}
 
 /// Represents a log file with an absolute path and an optional code fragment.
-#[derive(Debug)]
+///
+/// Two log files are equal, and hash alike, when their paths and code fragments are. The
+/// [`spans`](Self::spans) within the original line are metadata of the parse, so the same
+/// diagnostic printed with different spacing, or read from an xcresult bundle, is equal
+/// to itself. [`dedup`] matches diagnostics by their path, location and text, and
+/// [`Fingerprint`] by their path, severity and text across builds.
+#[derive(Debug, Clone, Serialize)]
 pub struct LogFile<T: TaskMessage> {
     absolute_path: String,
     code_fragment: Option<CodeFragment<T>>,
+    /// Boxed to keep diagnostics parsed from other sources small.
+    #[serde(skip)]
+    spans: Option<Box<Spans>>,
+}
+
+impl<T: TaskMessage + PartialEq> PartialEq for LogFile<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.absolute_path == other.absolute_path && self.code_fragment == other.code_fragment
+    }
 }
 
-impl<T: TaskMessage + Deserialize<'static>> RegexParse for LogFile<T> {
+impl<T: TaskMessage + Eq> Eq for LogFile<T> {}
+
+impl<T: TaskMessage + Hash> Hash for LogFile<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.absolute_path.hash(state);
+        self.code_fragment.hash(state);
+    }
+}
+
+impl<T: TaskMessage> LogFile<T> {
+    /// Creates a new `LogFile` from values obtained outside of a log line.
+    pub(crate) fn new(absolute_path: String, code_fragment: Option<CodeFragment<T>>) -> Self {
+        LogFile {
+            absolute_path,
+            code_fragment,
+            spans: None,
+        }
+    }
+
+    /// Moves the spans, taken within the parsed text, to the line the text was read from.
+    /// They are dropped when the text was rewritten rather than sliced from the line, such
+    /// as to strip ANSI escapes.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The line as it was read.
+    /// * `parsed` - The text the diagnostic was parsed from.
+    pub(crate) fn relocate_spans(&mut self, line: &str, parsed: &str) {
+        match span::offset_in(line, parsed) {
+            Some(offset) => {
+                if let Some(spans) = self.spans.as_mut() {
+                    spans.shift(offset);
+                }
+            }
+            None => self.spans = None,
+        }
+    }
+
+    /// Returns the absolute path of the file the log line refers to.
+    pub fn absolute_path(&self) -> &str {
+        &self.absolute_path
+    }
+
+    /// Returns the code fragment parsed from the log line, if any.
+    pub fn code_fragment(&self) -> Option<&CodeFragment<T>> {
+        self.code_fragment.as_ref()
+    }
+
+    /// Returns the byte ranges of the path, location, severity keyword and text within the
+    /// parsed line, or `None` if the diagnostic was not parsed from a single line or the
+    /// line was rewritten before parsing, such as to strip its ANSI color codes.
+    pub fn spans(&self) -> Option<&Spans> {
+        self.spans.as_deref()
+    }
+
+    /// Returns the name of the dependency the file belongs to, such as `Alamofire` for a
+    /// file under `Pods/Alamofire` or `swift-nio` for a file under
+    /// `SourcePackages/checkouts/swift-nio`, or `None` for a file of the project itself.
+    pub fn dependency(&self) -> Option<&str> {
+        dependency::dependency_of(&self.absolute_path)
+    }
+
+    /// Returns whether the file belongs to the project rather than to a dependency.
+    pub fn is_first_party(&self) -> bool {
+        self.dependency().is_none()
+    }
+
+    /// Creates a new `LogFile` from the given log line using the default [`ParserBackend`].
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - A string slice that holds the log line to be parsed.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self>` - A `LogFile` instance if parsing is successful, otherwise `None`.
+    pub fn parse(haystack: &str) -> Option<Self> {
+        Self::parse_with(haystack, ParserBackend::default())
+    }
+
+    /// Creates a new `LogFile` from the given log line using the given backend.
+    ///
+    /// Both backends produce the same result; the scanner is faster on common lines.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - A string slice that holds the log line to be parsed.
+    /// * `backend` - The parser used for the `path:line:column:` prefix.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self>` - A `LogFile` instance if parsing is successful, otherwise `None`.
+    pub fn parse_with(haystack: &str, backend: ParserBackend) -> Option<Self> {
+        Self::parse_with_task(
+            haystack,
+            backend,
+            SeverityLexicon::standard(),
+            T::new_from_regex,
+        )
+    }
+
+    /// Creates a new `LogFile` from the given log line, parsing the task with the given function.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - A string slice that holds the log line to be parsed.
+    /// * `backend` - The parser used for the `path:line:column:` prefix.
+    /// * `lexicon` - The severity keywords recognized.
+    /// * `parse_task` - Parses the task from the message following the severity keyword.
+    pub(crate) fn parse_with_task(
+        haystack: &str,
+        backend: ParserBackend,
+        lexicon: &SeverityLexicon,
+        parse_task: impl FnOnce(&str) -> Option<T>,
+    ) -> Option<Self> {
+        let scanned = scanner::split(haystack, backend)?;
+        let mut spans = Spans::new(haystack, scanned.absolute_path);
+        let code_fragment = scanned.location.map(|(line, column, rest)| {
+            let (severity, keyword, message) = split_message(rest, lexicon);
+            spans.locate(haystack, rest, keyword, message.trim());
+            CodeFragment::from_message(line, column, severity, message, parse_task)
+        });
+
+        Some(LogFile {
+            absolute_path: scanned.absolute_path.to_string(),
+            code_fragment,
+            spans: Some(Box::new(spans)),
+        })
+    }
+}
+
+impl<T: TaskMessage + Serialize> LogFile<T> {
+    /// Serializes the log file into a JSON object, following the schema described in [`json`].
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+impl<T: TaskMessage> RegexParse for LogFile<T> {
     /// Returns the regular expression used to parse a log file.
-    fn regex_value() -> regex::Regex {
-        regex::Regex::new(r#"^(.+?):(.*)?"#).unwrap()
+    fn regex_value() -> &'static Regex {
+        &LOG_FILE_REGEX
     }
 
     /// Creates a new `LogFile` from the given string using regular expression parsing.
@@ -24,29 +418,351 @@ impl<T: TaskMessage + Deserialize<'static>> RegexParse for LogFile<T> {
     ///
     /// * `Option<Self>` - A `LogFile` instance if parsing is successful, otherwise `None`.
     fn new_from_regex(haystack: &str) -> Option<Self> {
-        let cap = &Self::regex_value().captures(haystack)?;
-        let absolute_path = cap.get(1).map(|m| m.as_str())?.to_string();
-        let haystack = cap.get(2).map(|m| m.as_str())?;
+        Self::parse_with(haystack, ParserBackend::Regex)
+    }
 
-        Some(LogFile {
-            absolute_path,
-            code_fragment: CodeFragment::new_from_regex(haystack),
+    /// Creates a new `LogFile` holding a complete diagnostic, reporting why parsing failed.
+    ///
+    /// Unlike [`new_from_regex`](Self::new_from_regex), the line must carry a location,
+    /// a known severity and a task payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - A string slice that holds the log line to be parsed.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, ParseError>` - A `LogFile` instance if parsing is successful, otherwise the stage that failed.
+    fn try_parse(haystack: &str) -> Result<Self, ParseError> {
+        let (absolute_path, rest) =
+            split_path(haystack).ok_or_else(|| ParseError::MissingPath(haystack.to_string()))?;
+        let code_fragment = CodeFragment::try_parse(rest)?;
+        let mut spans = Spans::new(haystack, absolute_path);
+        let location = CODE_FRAGMENT_REGEX.captures(rest);
+        if let Some(rest) = location.and_then(|cap| cap.get(3)).map(|m| m.as_str()) {
+            let (_, keyword, message) = split_message(rest, SeverityLexicon::standard());
+            spans.locate(haystack, rest, keyword, message.trim());
+        }
+
+        Ok(LogFile {
+            absolute_path: absolute_path.to_string(),
+            code_fragment: Some(code_fragment),
+            spans: Some(Box::new(spans)),
         })
     }
 }
 
+impl<T: TaskMessage> FromStr for LogFile<T> {
+    type Err = ParseError;
+
+    /// Parses a log line holding a complete diagnostic, the same way as [`RegexParse::try_parse`].
+    fn from_str(haystack: &str) -> Result<Self, Self::Err> {
+        Self::try_parse(haystack)
+    }
+}
+
+impl<T: TaskMessage> fmt::Display for LogFile<T> {
+    /// Renders the diagnostic in the canonical `path:line:column: severity: text` form,
+    /// or only the path if the line holds no location.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.code_fragment {
+            Some(code_fragment) => write!(f, "{}:{}", self.absolute_path, code_fragment),
+            None => f.write_str(&self.absolute_path),
+        }
+    }
+}
+
 /// Represents a fragment of code with line and column information, and optional task information.
-#[derive(Debug)]
+///
+/// Two code fragments are equal when every field is, including the task, the continuation
+/// lines, notes and snippet attached by [`BuildLog`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct CodeFragment<T: TaskMessage> {
     line: usize,
     column: usize,
+    severity: Option<Severity>,
+    text: String,
     task_info: Option<Message<T>>,
+    source_excerpt: Option<String>,
+    caret: Option<String>,
+    fixit: Option<String>,
+    notes: Vec<Note>,
+    snippet: Option<Snippet>,
+    rule: Option<String>,
+    category: Option<Category>,
+    type_check_ms: Option<u64>,
+    flag: Option<String>,
+    escalated: bool,
+    theme: Theme,
 }
 
-impl<T: TaskMessage + Deserialize<'static>> RegexParse for CodeFragment<T> {
+impl<T: TaskMessage> CodeFragment<T> {
+    /// Creates a new `CodeFragment` from values obtained outside of a log line.
+    ///
+    /// The task information is parsed from `text`, the same way it is for a log line.
+    pub(crate) fn new(line: usize, column: usize, severity: Option<Severity>, text: String) -> Self {
+        let task_info = severity.and_then(|severity| {
+            T::new_from_regex(&text).map(|task| Message::with_severity(severity, task))
+        });
+        let type_check_ms = type_check_ms(&text);
+        let flag = warning_flag(&text);
+        let escalated = escalated_by_werror(&text);
+        let theme = Theme::classify(&text, flag.as_deref());
+
+        CodeFragment {
+            line,
+            column,
+            severity,
+            text,
+            task_info,
+            source_excerpt: None,
+            caret: None,
+            fixit: None,
+            notes: Vec::new(),
+            snippet: None,
+            rule: None,
+            category: None,
+            type_check_ms,
+            flag,
+            escalated,
+            theme,
+        }
+    }
+
+    /// Returns the line number of the code fragment.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the column number of the code fragment, counted in UTF-8 bytes as reported by
+    /// the compiler. See [`columns`](Self::columns) for the column in characters.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Returns the severity of the diagnostic, if the log line names a known one.
+    pub fn severity(&self) -> Option<Severity> {
+        self.severity
+    }
+
+    /// Returns the text of the diagnostic without the severity keyword.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the task information attached to the code fragment, if any.
+    pub fn task_info(&self) -> Option<&Message<T>> {
+        self.task_info.as_ref()
+    }
+
+    /// Returns the offending source line printed after the diagnostic, if any.
+    pub fn source_excerpt(&self) -> Option<&str> {
+        self.source_excerpt.as_deref()
+    }
+
+    /// Returns the caret line marking the offending range in the source excerpt, if any.
+    pub fn caret(&self) -> Option<&str> {
+        self.caret.as_deref()
+    }
+
+    /// Returns the suggested replacement printed below the caret line, if any.
+    pub fn fixit(&self) -> Option<&str> {
+        self.fixit.as_deref()
+    }
+
+    /// Returns the notes printed after the diagnostic, such as "expanded from macro".
+    pub fn notes(&self) -> &[Note] {
+        &self.notes
+    }
+
+    /// Returns the source lines around the diagnostic, once attached by [`attach_snippets`].
+    pub fn snippet(&self) -> Option<&Snippet> {
+        self.snippet.as_ref()
+    }
+
+    /// Returns the identifier of the lint rule or analyzer checker that reported the
+    /// diagnostic, such as `line_length` when parsed with [`Dialect::SwiftLint`], or
+    /// `core.NullDereference` for the Clang Static Analyzer.
+    pub fn rule(&self) -> Option<&str> {
+        self.rule.as_deref()
+    }
+
+    /// Returns the kind of tool that reported the diagnostic, or `None` for the compiler.
+    pub fn category(&self) -> Option<Category> {
+        self.category
+    }
+
+    /// Returns how many milliseconds the Swift compiler spent type-checking the expression
+    /// or function the diagnostic reports, for the warnings enabled by
+    /// `-warn-long-expression-type-checking` and `-warn-long-function-bodies`.
+    pub fn type_check_ms(&self) -> Option<u64> {
+        self.type_check_ms
+    }
+
+    /// Returns the warning flag that enabled the diagnostic, such as `-Wdeprecated-declarations`
+    /// for a clang warning ending in `[-Wdeprecated-declarations]`.
+    pub fn flag(&self) -> Option<&str> {
+        self.flag.as_deref()
+    }
+
+    /// Returns whether the diagnostic is a warning turned into an error by `-Werror`, such as
+    /// `error: unused variable 'x' [-Werror,-Wunused-variable]`.
+    ///
+    /// The [`severity`](Self::severity) stays the one printed, `error`, and the
+    /// [`flag`](Self::flag) names the original warning, so reports can tell these errors
+    /// from genuine compile errors.
+    pub fn is_escalated(&self) -> bool {
+        self.escalated && self.severity == Some(Severity::Error)
+    }
+
+    /// Returns the kind of problem the diagnostic is about, such as a deprecation or unused code.
+    pub fn theme(&self) -> Theme {
+        self.theme
+    }
+
+    /// Returns the deprecated symbol, its replacement and the OS version it was deprecated in,
+    /// if the diagnostic reports the use of a deprecated API.
+    pub fn deprecation(&self) -> Option<Deprecation> {
+        if self.theme != Theme::Deprecation {
+            return None;
+        }
+        Deprecation::from_text(&self.text)
+    }
+
+    /// Returns the API used, the OS version it requires and the deployment target, if the
+    /// diagnostic reports the use of an API unavailable on the deployment target.
+    pub fn availability(&self) -> Option<Availability> {
+        if self.theme != Theme::Availability {
+            return None;
+        }
+        Availability::from_diagnostic(&self.text, &self.notes)
+    }
+
+    /// Returns the column of the diagnostic in bytes, characters and UTF-16 code units.
+    ///
+    /// The conversion needs the source line, taken from the [`snippet`](Self::snippet) once
+    /// attached, otherwise from the [`source_excerpt`](Self::source_excerpt) printed in the log.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Columns>` - The columns, or `None` if the source line is unknown.
+    pub fn columns(&self) -> Option<Columns> {
+        let source_line = match &self.snippet {
+            Some(snippet) => snippet.highlighted_line(),
+            None => self.source_excerpt.as_deref()?,
+        };
+        Some(Columns::from_byte_column(source_line, self.column))
+    }
+
+    /// Attaches the source lines around the diagnostic.
+    pub(crate) fn attach_snippet(&mut self, snippet: Option<Snippet>) {
+        self.snippet = snippet;
+    }
+
+    /// Attaches the kind of tool that reported the diagnostic, and its rule or checker if named.
+    pub(crate) fn categorize(&mut self, category: Category, rule: Option<String>) {
+        self.category = Some(category);
+        self.rule = rule;
+    }
+
+    /// Attaches a note printed after the diagnostic.
+    pub(crate) fn push_note(&mut self, note: Note) {
+        self.notes.push(note);
+    }
+
+    /// Creates a new `CodeFragment` from a location and the rest of the log line following it.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The line number of the location.
+    /// * `column` - The column number of the location.
+    /// * `haystack` - A string slice that holds the message following the location.
+    fn from_location(line: usize, column: usize, haystack: &str) -> Self {
+        Self::from_location_with(
+            line,
+            column,
+            haystack,
+            SeverityLexicon::standard(),
+            T::new_from_regex,
+        )
+    }
+
+    /// Creates a new `CodeFragment` from a location, parsing the task with the given function.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The line number of the location.
+    /// * `column` - The column number of the location.
+    /// * `haystack` - A string slice that holds the message following the location.
+    /// * `lexicon` - The severity keywords recognized.
+    /// * `parse_task` - Parses the task from the message following the severity keyword.
+    fn from_location_with(
+        line: usize,
+        column: usize,
+        haystack: &str,
+        lexicon: &SeverityLexicon,
+        parse_task: impl FnOnce(&str) -> Option<T>,
+    ) -> Self {
+        let (severity, _, message) = split_message(haystack, lexicon);
+        Self::from_message(line, column, severity, message, parse_task)
+    }
+
+    /// Creates a new `CodeFragment` from a location and the message following its severity.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The line number of the location.
+    /// * `column` - The column number of the location.
+    /// * `severity` - The severity named before the message, if known.
+    /// * `message` - A string slice that holds the message, as split by [`split_message`].
+    /// * `parse_task` - Parses the task from the message, when it follows a known severity.
+    fn from_message(
+        line: usize,
+        column: usize,
+        severity: Option<Severity>,
+        message: &str,
+        parse_task: impl FnOnce(&str) -> Option<T>,
+    ) -> Self {
+        let task_info = severity.and_then(|severity| {
+            parse_task(message).map(|task| Message::with_severity(severity, task))
+        });
+        let text = message.trim();
+        let flag = warning_flag(text);
+        let escalated = escalated_by_werror(text);
+        let theme = Theme::classify(text, flag.as_deref());
+
+        CodeFragment {
+            line,
+            column,
+            severity,
+            text: text.to_string(),
+            task_info,
+            source_excerpt: None,
+            caret: None,
+            fixit: None,
+            notes: Vec::new(),
+            snippet: None,
+            rule: None,
+            category: None,
+            type_check_ms: type_check_ms(text),
+            flag,
+            escalated,
+            theme,
+        }
+    }
+
+    /// Attaches the continuation lines printed after the diagnostic.
+    pub(crate) fn attach_continuation(&mut self, continuation: &continuation::Continuation) {
+        self.source_excerpt = Some(continuation.source_excerpt.to_string());
+        self.caret = Some(continuation.caret.to_string());
+        self.fixit = continuation.fixit.map(|fixit| fixit.trim().to_string());
+    }
+}
+
+impl<T: TaskMessage> RegexParse for CodeFragment<T> {
     /// Returns the regular expression used to parse a code fragment.
-    fn regex_value() -> regex::Regex {
-        regex::Regex::new(r#"(\d+):(\d+):(.*)?"#).unwrap()
+    fn regex_value() -> &'static Regex {
+        &CODE_FRAGMENT_REGEX
     }
 
     /// Creates a new `CodeFragment` from the given string using regular expression parsing.
@@ -64,24 +780,138 @@ impl<T: TaskMessage + Deserialize<'static>> RegexParse for CodeFragment<T> {
         let column: usize = cap.get(2).map(|m| m.as_str())?.parse().ok()?;
         let haystack = cap.get(3).map(|m| m.as_str())?;
 
-        Some(CodeFragment {
+        Some(Self::from_location(line, column, haystack))
+    }
+
+    /// Creates a new `CodeFragment` holding a complete diagnostic, reporting why parsing failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - A string slice that holds the code fragment to be parsed.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, ParseError>` - A `CodeFragment` instance if parsing is successful, otherwise the stage that failed.
+    fn try_parse(haystack: &str) -> Result<Self, ParseError> {
+        let invalid_location = || ParseError::InvalidLocation(haystack.trim().to_string());
+        let cap = Self::regex_value()
+            .captures(haystack)
+            .ok_or_else(invalid_location)?;
+        let line: usize = cap[1].parse().map_err(|_| invalid_location())?;
+        let column: usize = cap[2].parse().map_err(|_| invalid_location())?;
+        let haystack = cap.get(3).map_or("", |m| m.as_str());
+
+        let task_info = Message::<T>::try_parse(haystack)?;
+        let text = Message::<T>::regex_value()
+            .captures(haystack)
+            .and_then(|cap| cap.get(2))
+            .map_or("", |m| m.as_str().trim());
+        let flag = warning_flag(text);
+        let escalated = escalated_by_werror(text);
+        let theme = Theme::classify(text, flag.as_deref());
+
+        Ok(CodeFragment {
             line,
             column,
-            task_info: Message::new_from_regex(haystack),
+            severity: Some(task_info.severity()),
+            text: text.to_string(),
+            task_info: Some(task_info),
+            source_excerpt: None,
+            caret: None,
+            fixit: None,
+            notes: Vec::new(),
+            snippet: None,
+            rule: None,
+            category: None,
+            type_check_ms: type_check_ms(text),
+            flag,
+            escalated,
+            theme,
         })
     }
 }
 
+impl<T: TaskMessage> FromStr for CodeFragment<T> {
+    type Err = ParseError;
+
+    /// Parses the part of a log line following the file path, the same way as [`RegexParse::try_parse`].
+    fn from_str(haystack: &str) -> Result<Self, Self::Err> {
+        Self::try_parse(haystack)
+    }
+}
+
+impl<T: TaskMessage> fmt::Display for CodeFragment<T> {
+    /// Renders the code fragment as `line:column: severity: text`, leaving out the
+    /// severity if it is unknown.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: ", self.line, self.column)?;
+        if let Some(severity) = self.severity {
+            write!(f, "{}: ", severity)?;
+        }
+        f.write_str(&self.text)
+    }
+}
+
 /// Represents a message with different types of task information.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Message<T: TaskMessage> {
     Warning(T),
+    Error(T),
+    Note(T),
+    Remark(T),
+}
+
+impl<T: TaskMessage> Message<T> {
+    /// Wraps the task into the message matching the given severity.
+    pub(crate) fn with_severity(severity: Severity, task: T) -> Self {
+        match severity {
+            Severity::Warning => Message::Warning(task),
+            Severity::Error => Message::Error(task),
+            Severity::Note => Message::Note(task),
+            Severity::Remark => Message::Remark(task),
+        }
+    }
+
+    /// Returns the task carried by the message, regardless of its severity.
+    pub fn task(&self) -> &T {
+        match self {
+            Message::Warning(task)
+            | Message::Error(task)
+            | Message::Note(task)
+            | Message::Remark(task) => task,
+        }
+    }
+
+    /// Returns the severity of the message.
+    pub fn severity(&self) -> Severity {
+        match self {
+            Message::Warning(_) => Severity::Warning,
+            Message::Error(_) => Severity::Error,
+            Message::Note(_) => Severity::Note,
+            Message::Remark(_) => Severity::Remark,
+        }
+    }
+
+    /// Returns the severity keyword of the message as it appears in the log.
+    pub fn name(&self) -> &'static str {
+        self.severity().name()
+    }
+}
+
+impl<T: TaskMessage + Serialize> Serialize for Message<T> {
+    /// Serializes the message as an object holding its `severity` and `task`.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Message", 2)?;
+        state.serialize_field("severity", &self.severity())?;
+        state.serialize_field("task", self.task())?;
+        state.end()
+    }
 }
 
-impl<T: TaskMessage + Deserialize<'static>> RegexParse for Message<T> {
+impl<T: TaskMessage> RegexParse for Message<T> {
     /// Returns the regular expression used to parse a message.
-    fn regex_value() -> regex::Regex {
-        regex::Regex::new(r#"\s?(.+?):\s?(.+)"#).unwrap()
+    fn regex_value() -> &'static Regex {
+        &MESSAGE_REGEX
     }
 
     /// Creates a new `Message` from the given string using regular expression parsing.
@@ -98,27 +928,109 @@ impl<T: TaskMessage + Deserialize<'static>> RegexParse for Message<T> {
         let message_type = cap.get(1).map(|m| m.as_str())?;
         let haystack = cap.get(2).map(|m| m.as_str())?;
 
-        match message_type {
-            MessageNames::WARNING => match T::new_from_regex(haystack) {
-                Some(value) => Some(Message::Warning(value)),
-                None => None,
-            },
-            _ => None,
-        }
+        let severity = Severity::from_name(message_type)?;
+        T::new_from_regex(haystack).map(|task| Message::with_severity(severity, task))
+    }
+
+    /// Creates a new `Message`, reporting why parsing failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - A string slice that holds the message to be parsed.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, ParseError>` - A `Message` instance if parsing is successful, otherwise the stage that failed.
+    fn try_parse(haystack: &str) -> Result<Self, ParseError> {
+        let cap = Self::regex_value()
+            .captures(haystack)
+            .ok_or_else(|| ParseError::MissingSeverity(haystack.trim().to_string()))?;
+        let severity = Severity::from_name(&cap[1])
+            .ok_or_else(|| ParseError::UnknownSeverity(cap[1].trim().to_string()))?;
+
+        T::try_parse(&cap[2]).map(|task| Message::with_severity(severity, task))
+    }
+}
+
+impl<T: TaskMessage> FromStr for Message<T> {
+    type Err = ParseError;
+
+    /// Parses a message starting with its severity keyword, the same way as [`RegexParse::try_parse`].
+    fn from_str(haystack: &str) -> Result<Self, Self::Err> {
+        Self::try_parse(haystack)
     }
 }
 
 /// Enum representing the names of message types.
 pub enum MessageNames {
     Warning,
+    Error,
+    Note,
+    Remark,
 }
 
 impl MessageNames {
     const WARNING: &'static str = "warning";
+    const ERROR: &'static str = "error";
+    const NOTE: &'static str = "note";
+    const REMARK: &'static str = "remark";
+}
+
+/// Represents the severity of a diagnostic, ordered from the least to the most severe.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Remark,
+    Note,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// Returns the severity matching the keyword used in the log, if any, ignoring case.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - A string slice that holds the severity keyword, such as `warning`.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self>` - The matching `Severity`, otherwise `None`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        SeverityLexicon::standard().severity(name)
+    }
+
+    /// Returns the keyword of the severity as it appears in the log.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Warning => MessageNames::WARNING,
+            Severity::Error => MessageNames::ERROR,
+            Severity::Note => MessageNames::NOTE,
+            Severity::Remark => MessageNames::REMARK,
+        }
+    }
+}
+
+impl FromStr for Severity {
+    type Err = ParseError;
+
+    /// Parses a severity keyword, ignoring case, the same way as [`Severity::from_name`].
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::from_name(name).ok_or_else(|| ParseError::UnknownSeverity(name.trim().to_string()))
+    }
+}
+
+impl fmt::Display for Severity {
+    /// Renders the keyword of the severity as it appears in the log.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
 }
 
 /// Represents a warning message with a summary and queue.
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct MyWarning {
     #[serde(rename = "summary")]
     summary: String,
@@ -145,8 +1057,9 @@ impl TaskMessage for MyWarning {
 
 impl RegexParse for MyWarning {
     /// Returns the regular expression used to parse a warning message.
-    fn regex_value() -> regex::Regex {
-        Regex::new(r#"s#(.+?)#s(.+)?"#).unwrap()
+    fn regex_value() -> &'static Regex {
+        static REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"s#(.+?)#s(.+)?"#).unwrap());
+        &REGEX
     }
 
     /// Creates a new `MyWarning` from the given string using regular expression parsing.
@@ -163,12 +1076,44 @@ impl RegexParse for MyWarning {
         let json_text = cap.get(1).map(|m| m.as_str())?;
         serde_json::from_str(json_text).ok()
     }
+
+    /// Creates a new `MyWarning`, reporting why parsing failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - A string slice that holds the warning message to be parsed.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, ParseError>` - A `MyWarning` instance if parsing is successful, otherwise the stage that failed.
+    fn try_parse(haystack: &str) -> Result<Self, ParseError> {
+        let cap = Self::regex_value()
+            .captures(haystack)
+            .ok_or_else(|| ParseError::MissingPayload(haystack.trim().to_string()))?;
+        let json_text = &cap[1];
+        serde_json::from_str(json_text).map_err(|error| ParseError::InvalidPayload {
+            payload: json_text.to_string(),
+            reason: error.to_string(),
+        })
+    }
+}
+
+impl FromStr for MyWarning {
+    type Err = ParseError;
+
+    /// Parses a warning message holding an `s#...#s` task payload, the same way as [`RegexParse::try_parse`].
+    fn from_str(haystack: &str) -> Result<Self, Self::Err> {
+        Self::try_parse(haystack)
+    }
 }
 
 /// A trait for parsing strings using regular expressions.
 pub trait RegexParse: Sized {
     /// Returns the regular expression used for parsing.
-    fn regex_value() -> regex::Regex;
+    ///
+    /// The expression is compiled once and shared by every call, typically by
+    /// keeping it in a `static` [`Lazy`], since it is used for every parsed line.
+    fn regex_value() -> &'static Regex;
     
     /// Creates a new instance from the given string using regular expression parsing.
     ///
@@ -180,10 +1125,25 @@ pub trait RegexParse: Sized {
     ///
     /// * `Option<Self>` - A new instance if parsing is successful, otherwise `None`.
     fn new_from_regex(haystack: &str) -> Option<Self>;
+
+    /// Creates a new instance from the given string, reporting why parsing failed.
+    ///
+    /// The default implementation only reports that the text did not match.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - A string slice that holds the text to be parsed.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, ParseError>` - A new instance if parsing is successful, otherwise the reason it failed.
+    fn try_parse(haystack: &str) -> Result<Self, ParseError> {
+        Self::new_from_regex(haystack).ok_or_else(|| ParseError::NoMatch(haystack.to_string()))
+    }
 }
 
 /// A trait representing a task message with methods for retrieving task details.
-pub trait TaskMessage: Deserialize<'static> + RegexParse + std::fmt::Debug {
+pub trait TaskMessage: DeserializeOwned + RegexParse + std::fmt::Debug {
     /// Returns the summary of the task.
     fn task_summary(&self) -> String;
     
@@ -195,6 +1155,8 @@ pub trait TaskMessage: Deserialize<'static> + RegexParse + std::fmt::Debug {
 }
 
 #[cfg(test)]
+// The early tests return from the arm of the match that passes.
+#[allow(clippy::needless_return)]
 mod tests {
     use super::*;
 
@@ -355,9 +1317,85 @@ mod tests {
                 assert_eq!(warning.summary, "Create a task");
                 // Add assertions for warning content if needed
             }
+            other => panic!("Expected a warning message, got {:?}", other),
+        }
+    }
+
+    /// Tests parsing an error line carrying a task payload.
+    #[test]
+    fn test_log_file_parse_error_severity() {
+        let log_line = r#"path/to/file.swift:10:5: error: s#{"queue": "TESTAPI", "summary": "Fix the build"}#s"#;
+        let log_file = LogFile::<MyWarning>::new_from_regex(log_line).unwrap();
+        match log_file.code_fragment.unwrap().task_info.unwrap() {
+            Message::Error(error) => assert_eq!(error.summary, "Fix the build"),
+            other => panic!("Expected an error message, got {:?}", other),
+        }
+    }
+
+    /// Tests parsing a note line carrying a task payload.
+    #[test]
+    fn test_log_file_parse_note_severity() {
+        let log_line = r#"path/to/file.swift:10:5: note: s#{"queue": "TESTAPI", "summary": "Check this"}#s"#;
+        let log_file = LogFile::<MyWarning>::new_from_regex(log_line).unwrap();
+        match log_file.code_fragment.unwrap().task_info.unwrap() {
+            Message::Note(note) => assert_eq!(note.summary, "Check this"),
+            other => panic!("Expected a note message, got {:?}", other),
+        }
+    }
+
+    /// Tests parsing a remark line carrying a task payload.
+    #[test]
+    fn test_log_file_parse_remark_severity() {
+        let log_line = r#"path/to/file.swift:10:5: remark: s#{"queue": "TESTAPI", "summary": "Worth a look"}#s"#;
+        let log_file = LogFile::<MyWarning>::new_from_regex(log_line).unwrap();
+        match log_file.code_fragment.unwrap().task_info.unwrap() {
+            Message::Remark(remark) => assert_eq!(remark.summary, "Worth a look"),
+            other => panic!("Expected a remark message, got {:?}", other),
         }
     }
 
+    /// Tests that an unknown severity keyword is not parsed as a message.
+    #[test]
+    fn test_log_file_parse_unknown_severity() {
+        let log_line = r#"path/to/file.swift:10:5: fatal: s#{"queue": "TESTAPI", "summary": "Create a task"}#s"#;
+        let log_file = LogFile::<MyWarning>::new_from_regex(log_line).unwrap();
+        assert!(log_file.code_fragment.unwrap().task_info.is_none());
+    }
+
+    /// Tests reading parsed data through the public accessors.
+    #[test]
+    fn test_log_file_accessors() {
+        let log_line = r#"path/to/file.swift:12:34: warning: s#{"queue": "TESTAPI", "summary": "Create a task"}#s"#;
+        let log_file = LogFile::<MyWarning>::new_from_regex(log_line).unwrap();
+        assert_eq!(log_file.absolute_path(), "path/to/file.swift");
+
+        let code_fragment = log_file.code_fragment().unwrap();
+        assert_eq!(code_fragment.line(), 12);
+        assert_eq!(code_fragment.column(), 34);
+
+        let message = code_fragment.task_info().unwrap();
+        assert_eq!(message.name(), "warning");
+        assert_eq!(message.task().task_queue(), "TESTAPI");
+        assert_eq!(message.task().task_summary(), "Create a task");
+    }
+
+    /// Tests that plain diagnostics keep their severity and text without a task payload.
+    #[test]
+    fn test_log_file_parse_plain_diagnostic() {
+        let log_line = "path/to/file.swift:7:9: error: cannot find 'foo' in scope";
+        let log_file = LogFile::<MyWarning>::new_from_regex(log_line).unwrap();
+        let code_fragment = log_file.code_fragment().unwrap();
+        assert_eq!(code_fragment.severity(), Some(Severity::Error));
+        assert_eq!(code_fragment.text(), "cannot find 'foo' in scope");
+        assert!(code_fragment.task_info().is_none());
+
+        let log_line = "path/to/file.swift:7:9: something happened: here";
+        let log_file = LogFile::<MyWarning>::new_from_regex(log_line).unwrap();
+        let code_fragment = log_file.code_fragment().unwrap();
+        assert_eq!(code_fragment.severity(), None);
+        assert_eq!(code_fragment.text(), "something happened: here");
+    }
+
     /// Tests parsing a log file with an invalid format.
     #[test]
     fn test_log_file_parse_failure_invalid_format() {
@@ -365,4 +1403,160 @@ mod tests {
         let log_file = LogFile::<MyWarning>::new_from_regex(invalid_log_line);
         assert!(log_file.is_none());
     }
+
+    /// Tests that `try_parse` returns the same diagnostic as `new_from_regex`.
+    #[test]
+    fn test_log_file_try_parse_success() {
+        let log_line = r#"/path/to/File.swift:12:3: warning: s#{"queue": "TESTAPI", "summary": "Create a task"}#s"#;
+        let log_file = LogFile::<MyWarning>::try_parse(log_line).unwrap();
+        assert_eq!(log_file.absolute_path(), "/path/to/File.swift");
+
+        let code_fragment = log_file.code_fragment().unwrap();
+        assert_eq!((code_fragment.line(), code_fragment.column()), (12, 3));
+        assert_eq!(code_fragment.severity(), Some(Severity::Warning));
+        assert_eq!(code_fragment.task_info().unwrap().task().task_queue(), "TESTAPI");
+    }
+
+    /// Tests re-rendering messy lines in their canonical form.
+    #[test]
+    fn test_log_file_display() {
+        let render = |line: &str| LogFile::<MyWarning>::parse(line).unwrap().to_string();
+        assert_eq!(
+            render("/src/A.swift:1:2:   Warning:\tunused variable 'x'  "),
+            "/src/A.swift:1:2: warning: unused variable 'x'"
+        );
+        assert_eq!(
+            render("/src/A.swift:1:2: deprecated: old"),
+            "/src/A.swift:1:2: deprecated: old"
+        );
+        assert_eq!(render("ld: warning: no location"), "ld");
+
+        let colored = "\x1b[1m/src/B.m:3:4: \x1b[0m\x1b[0;1;31merror: \x1b[0m\x1b[1mexpected ';'\x1b[0m";
+        let plain = "/src/C.swift:5:6: warning: unused";
+        let log = format!("{}\n{}\n", colored, plain);
+        let build_log = BuildLog::<MyWarning>::parse(&log);
+        let diagnostics = build_log.diagnostics();
+        assert_eq!(diagnostics[0].to_string(), "/src/B.m:3:4: error: expected ';'");
+        // The spans of a rewritten line would not index the original one.
+        assert!(diagnostics[0].spans().is_none());
+        assert_eq!(&plain[diagnostics[1].spans().unwrap().path()], "/src/C.swift");
+
+        let streamed: Vec<_> = LogFileIter::<_, MyWarning>::new(log.as_bytes())
+            .map(|item| item.unwrap().1)
+            .collect();
+        assert_eq!(streamed, diagnostics);
+        assert_eq!(
+            streamed[0].code_fragment().unwrap().severity(),
+            Some(Severity::Error)
+        );
+    }
+
+    /// Tests cloning, comparing and hashing parsed diagnostics.
+    #[test]
+    fn test_log_file_equality() {
+        use std::collections::HashSet;
+
+        let line = r#"/src/A.swift:1:2: warning: s#{"queue": "API", "summary": "Fix"}#s"#;
+        let log_file = LogFile::<MyWarning>::parse(line).unwrap();
+        assert_eq!(log_file.clone(), log_file);
+        assert_eq!(LogFile::<MyWarning>::parse(line).unwrap(), log_file);
+
+        let spaced = line.replace("warning: ", "warning:  ");
+        let respaced = LogFile::<MyWarning>::parse(&spaced).unwrap();
+        assert_ne!(respaced.spans(), log_file.spans());
+        assert_eq!(respaced, log_file);
+        let unparsed = LogFile::new(
+            log_file.absolute_path().to_string(),
+            log_file.code_fragment().cloned(),
+        );
+        assert_eq!(unparsed, log_file);
+
+        let other = LogFile::<MyWarning>::parse("/src/A.swift:1:2: warning: other").unwrap();
+        let set: HashSet<_> = [log_file.clone(), other, respaced, unparsed, log_file]
+            .into_iter()
+            .collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    /// Tests parsing lines and their parts with `str::parse`.
+    #[test]
+    fn test_from_str() {
+        let log_line = r#"/src/A.swift:1:2: error: s#{"queue": "API", "summary": "Fix"}#s"#;
+        let log_file: LogFile<MyWarning> = log_line.parse().unwrap();
+        assert_eq!(log_file.absolute_path(), "/src/A.swift");
+
+        let code_fragment = "1:2: error: s#{\"queue\": \"API\", \"summary\": \"Fix\"}#s"
+            .parse::<CodeFragment<MyWarning>>()
+            .unwrap();
+        assert_eq!(code_fragment.severity(), Some(Severity::Error));
+        let message = r#"note: s#{"queue": "API", "summary": "Fix"}#s"#
+            .parse::<Message<MyWarning>>()
+            .unwrap();
+        assert_eq!(message.task().task_summary(), "Fix");
+        assert_eq!("Remark".parse::<Severity>(), Ok(Severity::Remark));
+
+        assert_eq!(
+            "/src/A.swift:1:2: fatal: s#{}#s"
+                .parse::<LogFile<MyWarning>>()
+                .unwrap_err(),
+            ParseError::UnknownSeverity("fatal".to_string())
+        );
+        assert_eq!(
+            "unused".parse::<MyWarning>().unwrap_err(),
+            ParseError::MissingPayload("unused".to_string())
+        );
+    }
+
+    /// Tests that `try_parse` reports the stage at which parsing failed.
+    #[test]
+    fn test_log_file_try_parse_errors() {
+        let parse = |line: &str| LogFile::<MyWarning>::try_parse(line).unwrap_err();
+        assert_eq!(parse("no location"), ParseError::MissingPath("no location".to_string()));
+        assert_eq!(
+            parse("/a.swift:x:1: warning: oops"),
+            ParseError::InvalidLocation("x:1: warning: oops".to_string())
+        );
+        assert_eq!(
+            parse("/a.swift:1:1: fatal: s#{}#s"),
+            ParseError::UnknownSeverity("fatal".to_string())
+        );
+        assert_eq!(
+            parse("/a.swift:1:1: warning: unused variable"),
+            ParseError::MissingPayload("unused variable".to_string())
+        );
+        assert!(matches!(
+            parse(r#"/a.swift:1:1: error: s#{"queue": "Q"}#s"#),
+            ParseError::InvalidPayload { payload, .. } if payload == r#"{"queue": "Q"}"#
+        ));
+    }
+
+    /// Tests that regular expressions are compiled once and shared between calls.
+    #[test]
+    fn test_regex_value_is_cached() {
+        assert!(std::ptr::eq(
+            LogFile::<MyWarning>::regex_value(),
+            LogFile::<MyWarning>::regex_value()
+        ));
+        assert!(std::ptr::eq(MyWarning::regex_value(), MyWarning::regex_value()));
+    }
+
+    /// Tests the `TaskMessage` and `RegexParse` implementations generated by the derive macro.
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive_task_message() {
+        #[derive(Debug, serde::Deserialize, TaskMessage)]
+        #[task(summary = "title", queue = "board", open = "TASK", close = "TASK")]
+        struct Ticket {
+            title: String,
+            board: String,
+        }
+
+        let log_line = r#"/src/File.swift:3:1: warning: TASK{"title": "Retry", "board": "API"}TASK"#;
+        let log_file = LogFile::<Ticket>::new_from_regex(log_line).unwrap();
+        let task = log_file.code_fragment().unwrap().task_info().unwrap().task();
+        assert_eq!(task.task_summary(), "Retry");
+        assert_eq!(task.task_queue(), "API");
+        assert_eq!(task.warning_message_after_created(), "");
+        assert!(Ticket::new_from_regex(r#"s#{"title": "Retry", "board": "API"}#s"#).is_none());
+    }
 }
@@ -0,0 +1,406 @@
+use regex::Regex;
+
+/// Represents the kind of a linker error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LinkerErrorKind {
+    /// A symbol referenced but defined nowhere.
+    UndefinedSymbol,
+    /// A symbol defined in more than one object file.
+    DuplicateSymbol,
+}
+
+/// Represents an undefined or duplicate symbol reported by the linker.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LinkerError {
+    kind: LinkerErrorKind,
+    symbol: String,
+    architecture: String,
+    objects: Vec<String>,
+}
+
+impl LinkerError {
+    /// Returns the kind of the error.
+    pub fn kind(&self) -> LinkerErrorKind {
+        self.kind
+    }
+
+    /// Returns the name of the undefined or duplicate symbol.
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Returns the architecture the link failed for, such as `arm64`, or an empty string
+    /// if the linker did not print it.
+    pub fn architecture(&self) -> &str {
+        &self.architecture
+    }
+
+    /// Returns the object files referencing an undefined symbol.
+    pub fn referenced_from(&self) -> &[String] {
+        match self.kind {
+            LinkerErrorKind::UndefinedSymbol => &self.objects,
+            LinkerErrorKind::DuplicateSymbol => &[],
+        }
+    }
+
+    /// Returns the object files defining a duplicate symbol.
+    pub fn defined_in(&self) -> &[String] {
+        match self.kind {
+            LinkerErrorKind::UndefinedSymbol => &[],
+            LinkerErrorKind::DuplicateSymbol => &self.objects,
+        }
+    }
+
+    /// Returns the targets that built the object files of the error, which are the likely
+    /// culprits of a duplicate symbol.
+    ///
+    /// The target is read from the `Target.build` directory of an object file or compiled
+    /// Metal shader, the `libTarget.a` archive holding it, or the `Target.framework` it is
+    /// linked from.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<&str>` - The targets, without duplicates, in the order their objects are printed.
+    pub fn targets(&self) -> Vec<&str> {
+        let mut targets = Vec::new();
+        for target in self
+            .objects
+            .iter()
+            .filter_map(|object| object_target(object))
+        {
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+        targets
+    }
+}
+
+/// Returns the target that built an object file, read from its path.
+fn object_target(object: &str) -> Option<&str> {
+    let path = match object.split_once('(') {
+        Some((archive, _)) => archive,
+        None => object,
+    };
+    let components: Vec<&str> = path.split('/').collect();
+    if let Some(index) = components
+        .iter()
+        .position(|&component| component == "Objects-normal" || component == "Metal")
+    {
+        let directory = components.get(index.checked_sub(1)?)?;
+        return directory.strip_suffix(".build");
+    }
+    if let Some(framework) = components
+        .iter()
+        .rev()
+        .find_map(|component| component.strip_suffix(".framework"))
+    {
+        return Some(framework);
+    }
+    let file = components.last()?;
+    file.strip_prefix("lib")?.strip_suffix(".a")
+}
+
+/// A stateful parser for the multi-line `Undefined symbols for architecture` and
+/// `duplicate symbol` blocks.
+///
+/// ```text
+/// Undefined symbols for architecture arm64:
+///   "_OBJC_CLASS_$_Foo", referenced from:
+///       objc-class-ref in ViewController.o
+/// ld: symbol(s) not found for architecture arm64
+///
+/// duplicate symbol '_kTimeout' in:
+///     /build/App.build/Objects-normal/arm64/Config.o
+///     /build/libKit.a(Constants.o)
+/// ld: 1 duplicate symbol for architecture arm64
+/// ```
+///
+/// The architecture of duplicate symbols is read from the `ld:` line closing their blocks.
+///
+/// The errors of LLVM's `lld`, which `air-lld` uses to link Metal shaders, are read too.
+/// Their architecture is `air64` for `air-lld`, and empty otherwise.
+///
+/// ```text
+/// air-lld: error: undefined symbol: shade(float4)
+/// >>> referenced by Shaders.air
+/// ```
+///
+/// Lines are fed one at a time with [`push_line`](Self::push_line), and the errors
+/// collected so far are returned by [`finish`](Self::finish).
+#[derive(Debug)]
+pub struct LinkerErrorParser {
+    header: Regex,
+    symbol: Regex,
+    reference: Regex,
+    duplicate: Regex,
+    duplicate_object: Regex,
+    duplicate_summary: Regex,
+    lld_error: Regex,
+    lld_object: Regex,
+    architecture: Option<String>,
+    in_duplicate: bool,
+    in_lld: bool,
+    errors: Vec<LinkerError>,
+}
+
+impl Default for LinkerErrorParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LinkerErrorParser {
+    /// Creates a new `LinkerErrorParser`.
+    pub fn new() -> Self {
+        LinkerErrorParser {
+            header: Regex::new(r#"^Undefined symbols for architecture (\S+):$"#).unwrap(),
+            symbol: Regex::new(r#"^\s+"(.+)", referenced from:$"#).unwrap(),
+            reference: Regex::new(r#"^\s+(?:.+ in )?(\S.*?)$"#).unwrap(),
+            duplicate: Regex::new(r#"^duplicate symbol '?(.+?)'? in:(.*)$"#).unwrap(),
+            duplicate_object: Regex::new(r#"^\s+(\S.*?)$"#).unwrap(),
+            duplicate_summary: Regex::new(
+                r#"^ld: \d+ duplicate symbols?(?: for architecture (\S+))?$"#,
+            )
+            .unwrap(),
+            lld_error: Regex::new(
+                r#"^(?P<tool>air-lld|ld\.lld|ld64\.lld|lld): error: (?P<kind>undefined|duplicate) symbol: (?P<symbol>.+)$"#,
+            )
+            .unwrap(),
+            lld_object: Regex::new(
+                r#"^>>>\s+(?:referenced by |defined (?:in|at) )?(?P<object>\S.*?)$"#,
+            )
+            .unwrap(),
+            architecture: None,
+            in_duplicate: false,
+            in_lld: false,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Feeds the next line of the log to the parser.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - A string slice that holds the log line.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the line belongs to an undefined or duplicate symbols block.
+    pub fn push_line(&mut self, line: &str) -> bool {
+        let line = line.trim_end();
+        if let Some(cap) = self.header.captures(line) {
+            self.architecture = Some(cap[1].to_string());
+            self.in_duplicate = false;
+            return true;
+        }
+        if self.push_duplicate_line(line) || self.push_lld_line(line) {
+            return true;
+        }
+        let Some(architecture) = &self.architecture else {
+            return false;
+        };
+
+        if let Some(cap) = self.symbol.captures(line) {
+            self.errors.push(LinkerError {
+                kind: LinkerErrorKind::UndefinedSymbol,
+                symbol: cap[1].to_string(),
+                architecture: architecture.clone(),
+                objects: Vec::new(),
+            });
+            return true;
+        }
+        if let Some(cap) = self.reference.captures(line) {
+            if let Some(error) = self.errors.last_mut() {
+                error.objects.push(cap[1].to_string());
+                return true;
+            }
+        }
+
+        self.architecture = None;
+        false
+    }
+
+    /// Feeds a line to the duplicate symbol block, if it starts, belongs to or closes one.
+    fn push_duplicate_line(&mut self, line: &str) -> bool {
+        if let Some(cap) = self.duplicate.captures(line) {
+            self.architecture = None;
+            self.in_duplicate = true;
+            self.errors.push(LinkerError {
+                kind: LinkerErrorKind::DuplicateSymbol,
+                symbol: cap[1].to_string(),
+                architecture: String::new(),
+                objects: cap[2].split_whitespace().map(str::to_string).collect(),
+            });
+            return true;
+        }
+        if let Some(cap) = self.duplicate_summary.captures(line) {
+            self.in_duplicate = false;
+            let architecture = cap.get(1).map_or("", |architecture| architecture.as_str());
+            for error in self.errors.iter_mut().rev() {
+                if error.kind != LinkerErrorKind::DuplicateSymbol || !error.architecture.is_empty()
+                {
+                    break;
+                }
+                error.architecture = architecture.to_string();
+            }
+            return true;
+        }
+        if !self.in_duplicate {
+            return false;
+        }
+        if let Some(cap) = self.duplicate_object.captures(line) {
+            if let Some(error) = self.errors.last_mut() {
+                error.objects.push(cap[1].to_string());
+                return true;
+            }
+        }
+        self.in_duplicate = false;
+        false
+    }
+
+    /// Feeds a line to an `lld` error, if it starts one or lists its objects.
+    fn push_lld_line(&mut self, line: &str) -> bool {
+        if let Some(cap) = self.lld_error.captures(line) {
+            self.architecture = None;
+            self.in_lld = true;
+            let kind = match &cap["kind"] {
+                "undefined" => LinkerErrorKind::UndefinedSymbol,
+                _ => LinkerErrorKind::DuplicateSymbol,
+            };
+            let architecture = if &cap["tool"] == "air-lld" {
+                "air64"
+            } else {
+                ""
+            };
+            self.errors.push(LinkerError {
+                kind,
+                symbol: cap["symbol"].to_string(),
+                architecture: architecture.to_string(),
+                objects: Vec::new(),
+            });
+            return true;
+        }
+        if !self.in_lld {
+            return false;
+        }
+        if let Some(cap) = self.lld_object.captures(line) {
+            if let Some(error) = self.errors.last_mut() {
+                error.objects.push(cap["object"].to_string());
+                return true;
+            }
+        }
+        self.in_lld = false;
+        false
+    }
+
+    /// Consumes the parser and returns the linker errors found.
+    pub fn finish(self) -> Vec<LinkerError> {
+        self.errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests parsing an undefined symbols block with several symbols.
+    #[test]
+    fn test_linker_error_parser_undefined_symbols() {
+        let log = r#"Ld /build/App normal (in target 'App' from project 'App')
+Undefined symbols for architecture arm64:
+  "_OBJC_CLASS_$_Analytics", referenced from:
+      objc-class-ref in AppDelegate.o
+      objc-class-ref in ViewController.o
+  "App.helper() -> ()", referenced from:
+       _main in main.o
+ld: symbol(s) not found for architecture arm64
+clang: error: linker command failed with exit code 1 (use -v to see invocation)"#;
+        let mut parser = LinkerErrorParser::new();
+        let consumed: Vec<bool> = log.lines().map(|line| parser.push_line(line)).collect();
+        assert_eq!(
+            consumed,
+            vec![false, true, true, true, true, true, true, false, false]
+        );
+
+        let errors = parser.finish();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].symbol(), "_OBJC_CLASS_$_Analytics");
+        assert_eq!(errors[0].architecture(), "arm64");
+        assert_eq!(
+            errors[0].referenced_from(),
+            ["AppDelegate.o", "ViewController.o"]
+        );
+        assert_eq!(errors[1].symbol(), "App.helper() -> ()");
+        assert_eq!(errors[1].referenced_from(), ["main.o"]);
+    }
+
+    /// Tests parsing duplicate symbol blocks and the targets defining the symbols.
+    #[test]
+    fn test_linker_error_parser_duplicate_symbols() {
+        let log = "duplicate symbol '_kTimeout' in:
+    /build/App.build/Debug-iphoneos/App.build/Objects-normal/arm64/Config.o
+    /build/Products/Debug-iphoneos/libKit.a(Constants.o)
+duplicate symbol _OBJC_CLASS_$_Logger in: /build/Core.framework/Core /build/App.build/Debug/Net.build/Objects-normal/x86_64/Logger.o
+ld: 2 duplicate symbols for architecture arm64
+clang: error: linker command failed with exit code 1 (use -v to see invocation)";
+        let mut parser = LinkerErrorParser::new();
+        let consumed: Vec<bool> = log.lines().map(|line| parser.push_line(line)).collect();
+        assert_eq!(consumed, vec![true, true, true, true, true, false]);
+
+        let errors = parser.finish();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].kind(), LinkerErrorKind::DuplicateSymbol);
+        assert_eq!(errors[0].symbol(), "_kTimeout");
+        assert_eq!(errors[0].architecture(), "arm64");
+        assert_eq!(errors[0].defined_in().len(), 2);
+        assert!(errors[0].referenced_from().is_empty());
+        assert_eq!(errors[0].targets(), ["App", "Kit"]);
+        assert_eq!(errors[1].symbol(), "_OBJC_CLASS_$_Logger");
+        assert_eq!(
+            errors[1].defined_in(),
+            [
+                "/build/Core.framework/Core",
+                "/build/App.build/Debug/Net.build/Objects-normal/x86_64/Logger.o"
+            ]
+        );
+        assert_eq!(errors[1].targets(), ["Core", "Net"]);
+    }
+
+    /// Tests parsing the errors `air-lld` reports when linking Metal shaders.
+    #[test]
+    fn test_linker_error_parser_air_lld() {
+        let log = "MetalLink /build/App.app/default.metallib (in target 'App' from project 'App')
+air-lld: error: undefined symbol: shade(float4)
+>>> referenced by /build/App.build/Metal/Shaders.air
+air-lld: error: duplicate symbol: lerp(float, float, float)
+>>> defined in /build/App.build/Metal/Math.air
+>>> defined in /build/App.build/Metal/Shaders.air
+metallib: error: linker command failed with exit code 1 (use -v to see invocation)";
+        let mut parser = LinkerErrorParser::new();
+        let consumed: Vec<bool> = log.lines().map(|line| parser.push_line(line)).collect();
+        assert_eq!(consumed, vec![false, true, true, true, true, true, false]);
+
+        let errors = parser.finish();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].kind(), LinkerErrorKind::UndefinedSymbol);
+        assert_eq!(errors[0].symbol(), "shade(float4)");
+        assert_eq!(errors[0].architecture(), "air64");
+        assert_eq!(
+            errors[0].referenced_from(),
+            ["/build/App.build/Metal/Shaders.air"]
+        );
+        assert_eq!(errors[1].kind(), LinkerErrorKind::DuplicateSymbol);
+        assert_eq!(errors[1].defined_in().len(), 2);
+        assert_eq!(errors[1].targets(), ["App"]);
+    }
+
+    /// Tests that indented lines outside of a block are ignored.
+    #[test]
+    fn test_linker_error_parser_ignores_other_lines() {
+        let mut parser = LinkerErrorParser::new();
+        assert!(!parser.push_line("    cd /src/App"));
+        assert!(!parser.push_line("/src/A.swift:1:2: error: oops"));
+        assert!(parser.finish().is_empty());
+    }
+}
@@ -0,0 +1,923 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::json;
+use xcode_log_parser::format::compact::CompactFormatter;
+use xcode_log_parser::format::pretty::PrettyFormatter;
+#[cfg(feature = "template")]
+use xcode_log_parser::format::template::{TemplateError, TemplateFormatter};
+use xcode_log_parser::json;
+use xcode_log_parser::json::JsonLinesWriter;
+use xcode_log_parser::report::azure::to_azure_logging_commands;
+use xcode_log_parser::report::checkstyle::to_checkstyle;
+use xcode_log_parser::report::csv::to_csv;
+use xcode_log_parser::report::deprecations::{deprecated_apis, to_deprecation_report};
+use xcode_log_parser::report::github::to_github_annotations;
+use xcode_log_parser::report::gitlab::to_code_quality;
+use xcode_log_parser::report::markdown::to_markdown;
+use xcode_log_parser::report::teamcity::to_teamcity_messages;
+#[cfg(feature = "sqlite")]
+use xcode_log_parser::storage::sqlite::SqliteStore;
+use xcode_log_parser::xcactivitylog::{ActivityLog, DerivedData};
+use xcode_log_parser::{
+    attach_snippets, diff, Baseline, BaselineDiff, ChangedLines, Config, ConfigError, Date,
+    Dialect, LogFile, LogFileIter, LogParser, MyWarning, Policy, Severity, Suppressions, TailEvent,
+    TailParser,
+};
+
+/// Parses Xcode build logs and reports the diagnostics found in them.
+#[derive(Parser)]
+#[command(name = "xcode-log-parser", version, subcommand_required = false)]
+struct Cli {
+    /// Output format, `text` by default.
+    #[arg(long, global = true, value_enum)]
+    format: Option<Format>,
+
+    /// Reads the settings from this file instead of the closest `.xcodelogparser.toml`.
+    #[arg(long, global = true, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Tool that printed the build log.
+    #[arg(long, global = true, value_enum, default_value_t = Input::Xcodebuild)]
+    dialect: Input,
+
+    /// Keeps only diagnostics in paths matching this glob pattern.
+    #[arg(long, global = true, value_name = "GLOB")]
+    include: Vec<String>,
+
+    /// Drops diagnostics in paths matching this glob pattern.
+    #[arg(long, global = true, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Keeps only diagnostics enabled by this warning flag, such as `--flag=-Wdeprecated-declarations`.
+    #[arg(
+        long = "flag",
+        global = true,
+        value_name = "FLAG",
+        allow_hyphen_values = true
+    )]
+    flags: Vec<String>,
+
+    /// Reads this many source lines around each diagnostic from its file.
+    #[arg(long, global = true, value_name = "N")]
+    snippets: Option<usize>,
+
+    /// Ignores the diagnostics matched by the unexpired rules of this JSON suppressions file.
+    #[arg(long, global = true, value_name = "FILE")]
+    suppressions: Option<PathBuf>,
+
+    /// Fails with exit code 2 when there are more warnings.
+    #[arg(long, global = true, value_name = "N")]
+    max_warnings: Option<usize>,
+
+    /// Fails with exit code 2 when there are more errors.
+    #[arg(long, global = true, value_name = "N")]
+    max_errors: Option<usize>,
+
+    /// Fails with exit code 2 when the files matching a glob pattern have more warnings,
+    /// such as `--budget "Modules/Legacy/**=40"`.
+    #[arg(long = "budget", global = true, value_name = "GLOB=N", value_parser = parse_budget)]
+    budgets: Vec<(String, usize)>,
+
+    /// Keeps only diagnostics on the lines added or modified by this unified diff, such as
+    /// the output of `git diff origin/main`.
+    #[arg(long, global = true, value_name = "FILE")]
+    diff: Option<PathBuf>,
+
+    /// Build log of a previous build, compared against by `--fail-on-new`.
+    #[arg(long, global = true, value_name = "FILE")]
+    baseline: Option<PathBuf>,
+
+    /// Fails with exit code 2 when a diagnostic is not in the baseline.
+    #[arg(long, global = true, requires = "baseline")]
+    fail_on_new: bool,
+
+    /// Directory the sources of the build were checked out to, which the paths of `--diff`
+    /// are relative to, so that diagnostics also match the `--baseline` of a build checked
+    /// out elsewhere.
+    #[arg(long, global = true, value_name = "DIR")]
+    source_root: Option<String>,
+
+    /// Directory the sources of the `--baseline` build were checked out to, `--source-root`
+    /// by default.
+    #[arg(long, global = true, value_name = "DIR", requires = "source_root")]
+    baseline_root: Option<String>,
+
+    /// Renders each diagnostic through this Handlebars template instead of `--format`.
+    #[cfg(feature = "template")]
+    #[arg(long, global = true, value_name = "FILE")]
+    template: Option<PathBuf>,
+
+    /// Parses the standard input when no command is given.
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Prints every diagnostic found in a build log. `xcode-log-parser -` is short for
+    /// `xcode-log-parser parse -`.
+    #[command(alias = "-")]
+    Parse {
+        /// Path to the build log, or `-` (the default) to read standard input.
+        #[arg(default_value = STDIN)]
+        file: PathBuf,
+        /// Keeps reading the log as it grows, until the build prints its verdict.
+        #[arg(long)]
+        follow: bool,
+    },
+    /// Prints the number of diagnostics per severity.
+    Summary {
+        /// Path to the build log, or `-` to read standard input.
+        file: PathBuf,
+    },
+    /// Prints the deprecated APIs used, with their replacement and the OS version they were
+    /// deprecated in.
+    Deprecations {
+        /// Path to the build log, or `-` to read standard input.
+        file: PathBuf,
+    },
+    /// Prints every diagnostic of the newest build log Xcode kept in DerivedData.
+    LastBuild {
+        /// Name of the project or workspace whose logs are searched, all of them by default.
+        #[arg(long)]
+        project: Option<String>,
+    },
+    /// Prints the warnings per module over the latest runs recorded in a database.
+    #[cfg(feature = "sqlite")]
+    Trend {
+        /// Path to the SQLite database.
+        database: PathBuf,
+        /// Number of runs to compare.
+        #[arg(long, default_value_t = 5)]
+        runs: usize,
+    },
+}
+
+/// Tools whose build logs the command line tool reads.
+#[derive(Clone, Copy, ValueEnum)]
+enum Input {
+    Xcodebuild,
+    /// `swift build` and `swift test`.
+    Swiftpm,
+    /// SwiftLint's default reporter.
+    Swiftlint,
+    /// fastlane's `gym` and `scan`.
+    Fastlane,
+}
+
+impl From<Input> for Dialect {
+    fn from(input: Input) -> Self {
+        match input {
+            Input::Xcodebuild => Dialect::Xcodebuild,
+            Input::Swiftpm => Dialect::SwiftPm,
+            Input::Swiftlint => Dialect::SwiftLint,
+            Input::Fastlane => Dialect::Fastlane,
+        }
+    }
+}
+
+/// Output formats supported by the command line tool.
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Json,
+    Text,
+    /// GitHub Actions workflow commands.
+    Github,
+    /// A Markdown table for pull request comments, of the new diagnostics with `--baseline`.
+    Markdown,
+    /// One comma-separated row per diagnostic.
+    Csv,
+    /// A GitLab Code Quality report for merge requests.
+    Gitlab,
+    /// A Checkstyle XML report, read by most CI plugins.
+    Checkstyle,
+    /// TeamCity service messages, reporting errors as build problems.
+    Teamcity,
+    /// Azure Pipelines logging commands, listing warnings and errors in the Issues pane.
+    Azure,
+    /// Clang-style output, colored when printed to a terminal.
+    Pretty,
+    /// One short line per build step, test and diagnostic, printed while the log is read.
+    Compact,
+    /// One JSON object per diagnostic and line (JSON Lines), printed while the log is read.
+    Jsonl,
+}
+
+/// Number of diagnostics per severity.
+#[derive(Debug, Default, PartialEq)]
+struct Summary {
+    errors: usize,
+    /// Errors that are warnings turned into errors by `-Werror`, also counted in `errors`.
+    escalated: usize,
+    warnings: usize,
+    notes: usize,
+    remarks: usize,
+    other: usize,
+}
+
+impl Summary {
+    /// Counts the diagnostics by severity.
+    fn from_diagnostics(diagnostics: &[LogFile<MyWarning>]) -> Self {
+        let mut summary = Summary::default();
+        for code_fragment in diagnostics.iter().filter_map(LogFile::code_fragment) {
+            match code_fragment.severity() {
+                Some(Severity::Error) => summary.errors += 1,
+                Some(Severity::Warning) => summary.warnings += 1,
+                Some(Severity::Note) => summary.notes += 1,
+                Some(Severity::Remark) => summary.remarks += 1,
+                None => summary.other += 1,
+            }
+            if code_fragment.is_escalated() {
+                summary.escalated += 1;
+            }
+            summary.notes += code_fragment.notes().len();
+        }
+        summary
+    }
+
+    /// Returns the total number of diagnostics.
+    fn total(&self) -> usize {
+        self.errors + self.warnings + self.notes + self.remarks + self.other
+    }
+}
+
+/// Returns whether a diagnostic is matched by an unexpired suppression.
+type Suppresses<'a> = Box<dyn Fn(&LogFile<MyWarning>) -> bool + 'a>;
+
+/// The filters dropping diagnostics after they are parsed, checked one diagnostic at a time
+/// so that the formats printing diagnostics while the log is read drop the same ones.
+struct Filters<'a> {
+    /// The lines changed by the `--diff`, outside of which diagnostics are dropped.
+    changed_lines: Option<ChangedLines>,
+    /// Whether a diagnostic is matched by an unexpired suppression.
+    suppresses: Option<Suppresses<'a>>,
+}
+
+impl Filters<'_> {
+    /// Returns whether a diagnostic is kept.
+    fn keeps(&self, log_file: &LogFile<MyWarning>) -> bool {
+        self.changed_lines
+            .as_ref()
+            .is_none_or(|changed_lines| changed_lines.covers(log_file))
+            && !self
+                .suppresses
+                .as_ref()
+                .is_some_and(|suppresses| suppresses(log_file))
+    }
+}
+
+/// Path standing for the standard input, as in `xcodebuild ... 2>&1 | xcode-log-parser -`.
+const STDIN: &str = "-";
+
+fn main() -> ExitCode {
+    let mut cli = Cli::parse();
+    let command = cli.command.take().unwrap_or_else(|| Command::Parse {
+        file: PathBuf::from(STDIN),
+        follow: false,
+    });
+    let mut config = match load_config(cli.config.as_deref()) {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("xcode-log-parser: {}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    let format = match (cli.format, &config.output.format) {
+        (Some(format), _) => format,
+        (None, Some(name)) => match Format::from_str(name, true) {
+            Ok(format) => format,
+            Err(_) => {
+                eprintln!(
+                    "xcode-log-parser: unknown output format `{}` in the configuration",
+                    name
+                );
+                return ExitCode::FAILURE;
+            }
+        },
+        (None, None) => Format::Text,
+    };
+    let last_build;
+    let file = match &command {
+        Command::Parse { file, .. }
+        | Command::Summary { file }
+        | Command::Deprecations { file } => file,
+        Command::LastBuild { project } => match latest_build_log(project.as_deref()) {
+            Ok(path) => {
+                last_build = path;
+                &last_build
+            }
+            Err(error) => {
+                eprintln!("xcode-log-parser: {}", error);
+                return ExitCode::FAILURE;
+            }
+        },
+        #[cfg(feature = "sqlite")]
+        Command::Trend { database, runs } => return print_trend(database, *runs, format),
+    };
+
+    // Command line filters add to those of the configuration, and flags replace them.
+    config.filters.include.extend(cli.include.iter().cloned());
+    config.filters.exclude.extend(cli.exclude.iter().cloned());
+    if !cli.flags.is_empty() {
+        config.filters.flags = cli.flags.clone();
+    }
+    let parser = LogParser::builder()
+        .config(&config)
+        .dialect(cli.dialect.into())
+        .build();
+    // The diff and suppressions are read before the log, so that the diagnostics printed
+    // while it is read are filtered like the others.
+    let changed_lines = match &cli.diff {
+        Some(path) => match std::fs::read_to_string(path) {
+            Ok(diff) => {
+                let changed_lines = ChangedLines::parse(&diff);
+                Some(match &cli.source_root {
+                    Some(root) => changed_lines.with_root(root),
+                    None => changed_lines,
+                })
+            }
+            Err(error) => {
+                eprintln!("xcode-log-parser: {}: {}", path.display(), error);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => None,
+    };
+    let suppressions = match cli
+        .suppressions
+        .as_ref()
+        .or(config.filters.suppressions.as_ref())
+    {
+        Some(path) => match read_suppressions(path) {
+            Ok(suppressions) => Some(suppressions),
+            Err(error) => {
+                eprintln!("xcode-log-parser: {}: {}", path.display(), error);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => None,
+    };
+    let today = Date::today();
+    for suppression in suppressions
+        .iter()
+        .flat_map(|suppressions| suppressions.expired(today))
+    {
+        eprintln!(
+            "xcode-log-parser: the suppression of {} expired on {}",
+            suppression,
+            suppression.expires.unwrap()
+        );
+    }
+    let filters = Filters {
+        changed_lines,
+        suppresses: suppressions
+            .as_ref()
+            .map(|suppressions| Box::new(suppressions.matcher(today)) as Suppresses),
+    };
+
+    // The standard input is already read as it is written.
+    let follow = matches!(command, Command::Parse { follow: true, .. }) && file != Path::new(STDIN);
+    let diagnostics = match (&command, format) {
+        _ if follow => follow_diagnostics(file, parser.clone(), format, &filters, cli.snippets),
+        (Command::Parse { .. } | Command::LastBuild { .. }, Format::Compact) => {
+            format_compact(file, parser.clone(), &filters, std::io::stdout().lock())
+        }
+        (Command::Parse { .. } | Command::LastBuild { .. }, Format::Jsonl) => stream_json_lines(
+            file,
+            parser.clone(),
+            &filters,
+            cli.snippets,
+            std::io::stdout().lock(),
+        ),
+        _ => read_diagnostics(file, parser.clone()),
+    };
+    let mut diagnostics = match diagnostics {
+        Ok(diagnostics) => diagnostics,
+        Err(error) => {
+            eprintln!("xcode-log-parser: {}: {}", file.display(), error);
+            return ExitCode::FAILURE;
+        }
+    };
+    diagnostics.retain(|log_file| filters.keeps(log_file));
+    if let Some(context) = cli.snippets {
+        attach_snippets(&mut diagnostics, context);
+    }
+    let baseline = match &cli.baseline {
+        Some(path) => match read_diagnostics(path, parser) {
+            Ok(diagnostics) => {
+                let mut baseline = Baseline::from_diagnostics(&diagnostics);
+                if let Some(root) = &cli.baseline_root {
+                    baseline = baseline.with_root(root);
+                }
+                if let Some(root) = &cli.source_root {
+                    baseline = baseline.with_root(root);
+                }
+                Some(baseline)
+            }
+            Err(error) => {
+                eprintln!("xcode-log-parser: {}: {}", path.display(), error);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => None,
+    };
+
+    let diff = baseline
+        .as_ref()
+        .map(|baseline| diff(&diagnostics, baseline));
+    match command {
+        Command::Summary { .. } => print_summary(&Summary::from_diagnostics(&diagnostics), format),
+        Command::Deprecations { .. } => match format {
+            Format::Json => println!("{}", json!(deprecated_apis(&diagnostics))),
+            _ => print!("{}", to_deprecation_report(&diagnostics)),
+        },
+        #[cfg(feature = "template")]
+        Command::Parse { .. } if cli.template.is_some() || config.output.template.is_some() => {
+            let template = cli.template.as_ref().or(config.output.template.as_ref());
+            if let Err(error) = print_template(&diagnostics, template.unwrap()) {
+                eprintln!("xcode-log-parser: {}", error);
+                return ExitCode::FAILURE;
+            }
+        }
+        // Already printed while the log was followed.
+        Command::Parse { .. } if follow && matches!(format, Format::Text) => {}
+        _ => print_diagnostics(&diagnostics, diff.as_ref(), format),
+    }
+
+    // Command line thresholds replace those of the configuration.
+    config.thresholds.budgets.extend(cli.budgets);
+    let policy = Policy {
+        max_warnings: cli.max_warnings.or(config.thresholds.max_warnings),
+        max_errors: cli.max_errors.or(config.thresholds.max_errors),
+        fail_on_new: cli.fail_on_new || config.thresholds.fail_on_new,
+        ..config.policy()
+    };
+    let verdict = policy.evaluate(&diagnostics, diff.as_ref());
+    for violation in verdict.violations() {
+        eprintln!("xcode-log-parser: {}", violation);
+    }
+    if verdict.passed() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(2)
+    }
+}
+
+/// Parses a `--budget` value, a glob pattern and a number of warnings separated by the last `=`.
+fn parse_budget(value: &str) -> Result<(String, usize), String> {
+    value
+        .rsplit_once('=')
+        .and_then(|(pattern, max_warnings)| Some((pattern.to_string(), max_warnings.parse().ok()?)))
+        .ok_or_else(|| "expected GLOB=N".to_string())
+}
+
+/// Reads the configuration file given with `--config`, or the closest `.xcodelogparser.toml`.
+fn load_config(path: Option<&Path>) -> Result<Config, ConfigError> {
+    match path {
+        Some(path) => Config::load(path),
+        None => Ok(Config::discover()?.unwrap_or_default()),
+    }
+}
+
+/// Reads a JSON suppressions file.
+fn read_suppressions(path: &Path) -> std::io::Result<Suppressions> {
+    Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+}
+
+/// Opens the log at the given path, or the standard input for `-`. An `.xcactivitylog`
+/// is decoded into the text `xcodebuild` prints.
+fn open_log(path: &Path) -> std::io::Result<Box<dyn BufRead>> {
+    if path == Path::new(STDIN) {
+        return Ok(Box::new(std::io::stdin().lock()));
+    }
+    if path
+        .extension()
+        .is_some_and(|extension| extension == "xcactivitylog")
+    {
+        let text = ActivityLog::open(path)?.into_text();
+        return Ok(Box::new(Cursor::new(text)));
+    }
+    Ok(Box::new(BufReader::new(File::open(path)?)))
+}
+
+/// Finds the newest build log in the default DerivedData, of the given project if any.
+fn latest_build_log(project: Option<&str>) -> std::io::Result<PathBuf> {
+    let not_found = |message: String| std::io::Error::new(std::io::ErrorKind::NotFound, message);
+    let derived_data = DerivedData::discover()
+        .ok_or_else(|| not_found("no DerivedData directory found".to_string()))?;
+    derived_data.latest_log(project)?.ok_or_else(|| {
+        not_found(format!(
+            "no build log found in {}",
+            derived_data.path().display()
+        ))
+    })
+}
+
+/// Reads and parses every diagnostic in the log at the given path as a whole, attaching
+/// notes and continuation lines to their diagnostics.
+fn read_diagnostics(path: &Path, parser: LogParser) -> std::io::Result<Vec<LogFile<MyWarning>>> {
+    let reader = open_log(path)?;
+    Ok(parser.parse_reader(reader)?.into_diagnostics())
+}
+
+/// Prints one concise line per event of the log at the given path while parsing it,
+/// leaving out the diagnostics dropped by the filters.
+fn format_compact(
+    path: &Path,
+    parser: LogParser,
+    filters: &Filters,
+    mut output: impl Write,
+) -> std::io::Result<Vec<LogFile<MyWarning>>> {
+    let mut reader = open_log(path)?;
+    let mut formatter = CompactFormatter::with_parser(parser).color(use_color());
+    let mut buffer = Vec::new();
+    while reader.read_until(b'\n', &mut buffer)? > 0 {
+        let event = formatter.push_event(&String::from_utf8_lossy(&buffer));
+        buffer.clear();
+        let Some(event) = event else {
+            continue;
+        };
+        if let TailEvent::Diagnostic { log_file, .. } = &event {
+            if !filters.keeps(log_file) {
+                continue;
+            }
+        }
+        if let Some(line) = formatter.render_event(&event) {
+            writeln!(output, "{}", line)?;
+            output.flush()?;
+        }
+    }
+    Ok(formatter.finish().into_diagnostics())
+}
+
+/// Writes each diagnostic of the log at the given path kept by the filters as a JSON line
+/// while parsing it, with its notes and the given number of source lines around it.
+fn stream_json_lines(
+    path: &Path,
+    parser: LogParser,
+    filters: &Filters,
+    snippets: Option<usize>,
+    output: impl Write,
+) -> std::io::Result<Vec<LogFile<MyWarning>>> {
+    let reader = open_log(path)?;
+    let mut writer = JsonLinesWriter::new(output);
+    let mut diagnostics = Vec::new();
+    for item in LogFileIter::with_parser(reader, parser) {
+        let (_, mut log_file) = item?;
+        if filters.keeps(&log_file) {
+            if let Some(context) = snippets {
+                attach_snippets(std::slice::from_mut(&mut log_file), context);
+            }
+            writer.write(&log_file)?;
+            diagnostics.push(log_file);
+        }
+    }
+    Ok(diagnostics)
+}
+
+/// Follows the log at the given path until its verdict, printing each event as it arrives
+/// with the text and compact formats.
+fn follow_diagnostics(
+    path: &Path,
+    parser: LogParser,
+    format: Format,
+    filters: &Filters,
+    snippets: Option<usize>,
+) -> std::io::Result<Vec<LogFile<MyWarning>>> {
+    let formatter = CompactFormatter::new().color(use_color());
+    let mut diagnostics = Vec::new();
+    for event in TailParser::with_parser(path, parser) {
+        let mut event = event?;
+        if let TailEvent::Diagnostic { log_file, .. } = &mut event {
+            if !filters.keeps(log_file) {
+                continue;
+            }
+            if let Some(context) = snippets {
+                attach_snippets(std::slice::from_mut(log_file), context);
+            }
+        }
+        match (format, &event) {
+            (Format::Compact, event) => {
+                if let Some(line) = formatter.render_event(event) {
+                    println!("{}", line);
+                }
+            }
+            (Format::Text, TailEvent::Diagnostic { log_file, .. }) => print_text(log_file),
+            (Format::Jsonl, TailEvent::Diagnostic { log_file, .. }) => {
+                JsonLinesWriter::new(std::io::stdout().lock()).write(log_file)?
+            }
+            _ => {}
+        }
+        match event {
+            TailEvent::Diagnostic { log_file, .. } => diagnostics.push(log_file),
+            TailEvent::Truncated => diagnostics.clear(),
+            TailEvent::Outcome(_) => break,
+            TailEvent::BuildStep(_) | TailEvent::Test(_) => {}
+        }
+    }
+    Ok(diagnostics)
+}
+
+/// Returns whether colors are printed, which they are on a terminal unless `NO_COLOR` is set.
+fn use_color() -> bool {
+    std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+/// Prints the diagnostics in the requested format.
+fn print_diagnostics(
+    diagnostics: &[LogFile<MyWarning>],
+    diff: Option<&BaselineDiff<'_, MyWarning>>,
+    format: Format,
+) {
+    match format {
+        Format::Text => diagnostics.iter().for_each(print_text),
+        Format::Json => match json::to_json(diagnostics) {
+            Ok(output) => println!("{}", output),
+            Err(error) => eprintln!("xcode-log-parser: {}", error),
+        },
+        Format::Github => print!("{}", to_github_annotations(diagnostics)),
+        Format::Markdown => match diff {
+            Some(diff) => print!("{}", to_markdown(diff.added().iter().copied())),
+            None => print!("{}", to_markdown(diagnostics)),
+        },
+        Format::Csv => print!("{}", to_csv(diagnostics)),
+        Format::Gitlab => println!("{}", to_code_quality(diagnostics)),
+        Format::Checkstyle => print!("{}", to_checkstyle(diagnostics)),
+        Format::Teamcity => print!("{}", to_teamcity_messages(diagnostics)),
+        Format::Azure => print!("{}", to_azure_logging_commands(diagnostics)),
+        Format::Pretty => print!(
+            "{}",
+            PrettyFormatter::new()
+                .color(use_color())
+                .render(diagnostics)
+        ),
+        // Already printed while the log was read.
+        Format::Compact | Format::Jsonl => {}
+    }
+}
+
+/// Prints the diagnostics rendered through the template file.
+#[cfg(feature = "template")]
+fn print_template(
+    diagnostics: &[LogFile<MyWarning>],
+    template: &Path,
+) -> Result<(), TemplateError> {
+    print!(
+        "{}",
+        TemplateFormatter::from_file(template)?.render(diagnostics)?
+    );
+    Ok(())
+}
+
+/// Prints a diagnostic as a `path:line:column: severity: text` line.
+fn print_text(log_file: &LogFile<MyWarning>) {
+    if log_file.code_fragment().is_some() {
+        println!("{}", log_file);
+    }
+}
+
+/// Prints the summary in the requested format.
+fn print_summary(summary: &Summary, format: Format) {
+    match format {
+        Format::Text
+        | Format::Github
+        | Format::Markdown
+        | Format::Csv
+        | Format::Gitlab
+        | Format::Checkstyle
+        | Format::Teamcity
+        | Format::Azure
+        | Format::Pretty
+        | Format::Compact => {
+            println!("errors: {}", summary.errors);
+            if summary.escalated > 0 {
+                println!("  escalated warnings: {}", summary.escalated);
+            }
+            println!("warnings: {}", summary.warnings);
+            println!("notes: {}", summary.notes);
+            println!("remarks: {}", summary.remarks);
+            println!("other: {}", summary.other);
+            println!("total: {}", summary.total());
+        }
+        Format::Json | Format::Jsonl => {
+            let value = json!({
+                "errors": summary.errors,
+                "escalated_warnings": summary.escalated,
+                "warnings": summary.warnings,
+                "notes": summary.notes,
+                "remarks": summary.remarks,
+                "other": summary.other,
+                "total": summary.total(),
+            });
+            println!("{}", value);
+        }
+    }
+}
+
+/// Prints the warnings per module over the latest runs, flagging the modules that regressed.
+#[cfg(feature = "sqlite")]
+fn print_trend(database: &Path, runs: usize, format: Format) -> ExitCode {
+    let trend =
+        match SqliteStore::open(database).and_then(|store| store.trend(Severity::Warning, runs)) {
+            Ok(trend) => trend,
+            Err(error) => {
+                eprintln!("xcode-log-parser: {}: {}", database.display(), error);
+                return ExitCode::FAILURE;
+            }
+        };
+    match format {
+        Format::Json => {
+            let modules: Vec<_> = trend
+                .modules()
+                .iter()
+                .map(|module| {
+                    json!({
+                        "module": module.module(),
+                        "counts": module.counts(),
+                        "delta": module.delta(),
+                        "change": module.change(),
+                        "regression": module.is_regression(),
+                    })
+                })
+                .collect();
+            let runs: Vec<&str> = trend.runs().iter().map(|run| run.id.as_str()).collect();
+            println!("{}", json!({ "runs": runs, "modules": modules }));
+        }
+        _ => {
+            for module in trend.modules() {
+                let counts: Vec<String> = module.counts().iter().map(usize::to_string).collect();
+                println!(
+                    "{}: {} ({:+}){}",
+                    module.module(),
+                    counts.join(" -> "),
+                    module.delta(),
+                    if module.is_regression() {
+                        " regression"
+                    } else {
+                        ""
+                    }
+                );
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use xcode_log_parser::parse_log;
+
+    /// Tests counting diagnostics by severity.
+    #[test]
+    fn test_summary_counts_by_severity() {
+        let log = "a.swift:1:1: warning: one\n\
+                   a.swift:2:1: warning: two\n\
+                   b.swift:3:1: error: three\n\
+                   b.swift:4:1: note: four\n\
+                   c.swift:5:1: unknown five\n";
+        let summary = Summary::from_diagnostics(&parse_log(log));
+        assert_eq!(
+            summary,
+            Summary {
+                errors: 1,
+                escalated: 0,
+                warnings: 2,
+                notes: 1,
+                remarks: 0,
+                other: 1,
+            }
+        );
+        assert_eq!(summary.total(), 5);
+    }
+
+    /// Tests counting the notes attached to a diagnostic of a log read from a file.
+    #[test]
+    fn test_read_diagnostics_counts_attached_notes() {
+        let path = std::env::temp_dir().join("xcode_log_parser_read_diagnostics.log");
+        std::fs::write(
+            &path,
+            "/src/A.swift:3:9: warning: 'f()' is deprecated\n\
+             /src/B.swift:1:6: note: 'f()' was declared here\n",
+        )
+        .unwrap();
+        let diagnostics = read_diagnostics(&path, LogParser::default()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(diagnostics.len(), 1);
+        let summary = Summary::from_diagnostics(&diagnostics);
+        assert_eq!((summary.warnings, summary.notes), (1, 1));
+        assert_eq!(summary.total(), 2);
+    }
+
+    /// Tests accepting global options before a command, and `-` as the `parse` command.
+    #[test]
+    fn test_cli_parses_global_options_before_command() {
+        let cli = Cli::try_parse_from(["xcode-log-parser", "--format", "json", "summary", "t.log"])
+            .unwrap();
+        assert!(matches!(cli.format, Some(Format::Json)));
+        assert!(
+            matches!(cli.command, Some(Command::Summary { file }) if file == Path::new("t.log"))
+        );
+
+        let cli = Cli::try_parse_from(["xcode-log-parser", "--max-warnings", "0", "-"]).unwrap();
+        assert_eq!(cli.max_warnings, Some(0));
+        assert!(
+            matches!(cli.command, Some(Command::Parse { file, .. }) if file == Path::new(STDIN))
+        );
+        assert!(Cli::try_parse_from(["xcode-log-parser"])
+            .unwrap()
+            .command
+            .is_none());
+    }
+
+    /// Tests dropping the diagnostics outside of the diff and those suppressed from the
+    /// JSON lines and compact lines written while the log is read.
+    #[test]
+    fn test_stream_json_lines_filters() {
+        let path = std::env::temp_dir().join("xcode_log_parser_stream_json_lines.log");
+        std::fs::write(
+            &path,
+            "/src/App/A.swift:1:2: warning: unused variable 'a'\n\
+             /src/App/A.swift:5:2: warning: unused variable 'b'\n\
+             /src/App/Legacy/B.swift:5:2: warning: unused variable 'c'\n",
+        )
+        .unwrap();
+        let diff = "+++ b/A.swift\n@@ -5 +5 @@\n-let b = 1\n+var b = 1\n\
+                    +++ b/Legacy/B.swift\n@@ -5 +5 @@\n-let c = 1\n+var c = 1\n";
+        let suppressions: Suppressions =
+            serde_json::from_str(r#"{"suppressions": [{"path": "Legacy/**"}]}"#).unwrap();
+        let filters = Filters {
+            changed_lines: Some(ChangedLines::parse(diff)),
+            suppresses: Some(Box::new(suppressions.matcher(Date::today()))),
+        };
+        let mut output = Vec::new();
+        let diagnostics = stream_json_lines(
+            &path,
+            LogParser::builder().build(),
+            &filters,
+            None,
+            &mut output,
+        )
+        .unwrap();
+        let mut compact = Vec::new();
+        format_compact(&path, LogParser::builder().build(), &filters, &mut compact).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.contains("unused variable 'b'"));
+        assert_eq!(diagnostics.len(), 1);
+        let compact = String::from_utf8(compact).unwrap();
+        assert_eq!(
+            compact.lines().collect::<Vec<_>>(),
+            ["⚠️  /src/App/A.swift:5:2: unused variable 'b'"]
+        );
+    }
+
+    /// Tests that the JSON lines carry the notes and snippets of their diagnostic, so the
+    /// diagnostics streamed match a baseline read from the same log.
+    #[test]
+    fn test_stream_json_lines_matches_baseline() {
+        let dir = std::env::temp_dir().join("xcode_log_parser_stream_json_lines_baseline");
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("A.swift");
+        std::fs::write(&source, "func f() {}\nlet a = f()\n").unwrap();
+        let path = dir.join("build.log");
+        std::fs::write(
+            &path,
+            format!(
+                "{0}:2:9: warning: 'f()' is deprecated\n\
+                 {0}:1:6: note: 'f()' was declared here\n",
+                source.display()
+            ),
+        )
+        .unwrap();
+        let filters = Filters {
+            changed_lines: None,
+            suppresses: None,
+        };
+        let mut output = Vec::new();
+        let diagnostics =
+            stream_json_lines(&path, LogParser::default(), &filters, Some(1), &mut output).unwrap();
+        let baseline =
+            Baseline::from_diagnostics(&read_diagnostics(&path, LogParser::default()).unwrap());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.contains("was declared here"));
+        assert!(output.contains("let a = f()"));
+        let policy = Policy {
+            fail_on_new: true,
+            ..Policy::default()
+        };
+        assert!(policy
+            .evaluate(&diagnostics, Some(&diff(&diagnostics, &baseline)))
+            .passed());
+    }
+}
@@ -0,0 +1,94 @@
+use serde::Serialize;
+
+use crate::{CodeFragment, TaskMessage};
+
+/// Represents a `note:` line that belongs to the diagnostic printed before it,
+/// such as clang's "expanded from macro" or the Swift compiler's "declared here".
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Note {
+    absolute_path: String,
+    line: usize,
+    column: usize,
+    text: String,
+    source_excerpt: Option<String>,
+    caret: Option<String>,
+}
+
+impl Note {
+    /// Creates a new `Note` from a parsed `note:` diagnostic.
+    ///
+    /// # Arguments
+    ///
+    /// * `absolute_path` - The path of the file the note points at.
+    /// * `code_fragment` - The code fragment parsed from the note line.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A `Note` holding the location, text and source excerpt of the note.
+    pub(crate) fn new<T: TaskMessage>(
+        absolute_path: String,
+        code_fragment: CodeFragment<T>,
+    ) -> Self {
+        Note {
+            absolute_path,
+            line: code_fragment.line,
+            column: code_fragment.column,
+            text: code_fragment.text,
+            source_excerpt: code_fragment.source_excerpt,
+            caret: code_fragment.caret,
+        }
+    }
+
+    /// Returns the absolute path of the file the note points at.
+    pub fn absolute_path(&self) -> &str {
+        &self.absolute_path
+    }
+
+    /// Returns the line number the note points at.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the column number the note points at.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Returns the text of the note without the `note:` keyword.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the source line printed after the note, if any.
+    pub fn source_excerpt(&self) -> Option<&str> {
+        self.source_excerpt.as_deref()
+    }
+
+    /// Returns the caret line printed after the note, if any.
+    pub fn caret(&self) -> Option<&str> {
+        self.caret.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LogFile, MyWarning, RegexParse, Severity};
+
+    /// Tests creating a note from a parsed `note:` line.
+    #[test]
+    fn test_note_new_from_log_file() {
+        let log_file = LogFile::<MyWarning>::new_from_regex(
+            "/src/Macros.h:4:9: note: expanded from macro 'CHECK'",
+        )
+        .unwrap();
+        let code_fragment = log_file.code_fragment.unwrap();
+        assert_eq!(code_fragment.severity(), Some(Severity::Note));
+
+        let note = Note::new(log_file.absolute_path, code_fragment);
+        assert_eq!(note.absolute_path(), "/src/Macros.h");
+        assert_eq!((note.line(), note.column()), (4, 9));
+        assert_eq!(note.text(), "expanded from macro 'CHECK'");
+        assert_eq!(note.source_excerpt(), None);
+    }
+}
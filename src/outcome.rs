@@ -0,0 +1,263 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::{BuildStepHeader, Dialect, RegexParse};
+
+/// Matches the verdict printed by `xcodebuild`, such as `** BUILD FAILED **`.
+static VERDICT_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^\*\* ([A-Z ]+?) (SUCCEEDED|FAILED) \*\*"#).unwrap());
+
+/// Matches the summary SwiftLint prints at the end, such as
+/// `Done linting! Found 3 violations, 1 serious in 10 files.`
+static SWIFTLINT_DONE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^Done linting! Found \d+ violations?, (?P<serious>\d+) serious"#).unwrap()
+});
+
+/// Matches the line SwiftPM prints when a build succeeds, such as `Build complete! (1.52s)`.
+static SWIFTPM_COMPLETE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^Build (?:of product '[^']+' )?complete!"#).unwrap());
+
+/// Represents the final status of an `xcodebuild` action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuildStatus {
+    Succeeded,
+    Failed,
+}
+
+/// Represents the verdict printed at the end of an `xcodebuild` run, such as
+/// `** BUILD FAILED **`, along with the commands listed as failed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BuildOutcome {
+    action: String,
+    status: BuildStatus,
+    failed_commands: Vec<String>,
+    tool_errors: Vec<String>,
+}
+
+impl BuildOutcome {
+    /// Returns the action the verdict is about, such as `BUILD`, `TEST` or `ARCHIVE`.
+    pub fn action(&self) -> &str {
+        &self.action
+    }
+
+    /// Returns the final status of the action.
+    pub fn status(&self) -> BuildStatus {
+        self.status
+    }
+
+    /// Returns whether the action succeeded.
+    pub fn succeeded(&self) -> bool {
+        self.status == BuildStatus::Succeeded
+    }
+
+    /// Returns the commands listed under "The following build commands failed:".
+    pub fn failed_commands(&self) -> &[String] {
+        &self.failed_commands
+    }
+
+    /// Returns the errors reported by the tool running `xcodebuild`, such as fastlane's
+    /// `Error building the application - see the log above`.
+    pub fn tool_errors(&self) -> &[String] {
+        &self.tool_errors
+    }
+
+    /// Returns the failed commands that can be read as build step headers.
+    pub fn failed_steps(&self) -> Vec<BuildStepHeader> {
+        self.failed_commands
+            .iter()
+            .filter_map(|command| BuildStepHeader::new_from_regex(command))
+            .collect()
+    }
+}
+
+/// A stateful parser for the verdict and the failed commands block at the end of a log.
+///
+/// ```text
+/// ** BUILD FAILED **
+///
+/// The following build commands failed:
+///     SwiftCompile normal arm64 /src/File.swift (in target 'App' from project 'App')
+/// (1 failure)
+/// ```
+///
+/// Lines are fed one at a time with [`push_line`](Self::push_line). When several
+/// verdicts are printed, the last one wins.
+#[derive(Debug)]
+pub struct BuildOutcomeParser {
+    dialect: Dialect,
+    action: Option<String>,
+    status: Option<BuildStatus>,
+    failed_commands: Vec<String>,
+    tool_errors: Vec<String>,
+    in_failed_commands: bool,
+}
+
+impl Default for BuildOutcomeParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuildOutcomeParser {
+    /// Creates a new `BuildOutcomeParser` for `xcodebuild` logs.
+    pub fn new() -> Self {
+        Self::with_dialect(Dialect::default())
+    }
+
+    /// Creates a new `BuildOutcomeParser` for logs of the given tool.
+    ///
+    /// For [`Dialect::SwiftPm`], `Build complete!` is read as a successful `BUILD` and each
+    /// `error: terminated(1): command` line as a failed command. For [`Dialect::SwiftLint`],
+    /// `Done linting!` is read as a `LINT` that failed if any violation was serious. For
+    /// [`Dialect::Fastlane`], each `[!] message` line is read as a failure of the tool.
+    pub fn with_dialect(dialect: Dialect) -> Self {
+        BuildOutcomeParser {
+            dialect,
+            action: None,
+            status: None,
+            failed_commands: Vec::new(),
+            tool_errors: Vec::new(),
+            in_failed_commands: false,
+        }
+    }
+
+    /// Feeds the next line of the log to the parser.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - A string slice that holds the log line.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the line is a verdict or part of the failed commands block.
+    pub fn push_line(&mut self, line: &str) -> bool {
+        let line = self.dialect.undecorate(line);
+        let line = line.trim_end();
+        if self.in_failed_commands {
+            if line.starts_with(char::is_whitespace) && !line.trim().is_empty() {
+                self.failed_commands.push(line.trim().to_string());
+                return true;
+            }
+            self.in_failed_commands = false;
+            if line.starts_with('(') && line.contains("failure") {
+                return true;
+            }
+        }
+
+        if self.dialect == Dialect::SwiftLint {
+            if let Some(cap) = SWIFTLINT_DONE_REGEX.captures(line) {
+                self.action = Some("LINT".to_string());
+                self.status = Some(match &cap["serious"] {
+                    "0" => BuildStatus::Succeeded,
+                    _ => BuildStatus::Failed,
+                });
+                return true;
+            }
+        }
+        if self.dialect == Dialect::SwiftPm {
+            if SWIFTPM_COMPLETE_REGEX.is_match(line) {
+                self.action = Some("BUILD".to_string());
+                self.status = Some(BuildStatus::Succeeded);
+                return true;
+            }
+            let command = line
+                .strip_prefix("error: terminated(")
+                .and_then(|rest| rest.split_once("): "));
+            if let Some((_, command)) = command {
+                let command = command.strip_suffix(" output:").unwrap_or(command);
+                self.failed_commands.push(command.to_string());
+                return true;
+            }
+        }
+
+        if self.dialect == Dialect::Fastlane {
+            if let Some(message) = line.strip_prefix("[!] ") {
+                self.tool_errors.push(message.to_string());
+                self.status = Some(BuildStatus::Failed);
+                return true;
+            }
+        }
+
+        if line == "The following build commands failed:" {
+            self.in_failed_commands = true;
+            return true;
+        }
+        if let Some(cap) = VERDICT_REGEX.captures(line) {
+            self.action = Some(cap[1].to_string());
+            self.status = Some(match &cap[2] {
+                "SUCCEEDED" => BuildStatus::Succeeded,
+                _ => BuildStatus::Failed,
+            });
+            return true;
+        }
+        false
+    }
+
+    /// Consumes the parser and returns the outcome, if a verdict or failed commands were seen.
+    pub fn finish(self) -> Option<BuildOutcome> {
+        let status = match self.status {
+            Some(status) => status,
+            None if !self.failed_commands.is_empty() => BuildStatus::Failed,
+            None => return None,
+        };
+
+        Some(BuildOutcome {
+            action: self.action.unwrap_or_else(|| "BUILD".to_string()),
+            status,
+            failed_commands: self.failed_commands,
+            tool_errors: self.tool_errors,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BuildStep;
+
+    /// Tests parsing a failed build with its failed commands.
+    #[test]
+    fn test_build_outcome_parser_failed_build() {
+        let log = "** BUILD FAILED **\n\
+                   \n\
+                   The following build commands failed:\n\
+                   \tSwiftCompile normal arm64 /src/A.swift (in target 'App' from project 'App')\n\
+                   \tLd /build/App normal (in target 'App' from project 'App')\n\
+                   (2 failures)\n";
+        let mut parser = BuildOutcomeParser::new();
+        for line in log.lines() {
+            parser.push_line(line);
+        }
+
+        let outcome = parser.finish().unwrap();
+        assert_eq!(outcome.action(), "BUILD");
+        assert_eq!(outcome.status(), BuildStatus::Failed);
+        assert_eq!(outcome.failed_commands().len(), 2);
+
+        let steps = outcome.failed_steps();
+        assert_eq!(steps[0].step(), &BuildStep::CompileSwift);
+        assert_eq!(steps[1].step(), &BuildStep::Ld);
+        assert_eq!(steps[1].target(), Some("App"));
+    }
+
+    /// Tests parsing a successful test run.
+    #[test]
+    fn test_build_outcome_parser_succeeded() {
+        let mut parser = BuildOutcomeParser::new();
+        assert!(!parser.push_line("Test Suite 'All tests' passed"));
+        assert!(parser.push_line("** TEST SUCCEEDED **"));
+
+        let outcome = parser.finish().unwrap();
+        assert_eq!(outcome.action(), "TEST");
+        assert!(outcome.succeeded());
+        assert!(outcome.failed_commands().is_empty());
+    }
+
+    /// Tests that logs without a verdict have no outcome.
+    #[test]
+    fn test_build_outcome_parser_without_verdict() {
+        let mut parser = BuildOutcomeParser::new();
+        parser.push_line("/src/A.swift:1:2: warning: unused");
+        assert!(parser.finish().is_none());
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::path_filter::glob_regex;
+use crate::{LogFile, TaskMessage};
+
+/// The places GitHub looks for a CODEOWNERS file, relative to the root of the repository.
+const CODEOWNERS_PATHS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Represents a line of a CODEOWNERS file: a pattern and the owners of the paths matching it.
+#[derive(Debug, Clone)]
+struct OwnershipRule {
+    pattern: String,
+    /// Matches the pattern at any part of a path starting at a `/`.
+    regex: Regex,
+    /// Matches the pattern from the root, for the patterns relative to it.
+    rooted_regex: Option<Regex>,
+    owners: Vec<String>,
+}
+
+/// Maps the paths of a repository to the teams owning them, read from a CODEOWNERS file.
+///
+/// Each line holds a pattern followed by its owners, such as `/Sources/Payments/ @app/payments`,
+/// and the last line matching a path wins. A pattern starting with `/` or containing a `/`
+/// is relative to the root of the repository, while other patterns, such as `*.storyboard`,
+/// match at any depth. A pattern matching a directory matches every file in it. The same
+/// format can map paths to any other names, such as team names for routing tasks.
+///
+/// Diagnostics hold absolute paths, so patterns relative to the root are matched against the
+/// path relative to the [`root`](Self::with_root) when it is set, and against any part of the
+/// path starting at a `/` otherwise.
+///
+/// ```
+/// use xcode_log_parser::CodeOwners;
+///
+/// let owners = CodeOwners::parse("* @app/core\n/Sources/Payments/ @app/payments\n")
+///     .with_root("/src/App");
+/// assert_eq!(owners.owners("/src/App/Sources/Payments/Cart.swift"), ["@app/payments"]);
+/// assert_eq!(owners.owners("/src/App/Sources/Feed/Feed.swift"), ["@app/core"]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CodeOwners {
+    rules: Vec<OwnershipRule>,
+    root: Option<String>,
+}
+
+impl CodeOwners {
+    /// Reads the rules of a CODEOWNERS file. Comments, blank lines and the section headers
+    /// of GitLab, such as `[iOS]`, are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The contents of the file.
+    pub fn parse(text: &str) -> Self {
+        let rules = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('['))
+            .filter_map(|line| {
+                let line = line.split(" #").next().unwrap_or(line);
+                let mut fields = line.split_whitespace();
+                let pattern = fields.next()?.to_string();
+                let (regex, rooted_regex) = pattern_regexes(&pattern)?;
+                Some(OwnershipRule {
+                    regex,
+                    rooted_regex,
+                    owners: fields.map(str::to_string).collect(),
+                    pattern,
+                })
+            })
+            .collect();
+        CodeOwners { rules, root: None }
+    }
+
+    /// Reads the CODEOWNERS or custom mapping file at the given path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<Self>` - The rules of the file, or an error if it could not be read.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::parse(&std::fs::read_to_string(path)?))
+    }
+
+    /// Reads the CODEOWNERS file of a repository from `.github/`, the root or `docs/`,
+    /// the places GitHub looks for it, and sets its root.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The root of the repository.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<Option<Self>>` - The rules, `None` if the repository has no CODEOWNERS
+    ///   file, or an error if it could not be read.
+    pub fn discover(root: impl AsRef<Path>) -> io::Result<Option<Self>> {
+        let root = root.as_ref();
+        let Some(path) = CODEOWNERS_PATHS
+            .iter()
+            .map(|path| root.join(path))
+            .find(|path| path.is_file())
+        else {
+            return Ok(None);
+        };
+        Ok(Some(
+            Self::load(path)?.with_root(root.to_string_lossy().into_owned()),
+        ))
+    }
+
+    /// Matches the patterns relative to the root of the repository against the paths
+    /// relative to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The absolute path of the root of the repository, such as `/src/App`.
+    pub fn with_root(mut self, root: impl Into<String>) -> Self {
+        self.root = Some(root.into().trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Returns the patterns of the rules, in the order of the file.
+    pub fn patterns(&self) -> impl Iterator<Item = &str> {
+        self.rules.iter().map(|rule| rule.pattern.as_str())
+    }
+
+    /// Returns the owners of a path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of a file, such as the path of a diagnostic.
+    ///
+    /// # Returns
+    ///
+    /// * `&[String]` - The owners named by the last rule matching the path, empty if none
+    ///   matches, the rule names no owner or the path is outside the root.
+    pub fn owners(&self, path: &str) -> &[String] {
+        let path = match &self.root {
+            Some(root) => match path.strip_prefix(root.as_str()) {
+                Some(relative) if relative.starts_with('/') => relative,
+                _ => return &[],
+            },
+            None => path,
+        };
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| match (&self.root, &rule.rooted_regex) {
+                (Some(_), Some(rooted_regex)) => rooted_regex.is_match(path),
+                _ => rule.regex.is_match(path),
+            })
+            .map_or(&[], |rule| &rule.owners)
+    }
+
+    /// Annotates each diagnostic with its owners.
+    ///
+    /// # Arguments
+    ///
+    /// * `diagnostics` - The diagnostics to annotate.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(&LogFile<T>, &[String])>` - Each diagnostic with the owners of its file, in the
+    ///   order of the diagnostics.
+    pub fn annotate<'a, T: TaskMessage + 'a>(
+        &'a self,
+        diagnostics: impl IntoIterator<Item = &'a LogFile<T>>,
+    ) -> Vec<(&'a LogFile<T>, &'a [String])> {
+        diagnostics
+            .into_iter()
+            .map(|log_file| (log_file, self.owners(log_file.absolute_path())))
+            .collect()
+    }
+
+    /// Groups diagnostics by owner, such as to report the warnings of each team.
+    ///
+    /// # Arguments
+    ///
+    /// * `diagnostics` - The diagnostics to group.
+    ///
+    /// # Returns
+    ///
+    /// * `BTreeMap<&str, Vec<&LogFile<T>>>` - The diagnostics of each owner, sorted by owner,
+    ///   in the order of the diagnostics. A diagnostic with several owners is listed under
+    ///   each of them, and one without owners under none.
+    pub fn group_by_owner<'a, T: TaskMessage + 'a>(
+        &'a self,
+        diagnostics: impl IntoIterator<Item = &'a LogFile<T>>,
+    ) -> BTreeMap<&'a str, Vec<&'a LogFile<T>>> {
+        let mut groups: BTreeMap<&str, Vec<&LogFile<T>>> = BTreeMap::new();
+        for (log_file, owners) in self.annotate(diagnostics) {
+            for owner in owners {
+                groups.entry(owner.as_str()).or_default().push(log_file);
+            }
+        }
+        groups
+    }
+}
+
+/// Translates a CODEOWNERS pattern into regular expressions matching the paths it owns,
+/// at any depth and, for the patterns relative to the root, from the root.
+fn pattern_regexes(pattern: &str) -> Option<(Regex, Option<Regex>)> {
+    let directory = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    let anchored = trimmed.starts_with('/') || trimmed.contains('/');
+    let glob = glob_regex(trimmed);
+    // `docs/*` owns the files of `docs` but not those of its subdirectories.
+    let contents = if trimmed.ends_with("/*") && !directory {
+        ""
+    } else {
+        "(?:/.*)?"
+    };
+    let regex = Regex::new(&format!("(?:^|/){}{}$", glob, contents)).ok()?;
+    let rooted_regex = match anchored {
+        true => Some(Regex::new(&format!("^/{}{}$", glob, contents)).ok()?),
+        false => None,
+    };
+    Some((regex, rooted_regex))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_log, MyWarning};
+
+    /// Tests finding the owners of diagnostics, the last matching rule winning.
+    #[test]
+    fn test_code_owners_group_by_owner() {
+        let codeowners = "# Owners of the app\n\
+                          *                   @app/core\n\
+                          *.storyboard        @app/design\n\
+                          /Sources/Payments/  @app/payments @alice # checkout\n\
+                          /Sources/Payments/Generated/\n\
+                          docs/*              @app/docs\n";
+        let owners = CodeOwners::parse(codeowners).with_root("/src/App/");
+        assert_eq!(
+            owners.owners("/src/App/Sources/Feed/Main.storyboard"),
+            ["@app/design"]
+        );
+        assert!(owners
+            .owners("/src/App/Sources/Payments/Generated/API.swift")
+            .is_empty());
+        assert_eq!(owners.owners("/src/App/docs/guide.md"), ["@app/docs"]);
+        assert_eq!(owners.owners("/src/App/docs/api/index.md"), ["@app/core"]);
+        assert!(owners.owners("/src/Other/A.swift").is_empty());
+        let unrooted = CodeOwners::parse(codeowners);
+        assert_eq!(
+            unrooted.owners("/src/App/Sources/Payments/Cart.swift"),
+            ["@app/payments", "@alice"]
+        );
+
+        let log = "/src/App/Sources/Payments/Cart.swift:1:2: warning: unused variable 'x'\n\
+                   /src/App/Sources/Feed/Feed.swift:3:4: warning: unused variable 'y'\n\
+                   /src/App/Sources/Payments/Checkout.swift:5:6: error: cannot find 'z' in scope\n";
+        let diagnostics = parse_log::<MyWarning>(log);
+        let groups: Vec<_> = owners
+            .group_by_owner(&diagnostics)
+            .into_iter()
+            .map(|(owner, diagnostics)| (owner, diagnostics.len()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![("@alice", 2), ("@app/core", 1), ("@app/payments", 2)]
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use std::convert::Infallible;
+
+use rayon::prelude::*;
+
+use crate::assembler::{DiagnosticAssembler, Entry};
+use crate::{LogFile, LogParser, TaskMessage};
+
+/// Parses the lines of a log across the threads of the rayon thread pool.
+///
+/// Like [`LogFileIter::with_parser`](crate::LogFileIter::with_parser), each line is read with
+/// the given parser, only lines with a code fragment are kept,
+/// and the source excerpt, caret and `note:` lines following a diagnostic are attached
+/// to it. Lines are parsed in parallel, then attached in order. The result is in the
+/// order of the log.
+///
+/// # Arguments
+///
+/// * `log` - A string slice that holds the whole build log.
+/// * `parser` - The parser used for each line.
+///
+/// # Returns
+///
+/// * `Vec<(usize, LogFile<T>)>` - The 1-based number of each source line and the diagnostic parsed from it.
+pub fn parse_log_parallel<T: TaskMessage + Send>(
+    log: &str,
+    parser: &LogParser,
+) -> Vec<(usize, LogFile<T>)> {
+    let lines: Vec<&str> = log.lines().collect();
+    let entries: Vec<Option<Entry<T, Infallible>>> = lines
+        .par_iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let log_file = parser.parse_line(line)?;
+            log_file.code_fragment()?;
+            Some(Entry::Diagnostic(index + 1, log_file))
+        })
+        .collect();
+
+    let mut assembler = DiagnosticAssembler::new(parser.dialect());
+    let mut diagnostics = Vec::new();
+    for (line, entry) in lines.into_iter().zip(entries) {
+        diagnostics.extend(assembler.push(line, entry));
+    }
+    diagnostics.extend(assembler.finish());
+    diagnostics
+        .into_iter()
+        .map(Entry::into_diagnostic)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_log, Dialect, MyWarning, ParseOptions, PathFilter};
+
+    /// Tests that the result matches sequential parsing, in order and with line numbers.
+    #[test]
+    fn test_parse_log_parallel_preserves_order() {
+        let log: String = (0..1000)
+            .map(|index| match index % 3 {
+                0 => format!("/src/File{}.swift:{}:1: warning: unused\n", index, index),
+                1 => format!("CompileSwift normal arm64 /src/File{}.swift\n", index),
+                _ => format!(
+                    "/src/File{}.swift:{}:2: error: s#{{\"queue\": \"Q\", \"summary\": \"S\"}}#s\n",
+                    index, index
+                ),
+            })
+            .collect();
+        let parallel = parse_log_parallel::<MyWarning>(&log, &LogParser::default());
+        let sequential = parse_log::<MyWarning>(&log);
+        assert_eq!(parallel.len(), sequential.len());
+
+        for ((line_number, log_file), expected) in parallel.iter().zip(&sequential) {
+            assert_eq!(log_file.absolute_path(), expected.absolute_path());
+            assert_eq!(*line_number, log_file.code_fragment().unwrap().line() + 1);
+        }
+    }
+
+    /// Tests that notes are attached to the diagnostic they follow.
+    #[test]
+    fn test_parse_log_parallel_attaches_notes() {
+        let log = "/src/A.swift:1:2: error: cannot find 'b' in scope\n\
+                   /src/B.swift:3:4: note: did you mean 'c'?\n\
+                   /src/C.swift:5:6: warning: unused\n";
+        let diagnostics = parse_log_parallel::<MyWarning>(log, &LogParser::default());
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].1.code_fragment().unwrap().notes().len(), 1);
+        assert_eq!(diagnostics[1].0, 3);
+    }
+
+    /// Tests that the configuration of the parser applies to every line.
+    #[test]
+    fn test_parse_log_parallel_with_parser() {
+        let log = "/App/Pods/A.swift:1:2: warning: unused\n\
+                   [10:00:00]: ▸ /App/Sources/B.swift:3:4: error: broken\n";
+        let parser = LogParser::builder()
+            .dialect(Dialect::Fastlane)
+            .options(ParseOptions {
+                paths: PathFilter::new().exclude("**/Pods/**"),
+                ..ParseOptions::default()
+            })
+            .build();
+        let diagnostics = parse_log_parallel::<MyWarning>(log, &parser);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].0, 2);
+        assert_eq!(diagnostics[0].1.absolute_path(), "/App/Sources/B.swift");
+    }
+}
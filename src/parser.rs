@@ -0,0 +1,448 @@
+use std::io::BufRead;
+
+use crate::analyzer;
+use crate::build_log::strip_ansi;
+#[cfg(feature = "toml")]
+use crate::Config;
+use crate::{
+    BuildLog, Category, CodeFragment, Dialect, LogFile, LogFileRef, ParserBackend, PathFilter,
+    PayloadDelimiters, PayloadFormat, Severity, SeverityLexicon, TaskMessage,
+};
+
+/// Selects the diagnostics kept by a [`LogParser`], by severity, path and warning flag.
+///
+/// ```
+/// use xcode_log_parser::{ParseOptions, Severity};
+///
+/// // Warnings and errors, but not notes or remarks.
+/// let options = ParseOptions {
+///     min_severity: Some(Severity::Warning),
+///     ..ParseOptions::default()
+/// };
+/// assert!(options.accepts(Some(Severity::Error)));
+/// assert!(!options.accepts(Some(Severity::Note)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Keeps diagnostics at least as severe as this one.
+    pub min_severity: Option<Severity>,
+    /// Keeps only diagnostics of these severities.
+    pub kinds: Option<Vec<Severity>>,
+    /// Keeps only lines whose path passes this filter.
+    pub paths: PathFilter,
+    /// Keeps only diagnostics enabled by one of these warning flags, such as `-Wdeprecated-declarations`.
+    pub flags: Option<Vec<String>>,
+}
+
+impl ParseOptions {
+    /// Returns whether a diagnostic of the given severity is kept.
+    ///
+    /// Diagnostics without a known severity are only kept when no severity filter is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `severity` - The severity of the diagnostic, if known.
+    pub fn accepts(&self, severity: Option<Severity>) -> bool {
+        if self.min_severity.is_none() && self.kinds.is_none() {
+            return true;
+        }
+        let Some(severity) = severity else {
+            return false;
+        };
+        self.min_severity
+            .is_none_or(|min_severity| severity >= min_severity)
+            && self
+                .kinds
+                .as_ref()
+                .is_none_or(|kinds| kinds.contains(&severity))
+    }
+
+    /// Returns whether a diagnostic enabled by the given warning flag is kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `flag` - The warning flag of the diagnostic, if any.
+    fn accepts_flag(&self, flag: Option<&str>) -> bool {
+        self.flags
+            .as_ref()
+            .is_none_or(|flags| flag.is_some_and(|flag| flags.iter().any(|kept| kept == flag)))
+    }
+
+    /// Returns whether a diagnostic is kept by every filter: path, severity and warning flag.
+    ///
+    /// [`LogParser::parse_line`] applies the same filters to the lines it parses, and
+    /// [`BuildLog`] to the diagnostics it reads from several lines, such as sanitizer reports.
+    ///
+    /// # Arguments
+    ///
+    /// * `log_file` - The parsed diagnostic.
+    pub fn keeps<T: TaskMessage>(&self, log_file: &LogFile<T>) -> bool {
+        let code_fragment = log_file.code_fragment();
+        self.paths.matches(log_file.absolute_path())
+            && code_fragment.is_none_or(|code_fragment| self.accepts(code_fragment.severity()))
+            && self.accepts_flag(code_fragment.and_then(CodeFragment::flag))
+    }
+}
+
+/// A configurable parser for log lines and whole build logs.
+///
+/// By default it behaves like [`LogFile::parse`] and [`BuildLog::parse`]: the task
+/// is parsed by the [`RegexParse`](crate::RegexParse) implementation of the task type.
+/// Setting payload delimiters or a payload format makes the parser extract the
+/// payload between those markers instead and deserialize the task from it, so custom
+/// markers and formats do not need a new `RegexParse` implementation.
+///
+/// ```
+/// use xcode_log_parser::{LogParser, MyWarning, PayloadDelimiters};
+///
+/// let parser = LogParser::builder()
+///     .payload_delimiters(PayloadDelimiters::new("TASK", "TASK"))
+///     .build();
+/// let line = r#"/src/File.swift:3:1: warning: TASK{"queue": "API", "summary": "Retry"}TASK"#;
+/// let log_file = parser.parse_line::<MyWarning>(line).unwrap();
+/// assert!(log_file.code_fragment().unwrap().task_info().is_some());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogParser {
+    backend: ParserBackend,
+    dialect: Dialect,
+    payload_delimiters: Option<PayloadDelimiters>,
+    payload_format: Option<PayloadFormat>,
+    options: ParseOptions,
+    lexicon: SeverityLexicon,
+}
+
+impl LogParser {
+    /// Returns a builder for a `LogParser` with the default configuration.
+    pub fn builder() -> LogParserBuilder {
+        LogParserBuilder::default()
+    }
+
+    /// Returns the backend used for the `path:line:column:` prefix of each line.
+    pub fn backend(&self) -> ParserBackend {
+        self.backend
+    }
+
+    /// Returns the tool whose logs are parsed.
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    /// Returns the markers surrounding the task payload, if set.
+    pub fn payload_delimiters(&self) -> Option<&PayloadDelimiters> {
+        self.payload_delimiters.as_ref()
+    }
+
+    /// Returns the format of the task payload, if set.
+    pub fn payload_format(&self) -> Option<PayloadFormat> {
+        self.payload_format
+    }
+
+    /// Returns the options selecting the diagnostics kept.
+    pub fn options(&self) -> &ParseOptions {
+        &self.options
+    }
+
+    /// Returns the severity keywords recognized.
+    pub fn lexicon(&self) -> &SeverityLexicon {
+        &self.lexicon
+    }
+
+    /// Parses a single log line.
+    ///
+    /// ANSI color codes and the decoration the [`Dialect`] adds around the `xcodebuild`
+    /// output are removed first. The [`spans`](LogFile::spans) of the diagnostic index the
+    /// line as given, decoration included, and are `None` when removing them rewrote the
+    /// line rather than cutting a prefix off it.
+    ///
+    /// Lines rejected by the severity and path filters of the [`ParseOptions`] are skipped
+    /// before their task is parsed, and diagnostics without one of its warning flags after.
+    /// The rule of lint violations is extracted when the [`Dialect`] has rules, and
+    /// diagnostics naming a `clang-analyzer-` checker are tagged as [`Category::Analyzer`].
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - A string slice that holds the log line to be parsed.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<LogFile<T>>` - A `LogFile` instance if parsing is successful, otherwise `None`.
+    pub fn parse_line<T: TaskMessage>(&self, line: &str) -> Option<LogFile<T>> {
+        let original = line;
+        let stripped = strip_ansi(line);
+        let line = &*self.dialect.undecorate(&stripped);
+        if self.options != ParseOptions::default() {
+            let log_file = LogFileRef::parse_with_lexicon(line, self.backend, &self.lexicon)?;
+            if !self.options.paths.matches(log_file.absolute_path()) {
+                return None;
+            }
+            if let Some(code_fragment) = log_file.code_fragment() {
+                if !self.options.accepts(code_fragment.severity()) {
+                    return None;
+                }
+            }
+        }
+        let mut log_file = if self.payload_delimiters.is_none() && self.payload_format.is_none() {
+            LogFile::parse_with_task(line, self.backend, &self.lexicon, T::new_from_regex)?
+        } else {
+            let default_delimiters = PayloadDelimiters::default();
+            let delimiters = self
+                .payload_delimiters
+                .as_ref()
+                .unwrap_or(&default_delimiters);
+            let format = self.payload_format.unwrap_or_default();
+            LogFile::parse_with_task(line, self.backend, &self.lexicon, |message| {
+                format.deserialize(delimiters.extract(message)?)
+            })?
+        };
+        if !self
+            .options
+            .accepts_flag(log_file.code_fragment().and_then(CodeFragment::flag))
+        {
+            return None;
+        }
+        if let Some(code_fragment) = log_file.code_fragment.as_mut() {
+            if let Some(rule) = self.dialect.rule(code_fragment.text()) {
+                code_fragment.categorize(Category::Lint, Some(rule));
+            } else if let Some(checker) = analyzer::checker(code_fragment.text(), false) {
+                code_fragment.categorize(Category::Analyzer, Some(checker));
+            }
+        }
+        log_file.relocate_spans(original, line);
+        Some(log_file)
+    }
+
+    /// Parses a complete build log, as described in [`BuildLog::parse`].
+    ///
+    /// # Arguments
+    ///
+    /// * `log` - A string slice that holds the whole build log.
+    ///
+    /// # Returns
+    ///
+    /// * `BuildLog<T>` - A `BuildLog` holding the diagnostics in the order they appear in the log.
+    pub fn parse_build_log<T: TaskMessage>(&self, log: &str) -> BuildLog<T> {
+        BuildLog::parse_with_parser(log, self)
+    }
+
+    /// Reads a build log to its end and parses it, as described in [`BuildLog::parse`].
+    ///
+    /// Invalid UTF-8 is replaced rather than rejected, as logs may quote source files in any encoding.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A buffered reader that yields the build log, such as the locked stdin.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<BuildLog<T>>` - A `BuildLog` holding the diagnostics in the order they appear in the log, or the read error.
+    pub fn parse_reader<T: TaskMessage, R: BufRead>(
+        &self,
+        mut reader: R,
+    ) -> std::io::Result<BuildLog<T>> {
+        let mut log = Vec::new();
+        reader.read_to_end(&mut log)?;
+        Ok(self.parse_build_log(&String::from_utf8_lossy(&log)))
+    }
+}
+
+/// A builder for [`LogParser`].
+#[derive(Debug, Clone, Default)]
+pub struct LogParserBuilder {
+    parser: LogParser,
+}
+
+impl LogParserBuilder {
+    /// Sets the backend used for the `path:line:column:` prefix of each line.
+    pub fn backend(mut self, backend: ParserBackend) -> Self {
+        self.parser.backend = backend;
+        self
+    }
+
+    /// Sets the tool whose logs are parsed, [`Dialect::Xcodebuild`] by default.
+    pub fn dialect(mut self, dialect: Dialect) -> Self {
+        self.parser.dialect = dialect;
+        self
+    }
+
+    /// Sets the markers surrounding the task payload.
+    pub fn payload_delimiters(mut self, payload_delimiters: PayloadDelimiters) -> Self {
+        self.parser.payload_delimiters = Some(payload_delimiters);
+        self
+    }
+
+    /// Sets the format of the task payload.
+    pub fn payload_format(mut self, payload_format: PayloadFormat) -> Self {
+        self.parser.payload_format = Some(payload_format);
+        self
+    }
+
+    /// Sets the options selecting the diagnostics kept.
+    pub fn options(mut self, options: ParseOptions) -> Self {
+        self.parser.options = options;
+        self
+    }
+
+    /// Sets the severity keywords recognized, `warning`, `error`, `note` and `remark` by default.
+    pub fn lexicon(mut self, lexicon: SeverityLexicon) -> Self {
+        self.parser.lexicon = lexicon;
+        self
+    }
+
+    /// Sets the options from the `[filters]` section of a configuration file, and the
+    /// payload markers and format if it sets them.
+    #[cfg(feature = "toml")]
+    pub fn config(mut self, config: &Config) -> Self {
+        self.parser.options = config.parse_options();
+        if let Some(payload_delimiters) = config.payload_delimiters() {
+            self.parser.payload_delimiters = Some(payload_delimiters);
+        }
+        if let Some(payload_format) = config.payload.format {
+            self.parser.payload_format = Some(payload_format);
+        }
+        self
+    }
+
+    /// Returns the configured `LogParser`.
+    pub fn build(self) -> LogParser {
+        self.parser
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MyWarning, TaskMessage};
+
+    /// Tests that the default parser reads the `s#...#s` payload.
+    #[test]
+    fn test_log_parser_default_payload() {
+        let parser = LogParser::default();
+        let line = r#"/src/A.swift:1:2: warning: s#{"queue": "Q", "summary": "S"}#s"#;
+        let log_file = parser.parse_line::<MyWarning>(line).unwrap();
+        let task_info = log_file.code_fragment().unwrap().task_info().unwrap();
+        assert_eq!(task_info.task().task_summary(), "S");
+    }
+
+    /// Tests parsing a build log with custom payload delimiters.
+    #[test]
+    fn test_log_parser_custom_payload_delimiters() {
+        let parser = LogParser::builder()
+            .backend(ParserBackend::Regex)
+            .payload_delimiters(PayloadDelimiters::new("<<", ">>"))
+            .build();
+        let log = "/src/A.swift:1:2: warning: <<{\"queue\": \"Q\", \"summary\": \"S\"}>>\n\
+                   /src/B.swift:3:4: error: s#{\"queue\": \"Q\", \"summary\": \"S\"}#s\n";
+        let build_log = parser.parse_build_log::<MyWarning>(log);
+        let tasks: Vec<bool> = build_log
+            .diagnostics()
+            .iter()
+            .map(|log_file| log_file.code_fragment().unwrap().task_info().is_some())
+            .collect();
+        assert_eq!(tasks, vec![true, false]);
+    }
+
+    /// Tests parsing a YAML payload between the default delimiters.
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_log_parser_yaml_payload() {
+        let parser = LogParser::builder()
+            .payload_format(PayloadFormat::Yaml)
+            .build();
+        let line = "/src/A.swift:1:2: warning: s#{queue: Q, summary: Clean up}#s";
+        let log_file = parser.parse_line::<MyWarning>(line).unwrap();
+        let task_info = log_file.code_fragment().unwrap().task_info().unwrap();
+        assert_eq!(task_info.task().task_summary(), "Clean up");
+    }
+
+    /// Tests that diagnostics rejected by the options are skipped.
+    #[test]
+    fn test_log_parser_options_filter_severities() {
+        let log = "/src/A.swift:1:1: note: one\n\
+                   /src/A.swift:2:1: error: two\n\
+                   /src/A.swift:3:1: warning: three\n\
+                   /src/A.swift:4:1: remark: four\n\
+                   /src/A.swift:5:1: other five\n";
+        let lines = |options: ParseOptions| -> Vec<usize> {
+            let parser = LogParser::builder().options(options).build();
+            parser
+                .parse_build_log::<MyWarning>(log)
+                .diagnostics()
+                .iter()
+                .map(|log_file| log_file.code_fragment().unwrap().line())
+                .collect()
+        };
+
+        assert_eq!(lines(ParseOptions::default()), vec![1, 2, 3, 4, 5]);
+        assert_eq!(
+            lines(ParseOptions {
+                min_severity: Some(Severity::Error),
+                ..ParseOptions::default()
+            }),
+            vec![2]
+        );
+        assert_eq!(
+            lines(ParseOptions {
+                kinds: Some(vec![Severity::Note, Severity::Warning]),
+                ..ParseOptions::default()
+            }),
+            vec![1, 3]
+        );
+    }
+
+    /// Tests that lines outside the included paths are skipped.
+    #[test]
+    fn test_log_parser_options_filter_paths() {
+        let log = "/App/Pods/Alamofire/Session.swift:1:1: warning: one\n\
+                   /App/Sources/View.swift:2:1: warning: two\n";
+        let parser = LogParser::builder()
+            .options(ParseOptions {
+                paths: PathFilter::new().exclude("Pods/**"),
+                ..ParseOptions::default()
+            })
+            .build();
+        let build_log = parser.parse_build_log::<MyWarning>(log);
+        let paths: Vec<&str> = build_log
+            .diagnostics()
+            .iter()
+            .map(LogFile::absolute_path)
+            .collect();
+        assert_eq!(paths, vec!["/App/Sources/View.swift"]);
+    }
+
+    /// Tests capturing the warning flag of clang diagnostics and keeping only some flags.
+    #[test]
+    fn test_log_parser_options_filter_flags() {
+        let log = "/src/A.m:1:1: warning: 'foo' is deprecated [-Wdeprecated-declarations]\n\
+                   /src/B.m:2:5: error: unused variable 'x' [-Werror,-Wunused-variable]\n\
+                   /src/C.swift:3:1: warning: unused\n";
+        let flags: Vec<Option<String>> = LogParser::default()
+            .parse_build_log::<MyWarning>(log)
+            .diagnostics()
+            .iter()
+            .map(|log_file| log_file.code_fragment().unwrap().flag().map(str::to_string))
+            .collect();
+        assert_eq!(
+            flags,
+            vec![
+                Some("-Wdeprecated-declarations".to_string()),
+                Some("-Wunused-variable".to_string()),
+                None,
+            ]
+        );
+
+        let parser = LogParser::builder()
+            .options(ParseOptions {
+                flags: Some(vec!["-Wunused-variable".to_string()]),
+                ..ParseOptions::default()
+            })
+            .build();
+        let build_log = parser.parse_build_log::<MyWarning>(log);
+        let paths: Vec<&str> = build_log
+            .diagnostics()
+            .iter()
+            .map(LogFile::absolute_path)
+            .collect();
+        assert_eq!(paths, vec!["/src/B.m"]);
+    }
+}
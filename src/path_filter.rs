@@ -0,0 +1,141 @@
+use regex::Regex;
+
+/// Keeps or drops diagnostics by the glob patterns their path matches.
+///
+/// A pattern matches a path when it matches the whole path or a part of it starting
+/// at a `/`, so `Pods/**` matches `/Users/me/App/Pods/Alamofire/Session.swift`.
+/// `*` and `?` do not cross `/`, while `**` does.
+///
+/// ```
+/// use xcode_log_parser::PathFilter;
+///
+/// let filter = PathFilter::new().include("Sources/**").exclude("Pods/**");
+/// assert!(filter.matches("/App/Sources/View.swift"));
+/// assert!(!filter.matches("/App/Pods/Alamofire/Session.swift"));
+/// assert!(!filter.matches("/App/Tests/ViewTests.swift"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PathFilter {
+    include: Vec<String>,
+    exclude: Vec<String>,
+    include_regex: Option<Regex>,
+    exclude_regex: Option<Regex>,
+}
+
+impl PathFilter {
+    /// Creates a `PathFilter` keeping every path.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps only paths matching this pattern or another included one.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - A glob pattern, such as `Sources/**`.
+    pub fn include(mut self, pattern: impl Into<String>) -> Self {
+        self.include.push(pattern.into());
+        self.include_regex = globs_regex(&self.include);
+        self
+    }
+
+    /// Drops paths matching this pattern, even if they are included.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - A glob pattern, such as `Pods/**`.
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.exclude.push(pattern.into());
+        self.exclude_regex = globs_regex(&self.exclude);
+        self
+    }
+
+    /// Returns the included patterns.
+    pub fn includes(&self) -> &[String] {
+        &self.include
+    }
+
+    /// Returns the excluded patterns.
+    pub fn excludes(&self) -> &[String] {
+        &self.exclude
+    }
+
+    /// Returns whether diagnostics in the given file are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - A string slice that holds the path of the file.
+    pub fn matches(&self, path: &str) -> bool {
+        self.include_regex
+            .as_ref()
+            .is_none_or(|regex| regex.is_match(path))
+            && !self
+                .exclude_regex
+                .as_ref()
+                .is_some_and(|regex| regex.is_match(path))
+    }
+}
+
+impl PartialEq for PathFilter {
+    fn eq(&self, other: &Self) -> bool {
+        self.include == other.include && self.exclude == other.exclude
+    }
+}
+
+impl Eq for PathFilter {}
+
+/// Returns a regular expression matching any of the given glob patterns, if there are any.
+fn globs_regex(patterns: &[String]) -> Option<Regex> {
+    if patterns.is_empty() {
+        return None;
+    }
+    let alternatives: Vec<String> = patterns.iter().map(|pattern| glob_regex(pattern)).collect();
+    Regex::new(&format!("(?:^|/)(?:{})$", alternatives.join("|"))).ok()
+}
+
+/// Translates a glob pattern into the source of an unanchored regular expression.
+pub(crate) fn glob_regex(pattern: &str) -> String {
+    let pattern = pattern.trim_start_matches('/');
+    let mut regex = String::new();
+    let mut rest = pattern;
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("**/") {
+            regex.push_str("(?:.*/)?");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("**") {
+            regex.push_str(".*");
+            rest = after;
+        } else {
+            match c {
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                _ => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests matching paths against single and double star patterns.
+    #[test]
+    fn test_path_filter_globs() {
+        let filter = PathFilter::new()
+            .exclude("Pods/**")
+            .exclude("**/*.generated.swift");
+        assert!(!filter.matches("/Users/me/App/Pods/Alamofire/Session.swift"));
+        assert!(!filter.matches("/App/Sources/Model.generated.swift"));
+        assert!(!filter.matches("Model.generated.swift"));
+        assert!(filter.matches("/App/Sources/MyPods/View.swift"));
+        assert!(filter.matches("/App/Sources/Model.swift"));
+
+        let filter = PathFilter::new().include("Sources/*.swift");
+        assert!(filter.matches("/App/Sources/View.swift"));
+        assert!(!filter.matches("/App/Sources/UI/View.swift"));
+        assert!(!filter.matches("/App/Sources/View.m"));
+    }
+}
@@ -0,0 +1,165 @@
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+/// The markers surrounding the task payload embedded in a diagnostic message,
+/// such as `s#` and `#s` in `warning: s#{"queue": "API", "summary": "..."}#s`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PayloadDelimiters {
+    open: String,
+    close: String,
+}
+
+impl Default for PayloadDelimiters {
+    /// Returns the `s#...#s` delimiters recognized by [`MyWarning`](crate::MyWarning).
+    fn default() -> Self {
+        Self::new("s#", "#s")
+    }
+}
+
+/// The format of the task payload embedded between the [`PayloadDelimiters`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum PayloadFormat {
+    /// `{"queue": "API", "summary": "..."}`
+    #[default]
+    Json,
+    /// `{queue: API, summary: ...}`, available with the `yaml` feature.
+    #[cfg(feature = "yaml")]
+    Yaml,
+    /// An inline table such as `{ queue = "API", summary = "..." }`, available with the `toml` feature.
+    #[cfg(feature = "toml")]
+    Toml,
+}
+
+impl PayloadFormat {
+    /// Deserializes a task from the given payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - A string slice that holds the payload without its delimiters.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<T>` - The deserialized task if the payload is valid, otherwise `None`.
+    pub fn deserialize<T: DeserializeOwned>(&self, payload: &str) -> Option<T> {
+        match self {
+            PayloadFormat::Json => serde_json::from_str(payload).ok(),
+            #[cfg(feature = "yaml")]
+            PayloadFormat::Yaml => serde_yaml::from_str(payload).ok(),
+            #[cfg(feature = "toml")]
+            PayloadFormat::Toml => {
+                let mut table: toml::Table =
+                    toml::from_str(&format!("payload = {}", payload.trim())).ok()?;
+                table.remove("payload")?.try_into().ok()
+            }
+        }
+    }
+}
+
+impl PayloadDelimiters {
+    /// Creates new `PayloadDelimiters`.
+    ///
+    /// # Arguments
+    ///
+    /// * `open` - The marker placed before the payload, such as `TASK`.
+    /// * `close` - The marker placed after the payload, such as `TASK`.
+    pub fn new(open: impl Into<String>, close: impl Into<String>) -> Self {
+        PayloadDelimiters {
+            open: open.into(),
+            close: close.into(),
+        }
+    }
+
+    /// Returns the marker placed before the payload.
+    pub fn open(&self) -> &str {
+        &self.open
+    }
+
+    /// Returns the marker placed after the payload.
+    pub fn close(&self) -> &str {
+        &self.close
+    }
+
+    /// Returns the non-empty payload between the first opening marker and the closing marker after it.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - A string slice that holds the diagnostic message.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&str>` - The payload if both markers are found, otherwise `None`.
+    pub fn extract<'a>(&self, haystack: &'a str) -> Option<&'a str> {
+        let start = haystack.find(&self.open)? + self.open.len();
+        let first = haystack[start..].chars().next()?;
+        let search_start = start + first.len_utf8();
+        let end = search_start + haystack[search_start..].find(&self.close)?;
+        Some(&haystack[start..end])
+    }
+
+    /// Extracts the payload from the message and deserializes it from JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - A string slice that holds the diagnostic message.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<T>` - The deserialized payload if it is found and valid, otherwise `None`.
+    pub fn deserialize<T: DeserializeOwned>(&self, haystack: &str) -> Option<T> {
+        PayloadFormat::Json.deserialize(self.extract(haystack)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MyWarning, TaskMessage};
+
+    /// Tests extracting payloads with the default and custom markers.
+    #[test]
+    fn test_payload_delimiters_extract() {
+        let delimiters = PayloadDelimiters::default();
+        assert_eq!(delimiters.extract("a s#{}#s b"), Some("{}"));
+        assert_eq!(delimiters.extract("s##s"), None);
+        assert_eq!(delimiters.extract("s#{} no close"), None);
+
+        let delimiters = PayloadDelimiters::new("TASK", "TASK");
+        assert_eq!(
+            delimiters.extract(r#"#warning("TASK{"queue": "Q"}TASK")"#),
+            Some(r#"{"queue": "Q"}"#)
+        );
+    }
+
+    /// Tests deserializing a payload between custom markers.
+    #[test]
+    fn test_payload_delimiters_deserialize() {
+        let delimiters = PayloadDelimiters::new("<task>", "</task>");
+        let warning: MyWarning = delimiters
+            .deserialize(r#"<task>{"queue": "Q", "summary": "S"}</task>"#)
+            .unwrap();
+        assert_eq!(warning.task_queue(), "Q");
+        assert!(delimiters
+            .deserialize::<MyWarning>(r#"<task>{"queue": "Q"}</task>"#)
+            .is_none());
+    }
+
+    /// Tests deserializing YAML and TOML payloads.
+    #[cfg(all(feature = "yaml", feature = "toml"))]
+    #[test]
+    fn test_payload_format_deserialize() {
+        let warning: MyWarning = PayloadFormat::Yaml
+            .deserialize("{queue: API, summary: Retry the request}")
+            .unwrap();
+        assert_eq!(warning.task_summary(), "Retry the request");
+
+        let warning: MyWarning = PayloadFormat::Toml
+            .deserialize(r#"{ queue = "API", summary = "Retry" }"#)
+            .unwrap();
+        assert_eq!(warning.task_queue(), "API");
+        assert!(PayloadFormat::Toml
+            .deserialize::<MyWarning>("queue: API")
+            .is_none());
+    }
+}
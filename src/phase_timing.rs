@@ -0,0 +1,284 @@
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::{BuildStep, Dialect};
+
+/// Matches the timestamp CI services and tools such as `ts` or fastlane put in front of log
+/// lines, such as `2024-05-01T10:23:45.1234567Z ` or `[10:23:45]: `.
+static TIMESTAMP_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"^(?:\d{4}-\d{2}-\d{2}[T ](?P<time>\d{2}:\d{2}:\d{2}(?:\.\d+)?)(?:Z|[+-]\d{2}:?\d{2})? |\[(?:\d{4}-\d{2}-\d{2}[T ])?(?P<bracketed>\d{2}:\d{2}:\d{2}(?:\.\d+)?)\]:? )"#,
+    )
+    .unwrap()
+});
+
+/// The number of seconds in a day, added when the time of day wraps around midnight.
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Represents the time spent on one kind of step of a target, such as its `CompileSwift` steps.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PhaseTiming {
+    step: BuildStep,
+    step_count: usize,
+    duration: Option<Duration>,
+}
+
+impl PhaseTiming {
+    /// Returns the kind of step.
+    pub fn step(&self) -> &BuildStep {
+        &self.step
+    }
+
+    /// Returns how many steps of this kind were run for the target.
+    pub fn step_count(&self) -> usize {
+        self.step_count
+    }
+
+    /// Returns the time spent on the steps, if the log is timestamped.
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration
+    }
+}
+
+/// Represents the time spent on the steps of one target.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TargetTiming {
+    target: String,
+    phases: Vec<PhaseTiming>,
+}
+
+impl TargetTiming {
+    /// Returns the name of the target.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Returns the time spent on each kind of step, in the order they were first started.
+    pub fn phases(&self) -> &[PhaseTiming] {
+        &self.phases
+    }
+
+    /// Returns how many steps were run for the target.
+    pub fn step_count(&self) -> usize {
+        self.phases.iter().map(PhaseTiming::step_count).sum()
+    }
+
+    /// Returns the time spent on the steps of the target, if the log is timestamped.
+    pub fn duration(&self) -> Option<Duration> {
+        self.phases.iter().map(PhaseTiming::duration).sum()
+    }
+}
+
+/// Represents the time spent on each target and each kind of step of a build.
+///
+/// A step is timed from the timestamp of its header to the timestamp of the next header,
+/// or of the last line of the log. `xcodebuild` runs steps in parallel and prints their
+/// headers as they start, so the durations attribute the wall-clock time of the build
+/// among the steps rather than measuring each of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PhaseTimings {
+    targets: Vec<TargetTiming>,
+}
+
+impl PhaseTimings {
+    /// Returns the targets, in the order their first step was started.
+    pub fn targets(&self) -> &[TargetTiming] {
+        &self.targets
+    }
+
+    /// Returns the targets, the slowest first, or the one with the most steps first if the
+    /// log is not timestamped.
+    pub fn slowest(&self) -> Vec<&TargetTiming> {
+        let mut targets: Vec<_> = self.targets.iter().collect();
+        targets.sort_by_key(|target| std::cmp::Reverse((target.duration(), target.step_count())));
+        targets
+    }
+}
+
+/// A stateful parser timing the build steps of a log.
+///
+/// ```text
+/// 2024-05-01T10:23:41.000Z SwiftCompile normal arm64 /src/A.swift (in target 'Core' from project 'App')
+/// 2024-05-01T10:23:45.500Z Ld /build/App.app/App normal (in target 'App' from project 'App')
+/// 2024-05-01T10:23:46.000Z ** BUILD SUCCEEDED **
+/// ```
+///
+/// Lines are fed one at a time with [`push_line`](Self::push_line), with their timestamps.
+/// Steps are still counted in logs without timestamps, but not timed.
+#[derive(Debug)]
+pub struct PhaseTimingsParser {
+    dialect: Dialect,
+    /// The target and step being run, with the time it was started.
+    current: Option<(String, BuildStep, Option<f64>)>,
+    /// The time of the last timestamped line, in seconds since the midnight of the first one.
+    last_time: Option<f64>,
+    timings: PhaseTimings,
+}
+
+impl Default for PhaseTimingsParser {
+    fn default() -> Self {
+        Self::with_dialect(Dialect::default())
+    }
+}
+
+impl PhaseTimingsParser {
+    /// Creates a new `PhaseTimingsParser` for `xcodebuild` logs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new `PhaseTimingsParser` reading the build steps of the given dialect.
+    ///
+    /// # Arguments
+    ///
+    /// * `dialect` - The tool that printed the log.
+    pub fn with_dialect(dialect: Dialect) -> Self {
+        PhaseTimingsParser {
+            dialect,
+            current: None,
+            last_time: None,
+            timings: PhaseTimings::default(),
+        }
+    }
+
+    /// Feeds the next line of the log to the parser.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - A string slice that holds the log line, with its timestamp, if any.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the line starts a build step.
+    pub fn push_line(&mut self, line: &str) -> bool {
+        let mut line = line.trim_end();
+        let mut time = None;
+        if let Some(cap) = TIMESTAMP_REGEX.captures(line) {
+            let timestamp = cap.name("time").or(cap.name("bracketed")).unwrap();
+            time = self.advance(timestamp.as_str());
+            line = &line[cap.get(0).unwrap().end()..];
+        }
+        let Some(header) = self.dialect.build_step(&self.dialect.undecorate(line)) else {
+            return false;
+        };
+        self.end_step();
+        if let Some(target) = header.target() {
+            self.current = Some((target.to_string(), header.step().clone(), time));
+        }
+        true
+    }
+
+    /// Consumes the parser and returns the timings of the steps found.
+    pub fn finish(mut self) -> PhaseTimings {
+        self.end_step();
+        self.timings
+    }
+
+    /// Returns the time of a timestamp, in seconds since the midnight of the first one.
+    fn advance(&mut self, timestamp: &str) -> Option<f64> {
+        let mut fields = timestamp.split(':');
+        let hours: f64 = fields.next()?.parse().ok()?;
+        let minutes: f64 = fields.next()?.parse().ok()?;
+        let seconds: f64 = fields.next()?.parse().ok()?;
+        let mut time = hours * 3600.0 + minutes * 60.0 + seconds;
+        if let Some(last_time) = self.last_time {
+            // The time of day wrapped around midnight.
+            time += (last_time / SECONDS_PER_DAY).floor() * SECONDS_PER_DAY;
+            if time < last_time {
+                time += SECONDS_PER_DAY;
+            }
+        }
+        self.last_time = Some(time);
+        Some(time)
+    }
+
+    /// Adds the step being run to the timings, timed until the last timestamped line.
+    fn end_step(&mut self) {
+        let Some((target, step, started)) = self.current.take() else {
+            return;
+        };
+        let duration = started
+            .zip(self.last_time)
+            .map(|(started, ended)| Duration::from_secs_f64((ended - started).max(0.0)));
+
+        let targets = &mut self.timings.targets;
+        let index = match targets.iter().position(|timing| timing.target == target) {
+            Some(index) => index,
+            None => {
+                targets.push(TargetTiming {
+                    target,
+                    phases: Vec::new(),
+                });
+                targets.len() - 1
+            }
+        };
+        let phases = &mut targets[index].phases;
+        match phases.iter_mut().find(|phase| phase.step == step) {
+            Some(phase) => {
+                phase.step_count += 1;
+                phase.duration = phase
+                    .duration
+                    .zip(duration)
+                    .map(|(total, duration)| total + duration);
+            }
+            None => phases.push(PhaseTiming {
+                step,
+                step_count: 1,
+                duration,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{BuildLog, BuildStep, MyWarning};
+
+    /// Tests timing the steps of a timestamped log, and counting those of a plain one.
+    #[test]
+    fn test_build_log_phase_timings() {
+        let log = "2024-05-01T23:59:50.000Z SwiftCompile normal arm64 /src/A.swift (in target 'Core' from project 'App')\n\
+                   2024-05-01T23:59:50.500Z /src/A.swift:1:2: warning: unused\n\
+                   2024-05-01T23:59:54.000Z SwiftCompile normal arm64 /src/B.swift (in target 'Core' from project 'App')\n\
+                   2024-05-01T23:59:58.000Z Ld /build/Core.framework/Core normal (in target 'Core' from project 'App')\n\
+                   2024-05-02T00:00:00.000Z SwiftCompile normal arm64 /src/App.swift (in target 'App' from project 'App')\n\
+                   2024-05-02T00:00:10.250Z ** BUILD SUCCEEDED **\n";
+        let timings = BuildLog::<MyWarning>::parse(log).phase_timings().clone();
+
+        let targets: Vec<_> = timings
+            .targets()
+            .iter()
+            .map(|target| (target.target(), target.step_count(), target.duration()))
+            .collect();
+        assert_eq!(
+            targets,
+            vec![
+                ("Core", 3, Some(Duration::from_secs(10))),
+                ("App", 1, Some(Duration::from_millis(10250))),
+            ]
+        );
+        let phases: Vec<_> = timings.targets()[0]
+            .phases()
+            .iter()
+            .map(|phase| (phase.step().clone(), phase.step_count(), phase.duration()))
+            .collect();
+        assert_eq!(
+            phases,
+            vec![
+                (BuildStep::CompileSwift, 2, Some(Duration::from_secs(8))),
+                (BuildStep::Ld, 1, Some(Duration::from_secs(2))),
+            ]
+        );
+        assert_eq!(timings.slowest()[0].target(), "App");
+
+        let build_log = BuildLog::<MyWarning>::parse(
+            "SwiftCompile normal arm64 /src/A.swift (in target 'Core' from project 'App')\n",
+        );
+        let target = &build_log.phase_timings().targets()[0];
+        assert_eq!((target.step_count(), target.duration()), (1, None));
+    }
+}
@@ -0,0 +1,257 @@
+use std::fmt;
+
+use crate::{BaselineDiff, LogFile, PathFilter, Severity, TaskMessage};
+
+/// Thresholds a build log must meet, turning the parser into a quality gate.
+///
+/// ```
+/// use xcode_log_parser::{parse_log, MyWarning, Policy};
+///
+/// let policy = Policy {
+///     max_warnings: Some(0),
+///     ..Policy::default()
+/// };
+/// let diagnostics = parse_log::<MyWarning>("/src/A.swift:1:2: warning: unused variable 'x'\n");
+/// assert!(!policy.evaluate(&diagnostics, None).passed());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Policy {
+    /// The maximum number of warnings allowed.
+    pub max_warnings: Option<usize>,
+    /// The maximum number of errors allowed.
+    pub max_errors: Option<usize>,
+    /// Fails when a diagnostic is missing from the baseline.
+    pub fail_on_new: bool,
+    /// The maximum number of warnings allowed in each directory.
+    pub budgets: Vec<Budget>,
+}
+
+/// The maximum number of warnings allowed in the files matching a glob pattern, such as
+/// `Modules/Legacy/**`, so each module can be ratcheted down on its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Budget {
+    pattern: String,
+    max_warnings: usize,
+}
+
+impl Budget {
+    /// Creates a new `Budget`.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The glob pattern of the files, matched as in [`PathFilter`].
+    /// * `max_warnings` - The maximum number of warnings allowed in those files.
+    pub fn new(pattern: impl Into<String>, max_warnings: usize) -> Self {
+        Budget {
+            pattern: pattern.into(),
+            max_warnings,
+        }
+    }
+
+    /// Returns the glob pattern of the files.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Returns the maximum number of warnings allowed in the files.
+    pub fn max_warnings(&self) -> usize {
+        self.max_warnings
+    }
+}
+
+/// A threshold of a [`Policy`] that a build log does not meet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// More warnings than `max_warnings`.
+    TooManyWarnings { count: usize, max: usize },
+    /// More errors than `max_errors`.
+    TooManyErrors { count: usize, max: usize },
+    /// Diagnostics missing from the baseline, with `fail_on_new` set.
+    NewDiagnostics { count: usize },
+    /// More warnings than a [`Budget`] allows in the files matching its pattern.
+    BudgetExceeded {
+        pattern: String,
+        count: usize,
+        max: usize,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::TooManyWarnings { count, max } => {
+                write!(f, "{} warnings exceed the maximum of {}", count, max)
+            }
+            Violation::TooManyErrors { count, max } => {
+                write!(f, "{} errors exceed the maximum of {}", count, max)
+            }
+            Violation::NewDiagnostics { count } => {
+                write!(f, "{} diagnostics are not in the baseline", count)
+            }
+            Violation::BudgetExceeded {
+                pattern,
+                count,
+                max,
+            } => write!(
+                f,
+                "{} warnings in {} exceed the budget of {} by {}",
+                count,
+                pattern,
+                max,
+                count - max
+            ),
+        }
+    }
+}
+
+/// The result of evaluating a [`Policy`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Verdict {
+    violations: Vec<Violation>,
+}
+
+impl Verdict {
+    /// Returns whether every threshold of the policy is met.
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Returns the thresholds that are not met.
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+}
+
+impl Policy {
+    /// Evaluates the policy against parsed diagnostics.
+    ///
+    /// # Arguments
+    ///
+    /// * `diagnostics` - The diagnostics of the current build.
+    /// * `diff` - The comparison of the diagnostics against a baseline. `fail_on_new` is only checked when it is given.
+    ///
+    /// # Returns
+    ///
+    /// * `Verdict` - The thresholds that are not met, if any.
+    pub fn evaluate<T: TaskMessage>(
+        &self,
+        diagnostics: &[LogFile<T>],
+        diff: Option<&BaselineDiff<'_, T>>,
+    ) -> Verdict {
+        let count = |severity: Severity| {
+            diagnostics
+                .iter()
+                .filter_map(LogFile::code_fragment)
+                .filter(|code_fragment| code_fragment.severity() == Some(severity))
+                .count()
+        };
+
+        let mut violations = Vec::new();
+        if let Some(max) = self.max_errors {
+            let count = count(Severity::Error);
+            if count > max {
+                violations.push(Violation::TooManyErrors { count, max });
+            }
+        }
+        if let Some(max) = self.max_warnings {
+            let count = count(Severity::Warning);
+            if count > max {
+                violations.push(Violation::TooManyWarnings { count, max });
+            }
+        }
+        for budget in &self.budgets {
+            let paths = PathFilter::new().include(budget.pattern.clone());
+            let count = diagnostics
+                .iter()
+                .filter(|log_file| paths.matches(log_file.absolute_path()))
+                .filter_map(LogFile::code_fragment)
+                .filter(|code_fragment| code_fragment.severity() == Some(Severity::Warning))
+                .count();
+            if count > budget.max_warnings {
+                violations.push(Violation::BudgetExceeded {
+                    pattern: budget.pattern.clone(),
+                    count,
+                    max: budget.max_warnings,
+                });
+            }
+        }
+        if let Some(diff) = diff.filter(|_| self.fail_on_new) {
+            if !diff.added().is_empty() {
+                violations.push(Violation::NewDiagnostics {
+                    count: diff.added().len(),
+                });
+            }
+        }
+        Verdict { violations }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{diff, parse_log, Baseline, MyWarning};
+
+    /// Tests the violations reported for each threshold.
+    #[test]
+    fn test_policy_evaluate() {
+        let baseline = Baseline::from_log::<MyWarning>("/src/A.swift:1:1: warning: old\n");
+        let diagnostics = parse_log::<MyWarning>(
+            "/src/A.swift:1:1: warning: old\n\
+             /src/A.swift:2:1: warning: new\n\
+             /src/A.swift:3:1: error: broken\n",
+        );
+        let diff = diff(&diagnostics, &baseline);
+
+        let policy = Policy {
+            max_warnings: Some(2),
+            max_errors: Some(0),
+            fail_on_new: true,
+            budgets: Vec::new(),
+        };
+        assert_eq!(
+            policy.evaluate(&diagnostics, Some(&diff)).violations(),
+            &[
+                Violation::TooManyErrors { count: 1, max: 0 },
+                Violation::NewDiagnostics { count: 2 },
+            ]
+        );
+        assert_eq!(
+            policy.evaluate(&diagnostics, None).violations(),
+            &[Violation::TooManyErrors { count: 1, max: 0 }]
+        );
+        assert!(Policy::default()
+            .evaluate(&diagnostics, Some(&diff))
+            .passed());
+    }
+
+    /// Tests that each budget counts the warnings of the files matching its pattern.
+    #[test]
+    fn test_policy_evaluate_budgets() {
+        let diagnostics = parse_log::<MyWarning>(
+            "/App/Modules/Legacy/A.swift:1:1: warning: one\n\
+             /App/Modules/Legacy/B.swift:2:1: warning: two\n\
+             /App/Modules/Legacy/B.swift:3:1: error: three\n\
+             /App/Modules/Core/C.swift:4:1: warning: four\n",
+        );
+        let policy = Policy {
+            budgets: vec![
+                Budget::new("Modules/Legacy/**", 1),
+                Budget::new("Modules/Core/**", 1),
+            ],
+            ..Policy::default()
+        };
+        let verdict = policy.evaluate(&diagnostics, None);
+        assert_eq!(
+            verdict.violations(),
+            &[Violation::BudgetExceeded {
+                pattern: "Modules/Legacy/**".to_string(),
+                count: 2,
+                max: 1,
+            }]
+        );
+        assert_eq!(
+            verdict.violations()[0].to_string(),
+            "2 warnings in Modules/Legacy/** exceed the budget of 1 by 1"
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use serde_json::Value;
+
+use crate::{LogFileRef, PayloadDelimiters, TaskMessage};
+
+/// An object-safe view of a [`TaskMessage`].
+///
+/// `TaskMessage` itself cannot be used as a trait object because of its parsing
+/// functions, so tasks decoded by a [`MessageRegistry`] are returned as
+/// `Box<dyn DynTaskMessage>`.
+pub trait DynTaskMessage: Debug {
+    /// Returns the summary of the task.
+    fn task_summary(&self) -> String;
+
+    /// Returns the queue of the task.
+    fn task_queue(&self) -> String;
+
+    /// Returns the message to display after the task is created.
+    fn warning_message_after_created(&self) -> String;
+}
+
+/// Wraps a task so it can be used as a [`DynTaskMessage`].
+struct Erased<T>(T);
+
+impl<T: Debug> Debug for Erased<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: TaskMessage> DynTaskMessage for Erased<T> {
+    fn task_summary(&self) -> String {
+        self.0.task_summary()
+    }
+
+    fn task_queue(&self) -> String {
+        self.0.task_queue()
+    }
+
+    fn warning_message_after_created(&self) -> String {
+        self.0.warning_message_after_created()
+    }
+}
+
+/// Selects which registered decoder handles a payload.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Discriminator {
+    /// The value of the given field equals the registered key, such as `"type": "ticket"`.
+    Field(String),
+    /// The value of the given field starts with the registered key, such as a queue
+    /// named `API-123`. The longest matching key wins.
+    FieldPrefix(String),
+}
+
+type Decoder = fn(Value) -> Option<Box<dyn DynTaskMessage>>;
+
+/// A registry of task types, picking the type of each payload at runtime.
+///
+/// ```
+/// use xcode_log_parser::{Discriminator, MessageRegistry, MyWarning};
+///
+/// let mut registry = MessageRegistry::new(Discriminator::FieldPrefix("queue".to_string()));
+/// registry.register::<MyWarning>("API");
+///
+/// let line = r#"/src/A.swift:1:2: warning: s#{"queue": "API-7", "summary": "Retry"}#s"#;
+/// let (log_file, task) = registry.parse_line(line).unwrap();
+/// assert_eq!(log_file.absolute_path(), "/src/A.swift");
+/// assert_eq!(task.unwrap().task_summary(), "Retry");
+/// ```
+#[derive(Debug)]
+pub struct MessageRegistry {
+    discriminator: Discriminator,
+    delimiters: PayloadDelimiters,
+    decoders: HashMap<String, Decoder>,
+}
+
+impl MessageRegistry {
+    /// Creates an empty `MessageRegistry` reading payloads between the default delimiters.
+    ///
+    /// # Arguments
+    ///
+    /// * `discriminator` - Selects the decoder for each payload.
+    pub fn new(discriminator: Discriminator) -> Self {
+        Self::with_delimiters(discriminator, PayloadDelimiters::default())
+    }
+
+    /// Creates an empty `MessageRegistry` reading payloads between the given delimiters.
+    ///
+    /// # Arguments
+    ///
+    /// * `discriminator` - Selects the decoder for each payload.
+    /// * `delimiters` - The markers surrounding the JSON payload.
+    pub fn with_delimiters(discriminator: Discriminator, delimiters: PayloadDelimiters) -> Self {
+        MessageRegistry {
+            discriminator,
+            delimiters,
+            decoders: HashMap::new(),
+        }
+    }
+
+    /// Registers the task type decoding payloads matching the given key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The discriminator value, or value prefix, selecting `T`.
+    pub fn register<T: TaskMessage + 'static>(&mut self, key: impl Into<String>) -> &mut Self {
+        let decoder: Decoder = |value| {
+            let task: T = serde_json::from_value(value).ok()?;
+            Some(Box::new(Erased(task)))
+        };
+        self.decoders.insert(key.into(), decoder);
+        self
+    }
+
+    /// Decodes a JSON payload with the task type registered for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - A string slice that holds the JSON payload without its delimiters.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Box<dyn DynTaskMessage>>` - The decoded task, or `None` if no type is registered for it or decoding fails.
+    pub fn decode(&self, payload: &str) -> Option<Box<dyn DynTaskMessage>> {
+        let value: Value = serde_json::from_str(payload).ok()?;
+        let decoder = match &self.discriminator {
+            Discriminator::Field(field) => self.decoders.get(value.get(field)?.as_str()?)?,
+            Discriminator::FieldPrefix(field) => {
+                let discriminant = value.get(field)?.as_str()?;
+                self.decoders
+                    .iter()
+                    .filter(|(key, _)| discriminant.starts_with(key.as_str()))
+                    .max_by_key(|(key, _)| key.len())?
+                    .1
+            }
+        };
+        decoder(value)
+    }
+
+    /// Parses a log line, decoding its payload with the task type registered for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - A string slice that holds the log line to be parsed.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<(LogFileRef, Option<Box<dyn DynTaskMessage>>)>` - The diagnostic and its task, if any,
+    ///   or `None` if the line holds no code fragment.
+    pub fn parse_line<'a>(
+        &self,
+        line: &'a str,
+    ) -> Option<(LogFileRef<'a>, Option<Box<dyn DynTaskMessage>>)> {
+        let log_file = LogFileRef::parse(line)?;
+        let code_fragment = log_file.code_fragment()?;
+        let task = code_fragment
+            .severity()
+            .and_then(|_| self.delimiters.extract(code_fragment.text()))
+            .and_then(|payload| self.decode(payload));
+        Some((log_file, task))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MyWarning, RegexParse};
+
+    /// A second task type, told apart from `MyWarning` by its `type` field.
+    #[derive(Debug, serde::Deserialize)]
+    struct Ticket {
+        title: String,
+        board: String,
+    }
+
+    impl TaskMessage for Ticket {
+        fn task_summary(&self) -> String {
+            self.title.clone()
+        }
+
+        fn task_queue(&self) -> String {
+            self.board.clone()
+        }
+
+        fn warning_message_after_created(&self) -> String {
+            String::new()
+        }
+    }
+
+    impl RegexParse for Ticket {
+        fn regex_value() -> &'static regex::Regex {
+            MyWarning::regex_value()
+        }
+
+        fn new_from_regex(haystack: &str) -> Option<Self> {
+            PayloadDelimiters::default().deserialize(haystack)
+        }
+    }
+
+    /// Tests picking the task type from a `type` field.
+    #[test]
+    fn test_message_registry_decode_by_field() {
+        let mut registry = MessageRegistry::new(Discriminator::Field("type".to_string()));
+        registry
+            .register::<MyWarning>("warning")
+            .register::<Ticket>("ticket");
+
+        let task = registry
+            .decode(r#"{"type": "ticket", "title": "Retry", "board": "API"}"#)
+            .unwrap();
+        assert_eq!(task.task_queue(), "API");
+
+        let task = registry
+            .decode(r#"{"type": "warning", "summary": "Clean up", "queue": "CORE"}"#)
+            .unwrap();
+        assert_eq!(task.task_summary(), "Clean up");
+
+        assert!(registry
+            .decode(r#"{"type": "bug", "summary": "S"}"#)
+            .is_none());
+    }
+
+    /// Tests that the longest matching prefix wins.
+    #[test]
+    fn test_message_registry_decode_by_prefix() {
+        let mut registry = MessageRegistry::new(Discriminator::FieldPrefix("board".to_string()));
+        registry
+            .register::<MyWarning>("API")
+            .register::<Ticket>("API-V2");
+
+        let line = r#"/src/A.swift:1:2: warning: s#{"board": "API-V2-7", "title": "Retry"}#s"#;
+        let (_, task) = registry.parse_line(line).unwrap();
+        assert_eq!(task.unwrap().task_summary(), "Retry");
+
+        let (_, task) = registry
+            .parse_line("/src/A.swift:1:2: warning: unused variable")
+            .unwrap();
+        assert!(task.is_none());
+    }
+}
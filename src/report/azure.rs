@@ -0,0 +1,83 @@
+//! Conversion of parsed diagnostics into Azure Pipelines logging commands.
+
+use std::fmt::Write;
+
+use crate::{LogFile, Severity, TaskMessage};
+
+/// Converts parsed diagnostics into Azure Pipelines `##vso[task.logissue]` commands.
+///
+/// Each warning or error becomes one command which Azure Pipelines lists in the Issues
+/// pane of the run. Azure Pipelines has no issue type for notes and remarks, so other
+/// diagnostics are left out. The lint rule or warning flag of a diagnostic, if any, is
+/// passed as its `code`.
+///
+/// # Arguments
+///
+/// * `diagnostics` - The parsed diagnostics to convert.
+///
+/// # Returns
+///
+/// * `String` - The logging commands, one per line.
+pub fn to_azure_logging_commands<T: TaskMessage>(diagnostics: &[LogFile<T>]) -> String {
+    let mut output = String::new();
+    for log_file in diagnostics {
+        let Some(code_fragment) = log_file.code_fragment() else {
+            continue;
+        };
+        let issue_type = match code_fragment.severity() {
+            Some(Severity::Error) => "error",
+            Some(Severity::Warning) => "warning",
+            _ => continue,
+        };
+        let text = match code_fragment.task_info() {
+            Some(message) => message.task().task_summary(),
+            None => code_fragment.text().to_string(),
+        };
+        let _ = write!(
+            output,
+            "##vso[task.logissue type={};sourcepath={};linenumber={};columnnumber={}",
+            issue_type,
+            escape_property(log_file.absolute_path()),
+            code_fragment.line(),
+            code_fragment.column()
+        );
+        if let Some(code) = code_fragment.rule().or(code_fragment.flag()) {
+            let _ = write!(output, ";code={}", escape_property(code));
+        }
+        let _ = writeln!(output, "]{}", escape_data(&text));
+    }
+    output
+}
+
+/// Escapes the message part of a logging command.
+fn escape_data(value: &str) -> String {
+    value
+        .replace('%', "%AZP25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes a property value of a logging command.
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(';', "%3B").replace(']', "%5D")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_log, MyWarning};
+
+    /// Tests the command emitted for warnings and errors, leaving out other diagnostics.
+    #[test]
+    fn test_to_azure_logging_commands() {
+        let log = "/src/A;B.swift:3:7: warning: 100% unused\n\
+                   /src/B.m:9:1: error: 'f' is deprecated [-Wdeprecated-declarations]\n\
+                   /src/C.swift:10:2: remark: remark text\n";
+        let output = to_azure_logging_commands(&parse_log::<MyWarning>(log));
+        assert_eq!(
+            output,
+            "##vso[task.logissue type=warning;sourcepath=/src/A%3BB.swift;linenumber=3;columnnumber=7]100%AZP25 unused\n\
+             ##vso[task.logissue type=error;sourcepath=/src/B.m;linenumber=9;columnnumber=1;code=-Wdeprecated-declarations]'f' is deprecated [-Wdeprecated-declarations]\n"
+        );
+    }
+}
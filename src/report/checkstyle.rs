@@ -0,0 +1,108 @@
+//! Conversion of parsed diagnostics into Checkstyle XML reports.
+
+use std::fmt::Write;
+
+use super::escape_xml;
+use crate::{LogFile, Severity, TaskMessage};
+
+/// The Checkstyle format version declared by the reports.
+pub const CHECKSTYLE_VERSION: &str = "4.3";
+
+/// Converts parsed diagnostics into a Checkstyle XML report, read by CI plugins such as
+/// Jenkins Warnings NG and Danger.
+///
+/// Diagnostics are grouped in one `file` element per path, in the order the paths first
+/// appear in the log. Each becomes an `error` element holding its line, column, severity
+/// and message, and its lint rule or warning flag as the `source`, if any.
+///
+/// # Arguments
+///
+/// * `diagnostics` - The parsed diagnostics to convert.
+///
+/// # Returns
+///
+/// * `String` - The Checkstyle XML document.
+pub fn to_checkstyle<T: TaskMessage>(diagnostics: &[LogFile<T>]) -> String {
+    let mut files: Vec<(&str, Vec<&LogFile<T>>)> = Vec::new();
+    for log_file in diagnostics {
+        if log_file.code_fragment().is_none() {
+            continue;
+        }
+        match files
+            .iter_mut()
+            .find(|(path, _)| *path == log_file.absolute_path())
+        {
+            Some((_, log_files)) => log_files.push(log_file),
+            None => files.push((log_file.absolute_path(), vec![log_file])),
+        }
+    }
+
+    let mut output = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<checkstyle version=\"{}\">\n",
+        CHECKSTYLE_VERSION
+    );
+    for (path, log_files) in files {
+        let _ = writeln!(output, "  <file name=\"{}\">", escape_xml(path));
+        for code_fragment in log_files
+            .iter()
+            .filter_map(|log_file| log_file.code_fragment())
+        {
+            let message = match code_fragment.task_info() {
+                Some(message) => message.task().task_summary(),
+                None => code_fragment.text().to_string(),
+            };
+            let _ = write!(
+                output,
+                "    <error line=\"{}\" column=\"{}\" severity=\"{}\" message=\"{}\"",
+                code_fragment.line(),
+                code_fragment.column(),
+                severity(code_fragment.severity()),
+                escape_xml(&message)
+            );
+            if let Some(source) = code_fragment.rule().or(code_fragment.flag()) {
+                let _ = write!(output, " source=\"{}\"", escape_xml(source));
+            }
+            output.push_str("/>\n");
+        }
+        output.push_str("  </file>\n");
+    }
+    output.push_str("</checkstyle>\n");
+    output
+}
+
+/// Maps a severity to a Checkstyle severity.
+fn severity(severity: Option<Severity>) -> &'static str {
+    match severity {
+        Some(Severity::Error) => "error",
+        Some(Severity::Warning) => "warning",
+        _ => "info",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_log, MyWarning};
+
+    /// Tests grouping diagnostics by file and escaping their messages.
+    #[test]
+    fn test_to_checkstyle() {
+        let log = "/src/A.swift:3:7: warning: unused variable 'x'\n\
+                   /src/B.m:9:1: error: 'f' is deprecated [-Wdeprecated-declarations]\n\
+                   /src/A.swift:10:2: remark: a < b\n";
+        let output = to_checkstyle(&parse_log::<MyWarning>(log));
+        assert_eq!(
+            output,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <checkstyle version=\"4.3\">\n  \
+             <file name=\"/src/A.swift\">\n    \
+             <error line=\"3\" column=\"7\" severity=\"warning\" message=\"unused variable &apos;x&apos;\"/>\n    \
+             <error line=\"10\" column=\"2\" severity=\"info\" message=\"a &lt; b\"/>\n  \
+             </file>\n  \
+             <file name=\"/src/B.m\">\n    \
+             <error line=\"9\" column=\"1\" severity=\"error\" message=\"&apos;f&apos; is deprecated [-Wdeprecated-declarations]\" source=\"-Wdeprecated-declarations\"/>\n  \
+             </file>\n\
+             </checkstyle>\n"
+        );
+    }
+}
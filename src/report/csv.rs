@@ -0,0 +1,79 @@
+//! Conversion of parsed diagnostics into CSV for spreadsheets and BI tools.
+
+use std::fmt::Write;
+
+use crate::{LogFile, TaskMessage};
+
+/// The header row of the CSV export.
+pub const CSV_HEADER: &str = "path,line,column,severity,message,queue,summary";
+
+/// Converts parsed diagnostics into CSV, one row per diagnostic.
+///
+/// The columns are listed in [`CSV_HEADER`]. The severity is empty for diagnostics
+/// without one, and the queue and summary are empty for diagnostics without a task
+/// payload. Fields are quoted as described in RFC 4180 when needed.
+///
+/// # Arguments
+///
+/// * `diagnostics` - The parsed diagnostics to convert.
+///
+/// # Returns
+///
+/// * `String` - The CSV document, starting with the header row.
+pub fn to_csv<T: TaskMessage>(diagnostics: &[LogFile<T>]) -> String {
+    let mut output = format!("{}\n", CSV_HEADER);
+    for log_file in diagnostics {
+        let Some(code_fragment) = log_file.code_fragment() else {
+            continue;
+        };
+        let (queue, summary) = match code_fragment.task_info() {
+            Some(message) => (message.task().task_queue(), message.task().task_summary()),
+            None => (String::new(), String::new()),
+        };
+        let _ = writeln!(
+            output,
+            "{},{},{},{},{},{},{}",
+            escape_field(log_file.absolute_path()),
+            code_fragment.line(),
+            code_fragment.column(),
+            code_fragment
+                .severity()
+                .map_or("", |severity| severity.name()),
+            escape_field(code_fragment.text()),
+            escape_field(&queue),
+            escape_field(&summary)
+        );
+    }
+    output
+}
+
+/// Quotes a field holding a delimiter, a quote or a line break, doubling its quotes.
+fn escape_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_log, MyWarning};
+
+    /// Tests the rows of plain diagnostics and task payloads, with quoted fields.
+    #[test]
+    fn test_to_csv() {
+        let log = "/src/A,B.swift:3:7: warning: cannot find \"foo\"\n\
+                   /src/C.swift:9:1: warning: s#{\"queue\": \"API\", \"summary\": \"Retry\"}#s\n";
+        let csv = to_csv(&parse_log::<MyWarning>(log));
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows[0], CSV_HEADER);
+        assert_eq!(
+            rows[1],
+            "\"/src/A,B.swift\",3,7,warning,\"cannot find \"\"foo\"\"\",,"
+        );
+        assert!(rows[2].starts_with("/src/C.swift,9,1,warning,"));
+        assert!(rows[2].ends_with(",API,Retry"));
+    }
+}
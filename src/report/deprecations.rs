@@ -0,0 +1,181 @@
+//! Grouping of deprecation warnings by deprecated API, to plan migrations.
+
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::{Deprecation, LogFile, TaskMessage};
+
+/// Represents a deprecated API and the places it is used in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeprecatedApi {
+    #[serde(flatten)]
+    deprecation: Deprecation,
+    /// The path and line of each use.
+    locations: Vec<(String, usize)>,
+}
+
+impl DeprecatedApi {
+    /// Returns the deprecated symbol, its replacement and the OS version it was deprecated in.
+    pub fn deprecation(&self) -> &Deprecation {
+        &self.deprecation
+    }
+
+    /// Returns the path and line of each use, in the order they were reported.
+    pub fn locations(&self) -> &[(String, usize)] {
+        &self.locations
+    }
+}
+
+/// Groups the deprecation warnings of parsed diagnostics by deprecated symbol.
+///
+/// The same symbol may be reported with different messages, such as by the Swift
+/// compiler and by clang. The replacement and OS version of the first message naming
+/// them are kept.
+///
+/// # Arguments
+///
+/// * `diagnostics` - The diagnostics to group.
+///
+/// # Returns
+///
+/// * `Vec<DeprecatedApi>` - The deprecated APIs, the most used first. Ties are ordered by symbol.
+pub fn deprecated_apis<'a, T: TaskMessage + 'a>(
+    diagnostics: impl IntoIterator<Item = &'a LogFile<T>>,
+) -> Vec<DeprecatedApi> {
+    let mut apis: Vec<DeprecatedApi> = Vec::new();
+    for log_file in diagnostics {
+        let Some(code_fragment) = log_file.code_fragment() else {
+            continue;
+        };
+        let Some(deprecation) = code_fragment.deprecation() else {
+            continue;
+        };
+        let location = (log_file.absolute_path().to_string(), code_fragment.line());
+        match apis
+            .iter_mut()
+            .find(|api| api.deprecation.symbol() == deprecation.symbol())
+        {
+            Some(api) => {
+                api.deprecation.merge(deprecation);
+                if !api.locations.contains(&location) {
+                    api.locations.push(location);
+                }
+            }
+            None => apis.push(DeprecatedApi {
+                deprecation,
+                locations: vec![location],
+            }),
+        }
+    }
+    apis.sort_by(|a, b| {
+        b.locations
+            .len()
+            .cmp(&a.locations.len())
+            .then_with(|| a.deprecation.symbol().cmp(b.deprecation.symbol()))
+    });
+    apis
+}
+
+/// Converts the deprecation warnings of parsed diagnostics into a Markdown report.
+///
+/// The report starts with the number of deprecated APIs and of their uses, followed by a
+/// table of each API, the OS version it was deprecated in, its replacement and its number of uses.
+///
+/// ```
+/// use xcode_log_parser::report::deprecations::to_deprecation_report;
+/// use xcode_log_parser::{parse_log, MyWarning};
+///
+/// let diagnostics =
+///     parse_log::<MyWarning>("/src/A.swift:1:2: warning: 'foo()' is deprecated: use 'bar()' instead\n");
+/// let report = to_deprecation_report(&diagnostics);
+/// assert!(report.starts_with("**1 deprecated API, 1 use**"));
+/// ```
+///
+/// # Arguments
+///
+/// * `diagnostics` - The diagnostics to report.
+///
+/// # Returns
+///
+/// * `String` - The Markdown report.
+pub fn to_deprecation_report<'a, T: TaskMessage + 'a>(
+    diagnostics: impl IntoIterator<Item = &'a LogFile<T>>,
+) -> String {
+    let apis = deprecated_apis(diagnostics);
+    let uses: usize = apis.iter().map(|api| api.locations.len()).sum();
+    let mut output = format!(
+        "**{} deprecated API{}, {} use{}**\n",
+        apis.len(),
+        plural(apis.len()),
+        uses,
+        plural(uses)
+    );
+    if apis.is_empty() {
+        return output;
+    }
+    output.push_str("\n| API | Deprecated in | Replacement | Uses |\n| --- | --- | --- | --- |\n");
+    for api in &apis {
+        let deprecation = &api.deprecation;
+        let deprecated_in = match (deprecation.platform(), deprecation.version()) {
+            (Some(platform), Some(version)) => format!("{} {}", platform, version),
+            _ => "-".to_string(),
+        };
+        let replacement = deprecation.replacement().map_or_else(
+            || "-".to_string(),
+            |replacement| format!("`{}`", replacement),
+        );
+        let _ = writeln!(
+            output,
+            "| `{}` | {} | {} | {} |",
+            deprecation.symbol().replace('|', "\\|"),
+            deprecated_in,
+            replacement.replace('|', "\\|"),
+            api.locations.len()
+        );
+    }
+    output
+}
+
+/// Returns the plural suffix for a count.
+fn plural(count: usize) -> &'static str {
+    if count == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_log, MyWarning};
+
+    /// Tests grouping the uses of each deprecated API, the most used first.
+    #[test]
+    fn test_to_deprecation_report() {
+        let log = "/src/A.swift:3:7: warning: 'keyWindow' was deprecated in iOS 13.0: Should not be used for applications that support multiple scenes\n\
+                   /src/A.m:9:1: warning: 'sizeWithFont:' is deprecated: first deprecated in iOS 7.0 - Use -sizeWithAttributes: [-Wdeprecated-declarations]\n\
+                   /src/B.swift:4:2: warning: 'keyWindow' is deprecated: use 'connectedScenes' instead\n\
+                   /src/B.swift:5:2: warning: unused variable 'x'\n";
+        let diagnostics = parse_log::<MyWarning>(log);
+
+        let apis = deprecated_apis(&diagnostics);
+        assert_eq!(
+            apis[0].locations(),
+            &[
+                ("/src/A.swift".to_string(), 3),
+                ("/src/B.swift".to_string(), 4)
+            ]
+        );
+        assert_eq!(
+            to_deprecation_report(&diagnostics),
+            "**2 deprecated APIs, 3 uses**\n\
+             \n\
+             | API | Deprecated in | Replacement | Uses |\n\
+             | --- | --- | --- | --- |\n\
+             | `keyWindow` | iOS 13.0 | `connectedScenes` | 2 |\n\
+             | `sizeWithFont:` | iOS 7.0 | `-sizeWithAttributes:` | 1 |\n"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! Conversion of parsed diagnostics into GitHub Actions workflow commands.
+
+use crate::{LogFile, Severity, TaskMessage};
+
+/// Converts parsed diagnostics into GitHub Actions annotation commands.
+///
+/// Each diagnostic becomes one `::error`, `::warning` or `::notice` line which GitHub
+/// renders inline on the pull request diff when printed by a workflow step. The notes
+/// attached to a diagnostic follow it as `::notice` lines.
+///
+/// # Arguments
+///
+/// * `diagnostics` - The parsed diagnostics to convert.
+///
+/// # Returns
+///
+/// * `String` - The workflow commands, one per line.
+pub fn to_github_annotations<T: TaskMessage>(diagnostics: &[LogFile<T>]) -> String {
+    let mut output = String::new();
+    for log_file in diagnostics {
+        let Some(code_fragment) = log_file.code_fragment() else {
+            continue;
+        };
+        let command = match code_fragment.severity() {
+            Some(Severity::Error) => "error",
+            Some(Severity::Warning) => "warning",
+            _ => "notice",
+        };
+        let text = match code_fragment.task_info() {
+            Some(message) => message.task().task_summary(),
+            None => code_fragment.text().to_string(),
+        };
+        output.push_str(&format!(
+            "::{} file={},line={},col={}::{}\n",
+            command,
+            escape_property(log_file.absolute_path()),
+            code_fragment.line(),
+            code_fragment.column(),
+            escape_data(&text)
+        ));
+        for note in code_fragment.notes() {
+            output.push_str(&format!(
+                "::notice file={},line={},col={}::{}\n",
+                escape_property(note.absolute_path()),
+                note.line(),
+                note.column(),
+                escape_data(note.text())
+            ));
+        }
+    }
+    output
+}
+
+/// Escapes the message part of a workflow command.
+fn escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes a property value of a workflow command.
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_log, MyWarning};
+
+    /// Tests the command emitted for each severity.
+    #[test]
+    fn test_to_github_annotations() {
+        let log = "/src/A.swift:3:7: warning: 100% unused\n\
+                   /src/B,C.swift:9:1: error: s#{\"queue\": \"Q\", \"summary\": \"Fix it\"}#s\n\
+                   /src/B.swift:10:2: note: see here\n";
+        let output = to_github_annotations(&parse_log::<MyWarning>(log));
+        assert_eq!(
+            output,
+            "::warning file=/src/A.swift,line=3,col=7::100%25 unused\n\
+             ::error file=/src/B%2CC.swift,line=9,col=1::Fix it\n\
+             ::notice file=/src/B.swift,line=10,col=2::see here\n"
+        );
+    }
+}
@@ -0,0 +1,122 @@
+//! Conversion of parsed diagnostics into the Code Quality report of GitLab merge requests.
+
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+
+use crate::{Fingerprint, LogFile, Severity, TaskMessage};
+
+/// Converts parsed diagnostics into a GitLab Code Quality report, the subset of the
+/// Code Climate issue format GitLab reads from a `codequality` artifact.
+///
+/// Each diagnostic becomes an issue whose `check_name` is its lint rule, warning flag
+/// or severity keyword. Its `fingerprint` is derived from the path, severity and text,
+/// like [`Fingerprint`], so a warning moved by an edit above it is not reported as new.
+/// Repeated diagnostics are told apart by the order they appear in.
+///
+/// # Arguments
+///
+/// * `diagnostics` - The parsed diagnostics to convert.
+///
+/// # Returns
+///
+/// * `Value` - The report as a JSON array of issues.
+pub fn to_code_quality<T: TaskMessage>(diagnostics: &[LogFile<T>]) -> Value {
+    let mut occurrences: HashMap<(&str, Option<Severity>, &str), usize> = HashMap::new();
+    let mut issues = Vec::new();
+    for log_file in diagnostics {
+        let (Some(code_fragment), Some(fingerprint)) =
+            (log_file.code_fragment(), Fingerprint::of(log_file))
+        else {
+            continue;
+        };
+        let description = match code_fragment.task_info() {
+            Some(message) => message.task().task_summary(),
+            None => code_fragment.text().to_string(),
+        };
+        let check_name = code_fragment
+            .rule()
+            .or(code_fragment.flag())
+            .unwrap_or_else(|| code_fragment.severity().map_or("diagnostic", |s| s.name()));
+        let occurrence = occurrences
+            .entry((
+                log_file.absolute_path(),
+                code_fragment.severity(),
+                code_fragment.text(),
+            ))
+            .or_default();
+        *occurrence += 1;
+
+        issues.push(json!({
+            "description": description,
+            "check_name": check_name,
+            "fingerprint": fingerprint_hash(&fingerprint, *occurrence),
+            "severity": severity(code_fragment.severity()),
+            "location": {
+                "path": log_file.absolute_path(),
+                "lines": { "begin": code_fragment.line() },
+            },
+        }));
+    }
+    Value::Array(issues)
+}
+
+/// Maps a severity to a Code Climate severity.
+fn severity(severity: Option<Severity>) -> &'static str {
+    match severity {
+        Some(Severity::Error) => "major",
+        Some(Severity::Warning) => "minor",
+        _ => "info",
+    }
+}
+
+/// Returns a stable hexadecimal hash of a fingerprint and its occurrence, using 64-bit FNV-1a.
+fn fingerprint_hash(fingerprint: &Fingerprint, occurrence: usize) -> String {
+    let severity = fingerprint
+        .severity()
+        .map_or("", |severity| severity.name());
+    let occurrence = occurrence.to_string();
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for part in [
+        fingerprint.path(),
+        severity,
+        fingerprint.text(),
+        &occurrence,
+    ] {
+        for byte in part.bytes().chain([0]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_log, MyWarning};
+
+    /// Tests converting diagnostics into Code Quality issues with line-independent fingerprints.
+    #[test]
+    fn test_to_code_quality() {
+        let log = "/src/A.swift:3:7: warning: unused variable 'x'\n\
+                   /src/A.swift:9:1: warning: unused variable 'x'\n\
+                   /src/B.m:9:1: error: 'f' is deprecated [-Wdeprecated-declarations]\n";
+        let report = to_code_quality(&parse_log::<MyWarning>(log));
+        let issues = report.as_array().unwrap();
+        assert_eq!(issues.len(), 3);
+        assert_eq!(issues[0]["description"], "unused variable 'x'");
+        assert_eq!(issues[0]["check_name"], "warning");
+        assert_eq!(issues[0]["severity"], "minor");
+        assert_eq!(issues[0]["location"]["path"], "/src/A.swift");
+        assert_eq!(issues[0]["location"]["lines"]["begin"], 3);
+        assert_eq!(issues[2]["check_name"], "-Wdeprecated-declarations");
+        assert_eq!(issues[2]["severity"], "major");
+        assert_ne!(issues[0]["fingerprint"], issues[1]["fingerprint"]);
+
+        let moved = "/src/A.swift:4:7: warning: unused variable 'x'\n";
+        let moved = to_code_quality(&parse_log::<MyWarning>(moved));
+        assert_eq!(moved[0]["fingerprint"], issues[0]["fingerprint"]);
+        assert_eq!(issues[0]["fingerprint"].as_str().unwrap().len(), 16);
+    }
+}
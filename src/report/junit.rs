@@ -0,0 +1,105 @@
+//! Conversion of parsed diagnostics into JUnit XML reports.
+
+use std::fmt::Write;
+
+use super::escape_xml;
+use crate::{LogFile, Severity, TaskMessage};
+
+/// Converts parsed diagnostics into a JUnit XML report.
+///
+/// Every diagnostic becomes a test case named after its location, grouped in a single
+/// test suite. Errors are reported as failures; other diagnostics are passing test
+/// cases whose text is kept in `system-out`.
+///
+/// # Arguments
+///
+/// * `diagnostics` - The parsed diagnostics to convert.
+///
+/// # Returns
+///
+/// * `String` - The JUnit XML document.
+pub fn to_junit<T: TaskMessage>(diagnostics: &[LogFile<T>]) -> String {
+    let mut test_cases = String::new();
+    let mut tests = 0;
+    let mut failures = 0;
+
+    for log_file in diagnostics {
+        let Some(code_fragment) = log_file.code_fragment() else {
+            continue;
+        };
+        tests += 1;
+
+        let path = escape_xml(log_file.absolute_path());
+        let severity = code_fragment
+            .severity()
+            .map_or("diagnostic", |severity| severity.name());
+        let text = match code_fragment.task_info() {
+            Some(message) => escape_xml(&message.task().task_summary()),
+            None => escape_xml(code_fragment.text()),
+        };
+        let _ = write!(
+            test_cases,
+            "    <testcase classname=\"{}\" name=\"{}:{}:{}\">",
+            path,
+            path,
+            code_fragment.line(),
+            code_fragment.column()
+        );
+        if code_fragment.severity() == Some(Severity::Error) {
+            failures += 1;
+            let _ = write!(
+                test_cases,
+                "<failure type=\"{}\" message=\"{}\">{}</failure>",
+                severity, text, text
+            );
+        } else {
+            let _ = write!(
+                test_cases,
+                "<system-out>{}: {}</system-out>",
+                severity, text
+            );
+        }
+        test_cases.push_str("</testcase>\n");
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <testsuites tests=\"{tests}\" failures=\"{failures}\">\n  \
+         <testsuite name=\"{name}\" tests=\"{tests}\" failures=\"{failures}\" errors=\"0\">\n\
+         {test_cases}  </testsuite>\n\
+         </testsuites>\n",
+        name = env!("CARGO_PKG_NAME"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_log, MyWarning};
+
+    /// Tests that errors become failures and other diagnostics pass.
+    #[test]
+    fn test_to_junit_failures_for_errors() {
+        let log = "/src/A.swift:3:7: warning: 'x' < 'y'\n\
+                   /src/B.swift:9:1: error: cannot find \"foo\"\n";
+        let xml = to_junit(&parse_log::<MyWarning>(log));
+
+        assert!(xml.contains("<testsuites tests=\"2\" failures=\"1\">"));
+        assert!(xml.contains(
+            "<testcase classname=\"/src/A.swift\" name=\"/src/A.swift:3:7\">\
+             <system-out>warning: &apos;x&apos; &lt; &apos;y&apos;</system-out></testcase>"
+        ));
+        assert!(xml.contains(
+            "<failure type=\"error\" message=\"cannot find &quot;foo&quot;\">\
+             cannot find &quot;foo&quot;</failure>"
+        ));
+    }
+
+    /// Tests the report produced for a log without diagnostics.
+    #[test]
+    fn test_to_junit_empty() {
+        let xml = to_junit::<MyWarning>(&[]);
+        assert!(xml.contains("tests=\"0\" failures=\"0\""));
+        assert!(xml.ends_with("</testsuites>\n"));
+    }
+}
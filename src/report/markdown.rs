@@ -0,0 +1,147 @@
+//! Conversion of parsed diagnostics into Markdown tables for pull request comments.
+
+use std::fmt::Write;
+
+use crate::{LogFile, Severity, TaskMessage};
+
+/// The number of rows shown before the rest of the table is collapsed.
+pub const VISIBLE_ROWS: usize = 10;
+
+/// Converts the warnings and errors of parsed diagnostics into a Markdown report.
+///
+/// The report starts with the number of errors and warnings, followed by a table of
+/// their path, line and message. Past [`VISIBLE_ROWS`] rows, the rest of the table is
+/// put in a collapsed `<details>` section, so long lists keep the comment short.
+///
+/// ```
+/// use xcode_log_parser::report::markdown::to_markdown;
+/// use xcode_log_parser::{diff, parse_log, Baseline, MyWarning};
+///
+/// let baseline = Baseline::from_log::<MyWarning>("");
+/// let diagnostics = parse_log::<MyWarning>("/src/A.swift:1:2: warning: unused\n");
+/// let report = to_markdown(diff(&diagnostics, &baseline).added().iter().copied());
+/// assert!(report.starts_with("**0 errors, 1 warning**"));
+/// ```
+///
+/// # Arguments
+///
+/// * `diagnostics` - The diagnostics to report, such as the ones missing from a baseline.
+///
+/// # Returns
+///
+/// * `String` - The Markdown report.
+pub fn to_markdown<'a, T: TaskMessage + 'a>(
+    diagnostics: impl IntoIterator<Item = &'a LogFile<T>>,
+) -> String {
+    let mut rows = Vec::new();
+    let (mut errors, mut warnings) = (0, 0);
+    for log_file in diagnostics {
+        let Some(code_fragment) = log_file.code_fragment() else {
+            continue;
+        };
+        let severity = match code_fragment.severity() {
+            Some(Severity::Error) => {
+                errors += 1;
+                ":x: error"
+            }
+            Some(Severity::Warning) => {
+                warnings += 1;
+                ":warning: warning"
+            }
+            _ => continue,
+        };
+        let text = match code_fragment.task_info() {
+            Some(message) => message.task().task_summary(),
+            None => code_fragment.text().to_string(),
+        };
+        rows.push(format!(
+            "| {} | `{}` | {} | {} |\n",
+            severity,
+            escape_cell(log_file.absolute_path()),
+            code_fragment.line(),
+            escape_cell(&text)
+        ));
+    }
+
+    let mut output = format!(
+        "**{} error{}, {} warning{}**\n",
+        errors,
+        plural(errors),
+        warnings,
+        plural(warnings)
+    );
+    if rows.is_empty() {
+        return output;
+    }
+    let header = "\n| Severity | File | Line | Message |\n| --- | --- | --- | --- |\n";
+    output.push_str(header);
+    rows.iter()
+        .take(VISIBLE_ROWS)
+        .for_each(|row| output.push_str(row));
+    if rows.len() > VISIBLE_ROWS {
+        let hidden = &rows[VISIBLE_ROWS..];
+        let _ = write!(
+            output,
+            "\n<details>\n<summary>{} more</summary>\n{}",
+            hidden.len(),
+            header
+        );
+        hidden.iter().for_each(|row| output.push_str(row));
+        output.push_str("\n</details>\n");
+    }
+    output
+}
+
+/// Returns the plural suffix for a count.
+fn plural(count: usize) -> &'static str {
+    if count == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+/// Escapes a value for use in a table cell, which cannot hold pipes or line breaks.
+fn escape_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_log, MyWarning};
+
+    /// Tests the table rows, skipping remarks and escaping pipes.
+    #[test]
+    fn test_to_markdown() {
+        let log = "/src/A.swift:3:7: warning: a | b\n\
+                   /src/B.swift:9:1: error: s#{\"queue\": \"Q\", \"summary\": \"Fix it\"}#s\n\
+                   /src/B.swift:10:2: remark: see here\n";
+        let report = to_markdown(&parse_log::<MyWarning>(log));
+        assert_eq!(
+            report,
+            "**1 error, 1 warning**\n\
+             \n\
+             | Severity | File | Line | Message |\n\
+             | --- | --- | --- | --- |\n\
+             | :warning: warning | `/src/A.swift` | 3 | a \\| b |\n\
+             | :x: error | `/src/B.swift` | 9 | Fix it |\n"
+        );
+    }
+
+    /// Tests that rows past the visible ones are collapsed.
+    #[test]
+    fn test_to_markdown_collapses_long_lists() {
+        let log: String = (1..=12)
+            .map(|line| format!("/src/A.swift:{}:1: warning: unused\n", line))
+            .collect();
+        let report = to_markdown(&parse_log::<MyWarning>(&log));
+        let (visible, hidden) = report.split_once("<details>").unwrap();
+        assert_eq!(
+            visible.matches("| :warning: warning |").count(),
+            VISIBLE_ROWS
+        );
+        assert!(hidden.starts_with("\n<summary>2 more</summary>\n"));
+        assert!(hidden.ends_with("| 12 | unused |\n\n</details>\n"));
+    }
+}
@@ -0,0 +1,46 @@
+//! Report formats for parsed diagnostics.
+
+pub mod azure;
+pub mod checkstyle;
+pub mod csv;
+pub mod deprecations;
+pub mod github;
+pub mod gitlab;
+pub mod junit;
+pub mod markdown;
+pub mod teamcity;
+
+/// Escapes a string for use in XML text and attribute values.
+///
+/// Characters that are not allowed in XML 1.0 documents, such as the ANSI escape
+/// sequences some tools print, are dropped.
+pub(crate) fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for character in value.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(character),
+            character if character < ' ' => {}
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests escaping markup and dropping invalid control characters.
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(
+            escape_xml("<a href=\"x\">'&'</a>\u{1b}[0m"),
+            "&lt;a href=&quot;x&quot;&gt;&apos;&amp;&apos;&lt;/a&gt;[0m"
+        );
+    }
+}
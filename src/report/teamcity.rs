@@ -0,0 +1,87 @@
+//! Conversion of parsed diagnostics into TeamCity service messages.
+
+use std::fmt::Write;
+
+use crate::{LogFile, Severity, TaskMessage};
+
+/// Converts parsed diagnostics into TeamCity service messages.
+///
+/// Errors become `##teamcity[buildProblem ...]` messages, which fail the build and are
+/// listed on its overview page. Warnings become `##teamcity[message ... status='WARNING']`
+/// messages, and other diagnostics messages with the `NORMAL` status. Each message reads
+/// `path:line:column: text`.
+///
+/// # Arguments
+///
+/// * `diagnostics` - The parsed diagnostics to convert.
+///
+/// # Returns
+///
+/// * `String` - The service messages, one per line.
+pub fn to_teamcity_messages<T: TaskMessage>(diagnostics: &[LogFile<T>]) -> String {
+    let mut output = String::new();
+    for log_file in diagnostics {
+        let Some(code_fragment) = log_file.code_fragment() else {
+            continue;
+        };
+        let text = match code_fragment.task_info() {
+            Some(message) => message.task().task_summary(),
+            None => code_fragment.text().to_string(),
+        };
+        let text = escape_value(&format!(
+            "{}:{}:{}: {}",
+            log_file.absolute_path(),
+            code_fragment.line(),
+            code_fragment.column(),
+            text
+        ));
+        let message = match code_fragment.severity() {
+            Some(Severity::Error) => format!("buildProblem description='{}'", text),
+            Some(Severity::Warning) => format!("message text='{}' status='WARNING'", text),
+            _ => format!("message text='{}' status='NORMAL'", text),
+        };
+        let _ = writeln!(output, "##teamcity[{}]", message);
+    }
+    output
+}
+
+/// Escapes an attribute value of a service message.
+fn escape_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for character in value.chars() {
+        match character {
+            '|' => escaped.push_str("||"),
+            '\'' => escaped.push_str("|'"),
+            '\n' => escaped.push_str("|n"),
+            '\r' => escaped.push_str("|r"),
+            '[' => escaped.push_str("|["),
+            ']' => escaped.push_str("|]"),
+            '\u{0085}' => escaped.push_str("|x"),
+            '\u{2028}' => escaped.push_str("|l"),
+            '\u{2029}' => escaped.push_str("|p"),
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_log, MyWarning};
+
+    /// Tests the message emitted for each severity and the escaping of their text.
+    #[test]
+    fn test_to_teamcity_messages() {
+        let log = "/src/A.swift:3:7: warning: unused variable 'x'\n\
+                   /src/B.m:9:1: error: 'f' is deprecated [-Wdeprecated-declarations]\n\
+                   /src/C.swift:10:2: remark: a | b\n";
+        let output = to_teamcity_messages(&parse_log::<MyWarning>(log));
+        assert_eq!(
+            output,
+            "##teamcity[message text='/src/A.swift:3:7: unused variable |'x|'' status='WARNING']\n\
+             ##teamcity[buildProblem description='/src/B.m:9:1: |'f|' is deprecated |[-Wdeprecated-declarations|]']\n\
+             ##teamcity[message text='/src/C.swift:10:2: a || b' status='NORMAL']\n"
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::{Category, CodeFragment, LogFile, RegexParse, Severity, TaskMessage};
+
+/// Matches the first quoted name of an asset catalog message, such as `"AppIcon"` in
+/// `The app icon set "AppIcon" has an unassigned child.`.
+static QUOTED_NAME_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#""([^"]+)""#).unwrap());
+
+/// Represents the tool that compiled a resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceTool {
+    /// `actool`, compiling `.xcassets` asset catalogs.
+    AssetCatalog,
+    /// `ibtool`, compiling `.storyboard` and `.xib` Interface Builder documents.
+    InterfaceBuilder,
+}
+
+/// Represents a warning or error of `actool` or `ibtool`, which point at a member of an asset
+/// catalog or at an object of an Interface Builder document instead of a line and column.
+///
+/// ```text
+/// /src/Assets.xcassets:./AppIcon.appiconset/[][ipad][76x76][][][1x][][][]: warning: A 76x76@1x app icon is required for iPad apps
+/// /src/Main.storyboard:BYZ-38-t0r: warning: Frame for "Label" will be different at run time.
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResourceIssue {
+    tool: ResourceTool,
+    absolute_path: String,
+    identifier: Option<String>,
+    severity: Severity,
+    text: String,
+}
+
+impl ResourceIssue {
+    /// Returns the tool that reported the issue.
+    pub fn tool(&self) -> ResourceTool {
+        self.tool
+    }
+
+    /// Returns the path of the asset catalog or Interface Builder document.
+    pub fn absolute_path(&self) -> &str {
+        &self.absolute_path
+    }
+
+    /// Returns the asset catalog member, such as `AppIcon.appiconset/[][ipad][76x76][][][1x][][][]`
+    /// or the name quoted by the message, or the object ID of the Interface Builder document,
+    /// such as `BYZ-38-t0r`, if any.
+    pub fn identifier(&self) -> Option<&str> {
+        self.identifier.as_deref()
+    }
+
+    /// Returns the severity of the issue.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Returns the text of the issue without the severity keyword.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Converts the issue into a diagnostic at line 0 of the resource, tagged with the
+    /// [`Category`] of its tool.
+    pub fn to_log_file<T: TaskMessage>(&self) -> LogFile<T> {
+        let mut code_fragment = CodeFragment::new(0, 0, Some(self.severity), self.text.clone());
+        let category = match self.tool {
+            ResourceTool::AssetCatalog => Category::AssetCatalog,
+            ResourceTool::InterfaceBuilder => Category::InterfaceBuilder,
+        };
+        code_fragment.categorize(category, None);
+        LogFile::new(self.absolute_path.clone(), Some(code_fragment))
+    }
+}
+
+impl RegexParse for ResourceIssue {
+    /// Returns the regular expression used to parse an `actool` or `ibtool` issue.
+    fn regex_value() -> &'static Regex {
+        static REGEX: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(
+                r#"^(?P<path>.+?\.(?P<extension>xcassets|storyboard|xib))(?::(?:\./)?(?P<identifier>[^:]+?))?: (?P<severity>warning|error|note): (?P<text>.+)$"#,
+            )
+            .unwrap()
+        });
+        &REGEX
+    }
+
+    /// Creates a new `ResourceIssue` from the given string using regular expression parsing.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - A string slice that holds the log line to be parsed.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self>` - A `ResourceIssue` instance if parsing is successful, otherwise `None`.
+    fn new_from_regex(haystack: &str) -> Option<Self> {
+        let cap = Self::regex_value().captures(haystack.trim_end())?;
+        let text = cap["text"].trim().to_string();
+        let (tool, identifier) = match &cap["extension"] {
+            "xcassets" => {
+                let identifier = cap.name("identifier").map(|m| m.as_str()).or_else(|| {
+                    QUOTED_NAME_REGEX
+                        .captures(&text)
+                        .and_then(|name| name.get(1))
+                        .map(|name| name.as_str())
+                });
+                (ResourceTool::AssetCatalog, identifier.map(str::to_string))
+            }
+            _ => (
+                ResourceTool::InterfaceBuilder,
+                cap.name("identifier").map(|m| m.as_str().to_string()),
+            ),
+        };
+
+        Some(ResourceIssue {
+            tool,
+            absolute_path: cap["path"].to_string(),
+            identifier,
+            severity: Severity::from_name(&cap["severity"])?,
+            text,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BuildLog, BuildStep, MyWarning};
+
+    /// Tests reading `actool` and `ibtool` issues and reporting them as diagnostics.
+    #[test]
+    fn test_resource_issue_parse() {
+        let log = "CompileAssetCatalog /build/App.app /src/Assets.xcassets (in target 'App' from project 'App')\n\
+                   /src/Assets.xcassets:./AppIcon.appiconset/[][ipad][76x76][][][1x][][][]: warning: A 76x76@1x app icon is required for iPad apps targeting iOS 9.0 and later\n\
+                   /src/Assets.xcassets: warning: The image set \"Logo\" has an unassigned child.\n\
+                   CompileStoryboard /src/Main.storyboard (in target 'App' from project 'App')\n\
+                   /src/Main.storyboard:BYZ-38-t0r: warning: Frame for \"Label\" will be different at run time.\n\
+                   /src/LaunchScreen.storyboard: error: Launch screens may not use UIWebView.\n";
+        let build_log = BuildLog::<MyWarning>::parse(log);
+
+        let issues: Vec<_> = build_log
+            .resource_issues()
+            .iter()
+            .map(|issue| (issue.tool(), issue.identifier(), issue.severity()))
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                (
+                    ResourceTool::AssetCatalog,
+                    Some("AppIcon.appiconset/[][ipad][76x76][][][1x][][][]"),
+                    Severity::Warning
+                ),
+                (ResourceTool::AssetCatalog, Some("Logo"), Severity::Warning),
+                (
+                    ResourceTool::InterfaceBuilder,
+                    Some("BYZ-38-t0r"),
+                    Severity::Warning
+                ),
+                (ResourceTool::InterfaceBuilder, None, Severity::Error),
+            ]
+        );
+
+        let diagnostics: Vec<_> = build_log
+            .diagnostics_with_build_steps()
+            .map(|(log_file, step)| {
+                let code_fragment = log_file.code_fragment().unwrap();
+                (
+                    log_file.absolute_path(),
+                    code_fragment.category(),
+                    step.map(|step| step.step().clone()),
+                )
+            })
+            .collect();
+        assert_eq!(diagnostics.len(), 4);
+        assert_eq!(
+            diagnostics[2],
+            (
+                "/src/Main.storyboard",
+                Some(Category::InterfaceBuilder),
+                Some(BuildStep::Other("CompileStoryboard".to_string()))
+            )
+        );
+        assert!(ResourceIssue::new_from_regex("/src/A.swift:1:2: warning: unused").is_none());
+    }
+}
@@ -0,0 +1,390 @@
+use regex::Regex;
+
+use crate::{CodeFragment, LogFile, Severity, TaskMessage};
+
+/// Represents the runtime sanitizer that printed a report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sanitizer {
+    /// AddressSanitizer, reporting memory errors such as `heap-use-after-free`.
+    Address,
+    /// ThreadSanitizer, reporting data races.
+    Thread,
+    /// UndefinedBehaviorSanitizer, reporting `runtime error:`s.
+    UndefinedBehavior,
+    /// LeakSanitizer, reporting memory leaks.
+    Leak,
+}
+
+impl Sanitizer {
+    /// Returns the sanitizer matching the name printed in its reports, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - A string slice that holds the name, such as `AddressSanitizer`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "AddressSanitizer" => Some(Sanitizer::Address),
+            "ThreadSanitizer" => Some(Sanitizer::Thread),
+            "UndefinedBehaviorSanitizer" => Some(Sanitizer::UndefinedBehavior),
+            "LeakSanitizer" => Some(Sanitizer::Leak),
+            _ => None,
+        }
+    }
+
+    /// Returns the name printed in the reports of the sanitizer.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Sanitizer::Address => "AddressSanitizer",
+            Sanitizer::Thread => "ThreadSanitizer",
+            Sanitizer::UndefinedBehavior => "UndefinedBehaviorSanitizer",
+            Sanitizer::Leak => "LeakSanitizer",
+        }
+    }
+}
+
+/// Represents a symbolized frame of a sanitizer stack trace, such as
+/// `#0 0x100003f29 in main /src/main.c:6:10`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StackFrame {
+    index: usize,
+    function: String,
+    file: Option<String>,
+    line: Option<usize>,
+    column: Option<usize>,
+}
+
+impl StackFrame {
+    /// Returns the position of the frame in the stack, `0` being the innermost one.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the function of the frame, as printed by the symbolizer.
+    pub fn function(&self) -> &str {
+        &self.function
+    }
+
+    /// Returns the source file of the frame, if it was symbolized with one.
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
+    /// Returns the line in the source file, if known.
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// Returns the column in the source file, if known.
+    pub fn column(&self) -> Option<usize> {
+        self.column
+    }
+}
+
+/// Represents the report a runtime sanitizer printed for one error.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SanitizerReport {
+    sanitizer: Sanitizer,
+    kind: String,
+    address: Option<String>,
+    frames: Vec<StackFrame>,
+    /// The `path:line:column` of a `runtime error:` line.
+    location: Option<(String, usize, usize)>,
+}
+
+impl SanitizerReport {
+    /// Returns the sanitizer that printed the report.
+    pub fn sanitizer(&self) -> Sanitizer {
+        self.sanitizer
+    }
+
+    /// Returns the kind of error, such as `heap-use-after-free` or `data race`.
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// Returns the faulting address, such as `0x602000000010`, if the report names one.
+    pub fn address(&self) -> Option<&str> {
+        self.address.as_deref()
+    }
+
+    /// Returns the frames of the first stack trace of the report, where the error occurred.
+    ///
+    /// The stacks printed after it, such as where the memory was freed, are not kept.
+    pub fn frames(&self) -> &[StackFrame] {
+        &self.frames
+    }
+
+    /// Converts the report into an error diagnostic.
+    ///
+    /// It points at the location of a `runtime error:` line, or else at the innermost
+    /// frame with a source file.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<LogFile<T>>` - The diagnostic, or `None` if the report has no source location.
+    pub fn to_log_file<T: TaskMessage>(&self) -> Option<LogFile<T>> {
+        let (path, line, column) = match &self.location {
+            Some(location) => location.clone(),
+            None => self.frames.iter().find_map(|frame| {
+                Some((frame.file.clone()?, frame.line?, frame.column.unwrap_or(1)))
+            })?,
+        };
+        let mut text = format!("{}: {}", self.sanitizer.name(), self.kind);
+        if let Some(address) = &self.address {
+            text.push_str(&format!(" on address {}", address));
+        }
+        Some(LogFile::new(
+            path,
+            Some(CodeFragment::new(line, column, Some(Severity::Error), text)),
+        ))
+    }
+}
+
+/// A stateful parser for the multi-line reports of AddressSanitizer, ThreadSanitizer,
+/// UndefinedBehaviorSanitizer and LeakSanitizer found in test logs.
+///
+/// ```text
+/// ==4821==ERROR: AddressSanitizer: heap-use-after-free on address 0x602000000010 at pc 0x100003f2a
+/// READ of size 4 at 0x602000000010 thread T0
+///     #0 0x100003f29 in main /src/main.c:6:10
+///     #1 0x7ff80bd4c30f in start+0x79f (dyld:x86_64+0xfffffffffff4c30f)
+/// SUMMARY: AddressSanitizer: heap-use-after-free /src/main.c:6:10 in main
+/// ```
+///
+/// Lines are fed one at a time with [`push_line`](Self::push_line), and the reports
+/// collected so far are returned by [`finish`](Self::finish). A report ends with its
+/// `SUMMARY:` line, or with the first line that cannot belong to it.
+#[derive(Debug)]
+pub struct SanitizerReportParser {
+    error_header: Regex,
+    thread_header: Regex,
+    runtime_error: Regex,
+    frame: Regex,
+    access: Regex,
+    body: Regex,
+    in_report: bool,
+    first_stack_done: bool,
+    reports: Vec<SanitizerReport>,
+}
+
+impl Default for SanitizerReportParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SanitizerReportParser {
+    /// Creates a new `SanitizerReportParser`.
+    pub fn new() -> Self {
+        SanitizerReportParser {
+            error_header: Regex::new(
+                r#"^==\d+==ERROR: (?P<sanitizer>\w+Sanitizer): (?P<kind>detected memory leaks|\S+)(?: on (?:unknown )?address (?P<address>0x[0-9a-fA-F]+))?"#,
+            )
+            .unwrap(),
+            thread_header: Regex::new(r#"^WARNING: ThreadSanitizer: (?P<kind>.+?)(?: \(pid=\d+\))?$"#)
+                .unwrap(),
+            runtime_error: Regex::new(
+                r#"^(?P<path>.+?):(?P<line>\d+):(?P<column>\d+): runtime error: (?P<kind>.+)$"#,
+            )
+            .unwrap(),
+            frame: Regex::new(
+                r#"^\s*#(?P<index>\d+) (?:0x[0-9a-fA-F]+ in )?(?P<function>.+?)(?: (?P<file>\S+?):(?P<line>\d+)(?::(?P<column>\d+))?)?(?: \([^)]*\))?$"#,
+            )
+            .unwrap(),
+            access: Regex::new(r#" at (?P<address>0x[0-9a-fA-F]+)"#).unwrap(),
+            body: Regex::new(
+                r#"^(?:\s|=|$|[A-Z]+ of size |0x[0-9a-fA-F]+ is located|Address 0x|(?:freed|previously allocated|allocated) by|Thread T\d+|Shadow |HINT: |Direct leak|Indirect leak)"#,
+            )
+            .unwrap(),
+            in_report: false,
+            first_stack_done: false,
+            reports: Vec::new(),
+        }
+    }
+
+    /// Feeds the next line of the log to the parser.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - A string slice that holds the log line.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the line belongs to a sanitizer report.
+    pub fn push_line(&mut self, line: &str) -> bool {
+        let line = line.trim_end();
+        if let Some(report) = self.start_report(line) {
+            self.reports.push(report);
+            self.in_report = true;
+            self.first_stack_done = false;
+            return true;
+        }
+        if !self.in_report {
+            return false;
+        }
+        let Some(report) = self.reports.last_mut() else {
+            return false;
+        };
+
+        if line.starts_with("SUMMARY: ") {
+            self.in_report = false;
+            return true;
+        }
+        if let Some(cap) = self.frame.captures(line) {
+            let index = cap["index"].parse().unwrap_or(0);
+            // A frame #0 after the first stack starts another one, such as where the memory was freed.
+            if index == 0 && !report.frames.is_empty() {
+                self.first_stack_done = true;
+            }
+            if !self.first_stack_done {
+                report.frames.push(StackFrame {
+                    index,
+                    function: cap["function"].to_string(),
+                    file: cap.name("file").map(|m| m.as_str().to_string()),
+                    line: cap.name("line").and_then(|m| m.as_str().parse().ok()),
+                    column: cap.name("column").and_then(|m| m.as_str().parse().ok()),
+                });
+            }
+            return true;
+        }
+        if report.sanitizer == Sanitizer::UndefinedBehavior || !self.body.is_match(line) {
+            self.in_report = false;
+            return false;
+        }
+        if report.address.is_none() {
+            if let Some(cap) = self.access.captures(line) {
+                report.address = Some(cap["address"].to_string());
+            }
+        }
+        true
+    }
+
+    /// Consumes the parser and returns the sanitizer reports found.
+    pub fn finish(self) -> Vec<SanitizerReport> {
+        self.reports
+    }
+
+    /// Returns the report started by a line, if it is the first line of one.
+    fn start_report(&self, line: &str) -> Option<SanitizerReport> {
+        if let Some(cap) = self.error_header.captures(line) {
+            return Some(SanitizerReport {
+                sanitizer: Sanitizer::from_name(&cap["sanitizer"])?,
+                kind: cap["kind"].to_string(),
+                address: cap.name("address").map(|m| m.as_str().to_string()),
+                frames: Vec::new(),
+                location: None,
+            });
+        }
+        if let Some(cap) = self.thread_header.captures(line) {
+            return Some(SanitizerReport {
+                sanitizer: Sanitizer::Thread,
+                kind: cap["kind"].to_string(),
+                address: None,
+                frames: Vec::new(),
+                location: None,
+            });
+        }
+        let cap = self.runtime_error.captures(line)?;
+        Some(SanitizerReport {
+            sanitizer: Sanitizer::UndefinedBehavior,
+            kind: cap["kind"].to_string(),
+            address: None,
+            frames: Vec::new(),
+            location: Some((
+                cap["path"].to_string(),
+                cap["line"].parse().ok()?,
+                cap["column"].parse().ok()?,
+            )),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BuildLog, MyWarning};
+
+    /// Tests collecting AddressSanitizer, ThreadSanitizer and UBSan reports from a test log.
+    #[test]
+    fn test_sanitizer_report_parser() {
+        let log = "Test Case '-[AppTests testCache]' started.\n\
+                   =================================================================\n\
+                   ==4821==ERROR: AddressSanitizer: heap-use-after-free on address 0x602000000010 at pc 0x100003f2a bp 0x7ff7bfeff5c0 sp 0x7ff7bfeff5b8\n\
+                   READ of size 4 at 0x602000000010 thread T0\n\
+                   \x20   #0 0x100003f29 in Cache::get() /src/Cache.cpp:6:10\n\
+                   \x20   #1 0x7ff80bd4c30f in start+0x79f (dyld:x86_64+0xfffffffffff4c30f)\n\
+                   \n\
+                   freed by thread T0 here:\n\
+                   \x20   #0 0x100003eec in Cache::clear() /src/Cache.cpp:3:5\n\
+                   SUMMARY: AddressSanitizer: heap-use-after-free /src/Cache.cpp:6:10 in Cache::get()\n\
+                   ==================\n\
+                   WARNING: ThreadSanitizer: data race (pid=4822)\n\
+                   \x20 Write of size 8 at 0x7b0800000000 by thread T1:\n\
+                   \x20   #0 closure #1 in Store.load() Store.swift:20 (App:arm64+0x10000abcd)\n\
+                   Test Case '-[AppTests testCache]' failed (0.010 seconds).\n\
+                   /src/Math.c:12:5: runtime error: signed integer overflow: 2147483647 + 1 cannot be represented in type 'int'\n";
+        let build_log = BuildLog::<MyWarning>::parse(log);
+
+        let reports = build_log.sanitizer_reports();
+        assert_eq!(reports.len(), 3);
+        assert_eq!(
+            (
+                reports[0].sanitizer(),
+                reports[0].kind(),
+                reports[0].address()
+            ),
+            (
+                Sanitizer::Address,
+                "heap-use-after-free",
+                Some("0x602000000010")
+            )
+        );
+        let frames: Vec<_> = reports[0]
+            .frames()
+            .iter()
+            .map(|frame| (frame.function(), frame.file(), frame.line()))
+            .collect();
+        assert_eq!(
+            frames,
+            vec![
+                ("Cache::get()", Some("/src/Cache.cpp"), Some(6)),
+                ("start+0x79f", None, None),
+            ]
+        );
+        assert_eq!(
+            (reports[1].kind(), reports[1].address()),
+            ("data race", Some("0x7b0800000000"))
+        );
+        assert_eq!(
+            reports[1].frames()[0].function(),
+            "closure #1 in Store.load()"
+        );
+        assert_eq!(reports[2].sanitizer(), Sanitizer::UndefinedBehavior);
+        assert_eq!(build_log.test_events().len(), 2);
+
+        let diagnostics: Vec<_> = build_log
+            .diagnostics()
+            .iter()
+            .map(|log_file| {
+                let code_fragment = log_file.code_fragment().unwrap();
+                (
+                    log_file.absolute_path(),
+                    code_fragment.line(),
+                    code_fragment.severity(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            diagnostics,
+            vec![
+                ("/src/Cache.cpp", 6, Some(Severity::Error)),
+                ("Store.swift", 20, Some(Severity::Error)),
+                ("/src/Math.c", 12, Some(Severity::Error)),
+            ]
+        );
+        assert_eq!(
+            build_log.diagnostics()[0].code_fragment().unwrap().text(),
+            "AddressSanitizer: heap-use-after-free on address 0x602000000010"
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! Conversion of parsed diagnostics into SARIF 2.1.0 reports.
+
+use serde_json::{json, Value};
+
+use crate::{CodeFragment, LogFile, Severity, TaskMessage};
+
+/// The SARIF version produced by this module.
+pub const SARIF_VERSION: &str = "2.1.0";
+
+/// The JSON schema of the SARIF version produced by this module.
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// The `uriBaseId` of artifact locations made relative by [`to_sarif_with_root`].
+pub const SRCROOT_BASE_ID: &str = "%SRCROOT%";
+
+/// Converts parsed diagnostics into a SARIF report with a single run.
+///
+/// Each diagnostic becomes a result whose `ruleId` is its lint rule, such as a SwiftLint
+/// rule identifier, or else its warning flag, or else the tool and theme of the diagnostic,
+/// such as `compiler/deprecation`. Its location points at the reported file as a `file://`
+/// URI, and at the reported line and column, counted in UTF-16 code units when the source
+/// line is known. Diagnostics carrying a task use the task summary as the result message.
+///
+/// # Arguments
+///
+/// * `diagnostics` - The parsed diagnostics to convert.
+///
+/// # Returns
+///
+/// * `Value` - The SARIF log as a JSON value.
+pub fn to_sarif<T: TaskMessage>(diagnostics: &[LogFile<T>]) -> Value {
+    build_sarif(diagnostics, None)
+}
+
+/// Converts parsed diagnostics into a SARIF report whose locations are relative to a
+/// source root, as expected by code scanning tools that check out the sources elsewhere.
+///
+/// Paths under `root` are written relative to the [`SRCROOT_BASE_ID`] base, which the run
+/// maps to `root`. Other paths are written as `file://` URIs, as by [`to_sarif`].
+///
+/// # Arguments
+///
+/// * `diagnostics` - The parsed diagnostics to convert.
+/// * `root` - The absolute path of the source root, such as the checkout directory.
+///
+/// # Returns
+///
+/// * `Value` - The SARIF log as a JSON value.
+pub fn to_sarif_with_root<T: TaskMessage>(diagnostics: &[LogFile<T>], root: &str) -> Value {
+    build_sarif(diagnostics, Some(root.trim_end_matches('/')))
+}
+
+/// Converts parsed diagnostics into a SARIF report, relative to the given source root if any.
+fn build_sarif<T: TaskMessage>(diagnostics: &[LogFile<T>], root: Option<&str>) -> Value {
+    let mut rules: Vec<String> = Vec::new();
+    let mut results = Vec::new();
+
+    for log_file in diagnostics {
+        let Some(code_fragment) = log_file.code_fragment() else {
+            continue;
+        };
+        let rule_id = rule_id(code_fragment);
+        if !rules.contains(&rule_id) {
+            rules.push(rule_id.clone());
+        }
+        let text = match code_fragment.task_info() {
+            Some(message) => message.task().task_summary(),
+            None => code_fragment.text().to_string(),
+        };
+        // Without the source line the column cannot be converted, and a byte column
+        // is the UTF-16 column of an ASCII line.
+        let column = code_fragment
+            .columns()
+            .map_or(code_fragment.column(), |columns| columns.utf16());
+
+        results.push(json!({
+            "ruleId": rule_id,
+            "level": level(code_fragment.severity()),
+            "message": { "text": text },
+            "locations": [{
+                "physicalLocation": {
+                    "artifactLocation": artifact_location(log_file.absolute_path(), root),
+                    "region": {
+                        "startLine": code_fragment.line(),
+                        "startColumn": column,
+                    },
+                },
+            }],
+        }));
+    }
+
+    let rules: Vec<Value> = rules.into_iter().map(|id| json!({ "id": id })).collect();
+    let mut run = json!({
+        "tool": {
+            "driver": {
+                "name": env!("CARGO_PKG_NAME"),
+                "version": env!("CARGO_PKG_VERSION"),
+                "rules": rules,
+            },
+        },
+        "columnKind": "utf16CodeUnits",
+        "results": results,
+    });
+    if let Some(root) = root {
+        run["originalUriBaseIds"] = json!({
+            SRCROOT_BASE_ID: { "uri": format!("{}/", file_uri(root)) },
+        });
+    }
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [run],
+    })
+}
+
+/// Returns the rule identifier of a diagnostic.
+///
+/// The lint rule or analyzer checker comes first, then the warning flag. Other diagnostics
+/// are identified by the tool that reported them and their theme.
+fn rule_id<T: TaskMessage>(code_fragment: &CodeFragment<T>) -> String {
+    if let Some(rule) = code_fragment.rule().or_else(|| code_fragment.flag()) {
+        return rule.to_string();
+    }
+    let tool = code_fragment
+        .category()
+        .map_or_else(|| json!("compiler"), |category| json!(category));
+    let theme = json!(code_fragment.theme());
+    format!(
+        "{}/{}",
+        tool.as_str().unwrap_or_default(),
+        theme.as_str().unwrap_or_default()
+    )
+}
+
+/// Returns the SARIF artifact location of a path, relative to the source root if under it.
+fn artifact_location(path: &str, root: Option<&str>) -> Value {
+    let relative = root.and_then(|root| path.strip_prefix(root)?.strip_prefix('/'));
+    match relative {
+        Some(relative) => json!({ "uri": percent_encode(relative), "uriBaseId": SRCROOT_BASE_ID }),
+        None if path.starts_with('/') => json!({ "uri": file_uri(path) }),
+        None => json!({ "uri": percent_encode(path) }),
+    }
+}
+
+/// Returns the `file://` URI of an absolute path.
+fn file_uri(path: &str) -> String {
+    format!("file://{}", percent_encode(path))
+}
+
+/// Encodes the characters of a path that may not appear in a URI as `%XX` escapes.
+fn percent_encode(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+/// Maps a severity to a SARIF result level.
+fn level(severity: Option<Severity>) -> &'static str {
+    match severity {
+        Some(Severity::Error) => "error",
+        Some(Severity::Warning) => "warning",
+        Some(Severity::Note) | Some(Severity::Remark) => "note",
+        None => "none",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_log, MyWarning};
+
+    /// Tests converting diagnostics into SARIF results.
+    #[test]
+    fn test_to_sarif_results() {
+        let log = "/src/A.swift:3:7: warning: unused variable 'x'\n\
+                   /src/B.swift:9:1: error: s#{\"queue\": \"Q\", \"summary\": \"Fix it\"}#s\n\
+                   /src/B.swift:10:2: remark: remark text\n";
+        let sarif = to_sarif(&parse_log::<MyWarning>(log));
+        assert_eq!(sarif["version"], "2.1.0");
+
+        let run = &sarif["runs"][0];
+        let rules: Vec<&str> = run["tool"]["driver"]["rules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|rule| rule["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(rules, vec!["compiler/unused_code", "compiler/other"]);
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0]["level"], "warning");
+        assert_eq!(results[0]["message"]["text"], "unused variable 'x'");
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "file:///src/A.swift");
+        assert_eq!(location["region"]["startLine"], 3);
+        assert_eq!(location["region"]["startColumn"], 7);
+
+        assert_eq!(results[1]["level"], "error");
+        assert_eq!(results[1]["message"]["text"], "Fix it");
+        assert_eq!(results[2]["level"], "note");
+    }
+
+    /// Tests deriving rule identifiers from warning flags and converting columns to UTF-16.
+    #[test]
+    fn test_to_sarif_rules_and_columns() {
+        let log = "/src/My App/A.m:1:19: warning: unused variable 'x' [-Wunused-variable]\n\
+                   \x20 let 🐶 = \"ü\"; x()\n\
+                   \x20                  ^\n";
+        let build_log = crate::BuildLog::<MyWarning>::parse(log);
+        let sarif = to_sarif(build_log.diagnostics());
+        let result = &sarif["runs"][0]["results"][0];
+        assert_eq!(sarif["runs"][0]["columnKind"], "utf16CodeUnits");
+        assert_eq!(result["ruleId"], "-Wunused-variable");
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(
+            location["artifactLocation"]["uri"],
+            "file:///src/My%20App/A.m"
+        );
+        assert_eq!(location["region"]["startColumn"], 16);
+    }
+
+    /// Tests writing locations relative to the source root.
+    #[test]
+    fn test_to_sarif_with_root() {
+        let log = "/ci/work/App/A.swift:3:7: warning: unused variable 'x'\n\
+                   /usr/include/B.h:9:1: error: oops\n";
+        let sarif = to_sarif_with_root(&parse_log::<MyWarning>(log), "/ci/work/");
+        let run = &sarif["runs"][0];
+        assert_eq!(
+            run["originalUriBaseIds"][SRCROOT_BASE_ID]["uri"],
+            "file:///ci/work/"
+        );
+        let location = |index: usize| &run["results"][index]["locations"][0]["physicalLocation"];
+        assert_eq!(
+            location(0)["artifactLocation"],
+            json!({ "uri": "App/A.swift", "uriBaseId": "%SRCROOT%" })
+        );
+        assert_eq!(
+            location(1)["artifactLocation"],
+            json!({ "uri": "file:///usr/include/B.h" })
+        );
+    }
+
+    /// Tests that an empty set of diagnostics still produces a valid run.
+    #[test]
+    fn test_to_sarif_empty() {
+        let sarif = to_sarif::<MyWarning>(&[]);
+        assert!(sarif["runs"][0]["results"].as_array().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,224 @@
+use crate::{split_path, CODE_FRAGMENT_REGEX};
+
+/// Selects how the `path:line:column:` prefix of a log line is parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ParserBackend {
+    /// A hand-written scanner splitting on `:`, falling back to the regular
+    /// expressions for lines it cannot handle, such as lines holding a newline
+    /// or non-ASCII digits.
+    #[default]
+    Scanner,
+    /// The regular expressions of [`RegexParse`](crate::RegexParse).
+    Regex,
+}
+
+/// The parts of a log line found by [`split`].
+#[derive(Debug, PartialEq)]
+pub(crate) struct ScannedLine<'a> {
+    pub(crate) absolute_path: &'a str,
+    /// The line, the column and the rest of the line following them.
+    pub(crate) location: Option<(usize, usize, &'a str)>,
+}
+
+/// Splits a log line into its path and location with the given backend.
+///
+/// # Arguments
+///
+/// * `haystack` - A string slice that holds the log line.
+/// * `backend` - The parser used for the `path:line:column:` prefix.
+///
+/// # Returns
+///
+/// * `Option<ScannedLine>` - The parts of the line, or `None` if it holds no path.
+pub(crate) fn split(haystack: &str, backend: ParserBackend) -> Option<ScannedLine<'_>> {
+    if backend == ParserBackend::Scanner && can_scan(haystack) {
+        return scan(haystack);
+    }
+
+    let (absolute_path, rest) = split_path(haystack)?;
+    let location = CODE_FRAGMENT_REGEX.captures(rest).and_then(|cap| {
+        let line = cap.get(1)?.as_str().parse().ok()?;
+        let column = cap.get(2)?.as_str().parse().ok()?;
+        Some((line, column, cap.get(3)?.as_str()))
+    });
+
+    Some(ScannedLine {
+        absolute_path,
+        location,
+    })
+}
+
+/// Returns whether [`scan`] gives the same result as the regular expressions for the line.
+///
+/// The regular expressions stop at newlines and accept any Unicode digit, which
+/// the scanner does not handle.
+fn can_scan(haystack: &str) -> bool {
+    !haystack.contains('\n')
+        && (haystack.is_ascii() || !haystack.chars().any(|c| !c.is_ascii() && c.is_numeric()))
+}
+
+/// Splits a log line into its path and location, the same way the regular
+/// expressions of `LogFile` and `CodeFragment` do.
+///
+/// The path ends at the first `:line:column:` after the first character, so that it
+/// may hold `:`. Without one, the path ends at the first `:` after the first character
+/// and the location is the first `line:column:` pair found after it.
+///
+/// # Arguments
+///
+/// * `haystack` - A string slice that holds a log line accepted by [`can_scan`].
+///
+/// # Returns
+///
+/// * `Option<ScannedLine>` - The parts of the line, or `None` if it holds no path.
+fn scan(haystack: &str) -> Option<ScannedLine<'_>> {
+    let bytes = haystack.as_bytes();
+    let anchored = (1..bytes.len())
+        .filter(|&separator| bytes[separator] == b':')
+        .find_map(|separator| Some((separator, location_len(&bytes[separator + 1..])?)));
+    if let Some((separator, (line_end, column_end))) = anchored {
+        let rest = &haystack[separator + 1..];
+        let location = match (
+            rest[..line_end].parse(),
+            rest[line_end + 1..column_end].parse(),
+        ) {
+            (Ok(line), Ok(column)) => Some((line, column, &rest[column_end + 1..])),
+            _ => None,
+        };
+        return Some(ScannedLine {
+            absolute_path: &haystack[..separator],
+            location,
+        });
+    }
+
+    let first_len = haystack.chars().next()?.len_utf8();
+    let separator = haystack[first_len..].find(':')? + first_len;
+    let rest = &haystack[separator + 1..];
+
+    Some(ScannedLine {
+        absolute_path: &haystack[..separator],
+        location: find_location(rest),
+    })
+}
+
+/// Finds the leftmost `line:column:` pair in the given text.
+fn find_location(haystack: &str) -> Option<(usize, usize, &str)> {
+    let bytes = haystack.as_bytes();
+    let mut start = 0;
+    while start < bytes.len() {
+        if !bytes[start].is_ascii_digit() {
+            start += 1;
+            continue;
+        }
+
+        if let Some((line_end, column_end)) = location_len(&bytes[start..]) {
+            let line = haystack[start..start + line_end].parse().ok()?;
+            let column = haystack[start + line_end + 1..start + column_end]
+                .parse()
+                .ok()?;
+            return Some((line, column, &haystack[start + column_end + 1..]));
+        }
+        start += count_digits(&bytes[start..]);
+    }
+    None
+}
+
+/// Returns the ends of the line and the column if the bytes start with `line:column:`.
+fn location_len(bytes: &[u8]) -> Option<(usize, usize)> {
+    let line_end = count_digits(bytes);
+    if line_end == 0 || bytes.get(line_end) != Some(&b':') {
+        return None;
+    }
+    let column_start = line_end + 1;
+    let column_end = column_start + count_digits(&bytes[column_start..]);
+    (column_end > column_start && bytes.get(column_end) == Some(&b':'))
+        .then_some((line_end, column_end))
+}
+
+/// Returns the number of ASCII digits at the start of the given bytes.
+fn count_digits(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .take_while(|byte| byte.is_ascii_digit())
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LogFile, MyWarning, RegexParse};
+
+    /// Tests splitting a common diagnostic line.
+    #[test]
+    fn test_scan_diagnostic() {
+        let line = scan("/src/File.swift:12:34: warning: unused").unwrap();
+        assert_eq!(line.absolute_path, "/src/File.swift");
+        assert_eq!(line.location, Some((12, 34, " warning: unused")));
+
+        assert_eq!(scan("no separator"), None);
+        assert_eq!(scan("Build settings:").unwrap().location, None);
+    }
+
+    /// Tests that paths holding spaces, parentheses and colons are kept whole.
+    #[test]
+    fn test_scan_exotic_paths() {
+        let line = scan("/Users/me/My Project (iOS)/Build:2024/A.swift:3:9: error: x").unwrap();
+        assert_eq!(
+            line.absolute_path,
+            "/Users/me/My Project (iOS)/Build:2024/A.swift"
+        );
+        assert_eq!(line.location, Some((3, 9, " error: x")));
+
+        let line = scan("ld: warning: directory not found for option '-L/tmp/a:1'").unwrap();
+        assert_eq!((line.absolute_path, line.location), ("ld", None));
+    }
+
+    /// Tests that the scanner and the regular expressions agree on unusual lines.
+    #[test]
+    fn test_scan_matches_regex_backend() {
+        let lines = [
+            "/src/File.swift:12:34: error: s#{\"queue\": \"Q\", \"summary\": \"S\"}#s",
+            ":/src/File.swift:1:2: note: leading colon",
+            "ld: warning: object file built for newer version 14.0:1:2: x",
+            "/src/File.swift:1x:2:3:4: remark: digits after garbage",
+            "/src/File.swift:1:2",
+            "/src/File.swift:99999999999999999999999:1: overflow",
+            "/src/Fïlé.swift:1:2: warning: unicode",
+            "C:",
+            "/src/File.swift:١:2:3:4: warning: arabic-indic digit",
+            "/Users/me/My Project (iOS)/File.swift:7:1: warning: spaces",
+            "/Volumes/Build:2024/App/File.swift:3:9: error: colon in path",
+            "/tmp/a:1/File.swift:5:6: note: colon followed by a digit",
+            "é: warning: foo",
+        ];
+        for line in lines {
+            let scanned = LogFile::<MyWarning>::parse_with(line, ParserBackend::Scanner);
+            let regex = LogFile::<MyWarning>::new_from_regex(line);
+            assert_eq!(
+                scanned.as_ref().map(LogFile::absolute_path),
+                regex.as_ref().map(LogFile::absolute_path),
+                "{}",
+                line
+            );
+
+            let location = |log_file: Option<&LogFile<MyWarning>>| {
+                log_file
+                    .and_then(LogFile::code_fragment)
+                    .map(|code_fragment| {
+                        (
+                            code_fragment.line(),
+                            code_fragment.column(),
+                            code_fragment.severity(),
+                            code_fragment.text().to_string(),
+                        )
+                    })
+            };
+            assert_eq!(
+                location(scanned.as_ref()),
+                location(regex.as_ref()),
+                "{}",
+                line
+            );
+        }
+    }
+}
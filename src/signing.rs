@@ -0,0 +1,160 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::RegexParse;
+
+/// Represents the kind of a code signing or provisioning failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SigningIssueKind {
+    /// A generic `Code Signing Error:` line.
+    CodeSigning,
+    /// No provisioning profile matches the team, profile name or bundle identifier.
+    NoMatchingProfile,
+    /// The provisioning profile does not include the signing certificate.
+    ProfileMissingCertificate,
+}
+
+/// Represents a code signing or provisioning failure reported by `xcodebuild`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SigningIssue {
+    kind: SigningIssueKind,
+    message: String,
+    team: Option<String>,
+    profile: Option<String>,
+    certificate: Option<String>,
+    target: Option<String>,
+}
+
+impl SigningIssue {
+    /// Returns the kind of the failure.
+    pub fn kind(&self) -> SigningIssueKind {
+        self.kind
+    }
+
+    /// Returns the message describing the failure.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the development team identifier, if mentioned.
+    pub fn team(&self) -> Option<&str> {
+        self.team.as_deref()
+    }
+
+    /// Returns the provisioning profile name or bundle identifier, if mentioned.
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// Returns the signing certificate name, if mentioned.
+    pub fn certificate(&self) -> Option<&str> {
+        self.certificate.as_deref()
+    }
+
+    /// Returns the name of the target being signed, if mentioned.
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+}
+
+impl RegexParse for SigningIssue {
+    /// Returns the regular expression used to parse a signing issue.
+    fn regex_value() -> &'static Regex {
+        static REGEX: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(
+                r#"(?P<issue>Code Sign(?:ing)? Error:|No profiles? for team '(?P<team>[^']+)' matching '(?P<profile>[^']+)'|No profiles? for '(?P<bundle>[^']+)' (?:were|was) found|Provisioning profile "(?P<certificate_profile>[^"]+)" doesn't include signing certificate "(?P<certificate>[^"]+)")(?P<rest>.*?)(?: \(in target '(?P<target>[^']+)' from project '[^']+'\))?$"#,
+            )
+            .unwrap()
+        });
+        &REGEX
+    }
+
+    /// Creates a new `SigningIssue` from the given string using regular expression parsing.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - A string slice that holds the log line to be parsed.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self>` - A `SigningIssue` instance if parsing is successful, otherwise `None`.
+    fn new_from_regex(haystack: &str) -> Option<Self> {
+        let cap = &Self::regex_value().captures(haystack.trim_end())?;
+        let issue = cap.name("issue")?.as_str();
+        let rest = cap.name("rest").map_or("", |m| m.as_str());
+        let capture = |name: &str| cap.name(name).map(|m| m.as_str().to_string());
+
+        let (kind, message) = if cap.name("certificate").is_some() {
+            (
+                SigningIssueKind::ProfileMissingCertificate,
+                format!("{}{}", issue, rest),
+            )
+        } else if cap.name("team").is_some() || cap.name("bundle").is_some() {
+            (
+                SigningIssueKind::NoMatchingProfile,
+                format!("{}{}", issue, rest),
+            )
+        } else {
+            (SigningIssueKind::CodeSigning, rest.trim().to_string())
+        };
+
+        Some(SigningIssue {
+            kind,
+            message,
+            team: capture("team"),
+            profile: capture("profile")
+                .or_else(|| capture("bundle"))
+                .or_else(|| capture("certificate_profile")),
+            certificate: capture("certificate"),
+            target: capture("target"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests parsing a missing profile for a team.
+    #[test]
+    fn test_signing_issue_parse_no_matching_profile() {
+        let line = "/src/App.xcodeproj: error: No profile for team 'ABCDE12345' matching 'App Store' found: Xcode couldn't find any provisioning profiles matching 'ABCDE12345/App Store'. (in target 'App' from project 'App')";
+        let issue = SigningIssue::new_from_regex(line).unwrap();
+        assert_eq!(issue.kind(), SigningIssueKind::NoMatchingProfile);
+        assert_eq!(issue.team(), Some("ABCDE12345"));
+        assert_eq!(issue.profile(), Some("App Store"));
+        assert_eq!(issue.target(), Some("App"));
+        assert_eq!(
+            issue.message(),
+            "No profile for team 'ABCDE12345' matching 'App Store' found: Xcode couldn't find any provisioning profiles matching 'ABCDE12345/App Store'."
+        );
+    }
+
+    /// Tests parsing a profile that lacks the signing certificate.
+    #[test]
+    fn test_signing_issue_parse_missing_certificate() {
+        let line = r#"error: Provisioning profile "App Dev" doesn't include signing certificate "Apple Development: Jane Doe (XYZ)"."#;
+        let issue = SigningIssue::new_from_regex(line).unwrap();
+        assert_eq!(issue.kind(), SigningIssueKind::ProfileMissingCertificate);
+        assert_eq!(issue.profile(), Some("App Dev"));
+        assert_eq!(
+            issue.certificate(),
+            Some("Apple Development: Jane Doe (XYZ)")
+        );
+        assert_eq!(issue.target(), None);
+    }
+
+    /// Tests parsing a generic code signing error.
+    #[test]
+    fn test_signing_issue_parse_code_signing_error() {
+        let line = "Code Signing Error: Signing for \"App\" requires a development team.";
+        let issue = SigningIssue::new_from_regex(line).unwrap();
+        assert_eq!(issue.kind(), SigningIssueKind::CodeSigning);
+        assert_eq!(
+            issue.message(),
+            "Signing for \"App\" requires a development team."
+        );
+
+        assert!(SigningIssue::new_from_regex("CodeSign /build/App.app").is_none());
+    }
+}
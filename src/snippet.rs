@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+
+use serde::Serialize;
+
+use crate::{Columns, LogFile, TaskMessage};
+
+/// The source lines around a diagnostic, read from the file it points at.
+///
+/// Its `Display` implementation renders the lines with their numbers and a caret under
+/// the column of the diagnostic:
+///
+/// ```text
+///  9 | let total = 0
+/// 10 | let unused = compute()
+///    |     ^
+/// 11 | print(total)
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Snippet {
+    first_line: usize,
+    lines: Vec<String>,
+    line: usize,
+    column: usize,
+}
+
+impl Snippet {
+    /// Returns the lines around a location of a source text.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The content of the file.
+    /// * `line` - The line of the diagnostic, starting at 1.
+    /// * `column` - The column of the diagnostic, starting at 1.
+    /// * `context` - The number of lines kept before and after the line of the diagnostic.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self>` - The snippet, or `None` if the file has no such line.
+    pub fn from_source(source: &str, line: usize, column: usize, context: usize) -> Option<Self> {
+        if line == 0 {
+            return None;
+        }
+        let first_line = line.saturating_sub(context).max(1);
+        let lines: Vec<String> = source
+            .lines()
+            .skip(first_line - 1)
+            .take(line - first_line + context + 1)
+            .map(str::to_string)
+            .collect();
+        if lines.len() <= line - first_line {
+            return None;
+        }
+        Some(Snippet {
+            first_line,
+            lines,
+            line,
+            column,
+        })
+    }
+
+    /// Returns the number of the first line of the snippet.
+    pub fn first_line(&self) -> usize {
+        self.first_line
+    }
+
+    /// Returns the source lines, starting at [`first_line`](Self::first_line).
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Returns the line of the diagnostic.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the column of the diagnostic.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Returns the source line of the diagnostic.
+    pub fn highlighted_line(&self) -> &str {
+        &self.lines[self.line - self.first_line]
+    }
+
+    /// Returns the caret line pointing at the column, keeping the tabs of the source line.
+    ///
+    /// The column is counted in bytes, so the caret skips one space per character before it.
+    pub fn caret(&self) -> String {
+        let line = self.highlighted_line();
+        let mut caret: String = line
+            .chars()
+            .take(Columns::from_byte_column(line, self.column).character() - 1)
+            .map(|character| if character == '\t' { '\t' } else { ' ' })
+            .collect();
+        caret.push('^');
+        caret
+    }
+}
+
+impl fmt::Display for Snippet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let last_line = self.first_line + self.lines.len() - 1;
+        let width = last_line.to_string().len();
+        for (number, text) in (self.first_line..).zip(&self.lines) {
+            writeln!(f, "{:>width$} | {}", number, text)?;
+            if number == self.line {
+                writeln!(f, "{:>width$} | {}", "", self.caret())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Attaches the source lines around each diagnostic, so reports can show the code.
+///
+/// Each file is read once. Diagnostics whose file cannot be read, such as logs parsed
+/// on another machine, or whose line is past the end of the file are left unchanged.
+///
+/// # Arguments
+///
+/// * `diagnostics` - The parsed diagnostics to enrich.
+/// * `context` - The number of lines kept before and after the line of each diagnostic.
+pub fn attach_snippets<T: TaskMessage>(diagnostics: &mut [LogFile<T>], context: usize) {
+    let mut sources: HashMap<String, io::Result<String>> = HashMap::new();
+    for log_file in diagnostics {
+        let Some(code_fragment) = log_file.code_fragment.as_mut() else {
+            continue;
+        };
+        let source = sources
+            .entry(log_file.absolute_path.clone())
+            .or_insert_with(|| fs::read_to_string(&log_file.absolute_path));
+        if let Ok(source) = source {
+            let snippet = Snippet::from_source(
+                source,
+                code_fragment.line(),
+                code_fragment.column(),
+                context,
+            );
+            code_fragment.attach_snippet(snippet);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the lines kept at the start of a file and the rendered caret.
+    #[test]
+    fn test_snippet_from_source() {
+        let source = "import Foundation\n\tlet x = 1\nprint(x)\n}\n";
+        let snippet = Snippet::from_source(source, 2, 6, 1).unwrap();
+        assert_eq!(snippet.first_line(), 1);
+        assert_eq!(snippet.lines().len(), 3);
+        assert_eq!(
+            snippet.to_string(),
+            "1 | import Foundation\n\
+             2 | \tlet x = 1\n  \
+               | \t    ^\n\
+             3 | print(x)\n"
+        );
+        assert_eq!(Snippet::from_source(source, 9, 1, 1), None);
+    }
+
+    /// Tests attaching snippets from a file on disk and skipping missing files.
+    #[test]
+    fn test_attach_snippets() {
+        let path = std::env::temp_dir().join("xcode_log_parser_snippet.swift");
+        fs::write(&path, "let a = 1\nlet b = 2\nlet c = 3\n").unwrap();
+        let log = format!(
+            "{}:2:5: warning: unused\n/missing/File.swift:1:1: warning: unused\n",
+            path.display()
+        );
+        let mut diagnostics = crate::parse_log::<crate::MyWarning>(&log);
+        attach_snippets(&mut diagnostics, 0);
+        fs::remove_file(&path).unwrap();
+
+        let snippet = diagnostics[0].code_fragment().unwrap().snippet().unwrap();
+        assert_eq!(snippet.lines(), &["let b = 2".to_string()]);
+        assert_eq!(diagnostics[1].code_fragment().unwrap().snippet(), None);
+    }
+}
@@ -0,0 +1,207 @@
+use std::ops::Range;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Matches the digits at the end of a text, such as the line before the `:` preceding the column.
+static TRAILING_DIGITS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\d+$"#).unwrap());
+
+/// The byte ranges of the parts of a log line, for tools that rewrite or annotate the line.
+///
+/// ```
+/// use xcode_log_parser::{LogFile, MyWarning};
+///
+/// let line = "/src/A.swift:12:5: warning: unused variable 'x'";
+/// let log_file = LogFile::<MyWarning>::parse(line).unwrap();
+/// let spans = log_file.spans().unwrap();
+/// assert_eq!(&line[spans.path()], "/src/A.swift");
+/// assert_eq!(&line[spans.line().unwrap()], "12");
+/// assert_eq!(&line[spans.severity().unwrap()], "warning");
+/// assert_eq!(&line[spans.text().unwrap()], "unused variable 'x'");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Spans {
+    path: Range<usize>,
+    line: Option<Range<usize>>,
+    column: Option<Range<usize>>,
+    severity: Option<Range<usize>>,
+    text: Option<Range<usize>>,
+}
+
+impl Spans {
+    /// Creates the spans of a line holding a path but no location yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - The parsed log line.
+    /// * `path` - The path at the start of the line, borrowed from `haystack`.
+    pub(crate) fn new(haystack: &str, path: &str) -> Self {
+        Spans {
+            path: range_of(haystack, path),
+            line: None,
+            column: None,
+            severity: None,
+            text: None,
+        }
+    }
+
+    /// Records the location following the path and the parts of the message after it.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - The parsed log line.
+    /// * `rest` - The rest of the line following `line:column:`, borrowed from `haystack`.
+    /// * `keyword` - The severity keyword, if known, borrowed from `haystack`.
+    /// * `text` - The text of the diagnostic, borrowed from `haystack`.
+    pub(crate) fn locate(&mut self, haystack: &str, rest: &str, keyword: Option<&str>, text: &str) {
+        let column_end = range_of(haystack, rest).start - 1;
+        let column_start = trailing_digits(&haystack[..column_end]);
+        let line_end = column_start - 1;
+        self.line = Some(trailing_digits(&haystack[..line_end])..line_end);
+        self.column = Some(column_start..column_end);
+        self.severity = keyword.map(|keyword| range_of(haystack, keyword));
+        self.text = Some(range_of(haystack, text));
+    }
+
+    /// Moves every range by the given number of bytes, the start of the parsed text within
+    /// the line it was taken from.
+    pub(crate) fn shift(&mut self, offset: usize) {
+        let shift = |range: &mut Range<usize>| *range = range.start + offset..range.end + offset;
+        shift(&mut self.path);
+        for range in [
+            &mut self.line,
+            &mut self.column,
+            &mut self.severity,
+            &mut self.text,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            shift(range);
+        }
+    }
+
+    /// Returns the range of the file path.
+    pub fn path(&self) -> Range<usize> {
+        self.path.clone()
+    }
+
+    /// Returns the range of the line number, if the line holds a location.
+    pub fn line(&self) -> Option<Range<usize>> {
+        self.line.clone()
+    }
+
+    /// Returns the range of the column number, if the line holds a location.
+    pub fn column(&self) -> Option<Range<usize>> {
+        self.column.clone()
+    }
+
+    /// Returns the range of the severity keyword, if the line names a known one.
+    pub fn severity(&self) -> Option<Range<usize>> {
+        self.severity.clone()
+    }
+
+    /// Returns the range of the payload of the diagnostic, the text returned by
+    /// [`CodeFragment::text`](crate::CodeFragment::text), if the line holds a location.
+    pub fn text(&self) -> Option<Range<usize>> {
+        self.text.clone()
+    }
+}
+
+/// Returns the range a slice of the haystack covers.
+fn range_of(haystack: &str, part: &str) -> Range<usize> {
+    let start = part.as_ptr() as usize - haystack.as_ptr() as usize;
+    start..start + part.len()
+}
+
+/// Returns the start of a part within the haystack, or `None` if the part was not sliced
+/// from it, such as a line rewritten into a new string.
+pub(crate) fn offset_in(haystack: &str, part: &str) -> Option<usize> {
+    let start = (part.as_ptr() as usize).checked_sub(haystack.as_ptr() as usize)?;
+    (start + part.len() <= haystack.len()).then_some(start)
+}
+
+/// Returns the start of the digits ending the text.
+fn trailing_digits(text: &str) -> usize {
+    TRAILING_DIGITS_REGEX
+        .find(text)
+        .map_or(text.len(), |digits| digits.start())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use crate::{LogFile, MyWarning, ParserBackend, RegexParse};
+
+    /// Tests that the spans cover the parsed parts with both backends and the strict parser.
+    #[test]
+    fn test_log_file_spans() {
+        type Parts<'a> = (
+            &'a str,
+            Option<&'a str>,
+            Option<&'a str>,
+            Option<&'a str>,
+            Option<&'a str>,
+        );
+        fn parts(line: &str, log_file: LogFile<MyWarning>) -> Parts<'_> {
+            let spans = log_file.spans().unwrap();
+            let part = |range: Option<Range<usize>>| range.map(|range| &line[range]);
+            (
+                &line[spans.path()],
+                part(spans.line()),
+                part(spans.column()),
+                part(spans.severity()),
+                part(spans.text()),
+            )
+        }
+        let cases = [
+            (
+                "/My App (iOS)/A:B.swift:012:5:  Warning:  unused 'x' ",
+                (
+                    "/My App (iOS)/A:B.swift",
+                    Some("012"),
+                    Some("5"),
+                    Some("Warning"),
+                    Some("unused 'x'"),
+                ),
+            ),
+            (
+                "/src/A.swift:3:4: deprecated: 'old()' is deprecated",
+                (
+                    "/src/A.swift",
+                    Some("3"),
+                    Some("4"),
+                    None,
+                    Some("deprecated: 'old()' is deprecated"),
+                ),
+            ),
+            (
+                "ld: warning: found 12:3: in the message",
+                ("ld", Some("12"), Some("3"), None, Some("in the message")),
+            ),
+            ("ld: warning: no location", ("ld", None, None, None, None)),
+        ];
+        for (line, expected) in cases {
+            for backend in [ParserBackend::Scanner, ParserBackend::Regex] {
+                let log_file = LogFile::parse_with(line, backend).unwrap();
+                assert_eq!(parts(line, log_file), expected, "{}", line);
+            }
+        }
+
+        let line = r#"/src/A.swift:1:2: error: s#{"queue": "Q", "summary": "S"}#s"#;
+        assert_eq!(
+            parts(line, LogFile::try_parse(line).unwrap()),
+            (
+                "/src/A.swift",
+                Some("1"),
+                Some("2"),
+                Some("error"),
+                Some(r#"s#{"queue": "Q", "summary": "S"}#s"#)
+            )
+        );
+        assert!(LogFile::<MyWarning>::new(String::new(), None)
+            .spans()
+            .is_none());
+    }
+}
@@ -0,0 +1,332 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::{BuildLog, Severity, TaskMessage, Theme};
+
+/// Summary statistics of the diagnostics in a build log.
+///
+/// Only diagnostics pointing at a location are counted. `note:` lines attached to
+/// a diagnostic are not counted on their own.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Stats {
+    total: usize,
+    by_severity: BTreeMap<Severity, usize>,
+    /// Errors that are warnings turned into errors by `-Werror`, also counted as errors.
+    escalated: usize,
+    /// Diagnostics without a known severity.
+    other: usize,
+    by_file: BTreeMap<String, usize>,
+    by_target: BTreeMap<String, usize>,
+    by_flag: BTreeMap<String, usize>,
+    by_theme: BTreeMap<Theme, usize>,
+    with_payload: usize,
+    plain: usize,
+    /// Slow type-checking warnings, slowest first.
+    type_checks: Vec<TypeCheck>,
+}
+
+/// Represents an expression or function the Swift compiler was slow to type-check,
+/// reported by `-warn-long-expression-type-checking` or `-warn-long-function-bodies`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TypeCheck {
+    absolute_path: String,
+    line: usize,
+    column: usize,
+    text: String,
+    milliseconds: u64,
+}
+
+impl TypeCheck {
+    /// Returns the path of the file holding the expression or function.
+    pub fn absolute_path(&self) -> &str {
+        &self.absolute_path
+    }
+
+    /// Returns the line of the expression or function.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the column of the expression or function.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Returns the text of the warning, such as
+    /// `instance method 'load()' took 512ms to type-check (limit: 100ms)`.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns how many milliseconds type-checking took.
+    pub fn milliseconds(&self) -> u64 {
+        self.milliseconds
+    }
+}
+
+impl Stats {
+    /// Computes the statistics of a parsed build log.
+    ///
+    /// # Arguments
+    ///
+    /// * `build_log` - The parsed build log, whose build steps give the target of each diagnostic.
+    pub fn from_build_log<T: TaskMessage>(build_log: &BuildLog<T>) -> Self {
+        let mut stats = Stats::default();
+        for (log_file, step) in build_log.diagnostics_with_build_steps() {
+            let Some(code_fragment) = log_file.code_fragment() else {
+                continue;
+            };
+            stats.total += 1;
+            match code_fragment.severity() {
+                Some(severity) => *stats.by_severity.entry(severity).or_default() += 1,
+                None => stats.other += 1,
+            }
+            *stats
+                .by_file
+                .entry(log_file.absolute_path().to_string())
+                .or_default() += 1;
+            if let Some(target) = step.and_then(|step| step.target()) {
+                *stats.by_target.entry(target.to_string()).or_default() += 1;
+            }
+            *stats.by_theme.entry(code_fragment.theme()).or_default() += 1;
+            if code_fragment.is_escalated() {
+                stats.escalated += 1;
+            }
+            if let Some(flag) = code_fragment.flag() {
+                *stats.by_flag.entry(flag.to_string()).or_default() += 1;
+            }
+            if code_fragment.task_info().is_some() {
+                stats.with_payload += 1;
+            } else {
+                stats.plain += 1;
+            }
+            if let Some(milliseconds) = code_fragment.type_check_ms() {
+                stats.type_checks.push(TypeCheck {
+                    absolute_path: log_file.absolute_path().to_string(),
+                    line: code_fragment.line(),
+                    column: code_fragment.column(),
+                    text: code_fragment.text().to_string(),
+                    milliseconds,
+                });
+            }
+        }
+        // The sort is stable, so equally slow ones stay in log order.
+        stats
+            .type_checks
+            .sort_by_key(|type_check| Reverse(type_check.milliseconds));
+        stats
+    }
+
+    /// Returns the number of diagnostics.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Returns the number of diagnostics of the given severity.
+    pub fn count(&self, severity: Severity) -> usize {
+        self.by_severity.get(&severity).copied().unwrap_or(0)
+    }
+
+    /// Returns the number of errors that are warnings turned into errors by `-Werror`.
+    ///
+    /// They are also counted by [`count`](Self::count) as errors, so the number of
+    /// genuine compile errors is `count(Severity::Error) - escalated()`.
+    pub fn escalated(&self) -> usize {
+        self.escalated
+    }
+
+    /// Returns the number of diagnostics without a known severity.
+    pub fn other(&self) -> usize {
+        self.other
+    }
+
+    /// Returns the number of diagnostics per file.
+    pub fn by_file(&self) -> &BTreeMap<String, usize> {
+        &self.by_file
+    }
+
+    /// Returns the number of diagnostics per target, for diagnostics printed within a build step naming one.
+    pub fn by_target(&self) -> &BTreeMap<String, usize> {
+        &self.by_target
+    }
+
+    /// Returns the number of diagnostics per warning flag, for diagnostics naming one.
+    pub fn by_flag(&self) -> &BTreeMap<String, usize> {
+        &self.by_flag
+    }
+
+    /// Returns the number of diagnostics per theme, such as deprecations or unused code.
+    pub fn by_theme(&self) -> &BTreeMap<Theme, usize> {
+        &self.by_theme
+    }
+
+    /// Returns the number of diagnostics holding a task payload.
+    pub fn with_payload(&self) -> usize {
+        self.with_payload
+    }
+
+    /// Returns the number of diagnostics without a task payload.
+    pub fn plain(&self) -> usize {
+        self.plain
+    }
+
+    /// Returns the files with the most diagnostics, most first.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of files returned.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(&str, usize)>` - The paths and their number of diagnostics. Ties are ordered by path.
+    pub fn top_files(&self, limit: usize) -> Vec<(&str, usize)> {
+        let mut files: Vec<(&str, usize)> = self
+            .by_file
+            .iter()
+            .map(|(path, count)| (path.as_str(), *count))
+            .collect();
+        files.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        files.truncate(limit);
+        files
+    }
+
+    /// Returns the expressions and functions that were the slowest to type-check, slowest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of expressions and functions returned.
+    pub fn slowest_type_checks(&self, limit: usize) -> &[TypeCheck] {
+        &self.type_checks[..limit.min(self.type_checks.len())]
+    }
+
+    /// Serializes the statistics into a JSON object.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "total: {}", self.total)?;
+        for severity in [
+            Severity::Error,
+            Severity::Warning,
+            Severity::Note,
+            Severity::Remark,
+        ] {
+            writeln!(f, "{}s: {}", severity.name(), self.count(severity))?;
+            if severity == Severity::Error && self.escalated > 0 {
+                writeln!(f, "  escalated warnings: {}", self.escalated)?;
+            }
+        }
+        writeln!(f, "other: {}", self.other)?;
+        writeln!(f, "with payload: {}", self.with_payload)?;
+        writeln!(f, "plain: {}", self.plain)?;
+        writeln!(f, "targets:")?;
+        for (target, count) in &self.by_target {
+            writeln!(f, "  {}: {}", target, count)?;
+        }
+        if !self.by_flag.is_empty() {
+            writeln!(f, "flags:")?;
+            for (flag, count) in &self.by_flag {
+                writeln!(f, "  {}: {}", flag, count)?;
+            }
+        }
+        writeln!(f, "top files:")?;
+        for (path, count) in self.top_files(10) {
+            writeln!(f, "  {}: {}", path, count)?;
+        }
+        if !self.type_checks.is_empty() {
+            writeln!(f, "slowest type checks:")?;
+            for type_check in self.slowest_type_checks(10) {
+                writeln!(
+                    f,
+                    "  {}ms {}:{}:{}: {}",
+                    type_check.milliseconds,
+                    type_check.absolute_path,
+                    type_check.line,
+                    type_check.column,
+                    type_check.text
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MyWarning;
+
+    const LOG: &str = r#"CompileSwift normal arm64 /src/App/A.swift (in target 'App' from project 'App')
+/src/App/A.swift:1:1: warning: s#{"queue": "API", "summary": "Retry"}#s
+/src/App/A.swift:2:1: warning: unused variable 'x'
+CompileSwift normal arm64 /src/Kit/B.swift (in target 'Kit' from project 'App')
+/src/Kit/B.swift:3:1: error: cannot find 'y' in scope
+/src/Kit/A.swift:4:1: remark: slow type checking
+"#;
+
+    /// Tests counting diagnostics by severity, file, target and payload.
+    #[test]
+    fn test_stats_from_build_log() {
+        let stats = Stats::from_build_log(&BuildLog::<MyWarning>::parse(LOG));
+        assert_eq!(stats.total(), 4);
+        assert_eq!(stats.count(Severity::Warning), 2);
+        assert_eq!(stats.count(Severity::Note), 0);
+        assert_eq!(stats.by_target()["Kit"], 2);
+        assert_eq!((stats.with_payload(), stats.plain()), (1, 3));
+        assert_eq!(
+            stats.top_files(2),
+            vec![("/src/App/A.swift", 2), ("/src/Kit/A.swift", 1)]
+        );
+    }
+
+    /// Tests the keys of the JSON output.
+    #[test]
+    fn test_stats_to_json() {
+        let stats = Stats::from_build_log(&BuildLog::<MyWarning>::parse(LOG));
+        let value: serde_json::Value = serde_json::from_str(&stats.to_json().unwrap()).unwrap();
+        assert_eq!(value["by_severity"]["error"], 1);
+        assert_eq!(value["by_target"]["App"], 2);
+        assert_eq!(value["with_payload"], 1);
+        assert_eq!(value["by_theme"]["unused_code"], 1);
+    }
+
+    /// Tests counting the warnings turned into errors by `-Werror` apart from genuine errors.
+    #[test]
+    fn test_stats_escalated() {
+        let log = "/src/A.m:1:1: error: unused variable 'x' [-Werror,-Wunused-variable]\n\
+                   /src/A.m:2:1: error: 'foo' is deprecated [-Werror=deprecated-declarations]\n\
+                   /src/A.m:3:1: warning: 'bar' is deprecated [-Wdeprecated-declarations]\n\
+                   /src/B.m:4:1: error: use of undeclared identifier 'y'\n";
+        let stats = Stats::from_build_log(&BuildLog::<MyWarning>::parse(log));
+        assert_eq!((stats.count(Severity::Error), stats.escalated()), (3, 2));
+        assert_eq!(stats.by_flag()["-Wdeprecated-declarations"], 2);
+        assert!(stats
+            .to_string()
+            .starts_with("total: 4\nerrors: 3\n  escalated warnings: 2\nwarnings: 1\n"));
+    }
+
+    /// Tests ranking the slow type-checking warnings.
+    #[test]
+    fn test_stats_slowest_type_checks() {
+        let log = "/src/A.swift:9:5: warning: expression took 253ms to type-check (limit: 200ms)\n\
+                   /src/B.swift:2:10: warning: instance method 'load()' took 512ms to type-check (limit: 100ms)\n\
+                   /src/B.swift:4:1: warning: unused variable 'x'\n";
+        let stats = Stats::from_build_log(&BuildLog::<MyWarning>::parse(log));
+        let slowest: Vec<_> = stats
+            .slowest_type_checks(5)
+            .iter()
+            .map(|type_check| (type_check.absolute_path(), type_check.milliseconds()))
+            .collect();
+        assert_eq!(slowest, vec![("/src/B.swift", 512), ("/src/A.swift", 253)]);
+        assert_eq!(stats.slowest_type_checks(1).len(), 1);
+        assert!(stats
+            .to_string()
+            .ends_with("slowest type checks:\n  512ms /src/B.swift:2:10: instance method 'load()' took 512ms to type-check (limit: 100ms)\n  253ms /src/A.swift:9:5: expression took 253ms to type-check (limit: 200ms)\n"));
+    }
+}
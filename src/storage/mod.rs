@@ -0,0 +1,8 @@
+//! Persistence of parsed diagnostics across builds.
+//!
+//! Each backend is behind its own feature.
+
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(feature = "sqlite")]
+pub mod trend;
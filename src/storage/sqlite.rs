@@ -0,0 +1,347 @@
+//! Storage of diagnostics in a SQLite database, available with the `sqlite` feature.
+
+use std::path::Path;
+
+use rusqlite::{params, Connection, OptionalExtension, Result, Row};
+
+use super::trend::Trend;
+use crate::{BuildLog, LogFile, Severity, TaskMessage};
+
+/// The build a set of diagnostics was recorded for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    /// Identifies the run, such as the CI build number.
+    pub id: String,
+    /// The time of the run, in seconds since the Unix epoch.
+    pub timestamp: i64,
+    /// The commit that was built, if known.
+    pub commit_sha: Option<String>,
+}
+
+/// A diagnostic read back from the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredDiagnostic {
+    /// The absolute path of the file.
+    pub absolute_path: String,
+    /// The line number.
+    pub line: usize,
+    /// The column number.
+    pub column: usize,
+    /// The severity, if known.
+    pub severity: Option<Severity>,
+    /// The text of the diagnostic.
+    pub text: String,
+    /// The queue of the task payload, if any.
+    pub queue: Option<String>,
+    /// The summary of the task payload, if any.
+    pub summary: Option<String>,
+    /// The target the diagnostic was built in, or the directory of its file if unknown.
+    pub module: String,
+}
+
+/// A SQLite database holding the diagnostics of every recorded run, to track them across builds.
+///
+/// ```
+/// use xcode_log_parser::storage::sqlite::{Run, SqliteStore};
+/// use xcode_log_parser::{parse_log, MyWarning, Severity};
+///
+/// let mut store = SqliteStore::open_in_memory().unwrap();
+/// let run = Run {
+///     id: "build-1".to_string(),
+///     timestamp: 1_700_000_000,
+///     commit_sha: Some("0123abc".to_string()),
+/// };
+/// let diagnostics = parse_log::<MyWarning>("/src/A.swift:1:2: warning: unused\n");
+/// store.record(&run, &diagnostics).unwrap();
+/// assert_eq!(store.counts(Severity::Warning).unwrap(), vec![(run, 1)]);
+/// ```
+#[derive(Debug)]
+pub struct SqliteStore {
+    connection: Connection,
+}
+
+impl SqliteStore {
+    /// Opens the database at the given path, creating it and its tables if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the database file.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self>` - The store, or an error if the database cannot be opened.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    /// Opens a database held in memory, dropped with the store.
+    pub fn open_in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    /// Creates the tables missing from the database.
+    fn with_connection(connection: Connection) -> Result<Self> {
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS runs (
+                 id TEXT PRIMARY KEY,
+                 timestamp INTEGER NOT NULL,
+                 commit_sha TEXT
+             );
+             CREATE TABLE IF NOT EXISTS diagnostics (
+                 run_id TEXT NOT NULL REFERENCES runs (id),
+                 absolute_path TEXT NOT NULL,
+                 line INTEGER NOT NULL,
+                 column INTEGER NOT NULL,
+                 severity TEXT,
+                 text TEXT NOT NULL,
+                 queue TEXT,
+                 summary TEXT,
+                 module TEXT NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS diagnostics_run_id ON diagnostics (run_id);",
+        )?;
+        Ok(SqliteStore { connection })
+    }
+
+    /// Records the diagnostics of a run, replacing those recorded before under the same id.
+    ///
+    /// The module of each diagnostic is the directory of its file. Use
+    /// [`record_build_log`](Self::record_build_log) to record the targets instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `run` - The build the diagnostics come from.
+    /// * `diagnostics` - The parsed diagnostics.
+    pub fn record<T: TaskMessage>(&mut self, run: &Run, diagnostics: &[LogFile<T>]) -> Result<()> {
+        self.insert(run, diagnostics.iter().map(|log_file| (log_file, None)))
+    }
+
+    /// Records the diagnostics of a build log, with the target each one was built in as its module.
+    ///
+    /// # Arguments
+    ///
+    /// * `run` - The build the diagnostics come from.
+    /// * `build_log` - The parsed build log, whose build steps give the target of each diagnostic.
+    pub fn record_build_log<T: TaskMessage>(
+        &mut self,
+        run: &Run,
+        build_log: &BuildLog<T>,
+    ) -> Result<()> {
+        let diagnostics = build_log
+            .diagnostics_with_build_steps()
+            .map(|(log_file, step)| (log_file, step.and_then(|step| step.target())));
+        self.insert(run, diagnostics)
+    }
+
+    /// Replaces the diagnostics of a run with the given ones and their target, if known.
+    fn insert<'a, T: TaskMessage + 'a>(
+        &mut self,
+        run: &Run,
+        diagnostics: impl Iterator<Item = (&'a LogFile<T>, Option<&'a str>)>,
+    ) -> Result<()> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute("DELETE FROM diagnostics WHERE run_id = ?1", [&run.id])?;
+        transaction.execute(
+            "INSERT OR REPLACE INTO runs (id, timestamp, commit_sha) VALUES (?1, ?2, ?3)",
+            params![run.id, run.timestamp, run.commit_sha],
+        )?;
+        {
+            let mut insert = transaction.prepare(
+                "INSERT INTO diagnostics
+                     (run_id, absolute_path, line, column, severity, text, queue, summary, module)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?;
+            for (log_file, target) in diagnostics {
+                let Some(code_fragment) = log_file.code_fragment() else {
+                    continue;
+                };
+                let task = code_fragment.task_info().map(|message| message.task());
+                let module = target.unwrap_or_else(|| {
+                    Path::new(log_file.absolute_path())
+                        .parent()
+                        .and_then(Path::to_str)
+                        .unwrap_or_default()
+                });
+                insert.execute(params![
+                    run.id,
+                    log_file.absolute_path(),
+                    code_fragment.line(),
+                    code_fragment.column(),
+                    code_fragment.severity().map(|severity| severity.name()),
+                    code_fragment.text(),
+                    task.map(|task| task.task_queue()),
+                    task.map(|task| task.task_summary()),
+                    module,
+                ])?;
+            }
+        }
+        transaction.commit()
+    }
+
+    /// Returns the recorded runs, from the oldest to the latest.
+    pub fn runs(&self) -> Result<Vec<Run>> {
+        let mut select = self
+            .connection
+            .prepare("SELECT id, timestamp, commit_sha FROM runs ORDER BY timestamp, id")?;
+        let runs = select.query_map([], read_run)?;
+        runs.collect()
+    }
+
+    /// Returns the latest recorded run, if any.
+    pub fn latest_run(&self) -> Result<Option<Run>> {
+        self.connection
+            .query_row(
+                "SELECT id, timestamp, commit_sha FROM runs ORDER BY timestamp DESC, id DESC",
+                [],
+                read_run,
+            )
+            .optional()
+    }
+
+    /// Returns the number of diagnostics of a severity in each run, from the oldest to the latest.
+    ///
+    /// # Arguments
+    ///
+    /// * `severity` - The severity to count.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<(Run, usize)>>` - Every recorded run with its count, zero if it has none.
+    pub fn counts(&self, severity: Severity) -> Result<Vec<(Run, usize)>> {
+        let mut select = self.connection.prepare(
+            "SELECT runs.id, runs.timestamp, runs.commit_sha, COUNT(diagnostics.run_id)
+             FROM runs
+             LEFT JOIN diagnostics
+                 ON diagnostics.run_id = runs.id AND diagnostics.severity = ?1
+             GROUP BY runs.id
+             ORDER BY runs.timestamp, runs.id",
+        )?;
+        let counts =
+            select.query_map([severity.name()], |row| Ok((read_run(row)?, row.get(3)?)))?;
+        counts.collect()
+    }
+
+    /// Returns the diagnostics recorded for a run, in the order of its log.
+    ///
+    /// # Arguments
+    ///
+    /// * `run_id` - The id of the run.
+    pub fn diagnostics(&self, run_id: &str) -> Result<Vec<StoredDiagnostic>> {
+        let mut select = self.connection.prepare(
+            "SELECT absolute_path, line, column, severity, text, queue, summary, module
+             FROM diagnostics WHERE run_id = ?1 ORDER BY rowid",
+        )?;
+        let diagnostics = select.query_map([run_id], |row| {
+            let severity: Option<String> = row.get(3)?;
+            Ok(StoredDiagnostic {
+                absolute_path: row.get(0)?,
+                line: row.get(1)?,
+                column: row.get(2)?,
+                severity: severity.as_deref().and_then(Severity::from_name),
+                text: row.get(4)?,
+                queue: row.get(5)?,
+                summary: row.get(6)?,
+                module: row.get(7)?,
+            })
+        })?;
+        diagnostics.collect()
+    }
+
+    /// Returns the number of diagnostics of a severity per module over the latest runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `severity` - The severity to count, such as [`Severity::Warning`].
+    /// * `last` - The number of runs to compare, the latest ones.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Trend>` - The counts of every module seen in those runs.
+    pub fn trend(&self, severity: Severity, last: usize) -> Result<Trend> {
+        let mut runs = self.runs()?;
+        runs.drain(..runs.len().saturating_sub(last));
+        let mut select = self.connection.prepare(
+            "SELECT run_id, module, COUNT(*) FROM diagnostics WHERE severity = ?1
+             GROUP BY run_id, module",
+        )?;
+        let counts = select
+            .query_map([severity.name()], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .collect::<Result<Vec<(String, String, usize)>>>()?;
+        Ok(Trend::new(runs, counts))
+    }
+
+    /// Returns the files with the most diagnostics in a run.
+    ///
+    /// # Arguments
+    ///
+    /// * `run_id` - The id of the run.
+    /// * `limit` - The maximum number of files to return.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<(String, usize)>>` - The paths and their number of diagnostics, the largest first.
+    pub fn top_files(&self, run_id: &str, limit: usize) -> Result<Vec<(String, usize)>> {
+        let mut select = self.connection.prepare(
+            "SELECT absolute_path, COUNT(*) AS count FROM diagnostics WHERE run_id = ?1
+             GROUP BY absolute_path ORDER BY count DESC, absolute_path LIMIT ?2",
+        )?;
+        let files =
+            select.query_map(params![run_id, limit], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        files.collect()
+    }
+}
+
+/// Reads a run from the `id`, `timestamp` and `commit_sha` columns starting a row.
+fn read_run(row: &Row<'_>) -> Result<Run> {
+    Ok(Run {
+        id: row.get(0)?,
+        timestamp: row.get(1)?,
+        commit_sha: row.get(2)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_log, MyWarning};
+
+    fn run(id: &str, timestamp: i64) -> Run {
+        Run {
+            id: id.to_string(),
+            timestamp,
+            commit_sha: None,
+        }
+    }
+
+    /// Tests the counts of each run and the diagnostics read back from a run.
+    #[test]
+    fn test_sqlite_store_history() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        let first = parse_log::<MyWarning>(
+            "/src/A.swift:1:1: warning: s#{\"queue\": \"API\", \"summary\": \"Retry\"}#s\n\
+             /src/A.swift:2:1: warning: unused\n",
+        );
+        let second = parse_log::<MyWarning>("/src/B.swift:3:4: error: broken\n");
+        store.record(&run("2", 20), &second).unwrap();
+        store.record(&run("1", 10), &first).unwrap();
+        store.record(&run("1", 10), &first).unwrap();
+
+        assert_eq!(
+            store.counts(Severity::Warning).unwrap(),
+            vec![(run("1", 10), 2), (run("2", 20), 0)]
+        );
+        assert_eq!(store.latest_run().unwrap(), Some(run("2", 20)));
+        assert_eq!(
+            store.top_files("1", 5).unwrap(),
+            vec![("/src/A.swift".to_string(), 2)]
+        );
+
+        let diagnostics = store.diagnostics("1").unwrap();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].severity, Some(Severity::Warning));
+        assert_eq!(diagnostics[0].queue.as_deref(), Some("API"));
+        assert_eq!(diagnostics[1].summary, None);
+    }
+}
@@ -0,0 +1,150 @@
+//! Comparison of diagnostic counts per module across stored runs.
+
+use std::collections::BTreeMap;
+
+use super::sqlite::Run;
+
+/// The number of diagnostics of a module in each run of a [`Trend`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleTrend {
+    module: String,
+    counts: Vec<usize>,
+}
+
+impl ModuleTrend {
+    /// Returns the target or directory the diagnostics were counted in.
+    pub fn module(&self) -> &str {
+        &self.module
+    }
+
+    /// Returns the count of each run, from the oldest to the latest.
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+
+    /// Returns the count of the latest run minus the count of the run before it.
+    pub fn delta(&self) -> i64 {
+        match self.counts[..] {
+            [.., previous, latest] => latest as i64 - previous as i64,
+            _ => 0,
+        }
+    }
+
+    /// Returns the count of the latest run minus the count of the oldest one.
+    pub fn change(&self) -> i64 {
+        match (self.counts.first(), self.counts.last()) {
+            (Some(&first), Some(&latest)) => latest as i64 - first as i64,
+            _ => 0,
+        }
+    }
+
+    /// Returns whether the latest run has more diagnostics than the run before it.
+    pub fn is_regression(&self) -> bool {
+        self.delta() > 0
+    }
+}
+
+/// The diagnostic counts per module over the latest stored runs, returned by
+/// [`SqliteStore::trend`](super::sqlite::SqliteStore::trend).
+///
+/// ```
+/// use xcode_log_parser::storage::sqlite::{Run, SqliteStore};
+/// use xcode_log_parser::{parse_log, MyWarning, Severity};
+///
+/// let mut store = SqliteStore::open_in_memory().unwrap();
+/// for (id, log) in [("1", ""), ("2", "/src/Feature/A.swift:1:2: warning: unused\n")] {
+///     let run = Run { id: id.to_string(), timestamp: id.parse().unwrap(), commit_sha: None };
+///     store.record(&run, &parse_log::<MyWarning>(log)).unwrap();
+/// }
+/// let trend = store.trend(Severity::Warning, 5).unwrap();
+/// let regression = trend.regressions().next().unwrap();
+/// assert_eq!((regression.module(), regression.counts()), ("/src/Feature", &[0, 1][..]));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trend {
+    runs: Vec<Run>,
+    modules: Vec<ModuleTrend>,
+}
+
+impl Trend {
+    /// Creates a `Trend` from the counts of each module in each run.
+    ///
+    /// # Arguments
+    ///
+    /// * `runs` - The compared runs, from the oldest to the latest.
+    /// * `counts` - The run id, module and count of each module seen in a run. Counts of other runs are ignored.
+    pub(crate) fn new(
+        runs: Vec<Run>,
+        counts: impl IntoIterator<Item = (String, String, usize)>,
+    ) -> Self {
+        let mut modules: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (run_id, module, count) in counts {
+            let Some(index) = runs.iter().position(|run| run.id == run_id) else {
+                continue;
+            };
+            modules.entry(module).or_insert_with(|| vec![0; runs.len()])[index] = count;
+        }
+        Trend {
+            runs,
+            modules: modules
+                .into_iter()
+                .map(|(module, counts)| ModuleTrend { module, counts })
+                .collect(),
+        }
+    }
+
+    /// Returns the compared runs, from the oldest to the latest.
+    pub fn runs(&self) -> &[Run] {
+        &self.runs
+    }
+
+    /// Returns the counts of every module seen in the compared runs, sorted by module.
+    pub fn modules(&self) -> &[ModuleTrend] {
+        &self.modules
+    }
+
+    /// Returns the modules with more diagnostics in the latest run than in the run before it.
+    pub fn regressions(&self) -> impl Iterator<Item = &ModuleTrend> {
+        self.modules.iter().filter(|module| module.is_regression())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(id: &str) -> Run {
+        Run {
+            id: id.to_string(),
+            timestamp: 0,
+            commit_sha: None,
+        }
+    }
+
+    /// Tests the deltas of modules missing from some runs.
+    #[test]
+    fn test_trend_deltas() {
+        let counts = [
+            ("1", "App", 4),
+            ("2", "App", 2),
+            ("3", "App", 3),
+            ("2", "Core", 5),
+            ("0", "Old", 9),
+        ];
+        let trend = Trend::new(
+            vec![run("1"), run("2"), run("3")],
+            counts
+                .into_iter()
+                .map(|(run_id, module, count)| (run_id.to_string(), module.to_string(), count)),
+        );
+
+        let [app, core] = trend.modules() else {
+            panic!("unexpected modules: {:?}", trend.modules());
+        };
+        assert_eq!((app.delta(), app.change()), (1, -1));
+        assert_eq!(core.counts(), &[0, 5, 0]);
+        assert_eq!((core.delta(), core.change()), (-5, 0));
+        let regressions: Vec<&str> = trend.regressions().map(ModuleTrend::module).collect();
+        assert_eq!(regressions, vec!["App"]);
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::VecDeque;
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use futures_core::Stream;
+use tokio::io::AsyncBufRead;
+
+use crate::assembler::{DiagnosticAssembler, Entry};
+use crate::{LogFile, LogParser, TaskMessage};
+
+/// A stream that parses diagnostics from an asynchronous reader as lines arrive.
+///
+/// This is the asynchronous counterpart of [`LogFileIter`](crate::LogFileIter), meant for
+/// consuming the output of a running `xcodebuild` process. Each item carries the 1-based
+/// number of the source line it was parsed from, and the source excerpt, caret and
+/// `note:` lines that followed it. Invalid UTF-8 is replaced rather than rejected.
+pub struct LogFileStream<R: AsyncBufRead + Unpin, T: TaskMessage> {
+    reader: R,
+    buffer: Vec<u8>,
+    line_number: usize,
+    parser: LogParser,
+    assembler: DiagnosticAssembler<T>,
+    ready: VecDeque<(usize, LogFile<T>)>,
+    exhausted: bool,
+}
+
+impl<R: AsyncBufRead + Unpin, T: TaskMessage> LogFileStream<R, T> {
+    /// Creates a new `LogFileStream` reading from the given source.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - An asynchronous buffered reader, such as the stdout of a child process.
+    pub fn new(reader: R) -> Self {
+        Self::with_parser(reader, LogParser::default())
+    }
+
+    /// Creates a new `LogFileStream` reading from the given source with the given parser.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - An asynchronous buffered reader, such as the stdout of a child process.
+    /// * `parser` - The parser used for each line.
+    pub fn with_parser(reader: R, parser: LogParser) -> Self {
+        LogFileStream {
+            reader,
+            buffer: Vec::new(),
+            line_number: 0,
+            assembler: DiagnosticAssembler::new(parser.dialect()),
+            parser,
+            ready: VecDeque::new(),
+            exhausted: false,
+        }
+    }
+
+    /// Returns the number of lines read from the source so far.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// Reads into the buffer up to the next line break, like `read_until(b'\n')`.
+    ///
+    /// # Returns
+    ///
+    /// * `Poll<io::Result<bool>>` - Whether a line was read, `false` once the source is exhausted.
+    fn poll_read_line(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<bool>> {
+        loop {
+            let available = ready!(Pin::new(&mut self.reader).poll_fill_buf(cx))?;
+            if available.is_empty() {
+                return Poll::Ready(Ok(!self.buffer.is_empty()));
+            }
+            let (found, used) = match available.iter().position(|&byte| byte == b'\n') {
+                Some(index) => (true, index + 1),
+                None => (false, available.len()),
+            };
+            self.buffer.extend_from_slice(&available[..used]);
+            Pin::new(&mut self.reader).consume(used);
+            if found {
+                return Poll::Ready(Ok(true));
+            }
+        }
+    }
+}
+
+// No field is pinned, so the stream can be moved whatever the task type.
+impl<R: AsyncBufRead + Unpin, T: TaskMessage> Unpin for LogFileStream<R, T> {}
+
+impl<R: AsyncBufRead + Unpin, T: TaskMessage> Stream for LogFileStream<R, T> {
+    type Item = io::Result<(usize, LogFile<T>)>;
+
+    /// Polls for lines until the next diagnostic is found.
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(item) = this.ready.pop_front() {
+                return Poll::Ready(Some(Ok(item)));
+            }
+            if this.exhausted {
+                return Poll::Ready(None);
+            }
+            let entries = match this.poll_read_line(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(false)) => {
+                    this.exhausted = true;
+                    this.assembler.finish()
+                }
+                Poll::Ready(Ok(true)) => {
+                    this.line_number += 1;
+                    let line = String::from_utf8_lossy(&this.buffer);
+                    let line = line.trim_end_matches(['\n', '\r']);
+                    let entry = this
+                        .parser
+                        .parse_line(line)
+                        .filter(|log_file| log_file.code_fragment().is_some())
+                        .map(|log_file| Entry::Diagnostic(this.line_number, log_file));
+                    let entries = this.assembler.push(line, entry);
+                    this.buffer.clear();
+                    entries
+                }
+                Poll::Ready(Err(error)) => return Poll::Ready(Some(Err(error))),
+            };
+            this.ready
+                .extend(entries.into_iter().map(Entry::into_diagnostic));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MyWarning, PayloadDelimiters};
+    use std::future::poll_fn;
+
+    /// Tests that diagnostics are yielded from an asynchronous reader with their line numbers.
+    #[tokio::test]
+    async fn test_log_file_stream_yields_diagnostics() {
+        let log = "CompileSwift normal arm64\n\
+                   /path/File.swift:1:2: warning: s#{\"queue\": \"Q\", \"summary\": \"S\"}#s\n\
+                   ** BUILD SUCCEEDED **\n";
+        let mut stream = LogFileStream::<_, MyWarning>::new(log.as_bytes());
+
+        let (line_number, log_file) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(line_number, 2);
+        assert_eq!(log_file.absolute_path(), "/path/File.swift");
+
+        assert!(poll_fn(|cx| Pin::new(&mut stream).poll_next(cx))
+            .await
+            .is_none());
+        assert_eq!(stream.line_number(), 3);
+    }
+
+    /// Tests that a note following a diagnostic is attached to it rather than yielded.
+    #[tokio::test]
+    async fn test_log_file_stream_attaches_notes() {
+        let log = "/path/File.swift:1:2: error: cannot find 'b' in scope\n\
+                   /path/Other.swift:3:4: note: did you mean 'c'?\n";
+        let mut stream = LogFileStream::<_, MyWarning>::new(log.as_bytes());
+
+        let (line_number, log_file) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(line_number, 1);
+        assert_eq!(log_file.code_fragment().unwrap().notes().len(), 1);
+        assert!(poll_fn(|cx| Pin::new(&mut stream).poll_next(cx))
+            .await
+            .is_none());
+    }
+
+    /// Tests that invalid UTF-8 is replaced rather than ending the stream.
+    #[tokio::test]
+    async fn test_log_file_stream_invalid_utf8() {
+        let log: &[u8] = b"\xff\xfe garbage\n/path/File.swift:1:2: warning: unused \xff\n";
+        let mut stream = LogFileStream::<_, MyWarning>::new(log);
+
+        let (line_number, log_file) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(line_number, 2);
+        assert_eq!(log_file.code_fragment().unwrap().text(), "unused \u{fffd}");
+    }
+
+    /// Tests parsing colored lines with custom payload delimiters, as `LogFileIter` does.
+    #[tokio::test]
+    async fn test_log_file_stream_with_parser() {
+        let log = "\x1b[1m/path/File.swift:1:2: \x1b[0;1;35mwarning: \x1b[0mTASK{\"queue\": \"Q\", \"summary\": \"S\"}TASK\n";
+        let parser = LogParser::builder()
+            .payload_delimiters(PayloadDelimiters::new("TASK", "TASK"))
+            .build();
+        let mut stream = LogFileStream::<_, MyWarning>::with_parser(log.as_bytes(), parser.clone());
+
+        let (line_number, log_file) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(line_number, 1);
+        assert_eq!(log_file.absolute_path(), "/path/File.swift");
+        let code_fragment = log_file.code_fragment().unwrap();
+        assert_eq!(code_fragment.severity(), Some(crate::Severity::Warning));
+        assert_eq!(
+            code_fragment.task_info().unwrap().task().task_summary(),
+            "S"
+        );
+        let expected: Vec<_> =
+            crate::LogFileIter::<_, MyWarning>::with_parser(log.as_bytes(), parser)
+                .map(|item| item.unwrap())
+                .collect();
+        assert_eq!(expected, vec![(line_number, log_file)]);
+    }
+}
@@ -0,0 +1,370 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Baseline, Fingerprint, LogFile, PathFilter, TaskMessage};
+
+/// A calendar date, written `YYYY-MM-DD`, after which a [`Suppression`] expires.
+///
+/// ```
+/// use xcode_log_parser::Date;
+///
+/// let date: Date = "2025-02-28".parse().unwrap();
+/// assert_eq!(date.to_string(), "2025-02-28");
+/// assert!("2025-02-30".parse::<Date>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Date {
+    year: u16,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    /// Creates a `Date`, or returns `None` if the day does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `year` - The year, such as `2025`.
+    /// * `month` - The month, from 1 to 12.
+    /// * `day` - The day of the month, from 1.
+    pub fn new(year: u16, month: u8, day: u8) -> Option<Self> {
+        let leap =
+            year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+        let days = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap => 29,
+            2 => 28,
+            _ => return None,
+        };
+        (1..=days)
+            .contains(&day)
+            .then_some(Date { year, month, day })
+    }
+
+    /// Returns the current date in UTC.
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        // Converts the days since the epoch into a civil date, counting in 400-year eras
+        // starting on March 1st, 0000.
+        let days = seconds / 86_400 + 719_468;
+        let era = days / 146_097;
+        let day_of_era = days % 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_from_march = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+        let month = if month_from_march < 10 {
+            month_from_march + 3
+        } else {
+            month_from_march - 9
+        };
+        let year = year_of_era + era * 400 + u64::from(month <= 2);
+        Date {
+            year: year as u16,
+            month: month as u8,
+            day: day as u8,
+        }
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    /// Parses a `YYYY-MM-DD` date.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid date `{}`, expected YYYY-MM-DD", text);
+        let mut parts = text.trim().splitn(3, '-');
+        let mut part = |digits: usize| {
+            parts
+                .next()
+                .filter(|part| part.len() == digits && part.bytes().all(|b| b.is_ascii_digit()))
+                .and_then(|part| part.parse::<u16>().ok())
+                .ok_or_else(invalid)
+        };
+        let (year, month, day) = (part(4)?, part(2)?, part(2)?);
+        Date::new(year, month as u8, day as u8).ok_or_else(invalid)
+    }
+}
+
+impl TryFrom<String> for Date {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+impl From<Date> for String {
+    fn from(date: Date) -> Self {
+        date.to_string()
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// A rule of a [`Suppressions`] file, ignoring the diagnostics it matches until it expires.
+///
+/// A diagnostic is matched when it meets every criterion set: the path, severity and
+/// text of the `fingerprint`, regardless of the line, a `path` glob pattern, as in
+/// [`PathFilter`], and a warning `flag`. A rule without criteria matches nothing.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Suppression {
+    /// Matches the diagnostic this fingerprint was taken from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<Fingerprint>,
+    /// Matches diagnostics in paths matching this glob pattern, such as `Legacy/**`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Matches diagnostics enabled by this warning flag, such as `-Wdeprecated-declarations`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flag: Option<String>,
+    /// The last day the rule applies, after which the diagnostics it matches resurface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires: Option<Date>,
+    /// Why the diagnostics are ignored.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl Suppression {
+    /// Returns whether the rule has expired on the given day.
+    pub fn is_expired(&self, today: Date) -> bool {
+        self.expires.is_some_and(|expires| today > expires)
+    }
+}
+
+impl fmt::Display for Suppression {
+    /// Renders the criteria of the rule, such as `path Legacy/** and flag -Wshadow`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut criteria = Vec::new();
+        if let Some(fingerprint) = &self.fingerprint {
+            criteria.push(format!(
+                "`{}` in {}",
+                fingerprint.text(),
+                fingerprint.path()
+            ));
+        }
+        if let Some(path) = &self.path {
+            criteria.push(format!("path {}", path));
+        }
+        if let Some(flag) = &self.flag {
+            criteria.push(format!("flag {}", flag));
+        }
+        f.write_str(&criteria.join(" and "))
+    }
+}
+
+/// A list of [`Suppression`] rules, to pay down warning debt incrementally: known
+/// diagnostics are ignored until their rule expires, while new ones still fail the build.
+///
+/// It implements `Serialize` and `Deserialize`, so it can be kept as a JSON file next to
+/// the project:
+///
+/// ```
+/// use xcode_log_parser::{parse_log, Date, MyWarning, Suppressions};
+///
+/// let suppressions: Suppressions = serde_json::from_str(
+///     r#"{"suppressions": [{"path": "Legacy/**", "expires": "2030-01-01"}]}"#,
+/// )
+/// .unwrap();
+/// let mut diagnostics = parse_log::<MyWarning>(
+///     "/App/Legacy/A.swift:1:2: warning: unused\n/App/New/B.swift:1:2: warning: unused\n",
+/// );
+/// let today = Date::new(2029, 6, 1).unwrap();
+/// assert_eq!(suppressions.retain(&mut diagnostics, today), 1);
+/// assert_eq!(diagnostics[0].absolute_path(), "/App/New/B.swift");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Suppressions {
+    suppressions: Vec<Suppression>,
+}
+
+impl Suppressions {
+    /// Creates `Suppressions` without rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates `Suppressions` ignoring each diagnostic of a baseline, to start paying down
+    /// the warnings of an existing project.
+    ///
+    /// # Arguments
+    ///
+    /// * `baseline` - The diagnostics to ignore.
+    /// * `expires` - The last day they are ignored, if any.
+    pub fn from_baseline(baseline: &Baseline, expires: Option<Date>) -> Self {
+        Suppressions {
+            suppressions: baseline
+                .fingerprints()
+                .iter()
+                .map(|fingerprint| Suppression {
+                    fingerprint: Some(fingerprint.clone()),
+                    expires,
+                    ..Suppression::default()
+                })
+                .collect(),
+        }
+    }
+
+    /// Adds a rule.
+    pub fn suppress(mut self, suppression: Suppression) -> Self {
+        self.suppressions.push(suppression);
+        self
+    }
+
+    /// Returns the rules.
+    pub fn suppressions(&self) -> &[Suppression] {
+        &self.suppressions
+    }
+
+    /// Returns the rules that have expired on the given day.
+    pub fn expired(&self, today: Date) -> impl Iterator<Item = &Suppression> {
+        self.suppressions
+            .iter()
+            .filter(move |suppression| suppression.is_expired(today))
+    }
+
+    /// Removes the diagnostics matched by a rule that has not expired.
+    ///
+    /// # Arguments
+    ///
+    /// * `diagnostics` - The parsed diagnostics, filtered in place.
+    /// * `today` - The day the expiry dates are compared with, usually [`Date::today`].
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The number of diagnostics removed.
+    pub fn retain<T: TaskMessage>(&self, diagnostics: &mut Vec<LogFile<T>>, today: Date) -> usize {
+        let suppresses = self.matcher(today);
+        let before = diagnostics.len();
+        diagnostics.retain(|log_file| !suppresses(log_file));
+        before - diagnostics.len()
+    }
+
+    /// Returns a predicate telling whether a diagnostic is matched by a rule that has not
+    /// expired, to drop diagnostics one at a time while they are streamed.
+    ///
+    /// # Arguments
+    ///
+    /// * `today` - The day the expiry dates are compared with, usually [`Date::today`].
+    pub fn matcher<T: TaskMessage>(&self, today: Date) -> impl Fn(&LogFile<T>) -> bool + '_ {
+        let active: Vec<(&Suppression, Option<PathFilter>)> = self
+            .suppressions
+            .iter()
+            .filter(|suppression| !suppression.is_expired(today))
+            .filter(|suppression| {
+                suppression.fingerprint.is_some()
+                    || suppression.path.is_some()
+                    || suppression.flag.is_some()
+            })
+            .map(|suppression| {
+                let paths = suppression
+                    .path
+                    .as_ref()
+                    .map(|pattern| PathFilter::new().include(pattern.clone()));
+                (suppression, paths)
+            })
+            .collect();
+        move |log_file| {
+            active
+                .iter()
+                .any(|(suppression, paths)| matches(suppression, paths.as_ref(), log_file))
+        }
+    }
+}
+
+/// Returns whether a diagnostic meets every criterion of a rule.
+fn matches<T: TaskMessage>(
+    suppression: &Suppression,
+    paths: Option<&PathFilter>,
+    log_file: &LogFile<T>,
+) -> bool {
+    let Some(code_fragment) = log_file.code_fragment() else {
+        return false;
+    };
+    suppression
+        .fingerprint
+        .as_ref()
+        .is_none_or(|fingerprint| fingerprint.matches(log_file))
+        && paths.is_none_or(|paths| paths.matches(log_file.absolute_path()))
+        && suppression
+            .flag
+            .as_deref()
+            .is_none_or(|flag| code_fragment.flag() == Some(flag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_log, MyWarning};
+
+    /// Tests that each criterion is required and that expired rules no longer apply.
+    #[test]
+    fn test_suppressions_retain() {
+        let log = "/App/Legacy/A.m:1:1: warning: 'f' is deprecated [-Wdeprecated-declarations]\n\
+                   /App/Legacy/A.m:2:1: warning: unused variable 'x' [-Wunused-variable]\n\
+                   /App/New/B.swift:7:1: warning: unused variable 'y'\n\
+                   /App/New/C.swift:3:1: error: cannot find 'z' in scope\n";
+        let moved = parse_log::<MyWarning>("/App/New/B.swift:5:1: warning: unused variable 'y'\n");
+        let suppressions = Suppressions::from_baseline(
+            &Baseline::from_diagnostics(&moved),
+            Some(Date::new(2025, 3, 31).unwrap()),
+        )
+        .suppress(Suppression {
+            path: Some("Legacy/**".to_string()),
+            flag: Some("-Wdeprecated-declarations".to_string()),
+            ..Suppression::default()
+        })
+        .suppress(Suppression {
+            reason: Some("matches nothing".to_string()),
+            ..Suppression::default()
+        });
+
+        let lines = |today: Date| {
+            let mut diagnostics = parse_log::<MyWarning>(log);
+            suppressions.retain(&mut diagnostics, today);
+            diagnostics
+                .iter()
+                .map(|log_file| log_file.code_fragment().unwrap().line())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(lines(Date::new(2025, 3, 31).unwrap()), vec![2, 3]);
+        assert_eq!(lines(Date::new(2025, 4, 1).unwrap()), vec![2, 7, 3]);
+        assert_eq!(
+            suppressions
+                .expired(Date::new(2025, 4, 1).unwrap())
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["`unused variable 'y'` in /App/New/B.swift"]
+        );
+    }
+
+    /// Tests parsing dates and the current date.
+    #[test]
+    fn test_date() {
+        assert_eq!("2024-02-29".parse(), Ok(Date::new(2024, 2, 29).unwrap()));
+        for invalid in [
+            "2023-02-29",
+            "2024-13-01",
+            "2024-1-01",
+            "24-01-01",
+            "2024-01-01x",
+        ] {
+            assert!(invalid.parse::<Date>().is_err(), "{}", invalid);
+        }
+        assert!(Date::today() > Date::new(2024, 1, 1).unwrap());
+    }
+}
@@ -0,0 +1,313 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use crate::assembler::{DiagnosticAssembler, Entry};
+use crate::build_log::strip_ansi;
+use crate::{
+    BuildOutcome, BuildOutcomeParser, BuildStepHeader, LogFile, LogParser, RegexParse, TaskMessage,
+    TestEvent,
+};
+
+/// Something noteworthy found in the lines appended to a followed log.
+#[derive(Debug)]
+pub enum TailEvent<T: TaskMessage> {
+    /// A diagnostic, with the 1-based number of the line it was parsed from.
+    Diagnostic {
+        line_number: usize,
+        log_file: LogFile<T>,
+    },
+    /// The start of a build step.
+    BuildStep(BuildStepHeader),
+    /// A line of XCTest output.
+    Test(TestEvent),
+    /// A verdict such as `** ARCHIVE SUCCEEDED **`, usually the end of the build.
+    Outcome(BuildOutcome),
+    /// The file became shorter, so it was rewritten and is read again from the start.
+    Truncated,
+}
+
+impl<T: TaskMessage> TailEvent<T> {
+    /// Returns the event found in a line of a log, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `parser` - The parser used for diagnostic lines.
+    /// * `line` - The line, without its line break.
+    /// * `line_number` - The 1-based number of the line in the log.
+    pub(crate) fn from_line(parser: &LogParser, line: &str, line_number: usize) -> Option<Self> {
+        let original = line;
+        let stripped = strip_ansi(line);
+        let line = &*parser.dialect().undecorate(&stripped);
+        if let Some(event) = TestEvent::new_from_regex(line) {
+            return Some(TailEvent::Test(event));
+        }
+        if let Some(header) = parser.dialect().build_step(line) {
+            return Some(TailEvent::BuildStep(header));
+        }
+        if let Some(mut log_file) = parser.parse_line(line) {
+            log_file.relocate_spans(original, line);
+            return log_file
+                .code_fragment()
+                .is_some()
+                .then_some(TailEvent::Diagnostic {
+                    line_number,
+                    log_file,
+                });
+        }
+        let mut outcome = BuildOutcomeParser::with_dialect(parser.dialect());
+        if outcome.push_line(line) {
+            // A failed command alone does not end the build.
+            return outcome
+                .finish()
+                .filter(|outcome| outcome.failed_commands().is_empty())
+                .map(TailEvent::Outcome);
+        }
+        None
+    }
+}
+
+impl<T: TaskMessage> From<TailEvent<T>> for Entry<T, TailEvent<T>> {
+    fn from(event: TailEvent<T>) -> Self {
+        match event {
+            TailEvent::Diagnostic {
+                line_number,
+                log_file,
+            } => Entry::Diagnostic(line_number, log_file),
+            event => Entry::Other(event),
+        }
+    }
+}
+
+impl<T: TaskMessage> From<Entry<T, TailEvent<T>>> for TailEvent<T> {
+    fn from(entry: Entry<T, TailEvent<T>>) -> Self {
+        match entry {
+            Entry::Diagnostic(line_number, log_file) => TailEvent::Diagnostic {
+                line_number,
+                log_file,
+            },
+            Entry::Other(event) => event,
+        }
+    }
+}
+
+/// Follows a log file that is still being written, like `tail -f`, and parses the
+/// lines appended to it.
+///
+/// The file is polled: every [`poll`](Self::poll) reads what was appended since the
+/// previous one. A line is only parsed once its line break was written. A file that
+/// does not exist yet is treated as empty. As in [`BuildLog::parse`](crate::BuildLog::parse),
+/// the source excerpt, caret and `note:` lines following a diagnostic are attached to it,
+/// so a diagnostic is only returned once the line after them was written, or by the
+/// first poll finding no new line. Used as an [`Iterator`], the parser waits
+/// for new data forever, sleeping between polls.
+///
+/// ```
+/// use xcode_log_parser::{MyWarning, TailEvent, TailParser};
+///
+/// let path = std::env::temp_dir().join("xcode_log_parser_tail_doc.log");
+/// std::fs::write(&path, "/src/A.swift:1:2: warning: unused\n/src/B.swift:3:4: err").unwrap();
+/// let mut tail = TailParser::<MyWarning>::new(&path);
+/// assert!(tail.poll().unwrap().is_empty());
+/// let events = tail.poll().unwrap();
+/// assert!(matches!(events[..], [TailEvent::Diagnostic { line_number: 1, .. }]));
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct TailParser<T: TaskMessage> {
+    path: PathBuf,
+    parser: LogParser,
+    poll_interval: Duration,
+    offset: u64,
+    pending: Vec<u8>,
+    line_number: usize,
+    assembler: DiagnosticAssembler<T>,
+    events: VecDeque<TailEvent<T>>,
+}
+
+impl<T: TaskMessage> TailParser<T> {
+    /// Creates a new `TailParser` reading the file from its start.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the log file, which may not exist yet.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self::with_parser(path, LogParser::default())
+    }
+
+    /// Creates a new `TailParser` reading the file from its start with the given parser.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the log file, which may not exist yet.
+    /// * `parser` - The parser used for each line.
+    pub fn with_parser(path: impl AsRef<Path>, parser: LogParser) -> Self {
+        TailParser {
+            path: path.as_ref().to_path_buf(),
+            poll_interval: Duration::from_millis(500),
+            offset: 0,
+            pending: Vec::new(),
+            line_number: 0,
+            assembler: DiagnosticAssembler::new(parser.dialect()),
+            parser,
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Sets how long the iterator sleeps between polls when nothing was appended. Defaults to 500 ms.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Returns the number of complete lines read from the file so far.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// Reads the data appended to the file since the previous poll and parses its complete lines.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<Vec<TailEvent<T>>>` - The events found in the new lines, in the order they were written.
+    pub fn poll(&mut self) -> io::Result<Vec<TailEvent<T>>> {
+        let mut events = Vec::new();
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(events),
+            Err(error) => return Err(error),
+        };
+        if file.metadata()?.len() < self.offset {
+            self.offset = 0;
+            self.pending.clear();
+            self.line_number = 0;
+            events.extend(self.assembler.finish().into_iter().map(TailEvent::from));
+            events.push(TailEvent::Truncated);
+        }
+        file.seek(SeekFrom::Start(self.offset))?;
+        self.offset += file.read_to_end(&mut self.pending)? as u64;
+
+        let Some(end) = self.pending.iter().rposition(|&byte| byte == b'\n') else {
+            // Nothing followed the last diagnostic since the previous poll, so it is complete.
+            events.extend(self.assembler.finish().into_iter().map(TailEvent::from));
+            return Ok(events);
+        };
+        let complete: Vec<u8> = self.pending.drain(..=end).collect();
+        for line in String::from_utf8_lossy(&complete).lines() {
+            self.line_number += 1;
+            let line = line.trim_end_matches('\r');
+            let entry = TailEvent::from_line(&self.parser, line, self.line_number).map(Entry::from);
+            events.extend(
+                self.assembler
+                    .push(line, entry)
+                    .into_iter()
+                    .map(TailEvent::from),
+            );
+        }
+        Ok(events)
+    }
+}
+
+impl<T: TaskMessage> Iterator for TailParser<T> {
+    type Item = io::Result<TailEvent<T>>;
+
+    /// Polls the file until the next event is found, sleeping between polls.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self::Item>` - The next event or a read error. The iterator never ends.
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Some(Ok(event));
+            }
+            match self.poll() {
+                Ok(events) if events.is_empty() => thread::sleep(self.poll_interval),
+                Ok(events) => self.events.extend(events),
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BuildStatus, MyWarning};
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+
+    /// Tests that appended lines are parsed once complete, and a rewritten file from its start.
+    #[test]
+    fn test_tail_parser_poll() {
+        let path = std::env::temp_dir().join("xcode_log_parser_tail.log");
+        fs::write(
+            &path,
+            "CompileSwift normal arm64 /src/A.swift (in target 'App' from project 'App')\n/src/A",
+        )
+        .unwrap();
+        let mut tail = TailParser::<MyWarning>::new(&path);
+        let events = tail.poll().unwrap();
+        assert!(matches!(events[..], [TailEvent::BuildStep(_)]));
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b".swift:1:2: warning: unused\n** ARCHIVE SUCCEEDED **\n")
+            .unwrap();
+        let events = tail.poll().unwrap();
+        let [TailEvent::Diagnostic {
+            line_number,
+            log_file,
+        }, TailEvent::Outcome(outcome)] = &events[..]
+        else {
+            panic!("unexpected events: {:?}", events);
+        };
+        assert_eq!(
+            (*line_number, log_file.absolute_path()),
+            (2, "/src/A.swift")
+        );
+        assert_eq!(
+            (outcome.action(), outcome.status()),
+            ("ARCHIVE", BuildStatus::Succeeded)
+        );
+        assert!(tail.poll().unwrap().is_empty());
+
+        fs::write(&path, "/src/B.swift:3:4: error: boom\n").unwrap();
+        let events = tail
+            .next()
+            .into_iter()
+            .chain(tail.next())
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            events[..],
+            [
+                TailEvent::Truncated,
+                TailEvent::Diagnostic { line_number: 1, .. }
+            ]
+        ));
+    }
+
+    /// Tests that a note is attached to the diagnostic it follows, which the verdict ends.
+    #[test]
+    fn test_tail_parser_attaches_notes() {
+        let path = std::env::temp_dir().join("xcode_log_parser_tail_notes.log");
+        fs::write(
+            &path,
+            "/src/A.swift:1:2: error: cannot find 'b' in scope\n\
+             /src/B.swift:3:4: note: did you mean 'c'?\n\
+             ** BUILD FAILED **\n",
+        )
+        .unwrap();
+        let events = TailParser::<MyWarning>::new(&path).poll().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let [TailEvent::Diagnostic { log_file, .. }, TailEvent::Outcome(_)] = &events[..] else {
+            panic!("unexpected events: {:?}", events);
+        };
+        assert_eq!(log_file.code_fragment().unwrap().notes().len(), 1);
+    }
+}
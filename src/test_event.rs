@@ -0,0 +1,220 @@
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::RegexParse;
+
+/// Identifies an XCTest test case by its suite and test method.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TestCase {
+    suite: String,
+    name: String,
+}
+
+impl TestCase {
+    /// Returns the name of the test suite, such as `AppTests.LoginTests`.
+    pub fn suite(&self) -> &str {
+        &self.suite
+    }
+
+    /// Returns the name of the test method, such as `testLogin`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Creates a new `TestCase`.
+    ///
+    /// # Arguments
+    ///
+    /// * `suite` - The name of the test suite.
+    /// * `name` - The name of the test method.
+    pub(crate) fn new(suite: impl Into<String>, name: impl Into<String>) -> Self {
+        TestCase {
+            suite: suite.into(),
+            name: name.into(),
+        }
+    }
+
+    /// Parses a test case identifier as printed by XCTest.
+    ///
+    /// Both the Objective-C form used on Apple platforms (`-[Suite test]`) and the
+    /// dotted form used by swift-corelibs-xctest (`Suite.test`) are accepted.
+    fn from_identifier(identifier: &str) -> Option<Self> {
+        let (suite, name) = match identifier.strip_prefix("-[") {
+            Some(identifier) => identifier.strip_suffix(']')?.split_once(' ')?,
+            None => identifier.rsplit_once('.')?,
+        };
+        if suite.is_empty() || name.is_empty() {
+            return None;
+        }
+
+        Some(TestCase {
+            suite: suite.to_string(),
+            name: name.to_string(),
+        })
+    }
+}
+
+/// Represents a line of XCTest output.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TestEvent {
+    /// `Test Case '-[Suite test]' started.`
+    Started { test_case: TestCase },
+    /// `Test Case '-[Suite test]' passed (0.123 seconds).`
+    Passed {
+        test_case: TestCase,
+        duration: Duration,
+    },
+    /// `Test Case '-[Suite test]' failed (0.123 seconds).`
+    Failed {
+        test_case: TestCase,
+        duration: Duration,
+    },
+    /// `/path/Tests.swift:42: error: -[Suite test] : message`
+    Failure {
+        test_case: TestCase,
+        absolute_path: String,
+        line: usize,
+        message: String,
+    },
+}
+
+impl TestEvent {
+    /// Returns the test case the event refers to.
+    pub fn test_case(&self) -> &TestCase {
+        match self {
+            TestEvent::Started { test_case }
+            | TestEvent::Passed { test_case, .. }
+            | TestEvent::Failed { test_case, .. }
+            | TestEvent::Failure { test_case, .. } => test_case,
+        }
+    }
+}
+
+impl RegexParse for TestEvent {
+    /// Returns the regular expression used to parse a test event.
+    fn regex_value() -> &'static Regex {
+        static REGEX: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(
+                r#"^(?:Test Case '(?P<case>[^']+)' (?P<status>started|passed|failed)(?: \((?P<duration>\d+(?:\.\d+)?) seconds\))?\.?|(?P<path>.+?):(?P<line>\d+): error: (?P<failure_case>-\[[^\]]+\]|\S+) : (?P<message>.*))$"#,
+            )
+            .unwrap()
+        });
+        &REGEX
+    }
+
+    /// Creates a new `TestEvent` from the given string using regular expression parsing.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - A string slice that holds the log line to be parsed.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self>` - A `TestEvent` instance if parsing is successful, otherwise `None`.
+    fn new_from_regex(haystack: &str) -> Option<Self> {
+        let cap = &Self::regex_value().captures(haystack.trim_end())?;
+
+        if let Some(identifier) = cap.name("failure_case") {
+            return Some(TestEvent::Failure {
+                test_case: TestCase::from_identifier(identifier.as_str())?,
+                absolute_path: cap.name("path")?.as_str().to_string(),
+                line: cap.name("line")?.as_str().parse().ok()?,
+                message: cap.name("message")?.as_str().trim().to_string(),
+            });
+        }
+
+        let test_case = TestCase::from_identifier(cap.name("case")?.as_str())?;
+        let duration = match cap.name("duration") {
+            Some(duration) => Duration::from_secs_f64(duration.as_str().parse().ok()?),
+            None => Duration::ZERO,
+        };
+        match cap.name("status")?.as_str() {
+            "started" => Some(TestEvent::Started { test_case }),
+            "passed" => Some(TestEvent::Passed {
+                test_case,
+                duration,
+            }),
+            "failed" => Some(TestEvent::Failed {
+                test_case,
+                duration,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests parsing passed and failed test case lines.
+    #[test]
+    fn test_test_event_parse_results() {
+        let event = TestEvent::new_from_regex(
+            "Test Case '-[AppTests.LoginTests testLogin]' passed (0.123 seconds).",
+        )
+        .unwrap();
+        match event {
+            TestEvent::Passed {
+                test_case,
+                duration,
+            } => {
+                assert_eq!(test_case.suite(), "AppTests.LoginTests");
+                assert_eq!(test_case.name(), "testLogin");
+                assert_eq!(duration, Duration::from_millis(123));
+            }
+            other => panic!("Expected a passed test, got {:?}", other),
+        }
+
+        let event =
+            TestEvent::new_from_regex("Test Case 'LoginTests.testLogout' failed (2.000 seconds)")
+                .unwrap();
+        match event {
+            TestEvent::Failed {
+                test_case,
+                duration,
+            } => {
+                assert_eq!(test_case.suite(), "LoginTests");
+                assert_eq!(test_case.name(), "testLogout");
+                assert_eq!(duration, Duration::from_secs(2));
+            }
+            other => panic!("Expected a failed test, got {:?}", other),
+        }
+
+        let event =
+            TestEvent::new_from_regex("Test Case '-[AppTests.LoginTests testLogin]' started.")
+                .unwrap();
+        assert!(matches!(event, TestEvent::Started { .. }));
+    }
+
+    /// Tests parsing a failure location line.
+    #[test]
+    fn test_test_event_parse_failure_location() {
+        let line = "/src/AppTests/LoginTests.swift:42: error: -[AppTests.LoginTests testLogin] : XCTAssertTrue failed - not logged in";
+        match TestEvent::new_from_regex(line).unwrap() {
+            TestEvent::Failure {
+                test_case,
+                absolute_path,
+                line,
+                message,
+            } => {
+                assert_eq!(test_case.name(), "testLogin");
+                assert_eq!(absolute_path, "/src/AppTests/LoginTests.swift");
+                assert_eq!(line, 42);
+                assert_eq!(message, "XCTAssertTrue failed - not logged in");
+            }
+            other => panic!("Expected a failure, got {:?}", other),
+        }
+    }
+
+    /// Tests that compiler diagnostics and other lines are not test events.
+    #[test]
+    fn test_test_event_parse_unrelated_lines() {
+        assert!(TestEvent::new_from_regex("/src/File.swift:1:2: error: oops").is_none());
+        assert!(
+            TestEvent::new_from_regex("Test Suite 'All tests' started at 2024-01-01").is_none()
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use serde::Serialize;
+
+/// The kind of problem a diagnostic is about, inferred from its message and warning flag,
+/// to slice warning debt by theme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    /// The use of a deprecated API, such as `'foo' was deprecated in iOS 13.0`.
+    Deprecation,
+    /// Unused or unreachable code, such as `initialization of immutable value 'x' was never used`.
+    UnusedCode,
+    /// Missing or conflicting nullability specifiers in Objective-C headers.
+    Nullability,
+    /// Data races and actor isolation, such as non-`Sendable` values crossing actors.
+    Concurrency,
+    /// APIs used on OS versions they are not available on.
+    Availability,
+    /// Any other diagnostic.
+    #[default]
+    Other,
+}
+
+impl Theme {
+    /// Returns the theme of a diagnostic.
+    ///
+    /// The warning flag decides when there is one, and otherwise the words of the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The message of the diagnostic, following its severity.
+    /// * `flag` - The warning flag of the diagnostic, such as `-Wdeprecated-declarations`.
+    pub(crate) fn classify(text: &str, flag: Option<&str>) -> Self {
+        if let Some(flag) = flag.and_then(|flag| flag.strip_prefix("-W")) {
+            let starts = |prefixes: &[&str]| prefixes.iter().any(|p| flag.starts_with(p));
+            if starts(&["deprecated"]) {
+                return Theme::Deprecation;
+            } else if starts(&["unused", "unreachable"]) {
+                return Theme::UnusedCode;
+            } else if starts(&["nullability", "nonnull"]) {
+                return Theme::Nullability;
+            } else if starts(&["thread-safety"]) {
+                return Theme::Concurrency;
+            } else if flag.contains("availability") {
+                return Theme::Availability;
+            }
+        }
+
+        let text = text.to_lowercase();
+        let mentions = |words: &[&str]| words.iter().any(|word| text.contains(word));
+        if mentions(&["deprecated"]) {
+            Theme::Deprecation
+        } else if mentions(&[
+            "is only available in",
+            "is unavailable",
+            "unguarded availability",
+        ]) {
+            Theme::Availability
+        } else if mentions(&[
+            "never used",
+            "unused",
+            "will never be executed",
+            "unreachable",
+        ]) {
+            Theme::UnusedCode
+        } else if mentions(&["nullability", "nullable", "nonnull", "non-null"]) {
+            Theme::Nullability
+        } else if mentions(&[
+            "sendable",
+            "actor-isolated",
+            "main actor",
+            "data race",
+            "concurrency",
+        ]) {
+            Theme::Concurrency
+        } else {
+            Theme::Other
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests inferring the theme from the flag first, then from the message.
+    #[test]
+    fn test_theme_classify() {
+        let cases = [
+            ("'foo' is deprecated", Some("-Wdeprecated-declarations"), Theme::Deprecation),
+            ("unused variable 'x'", Some("-Wunused-variable"), Theme::UnusedCode),
+            ("pointer is missing a nullability type specifier", Some("-Wnullability-completeness"), Theme::Nullability),
+            ("'bar' is only available on iOS 15.0 or newer", Some("-Wunguarded-availability-new"), Theme::Availability),
+            ("'init()' was deprecated in iOS 13.0: use init(frame:)", None, Theme::Deprecation),
+            ("initialization of immutable value 'x' was never used", None, Theme::UnusedCode),
+            ("'fetch()' is only available in iOS 15.0 or newer", None, Theme::Availability),
+            ("capture of 'self' with non-sendable type 'Store' in a `@Sendable` closure", None, Theme::Concurrency),
+            ("main actor-isolated property 'title' can not be mutated from a nonisolated context", None, Theme::Concurrency),
+            ("implicit conversion loses integer precision", Some("-Wshorten-64-to-32"), Theme::Other),
+        ];
+        for (text, flag, theme) in cases {
+            assert_eq!(Theme::classify(text, flag), theme, "{}", text);
+        }
+    }
+}
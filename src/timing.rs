@@ -0,0 +1,167 @@
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Matches the line starting the block printed by `xcodebuild -showBuildTimingSummary`.
+const HEADER: &str = "Build Timing Summary";
+
+/// Matches a line of the timing summary, such as `CompileSwiftSources (4 tasks) | 28.417 seconds`.
+static TASK_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^(?P<name>\S+) \((?P<count>\d+) tasks?\) \| (?P<seconds>\d+(?:\.\d+)?) seconds$"#)
+        .unwrap()
+});
+
+/// Represents the time spent on one kind of task, such as every `CompileC` of the build.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TaskTiming {
+    name: String,
+    task_count: usize,
+    duration: Duration,
+}
+
+impl TaskTiming {
+    /// Returns the kind of task, such as `CompileSwiftSources`, `Ld` or `PhaseScriptExecution`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns how many tasks of this kind were run.
+    pub fn task_count(&self) -> usize {
+        self.task_count
+    }
+
+    /// Returns the time spent on the tasks of this kind, summed over all of them.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+/// Represents the `Build Timing Summary` block printed by `xcodebuild -showBuildTimingSummary`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TimingSummary {
+    tasks: Vec<TaskTiming>,
+}
+
+impl TimingSummary {
+    /// Returns the time spent on each kind of task, in the order they are printed,
+    /// which is usually the slowest first.
+    pub fn tasks(&self) -> &[TaskTiming] {
+        &self.tasks
+    }
+
+    /// Returns the time spent on every task.
+    ///
+    /// Tasks run in parallel, so this is usually longer than the build itself.
+    pub fn total(&self) -> Duration {
+        self.tasks.iter().map(TaskTiming::duration).sum()
+    }
+}
+
+/// A stateful parser for the timing summary printed at the end of a build.
+///
+/// ```text
+/// Build Timing Summary
+///
+/// CompileSwiftSources (4 tasks) | 28.417 seconds
+/// Ld (2 tasks) | 1.034 seconds
+/// PhaseScriptExecution (1 task) | 0.512 seconds
+/// ```
+///
+/// Lines are fed one at a time with [`push_line`](Self::push_line). When several
+/// summaries are printed, the last one wins.
+#[derive(Debug, Default)]
+pub struct TimingSummaryParser {
+    in_summary: bool,
+    tasks: Option<Vec<TaskTiming>>,
+}
+
+impl TimingSummaryParser {
+    /// Creates a new `TimingSummaryParser`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds the next line of the log to the parser.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - A string slice that holds the log line.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the line belongs to the timing summary.
+    pub fn push_line(&mut self, line: &str) -> bool {
+        let line = line.trim_end();
+        if line == HEADER {
+            self.in_summary = true;
+            self.tasks = Some(Vec::new());
+            return true;
+        }
+        if !self.in_summary {
+            return false;
+        }
+        if line.is_empty() {
+            return true;
+        }
+        let Some(cap) = TASK_REGEX.captures(line) else {
+            self.in_summary = false;
+            return false;
+        };
+        let (Ok(task_count), Ok(seconds)) = (cap["count"].parse(), cap["seconds"].parse()) else {
+            self.in_summary = false;
+            return false;
+        };
+        self.tasks.get_or_insert_with(Vec::new).push(TaskTiming {
+            name: cap["name"].to_string(),
+            task_count,
+            duration: Duration::from_secs_f64(seconds),
+        });
+        true
+    }
+
+    /// Consumes the parser and returns the timing summary, if the log contains one.
+    pub fn finish(self) -> Option<TimingSummary> {
+        self.tasks.map(|tasks| TimingSummary { tasks })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BuildLog, MyWarning};
+
+    /// Tests reading the timing summary printed before the verdict.
+    #[test]
+    fn test_build_log_timing_summary() {
+        let log = "/src/A.swift:1:2: warning: unused\n\
+                   Build Timing Summary\n\
+                   \n\
+                   CompileSwiftSources (4 tasks) | 28.417 seconds\n\
+                   Ld (2 tasks) | 1.034 seconds\n\
+                   PhaseScriptExecution (1 task) | 0.5 seconds\n\
+                   \n\
+                   ** BUILD SUCCEEDED ** [32.114 sec]\n";
+        let build_log = BuildLog::<MyWarning>::parse(log);
+
+        let summary = build_log.timing_summary().unwrap();
+        let tasks: Vec<_> = summary
+            .tasks()
+            .iter()
+            .map(|task| (task.name(), task.task_count(), task.duration().as_millis()))
+            .collect();
+        assert_eq!(
+            tasks,
+            vec![
+                ("CompileSwiftSources", 4, 28417),
+                ("Ld", 2, 1034),
+                ("PhaseScriptExecution", 1, 500),
+            ]
+        );
+        assert_eq!(summary.total().as_millis(), 29951);
+        assert!(build_log.outcome().unwrap().succeeded());
+        assert_eq!(build_log.diagnostics().len(), 1);
+        assert!(BuildLog::<MyWarning>::parse("** BUILD SUCCEEDED **\n")
+            .timing_summary()
+            .is_none());
+    }
+}
@@ -0,0 +1,834 @@
+//! Decoding of the `.xcactivitylog` build logs Xcode keeps in DerivedData.
+//!
+//! An `.xcactivitylog` is a gzip-compressed stream in the SLF0 format, which serializes the
+//! `IDEActivityLogSection` tree shown in Xcode's report navigator. [`SlfTokens`] reads the
+//! tokens of the stream, and [`ActivityLog`] reads the sections and the text they hold,
+//! which is the same output `xcodebuild` prints and is parsed with the crate's line parsers.
+//!
+//! ```no_run
+//! use xcode_log_parser::xcactivitylog::ActivityLog;
+//! use xcode_log_parser::MyWarning;
+//!
+//! let activity_log = ActivityLog::open("Build/ABC123.xcactivitylog").unwrap();
+//! let build_log = activity_log.build_log::<MyWarning>();
+//! println!("{} diagnostics", build_log.diagnostics().len());
+//! ```
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use flate2::read::GzDecoder;
+
+use crate::{BuildLog, TaskMessage};
+
+/// The location of DerivedData under the home directory, unless changed in Xcode's settings.
+const DERIVED_DATA_PATH: &str = "Library/Developer/Xcode/DerivedData";
+
+/// The bytes starting every SLF stream.
+const SLF_HEADER: &[u8] = b"SLF0";
+
+/// The bytes starting every gzip stream.
+const GZIP_HEADER: &[u8] = &[0x1f, 0x8b];
+
+/// Represents a token of an SLF0 stream, written as its value followed by a type character.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SlfToken {
+    /// An unsigned integer, such as `12#`.
+    Int(u64),
+    /// A double, written as its little-endian bytes in hexadecimal, such as `0000000000c0f040^`.
+    Double(f64),
+    /// A missing value, written `-`.
+    Null,
+    /// A string, written as its length in bytes and its contents, such as `5"hello`.
+    String(String),
+    /// The start of an array, written as its number of elements, such as `3(`.
+    Array(usize),
+    /// The declaration of a class name, written as its length and the name, such as
+    /// `21%IDEActivityLogSection`.
+    ClassName(String),
+    /// An instance of a declared class, written as the 1-based index of its name, such as `1@`.
+    /// The fields of the instance are the tokens that follow.
+    ClassInstance(String),
+}
+
+/// Iterates over the tokens of an uncompressed SLF0 stream.
+#[derive(Debug)]
+pub struct SlfTokens<'a> {
+    data: &'a [u8],
+    position: usize,
+    class_names: Vec<String>,
+}
+
+impl<'a> SlfTokens<'a> {
+    /// Starts reading the tokens of an uncompressed SLF0 stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The stream, starting with `SLF0`.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<Self>` - The tokens, or an error if the stream does not start with `SLF0`.
+    pub fn new(data: &'a [u8]) -> io::Result<Self> {
+        if !data.starts_with(SLF_HEADER) {
+            return Err(invalid_data("missing SLF0 header".to_string()));
+        }
+        Ok(SlfTokens {
+            data,
+            position: SLF_HEADER.len(),
+            class_names: Vec::new(),
+        })
+    }
+
+    /// Reads the token at the current position.
+    fn read_token(&mut self) -> io::Result<SlfToken> {
+        let start = self.position;
+        let value_len = self.data[start..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit() || (b'a'..=b'f').contains(byte))
+            .count();
+        let value = std::str::from_utf8(&self.data[start..start + value_len]).unwrap();
+        let Some(&kind) = self.data.get(start + value_len) else {
+            return Err(invalid_data(format!("truncated token at byte {}", start)));
+        };
+        self.position = start + value_len + 1;
+
+        let number = || {
+            value
+                .parse::<usize>()
+                .map_err(|_| invalid_data(format!("invalid number `{}` at byte {}", value, start)))
+        };
+        match kind {
+            b'#' => Ok(SlfToken::Int(number()? as u64)),
+            b'^' => {
+                let bytes = (0..value.len() / 2)
+                    .map(|index| u8::from_str_radix(&value[index * 2..index * 2 + 2], 16))
+                    .collect::<Result<Vec<_>, _>>()
+                    .ok()
+                    .and_then(|bytes| <[u8; 8]>::try_from(bytes).ok())
+                    .ok_or_else(|| {
+                        invalid_data(format!("invalid double `{}` at byte {}", value, start))
+                    })?;
+                Ok(SlfToken::Double(f64::from_le_bytes(bytes)))
+            }
+            b'-' => Ok(SlfToken::Null),
+            b'(' => Ok(SlfToken::Array(number()?)),
+            b'"' => Ok(SlfToken::String(self.read_bytes(number()?)?)),
+            b'%' => {
+                let class_name = self.read_bytes(number()?)?;
+                self.class_names.push(class_name.clone());
+                Ok(SlfToken::ClassName(class_name))
+            }
+            b'@' => {
+                let class_name = number()?
+                    .checked_sub(1)
+                    .and_then(|index| self.class_names.get(index))
+                    .ok_or_else(|| {
+                        invalid_data(format!("unknown class `{}` at byte {}", value, start))
+                    })?;
+                Ok(SlfToken::ClassInstance(class_name.clone()))
+            }
+            _ => Err(invalid_data(format!(
+                "unknown token type `{}` at byte {}",
+                kind as char, start
+            ))),
+        }
+    }
+
+    /// Reads the given number of bytes following the type character of a string token.
+    fn read_bytes(&mut self, len: usize) -> io::Result<String> {
+        // The length is read from the file, so it may be past the end of any stream.
+        let bytes = self
+            .position
+            .checked_add(len)
+            .and_then(|end| self.data.get(self.position..end))
+            .ok_or_else(|| invalid_data(format!("truncated string at byte {}", self.position)))?;
+        let end = self.position + bytes.len();
+        self.position = end;
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    }
+}
+
+impl Iterator for SlfTokens<'_> {
+    type Item = io::Result<SlfToken>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position >= self.data.len() {
+            return None;
+        }
+        let token = self.read_token();
+        if token.is_err() {
+            // A malformed token leaves no way to find the next one.
+            self.position = self.data.len();
+        }
+        Some(token)
+    }
+}
+
+/// Represents a section of an activity log, such as the build of a target or a compile step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogSection {
+    class_name: String,
+    title: String,
+    signature: String,
+    time_started: f64,
+    time_stopped: f64,
+}
+
+impl LogSection {
+    /// Returns the class of the section, such as `IDEActivityLogSection` or
+    /// `IDEActivityLogCommandInvocationSection`.
+    pub fn class_name(&self) -> &str {
+        &self.class_name
+    }
+
+    /// Returns the title Xcode shows for the section, such as `Compile AppDelegate.swift`.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns the signature of the section, which for a build step is the header
+    /// `xcodebuild` prints, such as `CompileSwift normal arm64 /src/AppDelegate.swift (in target 'App' from project 'App')`.
+    pub fn signature(&self) -> &str {
+        &self.signature
+    }
+
+    /// Returns the time the section started, in seconds since 2001-01-01 00:00 UTC.
+    pub fn time_started(&self) -> f64 {
+        self.time_started
+    }
+
+    /// Returns the time the section took, or `None` if it has no valid end time.
+    pub fn duration(&self) -> Option<Duration> {
+        Duration::try_from_secs_f64(self.time_stopped - self.time_started).ok()
+    }
+}
+
+/// Represents a decoded `.xcactivitylog`: its sections and the text they hold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivityLog {
+    version: Option<u64>,
+    sections: Vec<LogSection>,
+    text: String,
+}
+
+impl ActivityLog {
+    /// Reads and decodes the activity log at the given path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the `.xcactivitylog` file.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<Self>` - The decoded log, or an error if the file could not be read or
+    ///   is not an SLF0 stream.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::decode(&fs::read(path)?)
+    }
+
+    /// Decodes an activity log.
+    ///
+    /// The text is made of the signature of each section, which starts a build step, and
+    /// of the strings it holds, such as the command line and the compiler output. The titles
+    /// of the sections are left out, and so are the messages and locations, which are read
+    /// field by field: the diagnostic lines in the output already carry their titles and
+    /// paths, and their other strings, such as `com.apple.dt.IDE.diagnostic`, are identifiers.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The gzip-compressed or uncompressed SLF0 stream.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<Self>` - The decoded log, or an error if the data is not an SLF0 stream.
+    pub fn decode(data: &[u8]) -> io::Result<Self> {
+        if data.starts_with(GZIP_HEADER) {
+            let mut decompressed = Vec::new();
+            GzDecoder::new(data).read_to_end(&mut decompressed)?;
+            return Self::decode(&decompressed);
+        }
+
+        let mut tokens = SlfTokens::new(data)?.peekable();
+        let version = match tokens.peek() {
+            Some(Ok(SlfToken::Int(version))) => Some(*version),
+            _ => None,
+        };
+        let mut sections = Vec::new();
+        let mut text = String::new();
+        while let Some(token) = tokens.next() {
+            match token? {
+                SlfToken::ClassInstance(class_name) if is_section(&class_name) => {
+                    let section = read_section_header(&mut tokens, class_name)?;
+                    push_text(&mut text, &section.signature);
+                    sections.push(section);
+                }
+                // The titles of a message repeat a diagnostic of the text, and its other
+                // strings are identifiers.
+                SlfToken::ClassInstance(class_name) if class_name.ends_with("Message") => {
+                    skip_message(&mut Fields::new(&mut tokens, &class_name))?;
+                }
+                SlfToken::ClassInstance(class_name) if class_name.ends_with("Location") => {
+                    skip_location(&mut Fields::new(&mut tokens, &class_name))?;
+                }
+                SlfToken::String(string) => {
+                    let last_signature = sections.last().map(|section| section.signature.as_str());
+                    // The output of a step usually starts by repeating its signature.
+                    let string = match (last_signature, string.split_once('\n')) {
+                        (Some(signature), Some((first, rest)))
+                            if !signature.is_empty() && first.trim_end() == signature =>
+                        {
+                            rest
+                        }
+                        _ => string.as_str(),
+                    };
+                    push_text(&mut text, string);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(ActivityLog {
+            version,
+            sections,
+            text,
+        })
+    }
+
+    /// Returns the version of the SLF0 stream, such as `10`, if it starts with one.
+    pub fn version(&self) -> Option<u64> {
+        self.version
+    }
+
+    /// Returns every section of the log, in the order they start, subsections included.
+    pub fn sections(&self) -> &[LogSection] {
+        &self.sections
+    }
+
+    /// Returns the text of the log, in the format `xcodebuild` prints.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Consumes the log, returning its text.
+    pub fn into_text(self) -> String {
+        self.text
+    }
+
+    /// Parses the text of the log with [`BuildLog::parse`].
+    pub fn build_log<T: TaskMessage>(&self) -> BuildLog<T> {
+        BuildLog::parse(&self.text)
+    }
+}
+
+/// Represents a DerivedData directory, holding a `<Project>-<hash>` directory per project
+/// built by Xcode, whose build logs are kept in `Logs/Build`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivedData {
+    path: PathBuf,
+}
+
+impl DerivedData {
+    /// Creates a `DerivedData` at the given path, such as a custom location set in Xcode.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        DerivedData { path: path.into() }
+    }
+
+    /// Returns the default DerivedData, `~/Library/Developer/Xcode/DerivedData`.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self>` - The default DerivedData, or `None` if `HOME` is not set or the
+    ///   directory does not exist.
+    pub fn discover() -> Option<Self> {
+        let path = PathBuf::from(std::env::var_os("HOME")?).join(DERIVED_DATA_PATH);
+        path.is_dir().then_some(DerivedData { path })
+    }
+
+    /// Returns the path of the directory.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the most recently modified build log.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_name` - The name of the project or workspace whose logs are searched, such
+    ///   as `App`, or `None` to search the logs of every project.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<Option<PathBuf>>` - The path of the newest `.xcactivitylog`, `None` if
+    ///   there is none, or an error if the directory could not be read.
+    pub fn latest_log(&self, project_name: Option<&str>) -> io::Result<Option<PathBuf>> {
+        // Xcode replaces the spaces of the project name in the directory name.
+        let project_name = project_name.map(|name| name.replace(' ', "_"));
+        let mut latest: Option<(SystemTime, PathBuf)> = None;
+        for project in fs::read_dir(&self.path)? {
+            let project = project?;
+            let directory_name = project.file_name();
+            let directory_name = directory_name.to_string_lossy();
+            let name = directory_name
+                .rsplit_once('-')
+                .map_or(directory_name.as_ref(), |(name, _)| name);
+            if project_name
+                .as_ref()
+                .is_some_and(|project_name| project_name != name)
+            {
+                continue;
+            }
+            let Ok(logs) = fs::read_dir(project.path().join("Logs").join("Build")) else {
+                continue;
+            };
+            for log in logs {
+                let log = log?;
+                let path = log.path();
+                if path
+                    .extension()
+                    .is_none_or(|extension| extension != "xcactivitylog")
+                {
+                    continue;
+                }
+                let modified = log.metadata()?.modified()?;
+                if latest.as_ref().is_none_or(|(latest, _)| modified > *latest) {
+                    latest = Some((modified, path));
+                }
+            }
+        }
+        Ok(latest.map(|(_, path)| path))
+    }
+}
+
+/// Returns whether instances of a class are sections, whose first fields are
+/// their type, domain, title, signature, start and end times.
+fn is_section(class_name: &str) -> bool {
+    class_name.ends_with("Section") || class_name == "IDECommandLineBuildLog"
+}
+
+/// Reads the first fields of a section, which are the same in every version of the format.
+fn read_section_header(
+    tokens: &mut impl Iterator<Item = io::Result<SlfToken>>,
+    class_name: String,
+) -> io::Result<LogSection> {
+    let mut next = || {
+        tokens
+            .next()
+            .unwrap_or_else(|| Err(invalid_data(format!("truncated {}", class_name))))
+    };
+    let unexpected = |token: SlfToken| invalid_data(format!("unexpected {:?} in section", token));
+    let string = |token: SlfToken| match token {
+        SlfToken::String(string) => Ok(string),
+        SlfToken::Null => Ok(String::new()),
+        token => Err(unexpected(token)),
+    };
+    let double = |token: SlfToken| match token {
+        SlfToken::Double(double) => Ok(double),
+        token => Err(unexpected(token)),
+    };
+
+    match next()? {
+        SlfToken::Int(_) => {}
+        token => return Err(unexpected(token)),
+    }
+    let _domain_type = string(next()?)?;
+    let title = string(next()?)?;
+    let signature = string(next()?)?;
+    let time_started = double(next()?)?;
+    let time_stopped = double(next()?)?;
+    Ok(LogSection {
+        class_name,
+        title,
+        signature,
+        time_started,
+        time_stopped,
+    })
+}
+
+/// Reads the fields of an instance, declarations of class names aside.
+struct Fields<'a, I> {
+    tokens: &'a mut I,
+    class_name: &'a str,
+}
+
+impl<'a, I: Iterator<Item = io::Result<SlfToken>>> Fields<'a, I> {
+    /// Starts reading the fields of an instance of the given class.
+    fn new(tokens: &'a mut I, class_name: &'a str) -> Self {
+        Fields { tokens, class_name }
+    }
+
+    /// Reads the next field.
+    fn next(&mut self) -> io::Result<SlfToken> {
+        loop {
+            match self.tokens.next() {
+                // A class is declared right before its first instance.
+                Some(Ok(SlfToken::ClassName(_))) => continue,
+                Some(token) => return token,
+                None => return Err(invalid_data(format!("truncated {}", self.class_name))),
+            }
+        }
+    }
+
+    /// Builds the error returned for a field of an unexpected type.
+    fn unexpected(&self, token: SlfToken) -> io::Error {
+        invalid_data(format!("unexpected {:?} in {}", token, self.class_name))
+    }
+
+    /// Reads a string field, which is empty when missing.
+    fn string(&mut self) -> io::Result<String> {
+        match self.next()? {
+            SlfToken::String(string) => Ok(string),
+            SlfToken::Null => Ok(String::new()),
+            token => Err(self.unexpected(token)),
+        }
+    }
+
+    /// Reads a number field, written as an integer or a double depending on the version.
+    fn number(&mut self) -> io::Result<()> {
+        match self.next()? {
+            SlfToken::Int(_) | SlfToken::Double(_) => Ok(()),
+            token => Err(self.unexpected(token)),
+        }
+    }
+
+    /// Reads an array field, returning its number of elements.
+    fn array(&mut self) -> io::Result<usize> {
+        match self.next()? {
+            SlfToken::Array(len) => Ok(len),
+            SlfToken::Null => Ok(0),
+            token => Err(self.unexpected(token)),
+        }
+    }
+
+    /// Reads an instance field, returning its class name, or `None` if missing.
+    fn instance(&mut self) -> io::Result<Option<String>> {
+        match self.next()? {
+            SlfToken::ClassInstance(class_name) => Ok(Some(class_name)),
+            SlfToken::Null => Ok(None),
+            token => Err(self.unexpected(token)),
+        }
+    }
+
+    /// Reads the fields of a nested instance with the given function.
+    fn nested(
+        &mut self,
+        class_name: &str,
+        read: fn(&mut Fields<'_, I>) -> io::Result<()>,
+    ) -> io::Result<()> {
+        read(&mut Fields::new(self.tokens, class_name))
+    }
+}
+
+/// Reads the fields of an `IDEActivityLogMessage`, or of one of its subclasses: the title,
+/// short title, time, range in the section text, submessages, severity, type, location,
+/// category, secondary locations and additional description, then the fields of the
+/// analyzer subclasses.
+fn skip_message<I: Iterator<Item = io::Result<SlfToken>>>(
+    fields: &mut Fields<'_, I>,
+) -> io::Result<()> {
+    let _title = fields.string()?;
+    let _short_title = fields.string()?;
+    fields.number()?;
+    fields.number()?;
+    fields.number()?;
+    for _ in 0..fields.array()? {
+        if let Some(class_name) = fields.instance()? {
+            fields.nested(&class_name, skip_message)?;
+        }
+    }
+    fields.number()?;
+    let _type = fields.string()?;
+    skip_location_field(fields)?;
+    let _category = fields.string()?;
+    for _ in 0..fields.array()? {
+        skip_location_field(fields)?;
+    }
+    let _additional_description = fields.string()?;
+
+    match fields.class_name {
+        "IDEActivityLogAnalyzerResultMessage" => {
+            let _result_type = fields.string()?;
+            fields.number()?;
+        }
+        "IDEActivityLogAnalyzerControlFlowStepMessage" => {
+            fields.number()?;
+            skip_location_field(fields)?;
+            for _ in 0..fields.array()? {
+                // Each edge holds a start and an end location.
+                if fields.instance()?.is_some() {
+                    skip_location_field(fields)?;
+                    skip_location_field(fields)?;
+                }
+            }
+        }
+        "IDEActivityLogAnalyzerEventStepMessage" => {
+            fields.number()?;
+            let _description = fields.string()?;
+            fields.number()?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Reads a field holding a location, if any.
+fn skip_location_field<I: Iterator<Item = io::Result<SlfToken>>>(
+    fields: &mut Fields<'_, I>,
+) -> io::Result<()> {
+    match fields.instance()? {
+        Some(class_name) => fields.nested(&class_name, skip_location),
+        None => Ok(()),
+    }
+}
+
+/// Reads the fields of a `DVTDocumentLocation`: its URL and timestamp, then the lines,
+/// columns, character range and encoding of a `DVTTextDocumentLocation`, or the member of a
+/// `DVTMemberDocumentLocation`.
+fn skip_location<I: Iterator<Item = io::Result<SlfToken>>>(
+    fields: &mut Fields<'_, I>,
+) -> io::Result<()> {
+    let _url = fields.string()?;
+    fields.number()?;
+    match fields.class_name {
+        "DVTTextDocumentLocation" => {
+            for _ in 0..7 {
+                fields.number()?;
+            }
+        }
+        "DVTMemberDocumentLocation" => {
+            let _member = fields.string()?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Appends the lines of a string to the text, with Unix line endings.
+fn push_text(text: &mut String, string: &str) {
+    for line in string.lines() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            continue;
+        }
+        text.push_str(line);
+        text.push('\n');
+    }
+}
+
+/// Builds the error returned for a malformed stream.
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid xcactivitylog: {}", message),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MyWarning;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    /// Encodes a string token.
+    fn string(value: &str) -> String {
+        format!("{}\"{}", value.len(), value)
+    }
+
+    /// Encodes a class name token.
+    fn class_name(value: &str) -> String {
+        format!("{}%{}", value.len(), value)
+    }
+
+    /// Encodes a double token.
+    fn double(value: f64) -> String {
+        let hex: String = value
+            .to_le_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        format!("{}^", hex)
+    }
+
+    /// Encodes the fields of a message following its class instance, with a text location.
+    fn message(title: &str, short_title: &str, category: &str) -> String {
+        [
+            string(title),
+            short_title.to_string(),
+            double(102.0),
+            "0#".to_string(),
+            "0#".to_string(),
+            "0(".to_string(),
+            "1#".to_string(),
+            string("com.apple.dt.IDE.diagnostic"),
+            class_name("DVTTextDocumentLocation"),
+            "3@".to_string(),
+            string("file:///src/A.swift"),
+            double(102.0),
+            "2#3#2#4#18446744073709551615#0#1#".to_string(),
+            string(category),
+            "0(".to_string(),
+            string(""),
+        ]
+        .concat()
+    }
+
+    /// Tests decoding the sections and text of a compressed activity log and parsing its diagnostics.
+    #[test]
+    fn test_activity_log_decode() {
+        let signature =
+            "CompileSwift normal arm64 /src/A.swift (in target 'App' from project 'App')";
+        let output = format!(
+            "{}\r\n    cd /src\n/src/A.swift:3:9: warning: unused variable 'x'\n",
+            signature
+        );
+        let stream = [
+            "SLF0".to_string(),
+            "10#".to_string(),
+            class_name("IDEActivityLogSection"),
+            "1@".to_string(),
+            "0#".to_string(),
+            string("Xcode.IDEActivityLogDomainType.BuildLog"),
+            string("Build App"),
+            string("Build App"),
+            double(100.0),
+            double(112.5),
+            "1(".to_string(),
+            "1@".to_string(),
+            "2#".to_string(),
+            string("com.apple.dt.IDE.BuildLogSection"),
+            string("Compile A.swift"),
+            string(signature),
+            double(101.0),
+            double(103.0),
+            "0(".to_string(),
+            string(&output),
+            "1(".to_string(),
+            class_name("IDEDiagnosticActivityLogMessage"),
+            "2@".to_string(),
+            message("Unused variable 'x'", "-", "com.apple.dt.IDE.diagnostic"),
+        ]
+        .concat();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(stream.as_bytes()).unwrap();
+        let activity_log = ActivityLog::decode(&encoder.finish().unwrap()).unwrap();
+
+        assert_eq!(activity_log.version(), Some(10));
+        let sections: Vec<_> = activity_log
+            .sections()
+            .iter()
+            .map(|section| (section.title(), section.duration()))
+            .collect();
+        assert_eq!(
+            sections,
+            vec![
+                ("Build App", Some(Duration::from_secs_f64(12.5))),
+                ("Compile A.swift", Some(Duration::from_secs(2))),
+            ]
+        );
+        assert_eq!(
+            activity_log.text(),
+            format!(
+                "Build App\n{}\n    cd /src\n/src/A.swift:3:9: warning: unused variable 'x'\n",
+                signature
+            )
+        );
+
+        let build_log = activity_log.build_log::<MyWarning>();
+        let diagnostics: Vec<_> = build_log
+            .diagnostics_with_build_steps()
+            .map(|(log_file, step)| (log_file.absolute_path(), step.map(|step| step.target())))
+            .collect();
+        assert_eq!(diagnostics, vec![("/src/A.swift", Some(Some("App")))]);
+
+        assert!(ActivityLog::decode(b"not a log").is_err());
+        assert!(ActivityLog::decode(b"SLF03\"ab").is_err());
+    }
+
+    /// Tests that no field of a message leaks into the text, whatever its short title.
+    #[test]
+    fn test_activity_log_decode_message_fields() {
+        let signature =
+            "CompileSwift normal arm64 /src/A.swift (in target 'App' from project 'App')";
+        let stream = [
+            "SLF0".to_string(),
+            "10#".to_string(),
+            class_name("IDEActivityLogSection"),
+            "1@".to_string(),
+            "2#".to_string(),
+            string("com.apple.dt.IDE.BuildLogSection"),
+            string("Compile A.swift"),
+            string(signature),
+            double(101.0),
+            double(103.0),
+            "0(".to_string(),
+            string("/src/A.swift:3:9: warning: unused variable 'x'\n"),
+            "2(".to_string(),
+            class_name("IDEDiagnosticActivityLogMessage"),
+            "2@".to_string(),
+            message("Unused variable 'x'", "-", "Semantic Issue"),
+            "2@".to_string(),
+            message(
+                "Unused variable 'y'",
+                &string("Unused 'y'"),
+                "Semantic Issue",
+            ),
+            string("/src/A.swift:5:1: warning: unused variable 'y'\n"),
+        ]
+        .concat();
+        let activity_log = ActivityLog::decode(stream.as_bytes()).unwrap();
+        assert_eq!(
+            activity_log.text(),
+            format!(
+                "{}\n/src/A.swift:3:9: warning: unused variable 'x'\n\
+                 /src/A.swift:5:1: warning: unused variable 'y'\n",
+                signature
+            )
+        );
+    }
+
+    /// Tests rejecting corrupt streams whose string lengths run past the end of the data.
+    #[test]
+    fn test_activity_log_decode_oversized_string() {
+        for stream in [
+            &b"SLF010#18446744073709551615\"abc"[..],
+            b"SLF010#99999\"abc",
+            b"SLF010#18446744073709551615%abc",
+        ] {
+            let error = ActivityLog::decode(stream).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+            assert!(error.to_string().contains("truncated string"));
+        }
+    }
+
+    /// Tests finding the newest build log, of every project and of a single one.
+    #[test]
+    fn test_derived_data_latest_log() {
+        let root = std::env::temp_dir().join("xcode_log_parser_derived_data");
+        let write_log = |project: &str, name: &str, age: u64| {
+            let logs = root.join(project).join("Logs/Build");
+            fs::create_dir_all(&logs).unwrap();
+            let path = logs.join(name);
+            let file = fs::File::create(&path).unwrap();
+            file.set_modified(SystemTime::now() - Duration::from_secs(age))
+                .unwrap();
+            path
+        };
+        let app = write_log("My_App-abcdefghijklmnopqrstuvwxyzab", "1.xcactivitylog", 60);
+        write_log(
+            "My_App-abcdefghijklmnopqrstuvwxyzab",
+            "LogStoreManifest.plist",
+            0,
+        );
+        let kit = write_log("Kit-bcdefghijklmnopqrstuvwxyzabc", "2.xcactivitylog", 30);
+        let derived_data = DerivedData::new(&root);
+
+        assert_eq!(derived_data.latest_log(None).unwrap(), Some(kit));
+        assert_eq!(derived_data.latest_log(Some("My App")).unwrap(), Some(app));
+        assert_eq!(derived_data.latest_log(Some("Other")).unwrap(), None);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
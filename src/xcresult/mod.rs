@@ -0,0 +1,401 @@
+//! Reading of build issues and test failures from `.xcresult` bundles.
+//!
+//! Bundles are read through `xcrun xcresulttool`, so this only works on machines with
+//! Xcode installed. [`XcresultTool`] reads the objects of a bundle into typed models, such
+//! as [`ActionsInvocationRecord`], which convert into the crate's diagnostics and test
+//! events. The JSON it prints can also be converted directly with [`diagnostics_from_json`].
+//!
+//! The events `xcodebuild -resultStreamPath` writes while building use the same objects,
+//! and are decoded by [`ResultStream`].
+
+mod model;
+mod tool;
+
+use std::io;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde_json::de::IoRead;
+use serde_json::{StreamDeserializer, Value};
+
+use crate::{LogFile, Severity, TaskMessage, TestEvent};
+
+pub use model::{
+    ActionRecord, ActionResult, ActionTestPlanRunSummaries, ActionTestPlanRunSummary,
+    ActionTestSummary, ActionTestableSummary, ActionsInvocationRecord, DocumentLocation,
+    IssueSummary, Reference, ResultIssueSummaries, ResultMetrics, TestFailureIssueSummary,
+};
+pub use tool::XcresultTool;
+
+/// Represents an `.xcresult` bundle on disk.
+#[derive(Debug)]
+pub struct XcresultBundle {
+    path: PathBuf,
+}
+
+impl XcresultBundle {
+    /// Opens the bundle at the given path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the `.xcresult` directory.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<Self>` - The bundle, or an error if the path is not a directory.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        if !path.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} is not an xcresult bundle", path.display()),
+            ));
+        }
+        Ok(XcresultBundle { path })
+    }
+
+    /// Returns the path of the bundle.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads the build issues and test failures recorded in the bundle.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<Vec<LogFile<T>>>` - The diagnostics, or an error if `xcresulttool`
+    ///   could not be run or printed unexpected output.
+    pub fn diagnostics<T: TaskMessage>(&self) -> io::Result<Vec<LogFile<T>>> {
+        let record = XcresultTool::new().invocation_record(&self.path)?;
+        Ok(record.diagnostics())
+    }
+
+    /// Reads the outcome of each test run by the test actions of the bundle.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<Vec<TestEvent>>` - The `Passed` and `Failed` events, or an error if
+    ///   `xcresulttool` could not be run or printed unexpected output.
+    pub fn test_events(&self) -> io::Result<Vec<TestEvent>> {
+        let tool = XcresultTool::new();
+        let record = tool.invocation_record(&self.path)?;
+        let mut events = Vec::new();
+        for action in &record.actions {
+            if let Some(tests_ref) = &action.action_result.tests_ref {
+                events.extend(tool.test_summaries(&self.path, tests_ref)?.test_events());
+            }
+        }
+        Ok(events)
+    }
+}
+
+/// Converts the JSON printed by `xcresulttool get --format json` into diagnostics.
+///
+/// Error, warning and analyzer warning summaries keep their severity; test failures
+/// are reported as errors prefixed with the test case name. Issues without a source
+/// location, such as signing errors, are kept with an empty path.
+///
+/// # Arguments
+///
+/// * `json` - A string slice that holds the invocation record JSON.
+///
+/// # Returns
+///
+/// * `serde_json::Result<Vec<LogFile<T>>>` - The diagnostics, or an error if the JSON is invalid.
+pub fn diagnostics_from_json<T: TaskMessage>(json: &str) -> serde_json::Result<Vec<LogFile<T>>> {
+    let record: ActionsInvocationRecord = serde_json::from_str(json)?;
+    Ok(record.diagnostics())
+}
+
+/// Decodes the diagnostics of the JSON event stream written by
+/// `xcodebuild -resultStreamPath`, as they are read.
+///
+/// The stream is a sequence of `StreamedEvent` objects. Issues and test failures are
+/// converted the same way as by [`diagnostics_from_json`], so they yield the types a
+/// plain text log does, including issues without a source location. Other events, such
+/// as log sections, are skipped.
+///
+/// ```
+/// use xcode_log_parser::xcresult::ResultStream;
+/// use xcode_log_parser::MyWarning;
+///
+/// let stream = r#"{"name": {"_value": "actionStarted"}}
+/// {"name": {"_value": "issueEmitted"}, "structuredPayload": {
+///     "severity": {"_value": "warning"},
+///     "issue": {
+///         "message": {"_value": "unused variable"},
+///         "documentLocationInCreatingWorkspace": {"url": {"_value": "file:///src/A.swift#StartingColumnNumber=4&StartingLineNumber=9"}}
+///     }
+/// }}"#;
+/// let diagnostics: Vec<_> = ResultStream::<_, MyWarning>::new(stream.as_bytes())
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// assert_eq!(diagnostics[0].absolute_path(), "/src/A.swift");
+/// assert_eq!(diagnostics[0].code_fragment().unwrap().line(), 10);
+/// ```
+pub struct ResultStream<R: io::Read, T: TaskMessage> {
+    events: StreamDeserializer<'static, IoRead<R>, Value>,
+    task: PhantomData<fn() -> T>,
+}
+
+impl<R: io::Read, T: TaskMessage> ResultStream<R, T> {
+    /// Creates a new `ResultStream` reading events from the given source.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A reader that yields the event stream, such as the file passed to `-resultStreamPath`.
+    pub fn new(reader: R) -> Self {
+        ResultStream {
+            events: serde_json::Deserializer::from_reader(reader).into_iter(),
+            task: PhantomData,
+        }
+    }
+}
+
+impl<R: io::Read, T: TaskMessage> Iterator for ResultStream<R, T> {
+    type Item = serde_json::Result<LogFile<T>>;
+
+    /// Reads events until the next issue or test failure.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self::Item>` - The diagnostic, an error if an event is not valid JSON,
+    ///   or `None` once the stream is exhausted.
+    fn next(&mut self) -> Option<Self::Item> {
+        for event in self.events.by_ref() {
+            let event = match event {
+                Ok(event) => event,
+                Err(error) => return Some(Err(error)),
+            };
+            let payload = &event["structuredPayload"];
+            let log_file = match string_value(&event["name"]) {
+                Some("issueEmitted") => {
+                    let severity = match string_value(&payload["severity"]) {
+                        Some("error") => Severity::Error,
+                        Some("notice") => Severity::Note,
+                        Some(_) => Severity::Warning,
+                        None if string_value(&payload["issue"]["issueType"])
+                            .is_some_and(|issue_type| issue_type.contains("Error")) =>
+                        {
+                            Severity::Error
+                        }
+                        None => Severity::Warning,
+                    };
+                    IssueSummary::deserialize(&payload["issue"])
+                        .ok()
+                        .map(|issue| issue.to_log_file(severity))
+                }
+                Some("testFailureEmitted") => {
+                    TestFailureIssueSummary::deserialize(&payload["testFailure"])
+                        .ok()
+                        .map(|failure| failure.to_log_file())
+                }
+                _ => None,
+            };
+            if let Some(log_file) = log_file {
+                return Some(Ok(log_file));
+            }
+        }
+        None
+    }
+}
+
+/// Returns the string held by an `xcresulttool` string object.
+fn string_value(value: &Value) -> Option<&str> {
+    value["_value"].as_str()
+}
+
+/// Parses a document location URL into a path and 1-based line and column numbers.
+///
+/// The URL looks like `file:///path/File.swift#StartingLineNumber=9&StartingColumnNumber=4`,
+/// where the numbers are zero-based. The line and column are `None` when the URL does not
+/// name them, such as for an issue about a whole file.
+pub(crate) fn parse_location_url(url: &str) -> Option<(String, Option<usize>, Option<usize>)> {
+    let (path, fragment) = url.split_once('#').unwrap_or((url, ""));
+    let path = percent_decode(path.strip_prefix("file://")?);
+
+    let mut line = None;
+    let mut column = None;
+    for pair in fragment.split('&') {
+        match pair.split_once('=') {
+            Some(("StartingLineNumber", value)) => line = Some(value.parse::<usize>().ok()? + 1),
+            Some(("StartingColumnNumber", value)) => {
+                column = Some(value.parse::<usize>().ok()? + 1)
+            }
+            _ => {}
+        }
+    }
+    Some((path, line, column))
+}
+
+/// Decodes `%XX` escapes in a URL path.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = value.get(index + 1..index + 3).unwrap_or("");
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                decoded.push(byte);
+                index += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[index]);
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MyWarning;
+
+    const RECORD: &str = r#"{
+        "_type": { "_name": "ActionsInvocationRecord" },
+        "issues": {
+            "_type": { "_name": "ResultIssueSummaries" },
+            "errorSummaries": {
+                "_type": { "_name": "Array" },
+                "_values": [{
+                    "_type": { "_name": "IssueSummary" },
+                    "issueType": { "_type": { "_name": "String" }, "_value": "Swift Compiler Error" },
+                    "message": { "_type": { "_name": "String" }, "_value": "cannot find 'foo' in scope" },
+                    "documentLocationInEditor": {
+                        "_type": { "_name": "DocumentLocation" },
+                        "url": { "_type": { "_name": "String" }, "_value": "file:///Users/me/My%20App/File.swift#CharacterRangeLen=0&EndingColumnNumber=12&EndingLineNumber=41&StartingColumnNumber=12&StartingLineNumber=41" }
+                    }
+                }, {
+                    "_type": { "_name": "IssueSummary" },
+                    "message": { "_type": { "_name": "String" }, "_value": "Signing requires a development team" }
+                }]
+            },
+            "warningSummaries": {
+                "_type": { "_name": "Array" },
+                "_values": [{
+                    "_type": { "_name": "IssueSummary" },
+                    "message": { "_type": { "_name": "String" }, "_value": "s#{\"queue\": \"Q\", \"summary\": \"Task\"}#s" },
+                    "documentLocationInEditor": {
+                        "url": { "_value": "file:///src/A.swift#StartingColumnNumber=0&StartingLineNumber=2" }
+                    }
+                }]
+            },
+            "testFailureSummaries": {
+                "_type": { "_name": "Array" },
+                "_values": [{
+                    "_type": { "_name": "TestFailureIssueSummary" },
+                    "testCaseName": { "_value": "AppTests.testLogin()" },
+                    "message": { "_value": "XCTAssertTrue failed" },
+                    "documentLocationInEditor": {
+                        "url": { "_value": "file:///src/AppTests.swift#StartingColumnNumber=0&StartingLineNumber=9" }
+                    }
+                }]
+            }
+        }
+    }"#;
+
+    /// Tests converting build issues and test failures into diagnostics.
+    #[test]
+    fn test_diagnostics_from_json() {
+        let diagnostics = diagnostics_from_json::<MyWarning>(RECORD).unwrap();
+        assert_eq!(diagnostics.len(), 4);
+
+        assert_eq!(
+            diagnostics[0].absolute_path(),
+            "/Users/me/My App/File.swift"
+        );
+        let code_fragment = diagnostics[0].code_fragment().unwrap();
+        assert_eq!(code_fragment.line(), 42);
+        assert_eq!(code_fragment.column(), 13);
+        assert_eq!(code_fragment.severity(), Some(Severity::Error));
+        assert_eq!(code_fragment.text(), "cannot find 'foo' in scope");
+
+        assert_eq!(diagnostics[1].absolute_path(), "");
+        let code_fragment = diagnostics[1].code_fragment().unwrap();
+        assert_eq!((code_fragment.line(), code_fragment.column()), (0, 0));
+        assert_eq!(code_fragment.text(), "Signing requires a development team");
+
+        let code_fragment = diagnostics[2].code_fragment().unwrap();
+        assert_eq!(code_fragment.severity(), Some(Severity::Warning));
+        assert_eq!(
+            code_fragment.task_info().unwrap().task().task_summary(),
+            "Task"
+        );
+
+        let code_fragment = diagnostics[3].code_fragment().unwrap();
+        assert_eq!(code_fragment.line(), 10);
+        assert_eq!(
+            code_fragment.text(),
+            "AppTests.testLogin(): XCTAssertTrue failed"
+        );
+    }
+
+    /// Tests decoding issues and test failures from an event stream, skipping other events.
+    #[test]
+    fn test_result_stream() {
+        let stream = r#"{"_type": {"_name": "StreamedEvent"}, "name": {"_value": "logTextAppended"}, "structuredPayload": {"text": {"_value": "Compiling"}}}
+            {"_type": {"_name": "StreamedEvent"}, "name": {"_value": "issueEmitted"}, "structuredPayload": {
+                "_type": {"_name": "IssueEmittedEventPayload"},
+                "issue": {
+                    "issueType": {"_value": "Swift Compiler Error"},
+                    "message": {"_value": "cannot find 'foo' in scope"},
+                    "documentLocationInCreatingWorkspace": {"url": {"_value": "file:///src/A.swift#StartingColumnNumber=8&StartingLineNumber=2"}}
+                }
+            }}{"name": {"_value": "testFailureEmitted"}, "structuredPayload": {
+                "testFailure": {
+                    "testCaseName": {"_value": "AppTests.testLogin()"},
+                    "message": {"_value": "XCTAssertTrue failed"},
+                    "documentLocationInCreatingWorkspace": {"url": {"_value": "file:///src/AppTests.swift#StartingColumnNumber=0&StartingLineNumber=9"}}
+                }
+            }}
+            {"name": {"_value": "issueEmitted"}, "structuredPayload": {"severity": {"_value": "warning"}, "issue": {"message": {"_value": "no location"}}}}"#;
+        let diagnostics = ResultStream::<_, MyWarning>::new(stream.as_bytes())
+            .collect::<serde_json::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(diagnostics.len(), 3);
+
+        let code_fragment = diagnostics[0].code_fragment().unwrap();
+        assert_eq!((code_fragment.line(), code_fragment.column()), (3, 9));
+        assert_eq!(code_fragment.severity(), Some(Severity::Error));
+        assert_eq!(
+            diagnostics[1].code_fragment().unwrap().text(),
+            "AppTests.testLogin(): XCTAssertTrue failed"
+        );
+        assert_eq!(diagnostics[2].absolute_path(), "");
+        assert_eq!(
+            diagnostics[2].code_fragment().unwrap().text(),
+            "no location"
+        );
+        assert!(ResultStream::<_, MyWarning>::new(&b"{} {"[..])
+            .next()
+            .unwrap()
+            .is_err());
+    }
+
+    /// Tests that a location without a line or column leaves them unknown.
+    #[test]
+    fn test_parse_location_url_without_line() {
+        assert_eq!(
+            parse_location_url("file:///src/Info.plist"),
+            Some(("/src/Info.plist".to_string(), None, None))
+        );
+        assert_eq!(
+            parse_location_url("file:///src/A.swift#StartingLineNumber=4"),
+            Some(("/src/A.swift".to_string(), Some(5), None))
+        );
+        let location = DocumentLocation {
+            url: "file:///src/A.swift#StartingLineNumber=4".to_string(),
+        };
+        assert_eq!((location.line(), location.column()), (Some(5), None));
+        assert!(parse_location_url("file:///src/A.swift#StartingLineNumber=x").is_none());
+    }
+
+    /// Tests that invalid JSON is reported as an error.
+    #[test]
+    fn test_diagnostics_from_invalid_json() {
+        assert!(diagnostics_from_json::<MyWarning>("not json").is_err());
+    }
+}
@@ -0,0 +1,535 @@
+//! Typed models of the objects `xcresulttool get --format json` prints.
+//!
+//! `xcresulttool` wraps every value in an object naming its type, such as
+//! `{"_type": {"_name": "Int"}, "_value": "3"}`, and every array in one holding `_values`.
+//! The models unwrap these, keeping only the fields the crate uses; unknown fields are
+//! ignored so newer versions of Xcode can add them.
+
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+
+use super::parse_location_url;
+use crate::{CodeFragment, LogFile, Severity, TaskMessage, TestCase, TestEvent};
+
+/// The root object of an `.xcresult` bundle.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ActionsInvocationRecord {
+    /// The counts of issues and tests of the whole invocation.
+    pub metrics: ResultMetrics,
+    /// The build issues and test failures of the whole invocation.
+    pub issues: ResultIssueSummaries,
+    /// The actions run, such as building and testing a scheme.
+    #[serde(deserialize_with = "array")]
+    pub actions: Vec<ActionRecord>,
+}
+
+impl ActionsInvocationRecord {
+    /// Returns the build issues and test failures of the invocation as diagnostics.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<LogFile<T>>` - The diagnostics, including issues that have no source location.
+    pub fn diagnostics<T: TaskMessage>(&self) -> Vec<LogFile<T>> {
+        self.issues.diagnostics()
+    }
+}
+
+/// The counts of issues and tests recorded for an invocation or action.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ResultMetrics {
+    /// The number of errors.
+    #[serde(deserialize_with = "number")]
+    pub error_count: usize,
+    /// The number of warnings.
+    #[serde(deserialize_with = "number")]
+    pub warning_count: usize,
+    /// The number of static analyzer warnings.
+    #[serde(deserialize_with = "number")]
+    pub analyzer_warning_count: usize,
+    /// The number of tests run.
+    #[serde(deserialize_with = "number")]
+    pub tests_count: usize,
+    /// The number of tests that failed.
+    #[serde(deserialize_with = "number")]
+    pub tests_failed_count: usize,
+    /// The number of tests that were skipped.
+    #[serde(deserialize_with = "number")]
+    pub tests_skipped_count: usize,
+}
+
+/// An action of the invocation, such as `Build` or `Test`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ActionRecord {
+    /// The name of the action, such as `Test`.
+    #[serde(deserialize_with = "string")]
+    pub scheme_command_name: String,
+    /// The result of building the scheme.
+    pub build_result: ActionResult,
+    /// The result of the action itself.
+    pub action_result: ActionResult,
+}
+
+/// The result of building a scheme or running an action.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ActionResult {
+    /// The status, such as `succeeded` or `failed`.
+    #[serde(deserialize_with = "string")]
+    pub status: String,
+    /// The counts of issues and tests.
+    pub metrics: ResultMetrics,
+    /// The reference of the [`ActionTestPlanRunSummaries`] of a test action.
+    pub tests_ref: Option<Reference>,
+}
+
+/// A reference to another object of the bundle, read by passing its id to `xcresulttool`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Reference {
+    /// The id of the object.
+    #[serde(deserialize_with = "string")]
+    pub id: String,
+}
+
+/// The build issues and test failures of an invocation.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ResultIssueSummaries {
+    /// The errors.
+    #[serde(deserialize_with = "array")]
+    pub error_summaries: Vec<IssueSummary>,
+    /// The warnings.
+    #[serde(deserialize_with = "array")]
+    pub warning_summaries: Vec<IssueSummary>,
+    /// The static analyzer warnings.
+    #[serde(deserialize_with = "array")]
+    pub analyzer_warning_summaries: Vec<IssueSummary>,
+    /// The test failures.
+    #[serde(deserialize_with = "array")]
+    pub test_failure_summaries: Vec<TestFailureIssueSummary>,
+}
+
+impl ResultIssueSummaries {
+    /// Returns the issues as diagnostics.
+    ///
+    /// Errors, warnings and analyzer warnings keep their severity; test failures are
+    /// reported as errors prefixed with the test case name.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<LogFile<T>>` - The diagnostics, including issues that have no source location.
+    pub fn diagnostics<T: TaskMessage>(&self) -> Vec<LogFile<T>> {
+        let issues = [
+            (&self.error_summaries, Severity::Error),
+            (&self.warning_summaries, Severity::Warning),
+            (&self.analyzer_warning_summaries, Severity::Warning),
+        ];
+        let mut diagnostics: Vec<_> = issues
+            .into_iter()
+            .flat_map(|(issues, severity)| {
+                issues.iter().map(move |issue| issue.to_log_file(severity))
+            })
+            .collect();
+        diagnostics.extend(
+            self.test_failure_summaries
+                .iter()
+                .map(TestFailureIssueSummary::to_log_file),
+        );
+        diagnostics
+    }
+}
+
+/// A build issue, such as a compiler error.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct IssueSummary {
+    /// The kind of issue, such as `Swift Compiler Error`.
+    #[serde(deserialize_with = "optional_string")]
+    pub issue_type: Option<String>,
+    /// The message of the issue.
+    #[serde(deserialize_with = "string")]
+    pub message: String,
+    /// The location of the issue, as recorded in invocation records.
+    pub document_location_in_editor: Option<DocumentLocation>,
+    /// The location of the issue, as recorded in the event stream.
+    pub document_location_in_creating_workspace: Option<DocumentLocation>,
+}
+
+impl IssueSummary {
+    /// Returns the location of the issue, if it was recorded.
+    pub fn location(&self) -> Option<&DocumentLocation> {
+        self.document_location_in_editor
+            .as_ref()
+            .or(self.document_location_in_creating_workspace.as_ref())
+    }
+
+    /// Returns the issue as a diagnostic.
+    ///
+    /// See [`DocumentLocation`] for how an issue without a source location is reported.
+    ///
+    /// # Arguments
+    ///
+    /// * `severity` - The severity of the diagnostic.
+    ///
+    /// # Returns
+    ///
+    /// * `LogFile<T>` - The diagnostic.
+    pub fn to_log_file<T: TaskMessage>(&self, severity: Severity) -> LogFile<T> {
+        DocumentLocation::to_log_file(self.location(), severity, self.message.clone())
+    }
+}
+
+/// A test failure.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TestFailureIssueSummary {
+    /// The name of the failed test, such as `AppTests.testLogin()`.
+    #[serde(deserialize_with = "string")]
+    pub test_case_name: String,
+    /// The failure message.
+    #[serde(deserialize_with = "string")]
+    pub message: String,
+    /// The location of the failure, as recorded in invocation records.
+    pub document_location_in_editor: Option<DocumentLocation>,
+    /// The location of the failure, as recorded in the event stream.
+    pub document_location_in_creating_workspace: Option<DocumentLocation>,
+}
+
+impl TestFailureIssueSummary {
+    /// Returns the location of the failure, if it was recorded.
+    pub fn location(&self) -> Option<&DocumentLocation> {
+        self.document_location_in_editor
+            .as_ref()
+            .or(self.document_location_in_creating_workspace.as_ref())
+    }
+
+    /// Returns the failure as an error prefixed with the test case name.
+    ///
+    /// See [`DocumentLocation`] for how a failure without a source location is reported.
+    ///
+    /// # Returns
+    ///
+    /// * `LogFile<T>` - The diagnostic.
+    pub fn to_log_file<T: TaskMessage>(&self) -> LogFile<T> {
+        let text = if self.test_case_name.is_empty() {
+            self.message.clone()
+        } else {
+            format!("{}: {}", self.test_case_name, self.message)
+        };
+        DocumentLocation::to_log_file(self.location(), Severity::Error, text)
+    }
+}
+
+/// The location of an issue in a source file.
+///
+/// Issues converted into diagnostics keep their path, line and column. Without a line or
+/// column, they are reported at line or column 0, as the compiler does for errors without
+/// a location, and without a location at all, they are reported with an empty path.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct DocumentLocation {
+    /// The location as a URL, such as
+    /// `file:///path/File.swift#StartingLineNumber=9&StartingColumnNumber=4`.
+    #[serde(deserialize_with = "string")]
+    pub url: String,
+}
+
+impl DocumentLocation {
+    /// Returns the path of the source file, or `None` if the URL is not a `file://` URL.
+    pub fn path(&self) -> Option<String> {
+        parse_location_url(&self.url).map(|(path, _, _)| path)
+    }
+
+    /// Returns the 1-based line of the issue, or `None` if the URL does not name one.
+    pub fn line(&self) -> Option<usize> {
+        parse_location_url(&self.url).and_then(|(_, line, _)| line)
+    }
+
+    /// Returns the 1-based column of the issue, or `None` if the URL does not name one.
+    pub fn column(&self) -> Option<usize> {
+        parse_location_url(&self.url).and_then(|(_, _, column)| column)
+    }
+
+    /// Returns a diagnostic at the given location, if any.
+    fn to_log_file<T: TaskMessage>(
+        location: Option<&Self>,
+        severity: Severity,
+        text: String,
+    ) -> LogFile<T> {
+        let (absolute_path, line, column) = location
+            .and_then(|location| parse_location_url(&location.url))
+            .unwrap_or_default();
+        LogFile::new(
+            absolute_path,
+            Some(CodeFragment::new(
+                line.unwrap_or(0),
+                column.unwrap_or(0),
+                Some(severity),
+                text,
+            )),
+        )
+    }
+}
+
+/// The test results of a test action, one summary per test plan configuration.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ActionTestPlanRunSummaries {
+    /// The results of each configuration.
+    #[serde(deserialize_with = "array")]
+    pub summaries: Vec<ActionTestPlanRunSummary>,
+}
+
+impl ActionTestPlanRunSummaries {
+    /// Returns the outcome of each test as the event XCTest prints when it finishes.
+    ///
+    /// Tests that succeeded or failed as expected are reported as passed, and skipped
+    /// tests are left out.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<TestEvent>` - The `Passed` and `Failed` events, in the order the tests appear.
+    pub fn test_events(&self) -> Vec<TestEvent> {
+        let mut events = Vec::new();
+        for testable in self
+            .summaries
+            .iter()
+            .flat_map(|summary| &summary.testable_summaries)
+        {
+            for test in &testable.tests {
+                test.push_test_events(&testable.target_name, &mut events);
+            }
+        }
+        events
+    }
+}
+
+/// The test results of one test plan configuration.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ActionTestPlanRunSummary {
+    /// The name of the configuration.
+    #[serde(deserialize_with = "string")]
+    pub name: String,
+    /// The results of each test bundle.
+    #[serde(deserialize_with = "array")]
+    pub testable_summaries: Vec<ActionTestableSummary>,
+}
+
+/// The test results of one test bundle.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ActionTestableSummary {
+    /// The name of the test target, such as `AppTests`.
+    #[serde(deserialize_with = "string")]
+    pub target_name: String,
+    /// The test classes of the bundle.
+    #[serde(deserialize_with = "array")]
+    pub tests: Vec<ActionTestSummary>,
+}
+
+/// A group of tests, such as a test class, or a single test.
+///
+/// `xcresulttool` reports groups as `ActionTestSummaryGroup` objects with `subtests`, and
+/// tests as `ActionTestMetadata` objects with a `testStatus`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ActionTestSummary {
+    /// The name of the group or test, such as `testLogin()`.
+    #[serde(deserialize_with = "string")]
+    pub name: String,
+    /// The identifier of the group or test, such as `LoginTests/testLogin()`.
+    #[serde(deserialize_with = "optional_string")]
+    pub identifier: Option<String>,
+    /// The status of a test, such as `Success`, `Failure` or `Skipped`.
+    #[serde(deserialize_with = "optional_string")]
+    pub test_status: Option<String>,
+    /// The duration in seconds.
+    #[serde(deserialize_with = "optional_number")]
+    pub duration: Option<f64>,
+    /// The tests of a group.
+    #[serde(deserialize_with = "array")]
+    pub subtests: Vec<ActionTestSummary>,
+}
+
+impl ActionTestSummary {
+    /// Appends the events of this test, or of the tests of this group.
+    fn push_test_events(&self, target_name: &str, events: &mut Vec<TestEvent>) {
+        for subtest in &self.subtests {
+            subtest.push_test_events(target_name, events);
+        }
+        let (Some(status), Some(identifier)) = (&self.test_status, &self.identifier) else {
+            return;
+        };
+        let Some((class, method)) = identifier.rsplit_once('/') else {
+            return;
+        };
+        let test_case = TestCase::new(
+            format!("{}.{}", target_name, class),
+            method.trim_end_matches("()"),
+        );
+        let duration = Duration::from_secs_f64(self.duration.unwrap_or(0.0).max(0.0));
+        events.push(match status.as_str() {
+            "Success" | "Expected Failure" => TestEvent::Passed {
+                test_case,
+                duration,
+            },
+            "Failure" => TestEvent::Failed {
+                test_case,
+                duration,
+            },
+            _ => return,
+        });
+    }
+}
+
+/// A value as printed by `xcresulttool`, with its type name.
+#[derive(Deserialize)]
+struct Wrapped {
+    #[serde(rename = "_value")]
+    value: String,
+}
+
+/// An array as printed by `xcresulttool`.
+#[derive(Deserialize)]
+struct Array<T> {
+    #[serde(rename = "_values", default = "Vec::new")]
+    values: Vec<T>,
+}
+
+/// Deserializes a wrapped string.
+fn string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Wrapped::deserialize(deserializer).map(|wrapped| wrapped.value)
+}
+
+/// Deserializes a wrapped string that may be `null`.
+fn optional_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    Option::<Wrapped>::deserialize(deserializer).map(|wrapped| wrapped.map(|wrapped| wrapped.value))
+}
+
+/// Deserializes a wrapped number, which `xcresulttool` prints as a string.
+fn number<'de, D, N>(deserializer: D) -> Result<N, D::Error>
+where
+    D: Deserializer<'de>,
+    N: FromStr,
+    N::Err: Display,
+{
+    string(deserializer)?.parse().map_err(D::Error::custom)
+}
+
+/// Deserializes a wrapped number that may be `null`.
+fn optional_number<'de, D, N>(deserializer: D) -> Result<Option<N>, D::Error>
+where
+    D: Deserializer<'de>,
+    N: FromStr,
+    N::Err: Display,
+{
+    optional_string(deserializer)?
+        .map(|value| value.parse().map_err(D::Error::custom))
+        .transpose()
+}
+
+/// Deserializes the elements of an array.
+fn array<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Array::deserialize(deserializer).map(|array| array.values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests reading the tests of a test plan run into test events.
+    #[test]
+    fn test_action_test_plan_run_summaries_test_events() {
+        let json = r#"{
+            "_type": { "_name": "ActionTestPlanRunSummaries" },
+            "summaries": { "_values": [{
+                "name": { "_value": "Test Scheme Action" },
+                "testableSummaries": { "_values": [{
+                    "targetName": { "_value": "AppTests" },
+                    "tests": { "_values": [{
+                        "_type": { "_name": "ActionTestSummaryGroup" },
+                        "name": { "_value": "LoginTests" },
+                        "identifier": { "_value": "LoginTests" },
+                        "duration": { "_type": { "_name": "Double" }, "_value": "0.5" },
+                        "subtests": { "_values": [{
+                            "_type": { "_name": "ActionTestMetadata" },
+                            "name": { "_value": "testLogin()" },
+                            "identifier": { "_value": "LoginTests/testLogin()" },
+                            "testStatus": { "_value": "Success" },
+                            "duration": { "_value": "0.25" }
+                        }, {
+                            "_type": { "_name": "ActionTestMetadata" },
+                            "name": { "_value": "testLogout()" },
+                            "identifier": { "_value": "LoginTests/testLogout()" },
+                            "testStatus": { "_value": "Failure" },
+                            "duration": { "_value": "0.125" }
+                        }, {
+                            "_type": { "_name": "ActionTestMetadata" },
+                            "name": { "_value": "testSignUp()" },
+                            "identifier": { "_value": "LoginTests/testSignUp()" },
+                            "testStatus": { "_value": "Skipped" }
+                        }]}
+                    }]}
+                }]}
+            }]}
+        }"#;
+        let summaries: ActionTestPlanRunSummaries = serde_json::from_str(json).unwrap();
+        let events: Vec<_> = summaries
+            .test_events()
+            .into_iter()
+            .map(|event| match event {
+                TestEvent::Passed {
+                    test_case,
+                    duration,
+                } => (
+                    "passed",
+                    test_case.suite().to_string(),
+                    test_case.name().to_string(),
+                    duration.as_secs_f64(),
+                ),
+                TestEvent::Failed {
+                    test_case,
+                    duration,
+                } => (
+                    "failed",
+                    test_case.suite().to_string(),
+                    test_case.name().to_string(),
+                    duration.as_secs_f64(),
+                ),
+                event => panic!("unexpected event {:?}", event),
+            })
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                (
+                    "passed",
+                    "AppTests.LoginTests".to_string(),
+                    "testLogin".to_string(),
+                    0.25
+                ),
+                (
+                    "failed",
+                    "AppTests.LoginTests".to_string(),
+                    "testLogout".to_string(),
+                    0.125
+                ),
+            ]
+        );
+        assert!(
+            serde_json::from_str::<ResultMetrics>(r#"{"errorCount": {"_value": "x"}}"#).is_err()
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! Invocation of `xcrun xcresulttool`.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::de::DeserializeOwned;
+
+use super::{ActionTestPlanRunSummaries, ActionsInvocationRecord, Reference};
+
+/// Runs `xcrun xcresulttool` to read the objects of `.xcresult` bundles into typed models.
+///
+/// Only machines with Xcode installed can run it. Xcode 16 moved the JSON output behind
+/// the `--legacy` flag, which older versions reject, so each command is retried without
+/// it on failure.
+///
+/// ```no_run
+/// use xcode_log_parser::xcresult::XcresultTool;
+/// use xcode_log_parser::MyWarning;
+///
+/// let tool = XcresultTool::new();
+/// let record = tool.invocation_record("Test.xcresult").unwrap();
+/// println!("{} tests failed", record.metrics.tests_failed_count);
+/// let diagnostics = record.diagnostics::<MyWarning>();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct XcresultTool {
+    developer_dir: Option<PathBuf>,
+}
+
+impl XcresultTool {
+    /// Creates a new `XcresultTool` using the selected Xcode.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the Xcode to run `xcresulttool` from, as `DEVELOPER_DIR` does.
+    ///
+    /// # Arguments
+    ///
+    /// * `developer_dir` - The `Contents/Developer` directory of an Xcode installation.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The tool running that Xcode's `xcresulttool`.
+    pub fn with_developer_dir(mut self, developer_dir: impl Into<PathBuf>) -> Self {
+        self.developer_dir = Some(developer_dir.into());
+        self
+    }
+
+    /// Reads the root object of a bundle.
+    ///
+    /// # Arguments
+    ///
+    /// * `bundle` - The path of the `.xcresult` directory.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<ActionsInvocationRecord>` - The invocation record, or an error if
+    ///   `xcresulttool` could not be run or printed unexpected output.
+    pub fn invocation_record(
+        &self,
+        bundle: impl AsRef<Path>,
+    ) -> io::Result<ActionsInvocationRecord> {
+        self.get(bundle.as_ref(), None)
+    }
+
+    /// Reads the test results a test action refers to.
+    ///
+    /// # Arguments
+    ///
+    /// * `bundle` - The path of the `.xcresult` directory.
+    /// * `tests_ref` - The `tests_ref` of the action's [`ActionResult`](super::ActionResult).
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<ActionTestPlanRunSummaries>` - The test results, or an error if
+    ///   `xcresulttool` could not be run or printed unexpected output.
+    pub fn test_summaries(
+        &self,
+        bundle: impl AsRef<Path>,
+        tests_ref: &Reference,
+    ) -> io::Result<ActionTestPlanRunSummaries> {
+        self.get(bundle.as_ref(), Some(&tests_ref.id))
+    }
+
+    /// Reads an object of a bundle into a model.
+    ///
+    /// # Arguments
+    ///
+    /// * `bundle` - The path of the `.xcresult` directory.
+    /// * `id` - The id of the object, or `None` for the invocation record.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<M>` - The object, or an error if `xcresulttool` could not be run or
+    ///   printed unexpected output.
+    pub fn get<M: DeserializeOwned>(&self, bundle: &Path, id: Option<&str>) -> io::Result<M> {
+        let json = self.get_json(bundle, id)?;
+        serde_json::from_str(&json)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+
+    /// Runs `xcresulttool get` and returns the JSON of an object of a bundle.
+    ///
+    /// # Arguments
+    ///
+    /// * `bundle` - The path of the `.xcresult` directory.
+    /// * `id` - The id of the object, or `None` for the invocation record.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<String>` - The JSON, or an error if `xcresulttool` could not be run.
+    pub fn get_json(&self, bundle: &Path, id: Option<&str>) -> io::Result<String> {
+        let mut last_error = String::new();
+        for legacy in [true, false] {
+            let mut command = Command::new("xcrun");
+            if let Some(developer_dir) = &self.developer_dir {
+                command.env("DEVELOPER_DIR", developer_dir);
+            }
+            command.args(["xcresulttool", "get"]);
+            if legacy {
+                command.arg("--legacy");
+            }
+            command.args(["--format", "json", "--path"]).arg(bundle);
+            if let Some(id) = id {
+                command.args(["--id", id]);
+            }
+
+            let output = command.output()?;
+            if output.status.success() {
+                return String::from_utf8(output.stdout)
+                    .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error));
+            }
+            last_error = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        }
+        Err(io::Error::other(format!(
+            "xcresulttool failed: {}",
+            last_error
+        )))
+    }
+}